@@ -0,0 +1,9 @@
+use triad::examples::demonstrate_random_number_generation;
+
+fn main() {
+    // Инициализируем логирование
+    env_logger::init();
+
+    // Запускаем демонстрацию
+    demonstrate_random_number_generation();
+}
@@ -10,6 +10,7 @@ pub mod core {
 }
 
 /// Реэкспорт компонентов для квантовой симуляции на основе QuEST
+#[cfg(feature = "quest")]
 pub mod quest {
     pub use crate::quest::*;
 }
@@ -20,32 +21,103 @@ pub mod examples {
 }
 
 // Реэкспорт наиболее часто используемых типов для удобства
-pub use crate::core::{Qubit, QuantumState, QuantumSimulator, Amplitude};
+pub use crate::core::{Qubit, QuantumState, QuantumSimulator, Amplitude, Gate, StateVectorSimulator, Circuit, Counts};
+#[cfg(feature = "quest")]
 pub use crate::quest::QuESTSimulator;
+pub use crate::backend::{Backend, DefaultSimulator};
+pub use crate::error::TriadError;
+use crate::core::circuit::{Instruction, execute, validate_instruction};
+use crate::core::measurement::Povm;
+use crate::core::pauli::Pauli;
+use crate::core::qft::{QftOptions, qft_instructions};
+use crate::core::quantum_simulator::{AdvancedQuantumSimulator, validate_qubits};
 
 /// Структура для создания и управления квантовым симулятором.
 /// Предоставляет упрощенный интерфейс для работы с квантовыми вычислениями.
 pub struct QuantumEngine {
     /// Квантовый симулятор, выполняющий реальные операции.
-    simulator: QuESTSimulator,
+    simulator: Box<dyn AdvancedQuantumSimulator>,
+    /// Бэкенд, которым создан симулятор.
+    backend: Backend,
+    /// Классический регистр для результатов измерений.
+    classical: Vec<bool>,
 }
 
 impl QuantumEngine {
-    /// Создает новый экземпляр квантового движка с указанным числом кубитов.
+    /// Создает новый экземпляр квантового движка с указанным числом кубитов
+    /// на бэкенде по умолчанию (см. `Backend::default`).
     pub fn new(num_qubits: usize) -> Self {
-        Self {
-            simulator: QuESTSimulator::new(num_qubits),
-        }
+        Self::with_backend(num_qubits, Backend::default())
+    }
+    
+    /// Создает новый экземпляр квантового движка, возвращая ошибку вместо паники.
+    pub fn try_new(num_qubits: usize) -> Result<Self, TriadError> {
+        Self::try_with_backend(num_qubits, Backend::default())
+    }
+    
+    /// Создает квантовый движок на указанном бэкенде.
+    pub fn with_backend(num_qubits: usize, backend: Backend) -> Self {
+        Self::try_with_backend(num_qubits, backend).unwrap_or_else(|err| panic!("{}", err))
+    }
+    
+    /// Создает квантовый движок на указанном бэкенде, возвращая ошибку,
+    /// если бэкенд не поддерживает такое число кубитов.
+    pub fn try_with_backend(num_qubits: usize, backend: Backend) -> Result<Self, TriadError> {
+        Ok(Self {
+            simulator: backend.create(num_qubits)?,
+            backend,
+            classical: vec![false; num_qubits],
+        })
+    }
+    
+    /// Создает квантовый движок для схемы, выбирая бэкенд автоматически
+    /// (см. `Backend::auto`). Классический регистр имеет размер регистра схемы.
+    pub fn for_circuit(circuit: &Circuit) -> Result<Self, TriadError> {
+        let mut engine = Self::try_with_backend(circuit.num_qubits(), Backend::auto(circuit))?;
+        engine.set_num_cbits(circuit.num_cbits());
+        Ok(engine)
+    }
+    
+    /// Возвращает бэкенд движка.
+    pub fn backend(&self) -> Backend {
+        self.backend
+    }
+    
+    /// Возвращает число кубитов в движке.
+    pub fn num_qubits(&self) -> usize {
+        self.simulator.num_qubits()
+    }
+    
+    /// Возвращает число классических битов. По умолчанию регистр
+    /// содержит по одному биту на кубит.
+    pub fn num_cbits(&self) -> usize {
+        self.classical.len()
+    }
+    
+    /// Изменяет размер классического регистра; новые биты равны 0.
+    pub fn set_num_cbits(&mut self, num_cbits: usize) {
+        self.classical.resize(num_cbits, false);
+    }
+    
+    /// Возвращает значения классических битов.
+    pub fn classical_register(&self) -> &[bool] {
+        &self.classical
+    }
+    
+    /// Возвращает значение классического бита.
+    /// Паникует, если бит выходит за пределы регистра.
+    pub fn cbit(&self, cbit: usize) -> bool {
+        self.classical[cbit]
     }
     
     /// Возвращает ссылку на внутренний симулятор.
-    pub fn simulator(&self) -> &QuESTSimulator {
-        &self.simulator
+    pub fn simulator(&self) -> &dyn AdvancedQuantumSimulator {
+        self.simulator.as_ref()
     }
     
     /// Возвращает изменяемую ссылку на внутренний симулятор.
-    pub fn simulator_mut(&mut self) -> &mut QuESTSimulator {
-        &mut self.simulator
+    pub fn simulator_mut(&mut self) -> &mut dyn AdvancedQuantumSimulator {
+        self.simulator.as_mut()
     }
     
     /// Применяет гейт Адамара к указанному кубиту.
@@ -73,14 +145,279 @@ impl QuantumEngine {
         self.simulator.cnot(control, target);
     }
     
+    /// Применяет гейт Тоффоли (CCNOT).
+    pub fn toffoli(&mut self, control1: usize, control2: usize, target: usize) {
+        self.simulator.toffoli(control1, control2, target);
+    }
+    
+    /// Применяет гейт Фредкина (CSWAP).
+    pub fn fredkin(&mut self, control: usize, target1: usize, target2: usize) {
+        self.simulator.fredkin(control, target1, target2);
+    }
+    
+    /// Применяет X-гейт, управляемый несколькими кубитами.
+    pub fn mcx(&mut self, controls: &[usize], target: usize) {
+        self.simulator.mcx(controls, target);
+    }
+    
+    /// Применяет Z-гейт, управляемый несколькими кубитами.
+    pub fn mcz(&mut self, controls: &[usize], target: usize) {
+        self.simulator.mcz(controls, target);
+    }
+    
+    /// Применяет однокубитовый оператор (матрица 2x2 по строкам),
+    /// управляемый несколькими кубитами.
+    pub fn multi_controlled_unitary(&mut self, controls: &[usize], target: usize, matrix: &[Amplitude]) {
+        self.simulator.multi_controlled_unitary(controls, target, matrix);
+    }
+    
+    /// Применяет управляемое вращение вокруг оси X.
+    pub fn controlled_rx(&mut self, control: usize, target: usize, angle: f64) {
+        self.simulator.controlled_rx(control, target, angle);
+    }
+    
+    /// Применяет управляемое вращение вокруг оси Y.
+    pub fn controlled_ry(&mut self, control: usize, target: usize, angle: f64) {
+        self.simulator.controlled_ry(control, target, angle);
+    }
+    
+    /// Применяет управляемое вращение вокруг оси Z.
+    pub fn controlled_rz(&mut self, control: usize, target: usize, angle: f64) {
+        self.simulator.controlled_rz(control, target, angle);
+    }
+    
+    /// Применяет произвольный гейт из `core::gates` к указанным кубитам.
+    pub fn apply_gate(&mut self, gate: &dyn Gate, targets: &[usize]) {
+        self.simulator.apply_gate(gate, targets);
+    }
+    
     /// Измеряет указанный кубит и возвращает результат.
     pub fn measure(&mut self, qubit: usize) -> bool {
         self.simulator.measure(qubit)
     }
     
-    /// Сбрасывает состояние всех кубитов в |0⟩.
+    /// Измеряет кубит, записывает результат в классический бит `cbit`
+    /// и возвращает его.
+    pub fn measure_into(&mut self, qubit: usize, cbit: usize) -> bool {
+        self.try_measure_into(qubit, cbit).unwrap_or_else(|err| panic!("{}", err))
+    }
+    
+    /// Измеряет кубит в собственном базисе оператора Паули
+    /// (`false` — собственное значение +1, `true` — -1).
+    pub fn measure_in_basis(&mut self, qubit: usize, basis: Pauli) -> bool {
+        self.simulator.measure_in_basis(qubit, basis)
+    }
+    
+    /// Измеряет произведение операторов Паули с проекцией состояния
+    /// на собственное подпространство (`false` — +1, `true` — -1).
+    pub fn measure_pauli(&mut self, pauli_product: &[(usize, char)]) -> bool {
+        self.simulator.measure_pauli(pauli_product)
+    }
+    
+    /// Выполняет обобщенное измерение и возвращает номер исхода.
+    pub fn measure_povm(&mut self, povm: &Povm, targets: &[usize]) -> usize {
+        self.simulator.measure_povm(povm, targets)
+    }
+    
+    /// Сбрасывает кубит в состояние |0⟩ посреди вычисления.
+    pub fn reset_qubit(&mut self, qubit: usize) {
+        self.simulator.reset_qubit(qubit);
+    }
+    
+    /// Выполняет инструкцию, только если классические биты `cbits`
+    /// (бит `i` значения соответствует `cbits[i]`) равны `value`.
+    /// Возвращает, была ли инструкция выполнена.
+    pub fn c_if(&mut self, cbits: &[usize], value: u64, instruction: Instruction) -> bool {
+        self.try_c_if(cbits, value, instruction).unwrap_or_else(|err| panic!("{}", err))
+    }
+    
+    /// Применяет квантовое преобразование Фурье к кубитам `qubits`
+    /// (кубит `qubits[0]` — младший бит).
+    pub fn qft(&mut self, qubits: &[usize]) {
+        self.try_qft(qubits).unwrap_or_else(|err| panic!("{}", err))
+    }
+    
+    /// Применяет обратное квантовое преобразование Фурье к кубитам `qubits`.
+    pub fn iqft(&mut self, qubits: &[usize]) {
+        self.try_iqft(qubits).unwrap_or_else(|err| panic!("{}", err))
+    }
+    
+    /// Применяет QFT с указанной степенью приближения и обработкой SWAP-гейтов.
+    pub fn qft_with(&mut self, qubits: &[usize], options: QftOptions) {
+        self.try_qft_with(qubits, options).unwrap_or_else(|err| panic!("{}", err))
+    }
+    
+    /// Применяет обратное QFT с указанными параметрами.
+    pub fn iqft_with(&mut self, qubits: &[usize], options: QftOptions) {
+        self.try_iqft_with(qubits, options).unwrap_or_else(|err| panic!("{}", err))
+    }
+    
+    // === Методы, возвращающие ошибку вместо паники ===
+    
+    /// Применяет гейт Адамара, проверяя индекс кубита.
+    pub fn try_hadamard(&mut self, qubit: usize) -> Result<(), TriadError> {
+        self.simulator.try_hadamard(qubit)
+    }
+    
+    /// Применяет X-гейт, проверяя индекс кубита.
+    pub fn try_x(&mut self, qubit: usize) -> Result<(), TriadError> {
+        self.simulator.try_x(qubit)
+    }
+    
+    /// Применяет Y-гейт, проверяя индекс кубита.
+    pub fn try_y(&mut self, qubit: usize) -> Result<(), TriadError> {
+        self.simulator.try_y(qubit)
+    }
+    
+    /// Применяет Z-гейт, проверяя индекс кубита.
+    pub fn try_z(&mut self, qubit: usize) -> Result<(), TriadError> {
+        self.simulator.try_z(qubit)
+    }
+    
+    /// Применяет CNOT-гейт, проверяя индексы кубитов.
+    pub fn try_cnot(&mut self, control: usize, target: usize) -> Result<(), TriadError> {
+        self.simulator.try_cnot(control, target)
+    }
+    
+    /// Применяет гейт Тоффоли, проверяя индексы кубитов.
+    pub fn try_toffoli(&mut self, control1: usize, control2: usize, target: usize) -> Result<(), TriadError> {
+        self.simulator.try_toffoli(control1, control2, target)
+    }
+    
+    /// Применяет гейт Фредкина, проверяя индексы кубитов.
+    pub fn try_fredkin(&mut self, control: usize, target1: usize, target2: usize) -> Result<(), TriadError> {
+        self.simulator.try_fredkin(control, target1, target2)
+    }
+    
+    /// Применяет многоконтрольный X-гейт, проверяя индексы кубитов.
+    pub fn try_mcx(&mut self, controls: &[usize], target: usize) -> Result<(), TriadError> {
+        self.simulator.try_mcx(controls, target)
+    }
+    
+    /// Применяет многоконтрольный Z-гейт, проверяя индексы кубитов.
+    pub fn try_mcz(&mut self, controls: &[usize], target: usize) -> Result<(), TriadError> {
+        self.simulator.try_mcz(controls, target)
+    }
+    
+    /// Применяет многоконтрольный оператор, проверяя кубиты и унитарность матрицы.
+    pub fn try_multi_controlled_unitary(&mut self, controls: &[usize], target: usize, matrix: &[Amplitude]) -> Result<(), TriadError> {
+        self.simulator.try_multi_controlled_unitary(controls, target, matrix)
+    }
+    
+    /// Применяет произвольный гейт, проверяя целевые кубиты и унитарность матрицы.
+    pub fn try_apply_gate(&mut self, gate: &dyn Gate, targets: &[usize]) -> Result<(), TriadError> {
+        self.simulator.try_apply_gate(gate, targets)
+    }
+    
+    /// Измеряет кубит, проверяя его индекс.
+    pub fn try_measure(&mut self, qubit: usize) -> Result<bool, TriadError> {
+        self.simulator.try_measure(qubit)
+    }
+    
+    /// Измеряет кубит в классический бит, проверяя индексы.
+    pub fn try_measure_into(&mut self, qubit: usize, cbit: usize) -> Result<bool, TriadError> {
+        self.try_apply_instruction(&Instruction::Measure { qubit, cbit })?;
+        Ok(self.classical[cbit])
+    }
+    
+    /// Измеряет кубит в базисе оператора Паули, проверяя его индекс.
+    pub fn try_measure_in_basis(&mut self, qubit: usize, basis: Pauli) -> Result<bool, TriadError> {
+        self.simulator.try_measure_in_basis(qubit, basis)
+    }
+    
+    /// Измеряет произведение операторов Паули, проверяя операторы и кубиты.
+    pub fn try_measure_pauli(&mut self, pauli_product: &[(usize, char)]) -> Result<bool, TriadError> {
+        self.simulator.try_measure_pauli(pauli_product)
+    }
+    
+    /// Выполняет обобщенное измерение, проверяя целевые кубиты
+    /// и поддержку POVM бэкендом.
+    pub fn try_measure_povm(&mut self, povm: &Povm, targets: &[usize]) -> Result<usize, TriadError> {
+        self.simulator.try_measure_povm(povm, targets)
+    }
+    
+    /// Сбрасывает кубит в |0⟩, проверяя его индекс.
+    pub fn try_reset_qubit(&mut self, qubit: usize) -> Result<(), TriadError> {
+        self.simulator.try_reset_qubit(qubit)
+    }
+    
+    /// Выполняет инструкцию при условии на классические биты,
+    /// проверяя индексы кубитов и битов.
+    pub fn try_c_if(&mut self, cbits: &[usize], value: u64, instruction: Instruction) -> Result<bool, TriadError> {
+        let conditional = Instruction::Conditional { cbits: cbits.to_vec(), value, instruction: Box::new(instruction) };
+        validate_instruction(&conditional, self.num_qubits(), self.num_cbits())?;
+        
+        let actual = cbits.iter()
+            .enumerate()
+            .fold(0u64, |acc, (i, &c)| if self.classical[c] { acc | (1 << i) } else { acc });
+        self.try_apply_instruction(&conditional)?;
+        Ok(actual == value)
+    }
+    
+    /// Применяет QFT, проверяя индексы кубитов.
+    pub fn try_qft(&mut self, qubits: &[usize]) -> Result<(), TriadError> {
+        self.try_qft_with(qubits, QftOptions::default())
+    }
+    
+    /// Применяет обратное QFT, проверяя индексы кубитов.
+    pub fn try_iqft(&mut self, qubits: &[usize]) -> Result<(), TriadError> {
+        self.try_iqft_with(qubits, QftOptions::default())
+    }
+    
+    /// Применяет QFT с параметрами, проверяя индексы кубитов.
+    pub fn try_qft_with(&mut self, qubits: &[usize], options: QftOptions) -> Result<(), TriadError> {
+        self.apply_qft(qubits, options, false)
+    }
+    
+    /// Применяет обратное QFT с параметрами, проверяя индексы кубитов.
+    pub fn try_iqft_with(&mut self, qubits: &[usize], options: QftOptions) -> Result<(), TriadError> {
+        self.apply_qft(qubits, options, true)
+    }
+    
+    fn apply_qft(&mut self, qubits: &[usize], options: QftOptions, inverse: bool) -> Result<(), TriadError> {
+        validate_qubits(qubits, self.num_qubits())?;
+        for instruction in qft_instructions(qubits, options, inverse) {
+            self.try_apply_instruction(&instruction)?;
+        }
+        Ok(())
+    }
+    
+    /// Выполняет одну инструкцию схемы над регистрами движка:
+    /// результаты измерений записываются в классический регистр.
+    pub fn try_apply_instruction(&mut self, instruction: &Instruction) -> Result<(), TriadError> {
+        validate_instruction(instruction, self.num_qubits(), self.num_cbits())?;
+        execute(instruction, self.simulator.as_mut(), &mut self.classical)
+    }
+    
+    /// Выполняет квантовую схему, начиная с текущего состояния,
+    /// и возвращает значения ее классических битов. Результаты также
+    /// записываются в начало классического регистра движка.
+    pub fn run_circuit(&mut self, circuit: &Circuit) -> Result<Vec<bool>, TriadError> {
+        let cbits = circuit.run_on(self.simulator.as_mut())?;
+        if self.classical.len() < cbits.len() {
+            self.set_num_cbits(cbits.len());
+        }
+        self.classical[..cbits.len()].copy_from_slice(&cbits);
+        Ok(cbits)
+    }
+    
+    /// Разбирает программу OpenQASM 2.0 или 3 и выполняет ее,
+    /// возвращая значения классических битов.
+    pub fn run_qasm(&mut self, source: &str) -> Result<Vec<bool>, TriadError> {
+        let circuit = crate::qasm::parse_qasm(source)?;
+        self.run_circuit(&circuit)
+    }
+    
+    /// Сбрасывает состояние всех кубитов в |0⟩ и обнуляет классический регистр.
     pub fn reset(&mut self) {
         self.simulator.reset();
+        self.classical.iter_mut().for_each(|bit| *bit = false);
+    }
+    
+    /// Инициализирует генератор случайных чисел симулятора: одинаковые
+    /// значения `seed` дают одинаковые результаты измерений и выборок.
+    pub fn seed(&mut self, seed: u64) {
+        self.simulator.seed(seed);
     }
     
     // === Методы для работы с суперпозицией ===
@@ -88,7 +425,7 @@ impl QuantumEngine {
     /// Создаёт равную суперпозицию всех базисных состояний (|+⟩^⊗n)
     pub fn create_uniform_superposition(&mut self) {
         // Применяем гейт Адамара ко всем кубитам
-        let num_qubits = self.simulator.get_state().num_qubits();
+        let num_qubits = self.num_qubits();
         for qubit in 0..num_qubits {
             self.hadamard(qubit);
         }
@@ -101,8 +438,6 @@ impl QuantumEngine {
         self.reset();
         
         // Применяем вращение вокруг оси Y
-        // Привожу метод в область видимости для вызова
-        use crate::core::quantum_simulator::AdvancedQuantumSimulator;
         self.simulator.ry(qubit, 2.0 * theta);
     }
     
@@ -110,23 +445,20 @@ impl QuantumEngine {
     pub fn is_in_superposition(&self, qubit: usize) -> bool {
         // Кубит в суперпозиции, если вероятность измерения 0 не 0% и не 100%
         let prob_0 = self.simulator.probability_of_outcome(qubit, false);
-        !(prob_0 < 1e-10 || prob_0 > 1.0 - 1e-10)
+        (1e-10..=1.0 - 1e-10).contains(&prob_0)
     }
     
     /// Вычисляет вероятность измерить 0 для указанного кубита.
     pub fn probability_of_zero(&self, qubit: usize) -> f64 {
-        let prob_0 = self.simulator.probability_of_outcome(qubit, false);
-        prob_0
+        self.simulator.probability_of_outcome(qubit, false)
     }
     
     // === Методы для работы с запутанностью ===
     
     /// Создаёт состояние Белла (максимально запутанное состояние двух кубитов)
     /// |Φ⁺⟩ = (|00⟩ + |11⟩)/√2
-    pub fn create_bell_state(&mut self, qubit1: usize, qubit2: usize) -> Result<(), String> {
-        if qubit1 == qubit2 {
-            return Err("Кубиты должны быть различными".to_string());
-        }
+    pub fn create_bell_state(&mut self, qubit1: usize, qubit2: usize) -> Result<(), TriadError> {
+        validate_qubits(&[qubit1, qubit2], self.num_qubits())?;
         
         // Создаём состояние Белла
         self.reset();
@@ -138,10 +470,11 @@ impl QuantumEngine {
     
     /// Создаёт состояние GHZ (обобщение состояния Белла на n кубитов)
     /// |GHZ⟩ = (|0...0⟩ + |1...1⟩)/√2
-    pub fn create_ghz_state(&mut self) -> Result<(), String> {
-        let n = self.simulator.get_state().num_qubits();
+    pub fn create_ghz_state(&mut self) -> Result<(), TriadError> {
+        let n = self.num_qubits();
         if n < 2 {
-            return Err("Для создания GHZ-состояния требуется минимум 2 кубита".to_string());
+            // Для создания GHZ-состояния требуется минимум 2 кубита
+            return Err(TriadError::NotEnoughQubits { required: 2, available: n });
         }
         
         // Создаём GHZ-состояние
@@ -158,7 +491,7 @@ impl QuantumEngine {
     /// Проверяет, запутаны ли два кубита
     /// (упрощенная реализация, основанная на измерениях)
     pub fn is_entangled(&self, qubit1: usize, qubit2: usize) -> bool {
-        let n = self.simulator.get_state().num_qubits();
+        let n = self.num_qubits();
         
         if qubit1 >= n || qubit2 >= n || qubit1 == qubit2 {
             return false;
@@ -172,7 +505,6 @@ impl QuantumEngine {
         }
         
         // Проверяем корреляцию через вероятность совместных состояний
-        let state = self.simulator.get_state();
         let p00 = self.probability_of_state(0, 0, qubit1, qubit2);
         let p01 = self.probability_of_state(0, 1, qubit1, qubit2);
         let p10 = self.probability_of_state(1, 0, qubit1, qubit2);
@@ -187,29 +519,30 @@ impl QuantumEngine {
     
     /// Вычисляет вероятность совместного состояния двух кубитов
     fn probability_of_state(&self, val1: u8, val2: u8, qubit1: usize, qubit2: usize) -> f64 {
-        use crate::core::quantum_simulator::QuantumSimulator;
-        
-        let state_box = self.simulator.get_state();
-        let n = state_box.num_qubits();
-        let mut total_prob = 0.0;
-        
-        // Суммируем вероятности по всем состояниям, где указанные кубиты имеют заданные значения
-        for i in 0..(1 << n) {
-            let bit1 = (i >> qubit1) & 1;
-            let bit2 = (i >> qubit2) & 1;
-            
-            if bit1 == val1 as u64 && bit2 == val2 as u64 {
-                total_prob += state_box.probability(i as u64);
-            }
-        }
-        
-        total_prob
+        let outcome = (val1 as u64) | ((val2 as u64) << 1);
+        self.simulator.probability_of_joint_outcome(&[qubit1, qubit2], outcome)
+    }
+    
+    /// Вычисляет совместную вероятность получить битовую строку `outcome`
+    /// при измерении указанных кубитов (бит `i` соответствует `qubits[i]`).
+    pub fn probability_of_joint_outcome(&self, qubits: &[usize], outcome: u64) -> f64 {
+        self.simulator.probability_of_joint_outcome(qubits, outcome)
+    }
+    
+    /// Извлекает `shots` выборок результатов измерения указанных кубитов
+    /// из текущего состояния, не изменяя его.
+    pub fn sample(&mut self, shots: usize, qubits: &[usize]) -> Counts {
+        self.simulator.sample(shots, qubits)
+    }
+    
+    /// Извлекает выборки результатов измерения, проверяя индексы кубитов.
+    pub fn try_sample(&mut self, shots: usize, qubits: &[usize]) -> Result<Counts, TriadError> {
+        self.simulator.try_sample(shots, qubits)
     }
 }
 
 /// Функции для быстрого запуска квантовых алгоритмов
 pub mod algorithms {
-    use super::*;
     use crate::examples::{generate_random_number, run_deutsch_algorithm, FunctionType};
     use crate::examples::{quantum_teleportation, superdense_coding, grover_search};
     
@@ -242,7 +575,6 @@ pub mod algorithms {
 /// Модуль для визуализации квантовых состояний
 pub mod visualization {
     use super::QuantumEngine;
-    use crate::core::quantum_simulator::QuantumSimulator;
     
     /// Генерирует текстовое представление квантового состояния
     pub fn state_to_string(engine: &QuantumEngine) -> String {
@@ -267,7 +599,7 @@ pub mod visualization {
     
     /// Визуализирует запутанность между кубитами в виде графа
     pub fn entanglement_graph(engine: &QuantumEngine) -> String {
-        let n = engine.simulator().get_state().num_qubits();
+        let n = engine.num_qubits();
         let mut result = String::new();
         
         result.push_str("Граф запутанности:\n");
@@ -287,4 +619,101 @@ pub mod visualization {
         
         result
     }
-} 
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::gates::BasicGate;
+
+    #[test]
+    fn test_teleportation_with_feed_forward() {
+        let theta = 0.7;
+        for seed in 0..8 {
+            let mut engine = QuantumEngine::with_backend(3, Backend::StateVector);
+            engine.seed(seed);
+            engine.simulator_mut().ry(0, theta);
+            engine.hadamard(1);
+            engine.cnot(1, 2);
+            engine.cnot(0, 1);
+            engine.hadamard(0);
+
+            engine.measure_into(0, 0);
+            engine.measure_into(1, 1);
+            let flipped = engine.c_if(&[1], 1, Instruction::gate(BasicGate::PauliX, &[2]));
+            engine.c_if(&[0], 1, Instruction::gate(BasicGate::PauliZ, &[2]));
+
+            assert_eq!(flipped, engine.cbit(1));
+            let expected = (theta / 2.0).cos().powi(2);
+            assert!((engine.probability_of_zero(2) - expected).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_reset_qubit_and_classical_register() {
+        let mut engine = QuantumEngine::with_backend(2, Backend::StateVector);
+        engine.seed(7);
+        engine.set_num_cbits(3);
+        engine.create_bell_state(0, 1).unwrap();
+        engine.x(1);
+
+        // После сброса кубит 0 в |0⟩, а кубит 1 коллапсирует в противоположное значение
+        engine.reset_qubit(0);
+        assert!((engine.probability_of_zero(0) - 1.0).abs() < 1e-10);
+        let bit = engine.measure_into(1, 2);
+        assert_eq!(engine.classical_register(), &[false, false, bit]);
+
+        // Условие на несколько битов: значение 0b100 соответствует bit = 1,
+        // и тогда сброс возвращает измеренный кубит 1 в |0⟩
+        let executed = engine.c_if(&[0, 1, 2], 0b100, Instruction::Reset(1));
+        assert_eq!(executed, bit);
+        assert!((engine.probability_of_zero(1) - 1.0).abs() < 1e-10);
+
+        engine.reset();
+        assert_eq!(engine.classical_register(), &[false; 3]);
+    }
+
+    #[test]
+    fn test_classical_register_validation() {
+        let mut engine = QuantumEngine::with_backend(2, Backend::StateVector);
+        assert!(engine.try_measure_into(0, 2).is_err());
+        assert!(engine.try_measure_into(2, 0).is_err());
+        assert!(engine.try_reset_qubit(5).is_err());
+        assert!(engine.try_c_if(&[0], 2, Instruction::Reset(0)).is_err());
+        assert!(engine.try_c_if(&[3], 1, Instruction::Reset(0)).is_err());
+        assert_eq!(engine.try_c_if(&[0], 1, Instruction::Reset(0)), Ok(false));
+
+        engine.set_num_cbits(70);
+        let wide: Vec<usize> = (0..70).collect();
+        assert!(matches!(engine.try_c_if(&wide, 0, Instruction::Reset(0)), Err(TriadError::InvalidArgument(_))));
+
+        let mut circuit = Circuit::new(2, 4);
+        circuit.x(1).measure(1, 3);
+        let mut engine = QuantumEngine::for_circuit(&circuit).unwrap();
+        assert_eq!(engine.num_cbits(), 4);
+        engine.run_circuit(&circuit).unwrap();
+        assert!(engine.cbit(3));
+    }
+
+    #[test]
+    fn test_qft_matches_dft_of_basis_state() {
+        use std::f64::consts::PI;
+
+        // |x⟩ на кубитах [1, 2, 3] при x = 5 переходит в Σ_y e^{2πi·5y/8}|y⟩/√8
+        let mut engine = QuantumEngine::with_backend(4, Backend::StateVector);
+        engine.x(1);
+        engine.x(3);
+        engine.qft(&[1, 2, 3]);
+        let state = engine.simulator().get_state();
+        for y in 0..8u64 {
+            let expected = Amplitude::from_polar(1.0 / 8f64.sqrt(), 2.0 * PI * (5 * y) as f64 / 8.0);
+            assert!((state.amplitude(y << 1) - expected).norm() < 1e-10);
+        }
+
+        engine.iqft(&[1, 2, 3]);
+        assert!((engine.simulator().get_state().amplitude(0b1010) - Amplitude::new(1.0, 0.0)).norm() < 1e-10);
+
+        assert!(engine.try_qft(&[0, 4]).is_err());
+        assert!(engine.try_iqft_with(&[1, 1], QftOptions::default().without_swaps()).is_err());
+    }
+}
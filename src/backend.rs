@@ -0,0 +1,339 @@
+//! Выбор бэкенда симуляции во время выполнения.
+//!
+//! Каждый бэкенд эффективен для своего класса задач: стабилизаторный — для
+//! схем Клиффорда на тысячах кубитов, разреженный — для почти классических
+//! регистров, MPS — для слабо запутанных состояний, плотные — для небольших
+//! регистров с произвольными гейтами. `Backend::auto` реализует адаптивную
+//! стратегию из плана развития (раздел 2.2): бэкенд выбирается по набору
+//! гейтов схемы и числу кубитов так, чтобы расход ресурсов был минимальным.
+//!
+//! Набор доступных бэкендов определяется признаками крейта: `quest` добавляет
+//! `Backend::QuEST`, `native` — матрицу плотности, стабилизаторный, MPS и
+//! разреженный бэкенды. Вектор состояния на чистом Rust доступен всегда.
+
+use crate::core::circuit::Circuit;
+use crate::core::quantum_simulator::AdvancedQuantumSimulator;
+use crate::core::state_vector::{self, StateVectorSimulator};
+use crate::error::TriadError;
+#[cfg(feature = "native")]
+use crate::core::circuit::Instruction;
+#[cfg(feature = "native")]
+use crate::core::density_matrix::{self, DensityMatrixSimulator};
+#[cfg(feature = "native")]
+use crate::core::gates::Gate;
+#[cfg(feature = "native")]
+use crate::core::mps::{self, MpsSimulator};
+#[cfg(feature = "native")]
+use crate::core::quantum_state::Amplitude;
+#[cfg(feature = "native")]
+use crate::core::sparse::{self, SparseSimulator};
+#[cfg(feature = "native")]
+use crate::core::stabilizer::{self, StabilizerSimulator};
+#[cfg(feature = "quest")]
+use crate::quest::{self, QuESTSimulator};
+
+/// Симулятор, используемый примерами и движком по умолчанию: QuEST при
+/// включенном признаке `quest`, иначе вектор состояния на чистом Rust.
+#[cfg(feature = "quest")]
+pub type DefaultSimulator = QuESTSimulator;
+
+/// Симулятор, используемый примерами и движком по умолчанию: QuEST при
+/// включенном признаке `quest`, иначе вектор состояния на чистом Rust.
+#[cfg(not(feature = "quest"))]
+pub type DefaultSimulator = StateVectorSimulator;
+
+/// Наибольшее число кубитов, для которого `Backend::auto` выбирает плотный
+/// вектор состояния (2^20 амплитуд занимают 16 МБ).
+pub const DENSE_QUBIT_LIMIT: usize = 20;
+
+/// Наибольшее число ветвящихся гейтов, при котором `Backend::auto` выбирает
+/// разреженный бэкенд: каждый такой гейт не более чем удваивает число амплитуд.
+#[cfg(feature = "native")]
+pub const SPARSE_BRANCHING_LIMIT: usize = 16;
+
+/// Бэкенд симуляции, используемый `QuantumEngine`.
+///
+/// По умолчанию используется QuEST при включенном признаке `quest`,
+/// иначе вектор состояния на чистом Rust.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Backend {
+    /// Симулятор QuEST.
+    #[cfg(feature = "quest")]
+    #[default]
+    QuEST,
+    /// Плотный вектор состояния на чистом Rust.
+    #[cfg_attr(not(feature = "quest"), default)]
+    StateVector,
+    /// Матрица плотности для смешанных состояний.
+    #[cfg(feature = "native")]
+    DensityMatrix,
+    /// Стабилизаторный симулятор (только гейты Клиффорда).
+    #[cfg(feature = "native")]
+    Stabilizer,
+    /// Разреженный вектор состояния.
+    #[cfg(feature = "native")]
+    Sparse,
+    /// Матричное произведение состояний с ограниченной размерностью связи.
+    #[cfg(feature = "native")]
+    Mps { max_bond_dimension: usize },
+}
+
+impl Backend {
+    /// Возвращает название бэкенда.
+    pub fn name(&self) -> &'static str {
+        match self {
+            #[cfg(feature = "quest")]
+            Backend::QuEST => "quest",
+            Backend::StateVector => "state_vector",
+            #[cfg(feature = "native")]
+            Backend::DensityMatrix => "density_matrix",
+            #[cfg(feature = "native")]
+            Backend::Stabilizer => "stabilizer",
+            #[cfg(feature = "native")]
+            Backend::Sparse => "sparse",
+            #[cfg(feature = "native")]
+            Backend::Mps { .. } => "mps",
+        }
+    }
+
+    /// Возвращает наибольшее число кубитов, поддерживаемое бэкендом.
+    pub fn max_qubits(&self) -> usize {
+        match self {
+            #[cfg(feature = "quest")]
+            Backend::QuEST => quest::MAX_QUBITS,
+            Backend::StateVector => state_vector::MAX_QUBITS,
+            #[cfg(feature = "native")]
+            Backend::DensityMatrix => density_matrix::MAX_QUBITS,
+            #[cfg(feature = "native")]
+            Backend::Stabilizer => stabilizer::MAX_QUBITS,
+            #[cfg(feature = "native")]
+            Backend::Sparse => sparse::MAX_QUBITS,
+            #[cfg(feature = "native")]
+            Backend::Mps { .. } => mps::MAX_QUBITS,
+        }
+    }
+
+    /// Создает симулятор этого бэкенда с указанным числом кубитов.
+    pub fn create(&self, num_qubits: usize) -> Result<Box<dyn AdvancedQuantumSimulator>, TriadError> {
+        Ok(match *self {
+            #[cfg(feature = "quest")]
+            Backend::QuEST => Box::new(QuESTSimulator::try_new(num_qubits)?),
+            Backend::StateVector => Box::new(StateVectorSimulator::try_new(num_qubits)?),
+            #[cfg(feature = "native")]
+            Backend::DensityMatrix => Box::new(DensityMatrixSimulator::try_new(num_qubits)?),
+            #[cfg(feature = "native")]
+            Backend::Stabilizer => Box::new(StabilizerSimulator::try_new(num_qubits)?),
+            #[cfg(feature = "native")]
+            Backend::Sparse => Box::new(SparseSimulator::try_new(num_qubits)?),
+            #[cfg(feature = "native")]
+            Backend::Mps { max_bond_dimension } => {
+                Box::new(MpsSimulator::try_with_max_bond_dimension(num_qubits, max_bond_dimension)?)
+            },
+        })
+    }
+
+    /// Выбирает бэкенд для схемы по ее набору гейтов и числу кубитов:
+    ///
+    /// 1. только гейты Клиффорда — стабилизаторный симулятор;
+    /// 2. не больше `DENSE_QUBIT_LIMIT` кубитов — плотный вектор состояния;
+    /// 3. не больше 64 кубитов и не больше `SPARSE_BRANCHING_LIMIT` ветвящихся
+    ///    гейтов (не являющихся перестановкой с фазами) — разреженный вектор;
+    /// 4. иначе — MPS с размерностью связи по умолчанию.
+    ///
+    /// Без признака `native` всегда возвращает `Backend::StateVector`.
+    #[cfg(feature = "native")]
+    pub fn auto(circuit: &Circuit) -> Backend {
+        let num_qubits = circuit.num_qubits();
+        let matrices: Vec<Vec<Amplitude>> = circuit.instructions().iter()
+            .filter_map(gate_matrix)
+            .collect();
+
+        if num_qubits <= stabilizer::MAX_QUBITS && matrices.iter().all(|m| stabilizer::is_clifford(m)) {
+            return Backend::Stabilizer;
+        }
+        if num_qubits <= DENSE_QUBIT_LIMIT {
+            return Backend::StateVector;
+        }
+
+        let branching = matrices.iter().filter(|m| is_branching(m)).count();
+        if num_qubits <= sparse::MAX_QUBITS && branching <= SPARSE_BRANCHING_LIMIT {
+            return Backend::Sparse;
+        }
+
+        Backend::Mps { max_bond_dimension: mps::DEFAULT_MAX_BOND_DIMENSION }
+    }
+
+    /// Выбирает бэкенд для схемы. Без признака `native` доступен только
+    /// вектор состояния.
+    #[cfg(not(feature = "native"))]
+    pub fn auto(_circuit: &Circuit) -> Backend {
+        Backend::StateVector
+    }
+}
+
+/// Возвращает матрицу гейта инструкции, в том числе под классическим условием.
+#[cfg(feature = "native")]
+fn gate_matrix(instruction: &Instruction) -> Option<Vec<Amplitude>> {
+    match instruction {
+        Instruction::Gate { gate, .. } => Some(gate.matrix()),
+        Instruction::Conditional { instruction, .. } => gate_matrix(instruction),
+        _ => None,
+    }
+}
+
+/// Проверяет, может ли гейт увеличить число ненулевых амплитуд, то есть
+/// содержит ли какой-либо столбец его матрицы больше одного ненулевого элемента.
+#[cfg(feature = "native")]
+fn is_branching(matrix: &[Amplitude]) -> bool {
+    let dim = (matrix.len() as f64).sqrt() as usize;
+    (0..dim).any(|col| (0..dim).filter(|&row| matrix[row * dim + col].norm_sqr() > 1e-24).count() > 1)
+}
+
+#[cfg(all(test, feature = "native"))]
+mod tests {
+    use super::*;
+    use crate::api::QuantumEngine;
+    use crate::core::gates::{Axis, CustomGate, RotationGate};
+    use num_complex::Complex64;
+
+    #[test]
+    fn test_auto_prefers_cheapest_backend() {
+        let mut clifford = Circuit::new(500, 1);
+        clifford.h(0).cnot(0, 499).s(3).cz(2, 3).swap(1, 2).measure(499, 0);
+        assert_eq!(Backend::auto(&clifford), Backend::Stabilizer);
+
+        let mut small = Circuit::new(5, 0);
+        small.h(0).t(0).cnot(0, 4);
+        assert_eq!(Backend::auto(&small), Backend::StateVector);
+
+        let mut ledger = Circuit::new(48, 0);
+        ledger.x(3).cnot(3, 40).t(40).ry(7, 0.3).cnot(7, 8);
+        assert_eq!(Backend::auto(&ledger), Backend::Sparse);
+
+        let mut wide = Circuit::new(100, 0);
+        for qubit in 0..99 {
+            wide.ry(qubit, 0.1).cnot(qubit, qubit + 1);
+        }
+        assert_eq!(Backend::auto(&wide), Backend::Mps { max_bond_dimension: mps::DEFAULT_MAX_BOND_DIMENSION });
+    }
+
+    #[test]
+    fn test_conditional_and_custom_gates_are_inspected() {
+        let mut circuit = Circuit::new(30, 1);
+        let rotation = RotationGate { axis: Axis::Y, angle: 0.2 };
+        circuit.h(0).measure(0, 0).c_if(&[0], 1, Instruction::gate(rotation, &[1]));
+        assert_eq!(Backend::auto(&circuit), Backend::Sparse);
+
+        let (zero, phase) = (Complex64::new(0.0, 0.0), Complex64::from_polar(1.0, 0.3));
+        let diagonal = CustomGate { matrix: vec![phase, zero, zero, phase.conj()], num_qubits: 1, name: "D".to_string() };
+        assert!(!is_branching(&diagonal.matrix()));
+    }
+
+    #[test]
+    fn test_engine_runs_on_chosen_backend() {
+        let mut circuit = Circuit::new(1000, 2);
+        circuit.h(0).cnot(0, 999).measure(0, 0).measure(999, 1);
+
+        let mut engine = QuantumEngine::for_circuit(&circuit).unwrap();
+        assert_eq!(engine.backend(), Backend::Stabilizer);
+        let bits = engine.run_circuit(&circuit).unwrap();
+        assert_eq!(bits[0], bits[1]);
+
+        let mut engine = QuantumEngine::with_backend(3, Backend::Sparse);
+        engine.create_ghz_state().unwrap();
+        assert!(engine.is_entangled(0, 2));
+        assert_eq!(engine.backend().name(), "sparse");
+    }
+
+    #[test]
+    fn test_multi_controlled_gates_agree_across_backends() {
+        let phase = Complex64::from_polar(1.0, 0.7);
+        let zero = Complex64::new(0.0, 0.0);
+        let backends = [Backend::StateVector, Backend::DensityMatrix, Backend::Sparse, Backend::Mps { max_bond_dimension: 16 }];
+
+        let probabilities: Vec<Vec<f64>> = backends.iter().map(|backend| {
+            let mut simulator = backend.create(5).unwrap();
+            for qubit in 0..4 {
+                simulator.hadamard(qubit);
+            }
+            simulator.toffoli(0, 3, 4);
+            simulator.fredkin(4, 1, 2);
+            simulator.controlled_ry(2, 0, 0.9);
+            simulator.multi_controlled_unitary(&[4, 0, 1], 2, &[zero, phase, phase, zero]);
+            simulator.mcz(&[1, 3], 4);
+            simulator.hadamard(4);
+            let state = simulator.get_state();
+            (0..32).map(|basis| state.probability(basis)).collect()
+        }).collect();
+
+        for other in &probabilities[1..] {
+            for (expected, actual) in probabilities[0].iter().zip(other) {
+                assert!((expected - actual).abs() < 1e-10);
+            }
+        }
+        assert!((probabilities[0].iter().sum::<f64>() - 1.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_seeded_measurements_agree_across_backends() {
+        let backends = [
+            Backend::StateVector,
+            Backend::DensityMatrix,
+            Backend::Stabilizer,
+            Backend::Sparse,
+            Backend::Mps { max_bond_dimension: 16 },
+        ];
+        let sequences: Vec<Vec<bool>> = backends.iter().map(|&backend| {
+            let mut engine = QuantumEngine::with_backend(5, backend);
+            engine.seed(2024);
+            let mut bits = Vec::new();
+            for _ in 0..8 {
+                engine.reset();
+                engine.hadamard(0);
+                engine.cnot(0, 1);
+                engine.hadamard(2);
+                engine.hadamard(4);
+                bits.extend((0..5).map(|qubit| engine.measure(qubit)));
+            }
+            bits
+        }).collect();
+
+        for sequence in &sequences[1..] {
+            assert_eq!(sequence, &sequences[0]);
+        }
+        // Измерения Белл-пары согласованы, а случайные биты не постоянны
+        assert!(sequences[0].chunks(5).all(|shot| shot[0] == shot[1] && !shot[3]));
+        assert!(sequences[0].iter().any(|&bit| bit) && sequences[0].iter().any(|&bit| !bit));
+    }
+
+    #[test]
+    fn test_seeded_snapshots_measure_reproducibly() {
+        let backends = [Backend::StateVector, Backend::DensityMatrix, Backend::Stabilizer, Backend::Sparse, Backend::Mps { max_bond_dimension: 16 }];
+        for backend in backends {
+            let measure_snapshot = || {
+                let mut simulator = backend.create(3).unwrap();
+                simulator.seed(7);
+                for qubit in 0..3 {
+                    simulator.hadamard(qubit);
+                }
+                let mut state = simulator.get_state();
+                (0..3).map(|qubit| state.measure(qubit)).collect::<Vec<bool>>()
+            };
+            assert_eq!(measure_snapshot(), measure_snapshot());
+        }
+    }
+
+    #[test]
+    fn test_create_checks_qubit_limits() {
+        for backend in [Backend::StateVector, Backend::Stabilizer, Backend::Sparse, Backend::Mps { max_bond_dimension: 8 }] {
+            let simulator = backend.create(4).unwrap();
+            assert_eq!(simulator.num_qubits(), 4);
+            assert_eq!(
+                backend.create(backend.max_qubits() + 1).err(),
+                Some(TriadError::TooManyQubits { requested: backend.max_qubits() + 1, max: backend.max_qubits() }),
+            );
+        }
+        assert!(Backend::Mps { max_bond_dimension: 0 }.create(2).is_err());
+    }
+}
@@ -0,0 +1,970 @@
+//! Промежуточное представление квантовых схем.
+//!
+//! Схема (`Circuit`) — это последовательность инструкций над гейтами из
+//! `core::gates`, измерений, барьеров и классически управляемых операций.
+//! Одна и та же схема может быть воспроизведена на любом бэкенде,
+//! реализующем `QuantumSimulator`.
+
+use crate::core::gates::{Axis, BasicGate, CustomGate, Gate, ParametricGate, RotationGate, TwoQubitGate, identity_matrix};
+use crate::core::qft::{QftOptions, qft_instructions};
+use crate::core::quantum_simulator::{QuantumSimulator, validate_qubits};
+use crate::core::quantum_state::Amplitude;
+use crate::core::state_vector::StateVector;
+use crate::error::TriadError;
+use std::collections::BTreeMap;
+
+/// Гейт, хранящийся в схеме.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CircuitGate {
+    /// Базовый однокубитовый гейт
+    Basic(BasicGate),
+    /// Гейт вращения
+    Rotation(RotationGate),
+    /// Двухкубитовый гейт
+    TwoQubit(TwoQubitGate),
+    /// Параметризованный гейт из стандартной библиотеки
+    Parametric(ParametricGate),
+    /// Пользовательский гейт с произвольной матрицей
+    Custom(CustomGate),
+}
+
+impl CircuitGate {
+    /// Возвращает гейт, управляемый дополнительными кубитами. Управляющие кубиты
+    /// предшествуют целевым; `polarity[i]` задает, при каком значении (1 или 0)
+    /// `i`-го управляющего кубита применяется исходный гейт.
+    pub fn controlled_with(&self, polarity: &[bool]) -> CircuitGate {
+        if polarity.iter().all(|&active| active) {
+            return self.controlled(polarity.len());
+        }
+
+        let target_dim = 1usize << self.num_qubits();
+        let dim = target_dim << polarity.len();
+        let active = control_pattern(polarity) * target_dim;
+        let matrix = self.matrix();
+
+        let mut controlled = identity_matrix(dim);
+        for row in 0..target_dim {
+            for col in 0..target_dim {
+                controlled[(active + row) * dim + active + col] = matrix[row * target_dim + col];
+            }
+        }
+
+        CircuitGate::Custom(CustomGate {
+            matrix: controlled,
+            num_qubits: polarity.len() + self.num_qubits(),
+            name: format!("{}{}", control_prefix(polarity), self.name()),
+        })
+    }
+
+    /// Возвращает фазовый множитель `e^{iθ}`, управляемый кубитами с полярностями
+    /// `polarity` (для одного управляющего кубита это фазовый гейт P(θ)).
+    pub fn controlled_phase(angle: f64, polarity: &[bool]) -> CircuitGate {
+        let dim = 1usize << polarity.len();
+        let active = control_pattern(polarity);
+
+        let mut matrix = identity_matrix(dim);
+        matrix[active * dim + active] = Amplitude::from_polar(1.0, angle);
+
+        CircuitGate::Custom(CustomGate {
+            matrix,
+            num_qubits: polarity.len(),
+            name: format!("{}gphase", control_prefix(polarity)),
+        })
+    }
+
+    /// Возвращает внутренний гейт как `&dyn Gate`.
+    fn as_gate(&self) -> &dyn Gate {
+        match self {
+            CircuitGate::Basic(gate) => gate,
+            CircuitGate::Rotation(gate) => gate,
+            CircuitGate::TwoQubit(gate) => gate,
+            CircuitGate::Parametric(gate) => gate,
+            CircuitGate::Custom(gate) => gate,
+        }
+    }
+}
+
+/// Возвращает индекс базисного состояния управляющих кубитов, при котором
+/// срабатывает управление (первый управляющий кубит — старший бит).
+fn control_pattern(polarity: &[bool]) -> usize {
+    polarity.iter().fold(0, |acc, &active| (acc << 1) | active as usize)
+}
+
+/// Возвращает префикс имени управляемого гейта: `c` или `nc` для каждого кубита.
+fn control_prefix(polarity: &[bool]) -> String {
+    polarity.iter().map(|&active| if active { "c" } else { "nc" }).collect()
+}
+
+impl Gate for CircuitGate {
+    fn matrix(&self) -> Vec<Amplitude> {
+        self.as_gate().matrix()
+    }
+
+    fn num_qubits(&self) -> usize {
+        self.as_gate().num_qubits()
+    }
+
+    fn name(&self) -> &str {
+        self.as_gate().name()
+    }
+
+    fn inverse(&self) -> CircuitGate {
+        self.as_gate().inverse()
+    }
+
+    fn controlled(&self, num_controls: usize) -> CircuitGate {
+        self.as_gate().controlled(num_controls)
+    }
+
+    fn power(&self, exponent: i32) -> CircuitGate {
+        self.as_gate().power(exponent)
+    }
+}
+
+impl From<BasicGate> for CircuitGate {
+    fn from(gate: BasicGate) -> Self {
+        CircuitGate::Basic(gate)
+    }
+}
+
+impl From<RotationGate> for CircuitGate {
+    fn from(gate: RotationGate) -> Self {
+        CircuitGate::Rotation(gate)
+    }
+}
+
+impl From<TwoQubitGate> for CircuitGate {
+    fn from(gate: TwoQubitGate) -> Self {
+        CircuitGate::TwoQubit(gate)
+    }
+}
+
+impl From<ParametricGate> for CircuitGate {
+    fn from(gate: ParametricGate) -> Self {
+        CircuitGate::Parametric(gate)
+    }
+}
+
+impl From<CustomGate> for CircuitGate {
+    fn from(gate: CustomGate) -> Self {
+        CircuitGate::Custom(gate)
+    }
+}
+
+/// Инструкция квантовой схемы.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Instruction {
+    /// Применение гейта к указанным кубитам
+    Gate { gate: CircuitGate, qubits: Vec<usize> },
+    /// Измерение кубита с записью результата в классический бит
+    Measure { qubit: usize, cbit: usize },
+    /// Сброс кубита в состояние |0⟩
+    Reset(usize),
+    /// Барьер: запрещает переупорядочивание операций через него
+    Barrier(Vec<usize>),
+    /// Операция, выполняемая только если классические биты `cbits`
+    /// (бит `i` значения соответствует `cbits[i]`) равны `value`
+    Conditional { cbits: Vec<usize>, value: u64, instruction: Box<Instruction> },
+}
+
+impl Instruction {
+    /// Создает инструкцию применения гейта.
+    pub fn gate(gate: impl Into<CircuitGate>, qubits: &[usize]) -> Self {
+        Instruction::Gate { gate: gate.into(), qubits: qubits.to_vec() }
+    }
+
+    /// Возвращает кубиты, задействованные инструкцией.
+    pub fn qubits(&self) -> Vec<usize> {
+        match self {
+            Instruction::Gate { qubits, .. } => qubits.clone(),
+            Instruction::Measure { qubit, .. } => vec![*qubit],
+            Instruction::Reset(qubit) => vec![*qubit],
+            Instruction::Barrier(qubits) => qubits.clone(),
+            Instruction::Conditional { instruction, .. } => instruction.qubits(),
+        }
+    }
+
+    /// Возвращает классические биты, задействованные инструкцией.
+    pub fn cbits(&self) -> Vec<usize> {
+        match self {
+            Instruction::Gate { .. } | Instruction::Reset(_) | Instruction::Barrier(_) => Vec::new(),
+            Instruction::Measure { cbit, .. } => vec![*cbit],
+            Instruction::Conditional { cbits, instruction, .. } => {
+                let mut all = cbits.clone();
+                all.extend(instruction.cbits());
+                all
+            },
+        }
+    }
+
+    /// Возвращает копию инструкции с переназначенными кубитами и классическими битами.
+    fn remap(&self, qubit_map: &[usize], cbit_map: &[usize]) -> Instruction {
+        match self {
+            Instruction::Gate { gate, qubits } => Instruction::Gate {
+                gate: gate.clone(),
+                qubits: qubits.iter().map(|&q| qubit_map[q]).collect(),
+            },
+            Instruction::Measure { qubit, cbit } => Instruction::Measure {
+                qubit: qubit_map[*qubit],
+                cbit: cbit_map[*cbit],
+            },
+            Instruction::Reset(qubit) => Instruction::Reset(qubit_map[*qubit]),
+            Instruction::Barrier(qubits) => Instruction::Barrier(qubits.iter().map(|&q| qubit_map[q]).collect()),
+            Instruction::Conditional { cbits, value, instruction } => Instruction::Conditional {
+                cbits: cbits.iter().map(|&c| cbit_map[c]).collect(),
+                value: *value,
+                instruction: Box::new(instruction.remap(qubit_map, cbit_map)),
+            },
+        }
+    }
+}
+
+/// Именованный регистр (квантовый или классический), занимающий
+/// непрерывный диапазон индексов схемы.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Register {
+    /// Имя регистра.
+    pub name: String,
+    /// Число битов в регистре.
+    pub size: usize,
+}
+
+impl Register {
+    /// Создает регистр с заданным именем и размером.
+    pub fn new(name: &str, size: usize) -> Self {
+        Self { name: name.to_string(), size }
+    }
+}
+
+/// Квантовая схема: упорядоченный список инструкций над регистром
+/// из `num_qubits` кубитов и `num_cbits` классических битов.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "RawCircuit"))]
+pub struct Circuit {
+    /// Число кубитов в схеме.
+    num_qubits: usize,
+    /// Число классических битов в схеме.
+    num_cbits: usize,
+    /// Именованные квантовые регистры (пусто — один безымянный регистр).
+    qregs: Vec<Register>,
+    /// Именованные классические регистры (пусто — один безымянный регистр).
+    cregs: Vec<Register>,
+    /// Инструкции в порядке выполнения.
+    instructions: Vec<Instruction>,
+}
+
+impl Circuit {
+    /// Создает пустую схему с указанным числом кубитов и классических битов.
+    pub fn new(num_qubits: usize, num_cbits: usize) -> Self {
+        Self {
+            num_qubits,
+            num_cbits,
+            qregs: Vec::new(),
+            cregs: Vec::new(),
+            instructions: Vec::new(),
+        }
+    }
+
+    /// Создает пустую схему из именованных регистров. Регистры располагаются
+    /// подряд в порядке объявления: первый занимает младшие индексы.
+    pub fn with_registers(qregs: Vec<Register>, cregs: Vec<Register>) -> Self {
+        Self {
+            num_qubits: qregs.iter().map(|r| r.size).sum(),
+            num_cbits: cregs.iter().map(|r| r.size).sum(),
+            qregs,
+            cregs,
+            instructions: Vec::new(),
+        }
+    }
+
+    /// Возвращает пустую схему с теми же кубитами, классическими битами и регистрами.
+    pub fn empty_like(&self) -> Circuit {
+        Self {
+            num_qubits: self.num_qubits,
+            num_cbits: self.num_cbits,
+            qregs: self.qregs.clone(),
+            cregs: self.cregs.clone(),
+            instructions: Vec::new(),
+        }
+    }
+
+    /// Возвращает именованные квантовые регистры схемы.
+    pub fn qregs(&self) -> &[Register] {
+        &self.qregs
+    }
+
+    /// Возвращает именованные классические регистры схемы.
+    pub fn cregs(&self) -> &[Register] {
+        &self.cregs
+    }
+
+    /// Возвращает число кубитов в схеме.
+    pub fn num_qubits(&self) -> usize {
+        self.num_qubits
+    }
+
+    /// Возвращает число классических битов в схеме.
+    pub fn num_cbits(&self) -> usize {
+        self.num_cbits
+    }
+
+    /// Возвращает инструкции схемы.
+    pub fn instructions(&self) -> &[Instruction] {
+        &self.instructions
+    }
+
+    /// Возвращает число инструкций в схеме.
+    pub fn len(&self) -> usize {
+        self.instructions.len()
+    }
+
+    /// Проверяет, пуста ли схема.
+    pub fn is_empty(&self) -> bool {
+        self.instructions.is_empty()
+    }
+
+    /// Проверяет инструкцию относительно размеров схемы.
+    fn validate(&self, instruction: &Instruction) -> Result<(), TriadError> {
+        validate_instruction(instruction, self.num_qubits, self.num_cbits)
+    }
+
+    /// Добавляет инструкцию, возвращая ошибку для некорректных индексов.
+    pub fn try_push(&mut self, instruction: Instruction) -> Result<&mut Self, TriadError> {
+        self.validate(&instruction)?;
+        self.instructions.push(instruction);
+        Ok(self)
+    }
+
+    /// Добавляет инструкцию в конец схемы.
+    /// Паникует при некорректных индексах кубитов или классических битов.
+    pub fn push(&mut self, instruction: Instruction) -> &mut Self {
+        if let Err(err) = self.validate(&instruction) {
+            panic!("{}", err);
+        }
+        self.instructions.push(instruction);
+        self
+    }
+
+    // === Построитель схемы ===
+
+    /// Добавляет произвольный гейт.
+    pub fn gate(&mut self, gate: impl Into<CircuitGate>, qubits: &[usize]) -> &mut Self {
+        self.push(Instruction::gate(gate, qubits))
+    }
+
+    /// Добавляет гейт Адамара.
+    pub fn h(&mut self, qubit: usize) -> &mut Self {
+        self.gate(BasicGate::Hadamard, &[qubit])
+    }
+
+    /// Добавляет X-гейт.
+    pub fn x(&mut self, qubit: usize) -> &mut Self {
+        self.gate(BasicGate::PauliX, &[qubit])
+    }
+
+    /// Добавляет Y-гейт.
+    pub fn y(&mut self, qubit: usize) -> &mut Self {
+        self.gate(BasicGate::PauliY, &[qubit])
+    }
+
+    /// Добавляет Z-гейт.
+    pub fn z(&mut self, qubit: usize) -> &mut Self {
+        self.gate(BasicGate::PauliZ, &[qubit])
+    }
+
+    /// Добавляет фазовый S-гейт.
+    pub fn s(&mut self, qubit: usize) -> &mut Self {
+        self.gate(BasicGate::S, &[qubit])
+    }
+
+    /// Добавляет T-гейт.
+    pub fn t(&mut self, qubit: usize) -> &mut Self {
+        self.gate(BasicGate::T, &[qubit])
+    }
+
+    /// Добавляет вращение вокруг оси X.
+    pub fn rx(&mut self, qubit: usize, angle: f64) -> &mut Self {
+        self.gate(RotationGate { axis: Axis::X, angle }, &[qubit])
+    }
+
+    /// Добавляет вращение вокруг оси Y.
+    pub fn ry(&mut self, qubit: usize, angle: f64) -> &mut Self {
+        self.gate(RotationGate { axis: Axis::Y, angle }, &[qubit])
+    }
+
+    /// Добавляет вращение вокруг оси Z.
+    pub fn rz(&mut self, qubit: usize, angle: f64) -> &mut Self {
+        self.gate(RotationGate { axis: Axis::Z, angle }, &[qubit])
+    }
+
+    /// Добавляет CNOT-гейт.
+    pub fn cnot(&mut self, control: usize, target: usize) -> &mut Self {
+        self.gate(TwoQubitGate::CNOT, &[control, target])
+    }
+
+    /// Добавляет контролируемый Z-гейт.
+    pub fn cz(&mut self, control: usize, target: usize) -> &mut Self {
+        self.gate(TwoQubitGate::CZ, &[control, target])
+    }
+
+    /// Добавляет SWAP-гейт.
+    pub fn swap(&mut self, qubit1: usize, qubit2: usize) -> &mut Self {
+        self.gate(TwoQubitGate::SWAP, &[qubit1, qubit2])
+    }
+
+    /// Добавляет управляемый фазовый гейт CPhase(θ).
+    pub fn cphase(&mut self, control: usize, target: usize, theta: f64) -> &mut Self {
+        self.gate(ParametricGate::CPhase { theta }, &[control, target])
+    }
+
+    /// Добавляет квантовое преобразование Фурье на кубитах `qubits`
+    /// (кубит `qubits[0]` — младший бит).
+    pub fn qft(&mut self, qubits: &[usize]) -> &mut Self {
+        self.qft_with(qubits, QftOptions::default())
+    }
+
+    /// Добавляет обратное квантовое преобразование Фурье на кубитах `qubits`.
+    pub fn iqft(&mut self, qubits: &[usize]) -> &mut Self {
+        self.iqft_with(qubits, QftOptions::default())
+    }
+
+    /// Добавляет QFT с указанной степенью приближения и обработкой SWAP-гейтов.
+    pub fn qft_with(&mut self, qubits: &[usize], options: QftOptions) -> &mut Self {
+        for instruction in qft_instructions(qubits, options, false) {
+            self.push(instruction);
+        }
+        self
+    }
+
+    /// Добавляет обратное QFT с указанными параметрами.
+    pub fn iqft_with(&mut self, qubits: &[usize], options: QftOptions) -> &mut Self {
+        for instruction in qft_instructions(qubits, options, true) {
+            self.push(instruction);
+        }
+        self
+    }
+
+    /// Добавляет измерение кубита с записью в классический бит.
+    pub fn measure(&mut self, qubit: usize, cbit: usize) -> &mut Self {
+        self.push(Instruction::Measure { qubit, cbit })
+    }
+
+    /// Добавляет сброс кубита в состояние |0⟩.
+    pub fn reset(&mut self, qubit: usize) -> &mut Self {
+        self.push(Instruction::Reset(qubit))
+    }
+
+    /// Добавляет барьер на указанных кубитах.
+    pub fn barrier(&mut self, qubits: &[usize]) -> &mut Self {
+        self.push(Instruction::Barrier(qubits.to_vec()))
+    }
+
+    /// Добавляет барьер на всех кубитах схемы.
+    pub fn barrier_all(&mut self) -> &mut Self {
+        let qubits: Vec<usize> = (0..self.num_qubits).collect();
+        self.barrier(&qubits)
+    }
+
+    /// Добавляет инструкцию, выполняемую только если классические биты `cbits`
+    /// равны `value` (бит `i` значения соответствует `cbits[i]`).
+    pub fn c_if(&mut self, cbits: &[usize], value: u64, instruction: Instruction) -> &mut Self {
+        self.push(Instruction::Conditional {
+            cbits: cbits.to_vec(),
+            value,
+            instruction: Box::new(instruction),
+        })
+    }
+
+    // === Анализ схемы ===
+
+    /// Вычисляет глубину схемы: число слоев, в которых операции
+    /// над общими кубитами или классическими битами выполняются последовательно.
+    /// Барьеры синхронизируют кубиты, но не образуют отдельного слоя.
+    pub fn depth(&self) -> usize {
+        let mut qubit_depth = vec![0usize; self.num_qubits];
+        let mut cbit_depth = vec![0usize; self.num_cbits];
+
+        for instruction in &self.instructions {
+            let qubits = instruction.qubits();
+            let cbits = instruction.cbits();
+
+            let start = qubits.iter().map(|&q| qubit_depth[q])
+                .chain(cbits.iter().map(|&c| cbit_depth[c]))
+                .max()
+                .unwrap_or(0);
+
+            let end = match instruction {
+                Instruction::Barrier(_) => start,
+                _ => start + 1,
+            };
+
+            for q in qubits {
+                qubit_depth[q] = end;
+            }
+            for c in cbits {
+                cbit_depth[c] = end;
+            }
+        }
+
+        qubit_depth.into_iter().chain(cbit_depth).max().unwrap_or(0)
+    }
+
+    /// Подсчитывает число гейтов каждого типа (включая классически управляемые).
+    pub fn gate_counts(&self) -> BTreeMap<String, usize> {
+        fn count(instruction: &Instruction, counts: &mut BTreeMap<String, usize>) {
+            match instruction {
+                Instruction::Gate { gate, .. } => *counts.entry(gate.name().to_string()).or_insert(0) += 1,
+                Instruction::Conditional { instruction, .. } => count(instruction, counts),
+                Instruction::Measure { .. } | Instruction::Reset(_) | Instruction::Barrier(_) => {},
+            }
+        }
+
+        let mut counts = BTreeMap::new();
+        for instruction in &self.instructions {
+            count(instruction, &mut counts);
+        }
+        counts
+    }
+
+    // === Преобразования схемы ===
+
+    /// Строит обратную схему (U†): гейты в обратном порядке, каждый заменен обратным.
+    /// Возвращает ошибку, если схема содержит измерения или классические условия.
+    pub fn inverse(&self) -> Result<Circuit, TriadError> {
+        let mut inverse = self.clone();
+        inverse.instructions.clear();
+
+        for instruction in self.instructions.iter().rev() {
+            let inverted = match instruction {
+                Instruction::Gate { gate, qubits } => Instruction::Gate { gate: gate.inverse(), qubits: qubits.clone() },
+                Instruction::Barrier(qubits) => Instruction::Barrier(qubits.clone()),
+                Instruction::Measure { .. } | Instruction::Reset(_) | Instruction::Conditional { .. } => {
+                    return Err(TriadError::InvalidArgument(
+                        "невозможно обратить схему с измерениями, сбросами или классическими условиями".to_string()));
+                },
+            };
+            inverse.instructions.push(inverted);
+        }
+
+        Ok(inverse)
+    }
+
+    /// Возвращает новую схему: эта схема, за которой следует `other`
+    /// на тех же индексах кубитов и классических битов.
+    pub fn compose(&self, other: &Circuit) -> Result<Circuit, TriadError> {
+        let qubits: Vec<usize> = (0..other.num_qubits).collect();
+        let cbits: Vec<usize> = (0..other.num_cbits).collect();
+        self.compose_mapped(other, &qubits, &cbits)
+    }
+
+    /// Возвращает новую схему: эта схема, за которой следует `other`, где кубит `i`
+    /// схемы `other` отображается в `qubits[i]`, а классический бит `j` — в `cbits[j]`.
+    pub fn compose_mapped(&self, other: &Circuit, qubits: &[usize], cbits: &[usize]) -> Result<Circuit, TriadError> {
+        if qubits.len() != other.num_qubits || cbits.len() != other.num_cbits {
+            return Err(TriadError::InvalidArgument(format!(
+                "отображение должно содержать {} кубитов и {} классических битов",
+                other.num_qubits, other.num_cbits)));
+        }
+        validate_qubits(qubits, self.num_qubits)?;
+        validate_cbits(cbits, self.num_cbits)?;
+
+        let mut composed = self.clone();
+        for instruction in &other.instructions {
+            composed.instructions.push(instruction.remap(qubits, cbits));
+        }
+
+        Ok(composed)
+    }
+
+    /// Вычисляет унитарную матрицу схемы (2^n x 2^n по строкам, кубит `k` —
+    /// бит `k` индекса, как в векторе состояния). Барьеры пропускаются;
+    /// для схем с измерениями, сбросами или классическими условиями
+    /// возвращает ошибку. Предназначено для небольших схем.
+    pub fn unitary(&self) -> Result<Vec<Amplitude>, TriadError> {
+        let dim = 1usize << self.num_qubits;
+        let mut columns: Vec<StateVector> = (0..dim)
+            .map(|column| {
+                let mut amplitudes = vec![Amplitude::new(0.0, 0.0); dim];
+                amplitudes[column] = Amplitude::new(1.0, 0.0);
+                StateVector::from_amplitudes(amplitudes)
+            })
+            .collect();
+
+        for instruction in &self.instructions {
+            match instruction {
+                Instruction::Gate { gate, qubits } => {
+                    let matrix = gate.matrix();
+                    for column in &mut columns {
+                        column.apply_multi_qubit(qubits, &matrix);
+                    }
+                },
+                Instruction::Barrier(_) => {},
+                Instruction::Measure { .. } | Instruction::Reset(_) | Instruction::Conditional { .. } => {
+                    return Err(TriadError::InvalidArgument(
+                        "унитарная матрица не определена для схемы с измерениями, сбросами или классическими условиями".to_string()));
+                },
+            }
+        }
+
+        let mut unitary = vec![Amplitude::new(0.0, 0.0); dim * dim];
+        for (col, column) in columns.iter().enumerate() {
+            for (row, &amplitude) in column.amplitudes().iter().enumerate() {
+                unitary[row * dim + col] = amplitude;
+            }
+        }
+        Ok(unitary)
+    }
+
+    // === Выполнение ===
+
+    /// Выполняет схему на симуляторе, начиная с его текущего состояния,
+    /// и возвращает значения классических битов.
+    pub fn run_on<S: QuantumSimulator + ?Sized>(&self, simulator: &mut S) -> Result<Vec<bool>, TriadError> {
+        if simulator.num_qubits() < self.num_qubits {
+            return Err(TriadError::NotEnoughQubits {
+                required: self.num_qubits,
+                available: simulator.num_qubits(),
+            });
+        }
+
+        let mut cbits = vec![false; self.num_cbits];
+        for instruction in &self.instructions {
+            execute(instruction, simulator, &mut cbits)?;
+        }
+
+        Ok(cbits)
+    }
+}
+
+/// Схема в сериализованном виде, еще не прошедшая проверку.
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct RawCircuit {
+    num_qubits: usize,
+    num_cbits: usize,
+    qregs: Vec<Register>,
+    cregs: Vec<Register>,
+    instructions: Vec<Instruction>,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<RawCircuit> for Circuit {
+    type Error = TriadError;
+
+    /// Восстанавливает схему, проверяя регистры и инструкции так же, как
+    /// `try_push`; матрицы пользовательских гейтов проверяются на унитарность.
+    fn try_from(raw: RawCircuit) -> Result<Self, TriadError> {
+        for (registers, size) in [(&raw.qregs, raw.num_qubits), (&raw.cregs, raw.num_cbits)] {
+            let total = registers.iter().try_fold(0usize, |acc, r| acc.checked_add(r.size));
+            if !registers.is_empty() && total != Some(size) {
+                return Err(TriadError::InvalidArgument(format!(
+                    "суммарный размер регистров схемы не равен {}", size)));
+            }
+        }
+
+        let mut circuit = Circuit::new(raw.num_qubits, raw.num_cbits);
+        circuit.qregs = raw.qregs;
+        circuit.cregs = raw.cregs;
+        for instruction in raw.instructions {
+            circuit.validate(&instruction)?;
+            check_custom_gates(&instruction)?;
+            circuit.instructions.push(instruction);
+        }
+        Ok(circuit)
+    }
+}
+
+/// Проверяет унитарность матриц пользовательских гейтов инструкции.
+#[cfg(feature = "serde")]
+fn check_custom_gates(instruction: &Instruction) -> Result<(), TriadError> {
+    match instruction {
+        Instruction::Gate { gate: CircuitGate::Custom(gate), .. } => {
+            CustomGate::try_new(gate.matrix.clone(), gate.num_qubits, &gate.name).map(|_| ())
+        },
+        Instruction::Conditional { instruction, .. } => check_custom_gates(instruction),
+        _ => Ok(()),
+    }
+}
+
+/// Проверяет индексы кубитов и классических битов инструкции
+/// для регистра из `num_qubits` кубитов и `num_cbits` классических битов.
+pub(crate) fn validate_instruction(instruction: &Instruction, num_qubits: usize, num_cbits: usize) -> Result<(), TriadError> {
+    match instruction {
+        Instruction::Gate { gate, qubits } => {
+            if gate.num_qubits() != qubits.len() {
+                return Err(TriadError::InvalidArgument(format!(
+                    "гейт {} действует на {} кубит(ов), но указано {} целевых кубитов",
+                    gate.name(), gate.num_qubits(), qubits.len())));
+            }
+            let matrix = gate.matrix();
+            let arity = gate.num_qubits();
+            if arity == 0 || arity >= usize::BITS as usize / 2 || matrix.len() != 1 << (2 * arity) {
+                return Err(TriadError::InvalidArgument(format!(
+                    "матрица гейта {} не соответствует {} кубит(ам)", gate.name(), arity)));
+            }
+            // Библиотечные гейты унитарны при любых конечных параметрах, поэтому
+            // достаточно отсечь бесконечности и NaN, не умножая матрицы
+            if matrix.iter().any(|m| !m.re.is_finite() || !m.im.is_finite()) {
+                return Err(TriadError::NonUnitary(gate.name().to_string()));
+            }
+            validate_qubits(qubits, num_qubits)
+        },
+        Instruction::Measure { qubit, cbit } => {
+            validate_qubits(&[*qubit], num_qubits)?;
+            validate_cbits(&[*cbit], num_cbits)
+        },
+        Instruction::Reset(qubit) => validate_qubits(&[*qubit], num_qubits),
+        Instruction::Barrier(qubits) => validate_qubits(qubits, num_qubits),
+        Instruction::Conditional { cbits, value, instruction } => {
+            validate_cbits(cbits, num_cbits)?;
+            // Значение условия хранится в u64
+            if cbits.len() > 64 {
+                return Err(TriadError::InvalidArgument(format!(
+                    "условие на {} классических бит не помещается в 64 бита", cbits.len())));
+            }
+            if cbits.len() < 64 && value >> cbits.len() != 0 {
+                return Err(TriadError::InvalidArgument(format!(
+                    "значение {:b} не помещается в {} классических бит", value, cbits.len())));
+            }
+            validate_instruction(instruction, num_qubits, num_cbits)
+        },
+    }
+}
+
+/// Проверяет индексы классических битов.
+fn validate_cbits(cbits: &[usize], num_cbits: usize) -> Result<(), TriadError> {
+    for &cbit in cbits {
+        if cbit >= num_cbits {
+            return Err(TriadError::InvalidArgument(format!(
+                "индекс классического бита выходит за пределы: {} (классических битов: {})", cbit, num_cbits)));
+        }
+    }
+    Ok(())
+}
+
+/// Выполняет одну инструкцию на симуляторе, записывая результаты
+/// измерений в `cbits`. Индексы должны быть проверены заранее.
+pub(crate) fn execute<S: QuantumSimulator + ?Sized>(instruction: &Instruction, simulator: &mut S, cbits: &mut [bool]) -> Result<(), TriadError> {
+    match instruction {
+        Instruction::Gate { gate, qubits } => match (gate, qubits.as_slice()) {
+            // Используем встроенные операции симулятора там, где они есть
+            (CircuitGate::Basic(BasicGate::Hadamard), [q]) => simulator.try_hadamard(*q),
+            (CircuitGate::Basic(BasicGate::PauliX), [q]) => simulator.try_x(*q),
+            (CircuitGate::Basic(BasicGate::PauliY), [q]) => simulator.try_y(*q),
+            (CircuitGate::Basic(BasicGate::PauliZ), [q]) => simulator.try_z(*q),
+            (CircuitGate::TwoQubit(TwoQubitGate::CNOT), [c, t]) => simulator.try_cnot(*c, *t),
+            _ => simulator.try_apply_gate(gate, qubits),
+        },
+        Instruction::Measure { qubit, cbit } => {
+            cbits[*cbit] = simulator.try_measure(*qubit)?;
+            Ok(())
+        },
+        Instruction::Reset(qubit) => simulator.try_reset_qubit(*qubit),
+        Instruction::Barrier(_) => Ok(()),
+        Instruction::Conditional { cbits: condition, value, instruction } => {
+            let actual = condition.iter()
+                .enumerate()
+                .fold(0u64, |acc, (i, &c)| if cbits[c] { acc | (1 << i) } else { acc });
+
+            if actual == *value {
+                execute(instruction, simulator, cbits)
+            } else {
+                Ok(())
+            }
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::state_vector::StateVectorSimulator;
+    use std::f64::consts::PI;
+
+    fn bell_circuit() -> Circuit {
+        let mut circuit = Circuit::new(2, 2);
+        circuit.h(0).cnot(0, 1);
+        circuit
+    }
+
+    #[test]
+    fn test_builder_depth_and_counts() {
+        let mut circuit = Circuit::new(3, 3);
+        circuit.h(0).h(1).cnot(0, 1).t(2).barrier_all().measure(0, 0).measure(1, 1);
+
+        assert_eq!(circuit.len(), 7);
+        assert_eq!(circuit.depth(), 3);
+
+        let counts = circuit.gate_counts();
+        assert_eq!(counts.get("Hadamard"), Some(&2));
+        assert_eq!(counts.get("CNOT"), Some(&1));
+        assert_eq!(counts.get("T"), Some(&1));
+        assert_eq!(counts.get("measure"), None);
+    }
+
+    #[test]
+    fn test_run_bell_circuit() {
+        for _ in 0..10 {
+            let mut circuit = bell_circuit();
+            circuit.measure(0, 0).measure(1, 1);
+
+            let mut simulator = StateVectorSimulator::new(2);
+            let result = circuit.run_on(&mut simulator).unwrap();
+            assert_eq!(result[0], result[1]);
+        }
+    }
+
+    #[test]
+    fn test_inverse_restores_initial_state() {
+        let mut circuit = Circuit::new(3, 0);
+        circuit.h(0).s(0).t(1).rx(2, 0.3).ry(1, 1.1).cnot(0, 2).swap(1, 2).rz(0, PI / 3.0);
+
+        let identity = circuit.compose(&circuit.inverse().unwrap()).unwrap();
+        let mut simulator = StateVectorSimulator::new(3);
+        identity.run_on(&mut simulator).unwrap();
+
+        assert!((simulator.get_state().probability(0) - 1.0).abs() < 1e-10);
+        assert_eq!(circuit.inverse().unwrap().gate_counts().get("S†"), Some(&1));
+    }
+
+    #[test]
+    fn test_controlled_gates() {
+        assert!(matches!(CircuitGate::Basic(BasicGate::PauliX).controlled_with(&[true]), CircuitGate::TwoQubit(TwoQubitGate::CNOT)));
+
+        // Тоффоли с отрицательным управлением по кубиту 0: X на кубите 2, если q0 = 0 и q1 = 1
+        let toffoli = CircuitGate::Basic(BasicGate::PauliX).controlled_with(&[false, true]);
+        assert_eq!(toffoli.name(), "nccPauliX");
+
+        let mut circuit = Circuit::new(3, 0);
+        circuit.x(1).gate(toffoli, &[0, 1, 2]);
+        let mut simulator = StateVectorSimulator::new(3);
+        circuit.run_on(&mut simulator).unwrap();
+        assert!((simulator.get_state().probability(0b110) - 1.0).abs() < 1e-10);
+
+        let phase = CircuitGate::controlled_phase(PI / 2.0, &[true]);
+        assert!((phase.matrix()[3] - Amplitude::new(0.0, 1.0)).norm() < 1e-12);
+    }
+
+    #[test]
+    fn test_unitary_matches_simulation() {
+        let mut circuit = Circuit::new(2, 1);
+        circuit.x(0).cnot(0, 1).ry(1, 0.6);
+
+        // Столбец j — образ базисного состояния |j⟩, кубит 0 — младший бит
+        let unitary = circuit.unitary().unwrap();
+        let first_column = |row: usize| unitary[row * 4];
+        assert!((first_column(0b01) - Amplitude::new(-0.3f64.sin(), 0.0)).norm() < 1e-12);
+        assert!((first_column(0b11) - Amplitude::new(0.3f64.cos(), 0.0)).norm() < 1e-12);
+
+        circuit.measure(0, 0);
+        assert!(circuit.unitary().is_err());
+    }
+
+    #[test]
+    fn test_reset_returns_qubit_to_zero() {
+        let mut circuit = Circuit::new(2, 0);
+        circuit.h(0).cnot(0, 1).reset(0).reset(1);
+
+        let mut simulator = StateVectorSimulator::new(2);
+        circuit.run_on(&mut simulator).unwrap();
+        assert!((simulator.get_state().probability(0) - 1.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_inverse_rejects_measurement() {
+        let mut circuit = bell_circuit();
+        circuit.measure(0, 0);
+        assert!(matches!(circuit.inverse(), Err(TriadError::InvalidArgument(_))));
+    }
+
+    #[test]
+    fn test_compose_mapped() {
+        let mut big = Circuit::new(4, 2);
+        big.x(0);
+        let composed = big.compose_mapped(&bell_circuit(), &[2, 3], &[0, 1]).unwrap();
+
+        assert_eq!(composed.len(), 3);
+        assert_eq!(composed.instructions()[2].qubits(), vec![2, 3]);
+        assert!(big.compose_mapped(&bell_circuit(), &[2, 2], &[0, 1]).is_err());
+    }
+
+    #[test]
+    fn test_classical_feed_forward_teleportation() {
+        // Телепортация |1⟩ с кубита 0 на кубит 2 с классической коррекцией
+        for _ in 0..10 {
+            let mut circuit = Circuit::new(3, 2);
+            circuit.x(0)
+                .h(1).cnot(1, 2)
+                .cnot(0, 1).h(0)
+                .measure(0, 0).measure(1, 1)
+                .c_if(&[1], 1, Instruction::gate(BasicGate::PauliX, &[2]))
+                .c_if(&[0], 1, Instruction::gate(BasicGate::PauliZ, &[2]));
+
+            let mut simulator = StateVectorSimulator::new(3);
+            circuit.run_on(&mut simulator).unwrap();
+            assert!((simulator.probability_of_outcome(2, true) - 1.0).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_invalid_instructions_are_rejected() {
+        let mut circuit = Circuit::new(2, 1);
+        assert!(circuit.try_push(Instruction::gate(BasicGate::Hadamard, &[2])).is_err());
+        assert!(circuit.try_push(Instruction::Measure { qubit: 0, cbit: 1 }).is_err());
+        assert!(circuit.try_push(Instruction::gate(TwoQubitGate::CNOT, &[0])).is_err());
+        let infinite = RotationGate { axis: Axis::Z, angle: f64::INFINITY };
+        assert!(matches!(circuit.try_push(Instruction::gate(infinite, &[0])), Err(TriadError::NonUnitary(_))));
+        assert!(circuit.is_empty());
+
+        let mut simulator = StateVectorSimulator::new(1);
+        assert!(matches!(bell_circuit().run_on(&mut simulator), Err(TriadError::NotEnoughQubits { .. })));
+    }
+
+    #[test]
+    fn test_condition_wider_than_64_bits_is_rejected() {
+        let mut circuit = Circuit::new(1, 70);
+        let wide: Vec<usize> = (0..70).collect();
+        let conditional = |cbits: &[usize]| Instruction::Conditional {
+            cbits: cbits.to_vec(),
+            value: 0,
+            instruction: Box::new(Instruction::gate(BasicGate::PauliX, &[0])),
+        };
+        assert!(matches!(circuit.try_push(conditional(&wide)), Err(TriadError::InvalidArgument(_))));
+        assert!(circuit.try_push(conditional(&wide[..64])).is_ok());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let mut circuit = bell_circuit();
+        circuit.rx(1, 0.25).measure(0, 0)
+            .c_if(&[0], 1, Instruction::gate(CircuitGate::controlled_phase(PI, &[true, true]), &[0, 1]));
+
+        let json = serde_json::to_string(&circuit).unwrap();
+        let restored: Circuit = serde_json::from_str(&json).unwrap();
+        assert_eq!(serde_json::to_string(&restored).unwrap(), json);
+        assert_eq!(restored.gate_counts(), circuit.gate_counts());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_deserialization_validates_instructions() {
+        let mut circuit = Circuit::with_registers(vec![Register::new("q", 2)], vec![Register::new("c", 1)]);
+        circuit.measure(0, 0).gate(CustomGate::new(BasicGate::PauliX.matrix(), 1, "g"), &[1]);
+        let json = serde_json::to_string(&circuit).unwrap();
+        assert!(serde_json::from_str::<Circuit>(&json).is_ok());
+
+        let tampered = [
+            json.replace("\"cbit\":0", "\"cbit\":7"),
+            json.replace("[1.0,0.0]", "[2.0,0.0]"),
+            json.replace("\"size\":2", "\"size\":3"),
+        ];
+        for json in tampered {
+            assert!(serde_json::from_str::<Circuit>(&json).is_err(), "{}", json);
+        }
+    }
+}
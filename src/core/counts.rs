@@ -0,0 +1,373 @@
+//! Статистика многократных измерений (выборок) квантового состояния.
+//!
+//! `Counts` хранит число выборок для каждой битовой строки. Бит `i` строки
+//! соответствует `i`-му измеренному кубиту; при выводе старший бит пишется
+//! первым, как в OpenQASM.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+use rand::Rng;
+
+use crate::error::TriadError;
+
+/// Наибольшая длина битовых строк: результаты хранятся в `u64`.
+pub const MAX_COUNT_BITS: usize = 64;
+
+/// Гистограмма результатов измерений: битовая строка → число выборок.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Counts {
+    /// Длина битовых строк (число измеренных кубитов).
+    num_bits: usize,
+    /// Число выборок для каждой встретившейся битовой строки.
+    counts: BTreeMap<u64, usize>,
+}
+
+impl Counts {
+    /// Создает пустую статистику для битовых строк длины `num_bits`.
+    /// Паникует, если `num_bits` больше `MAX_COUNT_BITS`.
+    pub fn new(num_bits: usize) -> Self {
+        Self::try_new(num_bits).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Создает пустую статистику, возвращая ошибку,
+    /// если `num_bits` больше `MAX_COUNT_BITS`.
+    pub fn try_new(num_bits: usize) -> Result<Self, TriadError> {
+        validate_num_bits(num_bits)?;
+        Ok(Self { num_bits, counts: BTreeMap::new() })
+    }
+
+    /// Длина битовых строк.
+    pub fn num_bits(&self) -> usize {
+        self.num_bits
+    }
+
+    /// Общее число выборок.
+    pub fn shots(&self) -> usize {
+        self.counts.values().sum()
+    }
+
+    /// Проверяет, что не записано ни одной выборки.
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    /// Добавляет `count` выборок результата `outcome`.
+    /// Паникует, если результат не помещается в `num_bits` бит.
+    pub fn add(&mut self, outcome: u64, count: usize) {
+        self.try_add(outcome, count).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Добавляет `count` выборок результата `outcome`,
+    /// возвращая ошибку, если результат не помещается в `num_bits` бит.
+    pub fn try_add(&mut self, outcome: u64, count: usize) -> Result<(), TriadError> {
+        if self.num_bits < 64 && outcome >> self.num_bits != 0 {
+            return Err(TriadError::InvalidArgument(format!(
+                "результат измерения {:b} не помещается в {} бит", outcome, self.num_bits)));
+        }
+        if count > 0 {
+            *self.counts.entry(outcome).or_insert(0) += count;
+        }
+        Ok(())
+    }
+
+    /// Число выборок результата `outcome`.
+    pub fn get(&self, outcome: u64) -> usize {
+        self.counts.get(&outcome).copied().unwrap_or(0)
+    }
+
+    /// Итератор по встретившимся результатам в порядке возрастания.
+    pub fn iter(&self) -> impl Iterator<Item = (u64, usize)> + '_ {
+        self.counts.iter().map(|(&outcome, &count)| (outcome, count))
+    }
+
+    /// Относительная частота результата `outcome` (0 для пустой статистики).
+    pub fn frequency(&self, outcome: u64) -> f64 {
+        let shots = self.shots();
+        if shots == 0 {
+            return 0.0;
+        }
+        self.get(outcome) as f64 / shots as f64
+    }
+
+    /// Эмпирическое распределение вероятностей по встретившимся результатам.
+    pub fn probabilities(&self) -> BTreeMap<u64, f64> {
+        let shots = self.shots() as f64;
+        self.counts.iter().map(|(&outcome, &count)| (outcome, count as f64 / shots)).collect()
+    }
+
+    /// Наиболее частый результат; при равенстве выбирается меньший.
+    pub fn most_frequent(&self) -> Option<u64> {
+        self.counts.iter()
+            .max_by(|(a, ca), (b, cb)| ca.cmp(cb).then(b.cmp(a)))
+            .map(|(&outcome, _)| outcome)
+    }
+
+    /// Записывает результат в виде строки из `num_bits` символов,
+    /// начиная со старшего бита.
+    pub fn bitstring(&self, outcome: u64) -> String {
+        (0..self.num_bits).rev()
+            .map(|bit| if (outcome >> bit) & 1 == 1 { '1' } else { '0' })
+            .collect()
+    }
+
+    /// Маргинальная статистика по битам `bits`: бит `j` нового результата
+    /// равен биту `bits[j]` исходного. Паникует при некорректных индексах.
+    pub fn marginal(&self, bits: &[usize]) -> Counts {
+        self.try_marginal(bits).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Маргинальная статистика по битам `bits`, возвращающая ошибку,
+    /// если бит выходит за пределы строки или повторяется.
+    pub fn try_marginal(&self, bits: &[usize]) -> Result<Counts, TriadError> {
+        self.validate_bits(bits)?;
+
+        let mut marginal = Counts::new(bits.len());
+        for (&outcome, &count) in &self.counts {
+            *marginal.counts.entry(extract_bits(outcome, bits)).or_insert(0) += count;
+        }
+        Ok(marginal)
+    }
+
+    /// Эмпирическое среднее произведения Z-операторов на битах `bits`:
+    /// каждая выборка дает (-1)^(число единиц среди `bits`).
+    /// Для пустого набора битов возвращает 1. Паникует при некорректных индексах.
+    pub fn expectation_z(&self, bits: &[usize]) -> f64 {
+        self.try_expectation_z(bits).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Эмпирическое среднее произведения Z-операторов, возвращающее ошибку
+    /// для некорректных индексов битов или пустой статистики.
+    pub fn try_expectation_z(&self, bits: &[usize]) -> Result<f64, TriadError> {
+        self.validate_bits(bits)?;
+        let shots = self.shots();
+        if shots == 0 {
+            return Err(TriadError::InvalidArgument(
+                "нельзя вычислить среднее по пустой статистике".to_string()));
+        }
+
+        let mask = bits.iter().fold(0u64, |mask, &bit| mask | (1 << bit));
+        let sum: i64 = self.counts.iter()
+            .map(|(&outcome, &count)| {
+                let sign = if (outcome & mask).count_ones() % 2 == 0 { 1 } else { -1 };
+                sign * count as i64
+            })
+            .sum();
+        Ok(sum as f64 / shots as f64)
+    }
+
+    /// Добавляет выборки из другой статистики той же длины.
+    /// Паникует, если длины битовых строк различаются.
+    pub fn merge(&mut self, other: &Counts) {
+        self.try_merge(other).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Добавляет выборки из другой статистики, возвращая ошибку,
+    /// если длины битовых строк различаются.
+    pub fn try_merge(&mut self, other: &Counts) -> Result<(), TriadError> {
+        if self.num_bits != other.num_bits {
+            return Err(TriadError::InvalidArgument(format!(
+                "нельзя объединить статистику по {} и {} битам", self.num_bits, other.num_bits)));
+        }
+        for (&outcome, &count) in &other.counts {
+            *self.counts.entry(outcome).or_insert(0) += count;
+        }
+        Ok(())
+    }
+
+    /// Проверяет, что индексы битов находятся в пределах строки и не повторяются.
+    fn validate_bits(&self, bits: &[usize]) -> Result<(), TriadError> {
+        for (idx, &bit) in bits.iter().enumerate() {
+            if bit >= self.num_bits {
+                return Err(TriadError::InvalidArgument(format!(
+                    "бит {} выходит за пределы строки из {} бит", bit, self.num_bits)));
+            }
+            if bits[..idx].contains(&bit) {
+                return Err(TriadError::InvalidArgument(format!("бит {} указан повторно", bit)));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Counts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{{")?;
+        for (idx, (&outcome, &count)) in self.counts.iter().enumerate() {
+            if idx > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}: {}", self.bitstring(outcome), count)?;
+        }
+        write!(f, "}}")
+    }
+}
+
+/// Проверяет, что битовые строки длины `num_bits` помещаются в результат `u64`.
+pub fn validate_num_bits(num_bits: usize) -> Result<(), TriadError> {
+    if num_bits > MAX_COUNT_BITS {
+        return Err(TriadError::InvalidArgument(format!(
+            "результат измерения {} кубитов не помещается в {} бит", num_bits, MAX_COUNT_BITS)));
+    }
+    Ok(())
+}
+
+/// Собирает биты `qubits` базисного индекса `index` в результат измерения:
+/// бит `i` результата равен биту `qubits[i]` индекса. Кубиты с номерами
+/// от 64 и выше считаются нулевыми. Паникует, если кубитов больше `MAX_COUNT_BITS`.
+pub fn extract_bits(index: u64, qubits: &[usize]) -> u64 {
+    validate_num_bits(qubits.len()).unwrap_or_else(|err| panic!("{}", err));
+    qubits.iter().enumerate().fold(0u64, |acc, (i, &qubit)| {
+        let bit = if qubit < 64 { (index >> qubit) & 1 } else { 0 };
+        acc | (bit << i)
+    })
+}
+
+/// Извлекает `shots` выборок из распределения, заданного парами
+/// (результат, вероятность). Вероятности нормируются на их сумму,
+/// поэтому небольшие ошибки округления не влияют на результат.
+pub fn sample_distribution<R: Rng + ?Sized>(
+    distribution: &[(u64, f64)],
+    num_bits: usize,
+    shots: usize,
+    rng: &mut R,
+) -> Counts {
+    let mut cumulative = Vec::with_capacity(distribution.len());
+    let mut total = 0.0;
+    for &(_, prob) in distribution {
+        total += prob.max(0.0);
+        cumulative.push(total);
+    }
+
+    let mut counts = Counts::new(num_bits);
+    if distribution.is_empty() || total <= 0.0 {
+        return counts;
+    }
+
+    for _ in 0..shots {
+        let random = rng.gen::<f64>() * total;
+        let idx = cumulative.partition_point(|&c| c <= random).min(distribution.len() - 1);
+        counts.add(distribution[idx].0, 1);
+    }
+    counts
+}
+
+/// Извлекает `shots` выборок из `num_bits`-битного распределения, последовательно
+/// разыгрывая биты по условным вероятностям. `prefix_probability(len, prefix)`
+/// должна возвращать вероятность того, что младшие `len` бит результата равны
+/// `prefix`. Вероятности префиксов запоминаются, поэтому число вызовов
+/// ограничено числом различных префиксов среди выборок.
+pub fn sample_sequential<R, F>(num_bits: usize, shots: usize, rng: &mut R, mut prefix_probability: F) -> Counts
+where
+    R: Rng + ?Sized,
+    F: FnMut(usize, u64) -> f64,
+{
+    let mut cache: HashMap<(usize, u64), f64> = HashMap::new();
+    let mut counts = Counts::new(num_bits);
+
+    for _ in 0..shots {
+        let mut outcome = 0u64;
+        for bit in 0..num_bits {
+            let one = outcome | (1 << bit);
+            let prob_one = *cache.entry((bit + 1, one))
+                .or_insert_with(|| prefix_probability(bit + 1, one));
+            let prob_zero = *cache.entry((bit + 1, outcome))
+                .or_insert_with(|| prefix_probability(bit + 1, outcome));
+
+            let total = prob_zero + prob_one;
+            let threshold = if total > 0.0 { prob_one / total } else { 0.0 };
+            if rng.gen::<f64>() < threshold {
+                outcome = one;
+            }
+        }
+        counts.add(outcome, 1);
+    }
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn sample_counts() -> Counts {
+        let mut counts = Counts::new(3);
+        counts.add(0b000, 5);
+        counts.add(0b011, 3);
+        counts.add(0b110, 2);
+        counts
+    }
+
+    #[test]
+    fn test_marginal_and_expectation() {
+        let counts = sample_counts();
+        assert_eq!(counts.shots(), 10);
+        assert_eq!(counts.most_frequent(), Some(0));
+        assert_eq!(counts.bitstring(0b011), "011");
+
+        let marginal = counts.marginal(&[2, 0]);
+        assert_eq!(marginal.num_bits(), 2);
+        assert_eq!(marginal.get(0b00), 5);
+        assert_eq!(marginal.get(0b10), 3);
+        assert_eq!(marginal.get(0b01), 2);
+
+        // Z0: +5 -3 +2, Z0 Z1: +5 +3 -2
+        assert!((counts.expectation_z(&[0]) - 0.4).abs() < 1e-12);
+        assert!((counts.expectation_z(&[0, 1]) - 0.6).abs() < 1e-12);
+        assert!((counts.expectation_z(&[]) - 1.0).abs() < 1e-12);
+        assert!(counts.try_marginal(&[3]).is_err());
+        assert!(counts.try_expectation_z(&[1, 1]).is_err());
+    }
+
+    #[test]
+    fn test_merge_and_display() {
+        let mut counts = sample_counts();
+        let mut other = Counts::new(3);
+        other.add(0b011, 4);
+        counts.merge(&other);
+        assert_eq!(counts.get(0b011), 7);
+        assert_eq!(counts.shots(), 14);
+        assert_eq!(counts.to_string(), "{000: 5, 011: 7, 110: 2}");
+
+        assert!(counts.try_merge(&Counts::new(2)).is_err());
+        assert!(counts.try_add(0b1000, 1).is_err());
+    }
+
+    #[test]
+    fn test_rejects_more_than_64_bits() {
+        assert!(Counts::try_new(64).is_ok());
+        assert!(Counts::try_new(65).is_err());
+        assert_eq!(extract_bits(u64::MAX, &[63, 64, 0]), 0b101);
+
+        let counts = Counts::new(64);
+        assert!(counts.try_marginal(&[64]).is_err());
+        assert!(counts.try_expectation_z(&[70]).is_err());
+    }
+
+    #[test]
+    fn test_sampling_matches_distribution() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let distribution = [(0b00, 0.25), (0b11, 0.75)];
+        let direct = sample_distribution(&distribution, 2, 4000, &mut rng);
+        assert_eq!(direct.shots(), 4000);
+        assert!((direct.frequency(0b11) - 0.75).abs() < 0.05);
+
+        let sequential = sample_sequential(2, 4000, &mut rng, |len, prefix| {
+            let mask = (1u64 << len) - 1;
+            distribution.iter().filter(|(o, _)| o & mask == prefix).map(|(_, p)| p).sum()
+        });
+        assert_eq!(sequential.get(0b01) + sequential.get(0b10), 0);
+        assert!((sequential.frequency(0b00) - 0.25).abs() < 0.05);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_json_roundtrip() {
+        let counts = sample_counts();
+        let json = serde_json::to_string(&counts).unwrap();
+        let restored: Counts = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, counts);
+    }
+}
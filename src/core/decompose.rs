@@ -0,0 +1,844 @@
+//! Разложение гейтов по нативному базису бэкенда.
+//!
+//! Бэкенды поддерживают напрямую разные наборы гейтов: например, в QuEST
+//! нет SWAP, поэтому `AdvancedQuantumSimulator::swap` выражает его через
+//! три CNOT. Этот модуль переписывает схему так, чтобы в ней остались
+//! только гейты заданного базиса (`Basis`):
+//! - однокубитовые унитарные матрицы раскладываются по углам Эйлера ZYZ;
+//! - двухкубитовые — KAK-разложением в три CNOT и однокубитовые гейты;
+//! - многократно управляемые гейты — лестницами из гейтов Тоффоли и CNOT
+//!   (конструкция Баренко и др., без вспомогательных кубитов).
+//!
+//! Разложения точны с точностью до глобальной фазы всей схемы.
+
+use crate::core::circuit::{Circuit, CircuitGate, Instruction};
+use crate::core::gates::{
+    equal_up_to_phase, identity_matrix, is_unitary_matrix, Axis, BasicGate, Gate, ParametricGate, RotationGate,
+    TwoQubitGate,
+};
+use crate::core::quantum_simulator::UNITARITY_TOLERANCE;
+use crate::core::quantum_state::Amplitude;
+use crate::error::TriadError;
+use nalgebra::{Matrix2, Matrix4};
+use num_complex::Complex64;
+use std::collections::BTreeSet;
+use std::f64::consts::{E, FRAC_1_PI, FRAC_1_SQRT_2, FRAC_PI_2, PI, SQRT_2};
+
+/// Точность, с которой матрицы считаются совпадающими, а углы — нулевыми.
+const TOLERANCE: f64 = 1e-9;
+
+/// Гейт, который может входить в нативный базис бэкенда.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum BasisGate {
+    /// Гейт Адамара
+    H,
+    /// Гейт Паули-X
+    X,
+    /// Гейт Паули-Y
+    Y,
+    /// Гейт Паули-Z
+    Z,
+    /// Фазовый гейт S
+    S,
+    /// Гейт T
+    T,
+    /// Вращение вокруг оси X
+    RX,
+    /// Вращение вокруг оси Y
+    RY,
+    /// Вращение вокруг оси Z
+    RZ,
+    /// Общий однокубитовый гейт U3(θ, φ, λ)
+    U3,
+    /// Контролируемый NOT
+    CNOT,
+    /// Контролируемый Z
+    CZ,
+    /// SWAP-гейт
+    SWAP,
+    /// Гейт Тоффоли (CCX)
+    Toffoli,
+}
+
+impl BasisGate {
+    /// Определяет, каким гейтом базиса является гейт схемы (если является).
+    pub fn of(gate: &CircuitGate) -> Option<BasisGate> {
+        match gate {
+            CircuitGate::Basic(BasicGate::Hadamard) => Some(BasisGate::H),
+            CircuitGate::Basic(BasicGate::PauliX) => Some(BasisGate::X),
+            CircuitGate::Basic(BasicGate::PauliY) => Some(BasisGate::Y),
+            CircuitGate::Basic(BasicGate::PauliZ) => Some(BasisGate::Z),
+            CircuitGate::Basic(BasicGate::S) => Some(BasisGate::S),
+            CircuitGate::Basic(BasicGate::T) => Some(BasisGate::T),
+            CircuitGate::Rotation(RotationGate { axis, .. }) => Some(match axis {
+                Axis::X => BasisGate::RX,
+                Axis::Y => BasisGate::RY,
+                Axis::Z => BasisGate::RZ,
+            }),
+            CircuitGate::Parametric(ParametricGate::U3 { .. }) => Some(BasisGate::U3),
+            CircuitGate::TwoQubit(TwoQubitGate::CNOT) => Some(BasisGate::CNOT),
+            CircuitGate::TwoQubit(TwoQubitGate::CZ) => Some(BasisGate::CZ),
+            CircuitGate::TwoQubit(TwoQubitGate::SWAP) => Some(BasisGate::SWAP),
+            CircuitGate::Custom(custom) if custom.num_qubits == 3
+                && equal_up_to_phase(&custom.matrix, &toffoli().matrix(), TOLERANCE) => Some(BasisGate::Toffoli),
+            _ => None,
+        }
+    }
+}
+
+/// Гейт Тоффоли в представлении схемы (управляющие кубиты — первые два).
+fn toffoli() -> CircuitGate {
+    BasicGate::PauliX.controlled(2)
+}
+
+/// Нативный базис: набор гейтов, которые бэкенд применяет напрямую.
+///
+/// Для разложения произвольных гейтов базис должен содержать универсальный
+/// набор однокубитовых гейтов (U3, RZ+RY или RZ+RX) и CNOT или CZ.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Basis {
+    gates: BTreeSet<BasisGate>,
+}
+
+impl Basis {
+    /// Создает базис из перечисленных гейтов.
+    pub fn new(gates: &[BasisGate]) -> Self {
+        Self { gates: gates.iter().copied().collect() }
+    }
+
+    /// Базис бэкенда QuEST: все однокубитовые гейты, CNOT, CZ и Тоффоли,
+    /// но без SWAP.
+    pub fn quest() -> Self {
+        Self::new(&[
+            BasisGate::H, BasisGate::X, BasisGate::Y, BasisGate::Z, BasisGate::S, BasisGate::T,
+            BasisGate::RX, BasisGate::RY, BasisGate::RZ, BasisGate::U3,
+            BasisGate::CNOT, BasisGate::CZ, BasisGate::Toffoli,
+        ])
+    }
+
+    /// Проверяет, входит ли гейт в базис.
+    pub fn contains(&self, gate: BasisGate) -> bool {
+        self.gates.contains(&gate)
+    }
+
+    /// Проверяет, может ли гейт схемы быть применен без разложения.
+    pub fn supports(&self, gate: &CircuitGate) -> bool {
+        BasisGate::of(gate).is_some_and(|gate| self.contains(gate))
+    }
+
+    /// Возвращает гейты базиса в фиксированном порядке.
+    pub fn gates(&self) -> impl Iterator<Item = BasisGate> + '_ {
+        self.gates.iter().copied()
+    }
+}
+
+impl Default for Basis {
+    /// Минимальный универсальный базис: RZ, RY и CNOT.
+    fn default() -> Self {
+        Self::new(&[BasisGate::RZ, BasisGate::RY, BasisGate::CNOT])
+    }
+}
+
+/// Углы Эйлера однокубитового гейта: `U = e^{iα}·RZ(β)·RY(γ)·RZ(δ)`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EulerAngles {
+    /// Глобальная фаза α.
+    pub phase: f64,
+    /// Угол β последнего вращения вокруг Z.
+    pub beta: f64,
+    /// Угол γ вращения вокруг Y (от 0 до π).
+    pub gamma: f64,
+    /// Угол δ первого вращения вокруг Z.
+    pub delta: f64,
+}
+
+/// Вычисляет углы Эйлера ZYZ однокубитовой унитарной матрицы (2x2 по строкам).
+pub fn zyz_angles(matrix: &[Amplitude]) -> EulerAngles {
+    assert_eq!(matrix.len(), 4, "Матрица однокубитового оператора должна быть размера 2x2");
+
+    // Приводим матрицу к SU(2): V = e^{-iα}·U, det V = 1
+    let det = matrix[0] * matrix[3] - matrix[1] * matrix[2];
+    let phase = det.arg() / 2.0;
+    let rotation = Complex64::from_polar(1.0, -phase);
+    let (v00, v10, v11) = (matrix[0] * rotation, matrix[2] * rotation, matrix[3] * rotation);
+
+    // V = [[e^{-i(β+δ)/2}·cos(γ/2), ...], [e^{i(β-δ)/2}·sin(γ/2), e^{i(β+δ)/2}·cos(γ/2)]]
+    let gamma = 2.0 * v10.norm().atan2(v00.norm());
+    let sum = if v11.norm() < TOLERANCE { 0.0 } else { 2.0 * v11.arg() };
+    let difference = if v10.norm() < TOLERANCE { 0.0 } else { 2.0 * v10.arg() };
+
+    EulerAngles {
+        phase,
+        beta: (sum + difference) / 2.0,
+        gamma,
+        delta: (sum - difference) / 2.0,
+    }
+}
+
+/// KAK-разложение двухкубитового гейта:
+/// `U = e^{iφ}·(A0⊗A1)·exp(i(a·XX + b·YY + c·ZZ))·(B0⊗B1)`,
+/// где первый множитель тензорного произведения действует на первый
+/// (старший) кубит гейта.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KakDecomposition {
+    /// Глобальная фаза φ.
+    pub phase: f64,
+    /// Однокубитовые гейты (A0, A1), применяемые после взаимодействия.
+    pub after: (Vec<Amplitude>, Vec<Amplitude>),
+    /// Коэффициенты взаимодействия (a, b, c).
+    pub coefficients: (f64, f64, f64),
+    /// Однокубитовые гейты (B0, B1), применяемые до взаимодействия.
+    pub before: (Vec<Amplitude>, Vec<Amplitude>),
+}
+
+/// Вычисляет KAK-разложение двухкубитовой унитарной матрицы (4x4 по строкам).
+///
+/// В магическом базисе локальные гейты SU(2)⊗SU(2) становятся
+/// вещественными ортогональными матрицами, а взаимодействие — диагональной
+/// матрицей, поэтому разложение сводится к одновременной диагонализации
+/// вещественной и мнимой частей симметричной матрицы `MᵀM`.
+pub fn kak_decomposition(matrix: &[Amplitude]) -> KakDecomposition {
+    assert_eq!(matrix.len(), 16, "Матрица двухкубитового оператора должна быть размера 4x4");
+
+    let unitary = Matrix4::from_row_slice(matrix);
+    let normalization = unitary.determinant().arg() / 4.0;
+    let special = unitary * Complex64::from_polar(1.0, -normalization);
+
+    let magic = magic_basis();
+    let m = magic.adjoint() * special * magic;
+    let squared = m.transpose() * m;
+
+    let mut p = simultaneous_eigenvectors(&squared);
+    if p.determinant() < 0.0 {
+        p.column_mut(0).neg_mut();
+    }
+    let p = p.map(|x| Complex64::new(x, 0.0));
+
+    let diagonal = p.transpose() * squared * p;
+    let mut half: Vec<Complex64> = (0..4).map(|k| Complex64::from_polar(1.0, diagonal[(k, k)].arg() / 2.0)).collect();
+    let inverse_half = |half: &[Complex64]| Matrix4::from_fn(|i, j| if i == j { half[i].inv() } else { Complex64::new(0.0, 0.0) });
+
+    // M = K1·diag(half)·K2, где K2 = Pᵀ; K1 должна лежать в SO(4)
+    let mut k1 = m * p * inverse_half(&half);
+    if k1.determinant().re < 0.0 {
+        half[0] = -half[0];
+        k1 = m * p * inverse_half(&half);
+    }
+    let k1 = k1.map(|x| Complex64::new(x.re, 0.0));
+    let k2 = p.transpose();
+
+    // Фазы диагонали в магическом базисе: θ_k = g + a·x_k + b·y_k + c·z_k
+    let angles: Vec<f64> = half.iter().map(|h| h.arg()).collect();
+    let [x, y, z] = [pauli_pair(Axis::X), pauli_pair(Axis::Y), pauli_pair(Axis::Z)]
+        .map(|pauli| magic_diagonal(&magic, &pauli));
+    let project = |weights: &[f64; 4]| angles.iter().zip(weights).map(|(t, w)| t * w).sum::<f64>() / 4.0;
+
+    let (after0, after1) = tensor_factors(&(magic * k1 * magic.adjoint()));
+    let (before0, before1) = tensor_factors(&(magic * k2 * magic.adjoint()));
+
+    KakDecomposition {
+        phase: normalization + project(&[1.0; 4]),
+        after: (row_major(&after0), row_major(&after1)),
+        coefficients: (project(&x), project(&y), project(&z)),
+        before: (row_major(&before0), row_major(&before1)),
+    }
+}
+
+/// Магический базис: в нем SU(2)⊗SU(2) переходит в SO(4).
+fn magic_basis() -> Matrix4<Complex64> {
+    let (zero, one, i) = (Complex64::new(0.0, 0.0), Complex64::new(FRAC_1_SQRT_2, 0.0), Complex64::new(0.0, FRAC_1_SQRT_2));
+    Matrix4::new(
+        one, zero, zero, i,
+        zero, i, one, zero,
+        zero, i, -one, zero,
+        one, zero, zero, -i,
+    )
+}
+
+/// Диагональ оператора `P⊗P` в магическом базисе (элементы равны ±1).
+fn magic_diagonal(magic: &Matrix4<Complex64>, pauli: &Matrix4<Complex64>) -> [f64; 4] {
+    let diagonal = magic.adjoint() * pauli * magic;
+    [0, 1, 2, 3].map(|k| diagonal[(k, k)].re)
+}
+
+/// Матрица `P⊗P` для оператора Паули по оси `axis`.
+fn pauli_pair(axis: Axis) -> Matrix4<Complex64> {
+    let pauli = match axis {
+        Axis::X => Matrix2::from_row_slice(&BasicGate::PauliX.matrix()),
+        Axis::Y => Matrix2::from_row_slice(&BasicGate::PauliY.matrix()),
+        Axis::Z => Matrix2::from_row_slice(&BasicGate::PauliZ.matrix()),
+    };
+    pauli.kronecker(&pauli)
+}
+
+/// Находит вещественную ортогональную матрицу, диагонализующую одновременно
+/// вещественную и мнимую части симметричной унитарной матрицы (они коммутируют).
+/// Собственные векторы ищутся для случайной линейной комбинации частей,
+/// что снимает вырождение одной из них.
+fn simultaneous_eigenvectors(matrix: &Matrix4<Complex64>) -> Matrix4<f64> {
+    let real = matrix.map(|x| x.re);
+    let imag = matrix.map(|x| x.im);
+
+    // Детерминированный набор «случайных» коэффициентов
+    let mut fallback = Matrix4::identity();
+    for weight in [0.5772156649, 1.6180339887, E, FRAC_1_PI, SQRT_2] {
+        let vectors = (real + imag * weight).symmetric_eigen().eigenvectors;
+        let check = |part: &Matrix4<f64>| {
+            let diagonal = vectors.transpose() * part * vectors;
+            (0..4).all(|i| (0..4).all(|j| i == j || diagonal[(i, j)].abs() < 1e-7))
+        };
+        if check(&real) && check(&imag) {
+            return vectors;
+        }
+        fallback = vectors;
+    }
+    fallback
+}
+
+/// Раскладывает матрицу 4x4 вида `A⊗B` на множители `A` и `B`
+/// (с точностью до распределения скалярного множителя между ними).
+fn tensor_factors(matrix: &Matrix4<Complex64>) -> (Matrix2<Complex64>, Matrix2<Complex64>) {
+    // Опорный элемент — наибольший по модулю: A[i][j]·B[k][l] = M[2i+k][2j+l]
+    let (mut row, mut col) = (0, 0);
+    for i in 0..4 {
+        for j in 0..4 {
+            if matrix[(i, j)].norm() > matrix[(row, col)].norm() {
+                (row, col) = (i, j);
+            }
+        }
+    }
+
+    let (i0, k0, j0, l0) = (row / 2, row % 2, col / 2, col % 2);
+    let mut a = Matrix2::from_fn(|i, j| matrix[(2 * i + k0, 2 * j + l0)]);
+    let mut b = Matrix2::from_fn(|k, l| matrix[(2 * i0 + k, 2 * j0 + l)] / matrix[(row, col)]);
+
+    // Нормируем A на единичный определитель, компенсируя множитель в B
+    let scale = a.determinant().sqrt();
+    a /= scale;
+    b *= scale;
+    (a, b)
+}
+
+/// Возвращает элементы матрицы nalgebra по строкам.
+fn row_major<const N: usize>(matrix: &nalgebra::SMatrix<Complex64, N, N>) -> Vec<Amplitude> {
+    matrix.transpose().iter().copied().collect()
+}
+
+/// Матрица однокубитового вращения.
+fn rotation(axis: Axis, angle: f64) -> Matrix2<Complex64> {
+    Matrix2::from_row_slice(&RotationGate { axis, angle }.matrix())
+}
+
+/// Матрица базового однокубитового гейта.
+fn basic(gate: BasicGate) -> Matrix2<Complex64> {
+    Matrix2::from_row_slice(&gate.matrix())
+}
+
+/// Проверяет поэлементное совпадение матриц (без учета глобальной фазы).
+fn same(a: &Matrix2<Complex64>, b: &Matrix2<Complex64>) -> bool {
+    (a - b).iter().all(|x| x.norm() < TOLERANCE)
+}
+
+/// Приводит угол к диапазону (-π, π].
+fn normalize_angle(angle: f64) -> f64 {
+    let reduced = angle.rem_euclid(2.0 * PI);
+    if reduced > PI { reduced - 2.0 * PI } else { reduced }
+}
+
+/// Находит структуру управляемого гейта: матрица размера `dim` отличается
+/// от единичной только в блоке размера `target_dim`, соответствующем одному
+/// значению управляющих кубитов. Возвращает это значение и блок (по строкам).
+fn controlled_block(matrix: &[Amplitude], dim: usize, target_dim: usize) -> Option<(usize, Vec<Amplitude>)> {
+    let identity = identity_matrix(dim);
+    let differs = |index: usize| (matrix[index] - identity[index]).norm() > TOLERANCE;
+
+    let first = (0..dim * dim).find(|&index| differs(index))?;
+    let pattern = first / dim / target_dim;
+    let block = pattern * target_dim..(pattern + 1) * target_dim;
+
+    let outside = (0..dim * dim).any(|index| {
+        let (row, col) = (index / dim, index % dim);
+        !(block.contains(&row) && block.contains(&col)) && differs(index)
+    });
+    if outside {
+        return None;
+    }
+
+    let values = block.clone()
+        .flat_map(|row| block.clone().map(move |col| matrix[row * dim + col]))
+        .collect();
+    Some((pattern, values))
+}
+
+/// Синтезатор последовательности гейтов базиса.
+struct Synthesizer<'a> {
+    basis: &'a Basis,
+    gates: Vec<Instruction>,
+}
+
+impl Synthesizer<'_> {
+    fn new(basis: &Basis) -> Synthesizer<'_> {
+        Synthesizer { basis, gates: Vec::new() }
+    }
+
+    fn emit(&mut self, gate: impl Into<CircuitGate>, qubits: &[usize]) {
+        self.gates.push(Instruction::gate(gate, qubits));
+    }
+
+    /// Раскладывает гейт схемы, действующий на кубиты `qubits`.
+    fn gate(&mut self, gate: &CircuitGate, qubits: &[usize]) -> Result<(), TriadError> {
+        if self.basis.supports(gate) {
+            self.emit(gate.clone(), qubits);
+            return Ok(());
+        }
+
+        let matrix = gate.matrix();
+        if !is_unitary_matrix(&matrix, UNITARITY_TOLERANCE) {
+            return Err(TriadError::NonUnitary(gate.name().to_string()));
+        }
+        self.unitary(&matrix, qubits)
+    }
+
+    /// Раскладывает произвольную унитарную матрицу, первый кубит — старший бит.
+    fn unitary(&mut self, matrix: &[Amplitude], qubits: &[usize]) -> Result<(), TriadError> {
+        let dim = 1usize << qubits.len();
+        if equal_up_to_phase(matrix, &identity_matrix(dim), TOLERANCE) {
+            return Ok(());
+        }
+        if let [qubit] = qubits {
+            return self.single(*qubit, &Matrix2::from_row_slice(matrix));
+        }
+        if let [q0, q1] = qubits {
+            if equal_up_to_phase(matrix, &TwoQubitGate::SWAP.matrix(), TOLERANCE) {
+                return self.swap(*q0, *q1);
+            }
+        }
+
+        let (controls, target) = qubits.split_at(qubits.len() - 1);
+        if let Some((pattern, block)) = controlled_block(matrix, dim, 2) {
+            let flipped = inactive_controls(controls, pattern);
+            self.flip(&flipped)?;
+            self.controlled(controls, target[0], &Matrix2::from_row_slice(&block))?;
+            return self.flip(&flipped);
+        }
+
+        if let [q0, q1] = qubits {
+            return self.two_qubit(*q0, *q1, &Matrix4::from_row_slice(matrix));
+        }
+
+        // Управляемый SWAP (гейт Фредкина и его обобщения):
+        // C-SWAP(t0, t1) = CNOT(t1, t0)·C-CNOT(t0, t1)·CNOT(t1, t0)
+        let (controls, targets) = qubits.split_at(qubits.len() - 2);
+        if let Some((pattern, block)) = controlled_block(matrix, dim, 4) {
+            if block == TwoQubitGate::SWAP.matrix() {
+                let flipped = inactive_controls(controls, pattern);
+                let ladder: Vec<usize> = controls.iter().chain(&targets[..1]).copied().collect();
+                self.flip(&flipped)?;
+                self.cnot(targets[1], targets[0])?;
+                self.controlled(&ladder, targets[1], &basic(BasicGate::PauliX))?;
+                self.cnot(targets[1], targets[0])?;
+                return self.flip(&flipped);
+            }
+        }
+
+        Err(TriadError::InvalidArgument(format!(
+            "разложение {}-кубитового гейта без структуры управления не поддерживается", qubits.len())))
+    }
+
+    /// Раскладывает однокубитовый гейт по углам Эйлера.
+    fn single(&mut self, qubit: usize, matrix: &Matrix2<Complex64>) -> Result<(), TriadError> {
+        let matrix = row_major(matrix);
+        if equal_up_to_phase(&matrix, &identity_matrix(2), TOLERANCE) {
+            return Ok(());
+        }
+
+        let named = [
+            (BasisGate::H, BasicGate::Hadamard), (BasisGate::X, BasicGate::PauliX),
+            (BasisGate::Y, BasicGate::PauliY), (BasisGate::Z, BasicGate::PauliZ),
+            (BasisGate::S, BasicGate::S), (BasisGate::T, BasicGate::T),
+        ];
+        for (basis_gate, gate) in named {
+            if self.basis.contains(basis_gate) && equal_up_to_phase(&matrix, &gate.matrix(), TOLERANCE) {
+                self.emit(gate, &[qubit]);
+                return Ok(());
+            }
+        }
+
+        let EulerAngles { beta, gamma, delta, .. } = zyz_angles(&matrix);
+        if self.basis.contains(BasisGate::U3) {
+            self.emit(ParametricGate::U3 { theta: gamma, phi: beta, lambda: delta }, &[qubit]);
+        } else if self.basis.contains(BasisGate::RZ) && self.basis.contains(BasisGate::RY) {
+            self.rotations(qubit, Axis::Y, delta, gamma, beta);
+        } else if self.basis.contains(BasisGate::RZ) && self.basis.contains(BasisGate::RX) {
+            // RY(γ) = RZ(π/2)·RX(γ)·RZ(-π/2)
+            self.rotations(qubit, Axis::X, delta - FRAC_PI_2, gamma, beta + FRAC_PI_2);
+        } else {
+            return Err(TriadError::InvalidArgument(
+                "базис не содержит универсального набора однокубитовых гейтов (U3, RZ+RY или RZ+RX)".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Добавляет вращения RZ(first), R_axis(middle), RZ(last) в порядке
+    /// применения, пропуская нулевые углы.
+    fn rotations(&mut self, qubit: usize, axis: Axis, first: f64, middle: f64, last: f64) {
+        let middle = normalize_angle(middle);
+        let angles = if middle.abs() < TOLERANCE {
+            vec![(Axis::Z, first + last)]
+        } else {
+            vec![(Axis::Z, first), (axis, middle), (Axis::Z, last)]
+        };
+
+        for (axis, angle) in angles {
+            let angle = normalize_angle(angle);
+            if angle.abs() > TOLERANCE {
+                self.emit(RotationGate { axis, angle }, &[qubit]);
+            }
+        }
+    }
+
+    /// Применяет X к кубитам `qubits` (смена полярности управления).
+    fn flip(&mut self, qubits: &[usize]) -> Result<(), TriadError> {
+        for &qubit in qubits {
+            self.single(qubit, &basic(BasicGate::PauliX))?;
+        }
+        Ok(())
+    }
+
+    /// Добавляет CNOT, выражая его через CZ, если CNOT нет в базисе.
+    fn cnot(&mut self, control: usize, target: usize) -> Result<(), TriadError> {
+        if self.basis.contains(BasisGate::CNOT) {
+            self.emit(TwoQubitGate::CNOT, &[control, target]);
+        } else if self.basis.contains(BasisGate::CZ) {
+            let hadamard = basic(BasicGate::Hadamard);
+            self.single(target, &hadamard)?;
+            self.emit(TwoQubitGate::CZ, &[control, target]);
+            self.single(target, &hadamard)?;
+        } else {
+            return Err(TriadError::InvalidArgument("базис не содержит CNOT или CZ".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Добавляет SWAP или, если его нет в базисе, три CNOT.
+    fn swap(&mut self, q0: usize, q1: usize) -> Result<(), TriadError> {
+        if self.basis.contains(BasisGate::SWAP) {
+            self.emit(TwoQubitGate::SWAP, &[q0, q1]);
+            return Ok(());
+        }
+        self.cnot(q0, q1)?;
+        self.cnot(q1, q0)?;
+        self.cnot(q0, q1)
+    }
+
+    /// Раскладывает двухкубитовый гейт через KAK-разложение.
+    fn two_qubit(&mut self, q0: usize, q1: usize, matrix: &Matrix4<Complex64>) -> Result<(), TriadError> {
+        let kak = kak_decomposition(&row_major(matrix));
+        let [after0, after1, before0, before1] = [&kak.after.0, &kak.after.1, &kak.before.0, &kak.before.1]
+            .map(|m| Matrix2::from_row_slice(m));
+        let (a, b, c) = kak.coefficients;
+
+        if [a, b, c].iter().all(|x| normalize_angle(*x).abs() < TOLERANCE) {
+            self.single(q0, &(after0 * before0))?;
+            return self.single(q1, &(after1 * before1));
+        }
+
+        // exp(i(a·XX + b·YY + c·ZZ)) через три CNOT; крайние однокубитовые
+        // гейты шаблона объединяются с локальными гейтами разложения
+        self.single(q0, &before0)?;
+        self.single(q1, &(rotation(Axis::Z, FRAC_PI_2) * before1))?;
+        self.cnot(q1, q0)?;
+        self.single(q0, &rotation(Axis::Z, -2.0 * c - FRAC_PI_2))?;
+        self.single(q1, &rotation(Axis::Y, -2.0 * a - FRAC_PI_2))?;
+        self.cnot(q0, q1)?;
+        self.single(q1, &rotation(Axis::Y, 2.0 * b + FRAC_PI_2))?;
+        self.cnot(q1, q0)?;
+        self.single(q0, &(after0 * rotation(Axis::Z, -FRAC_PI_2)))?;
+        self.single(q1, &after1)
+    }
+
+    /// Раскладывает однокубитовый оператор `u`, управляемый кубитами `controls`.
+    fn controlled(&mut self, controls: &[usize], target: usize, u: &Matrix2<Complex64>) -> Result<(), TriadError> {
+        let pauli_x = basic(BasicGate::PauliX);
+
+        match controls {
+            [] => self.single(target, u),
+            [control] if same(u, &pauli_x) => self.cnot(*control, target),
+            [control] if same(u, &basic(BasicGate::PauliZ)) && self.basis.contains(BasisGate::CZ) => {
+                self.emit(TwoQubitGate::CZ, &[*control, target]);
+                Ok(())
+            },
+            [control] => {
+                // U = e^{iα}·A·X·B·X·C, где A·B·C = I
+                let EulerAngles { phase, beta, gamma, delta } = zyz_angles(&row_major(u));
+                let a = rotation(Axis::Z, beta) * rotation(Axis::Y, gamma / 2.0);
+                let b = rotation(Axis::Y, -gamma / 2.0) * rotation(Axis::Z, -(delta + beta) / 2.0);
+                let c = rotation(Axis::Z, (delta - beta) / 2.0);
+
+                self.single(target, &c)?;
+                self.cnot(*control, target)?;
+                self.single(target, &b)?;
+                self.cnot(*control, target)?;
+                self.single(target, &a)?;
+                self.single(*control, &Matrix2::from_row_slice(&ParametricGate::Phase { theta: phase }.matrix()))
+            },
+            [c0, c1] if same(u, &pauli_x) => self.toffoli(*c0, *c1, target),
+            [rest @ .., last] => {
+                // C^n(U) = C^{n-1}(V)·C^{n-1}X·C(V†)·C^{n-1}X·C(V), где V² = U
+                let v = square_root(u);
+                self.controlled(&[*last], target, &v)?;
+                self.controlled(rest, *last, &pauli_x)?;
+                self.controlled(&[*last], target, &v.adjoint())?;
+                self.controlled(rest, *last, &pauli_x)?;
+                self.controlled(rest, target, &v)
+            },
+        }
+    }
+
+    /// Добавляет гейт Тоффоли или его разложение в шесть CNOT.
+    fn toffoli(&mut self, c0: usize, c1: usize, target: usize) -> Result<(), TriadError> {
+        if self.basis.contains(BasisGate::Toffoli) {
+            self.emit(toffoli(), &[c0, c1, target]);
+            return Ok(());
+        }
+
+        let (h, t, t_dagger) = (basic(BasicGate::Hadamard), basic(BasicGate::T), basic(BasicGate::TDagger));
+        self.single(target, &h)?;
+        self.cnot(c1, target)?;
+        self.single(target, &t_dagger)?;
+        self.cnot(c0, target)?;
+        self.single(target, &t)?;
+        self.cnot(c1, target)?;
+        self.single(target, &t_dagger)?;
+        self.cnot(c0, target)?;
+        self.single(c1, &t)?;
+        self.single(target, &(h * t))?;
+        self.cnot(c0, c1)?;
+        self.single(c0, &t)?;
+        self.single(c1, &t_dagger)?;
+        self.cnot(c0, c1)
+    }
+}
+
+/// Возвращает управляющие кубиты, срабатывающие на |0⟩ в значении `pattern`
+/// (первый управляющий кубит — старший бит).
+fn inactive_controls(controls: &[usize], pattern: usize) -> Vec<usize> {
+    let n = controls.len();
+    controls.iter()
+        .enumerate()
+        .filter(|(j, _)| (pattern >> (n - 1 - j)) & 1 == 0)
+        .map(|(_, &qubit)| qubit)
+        .collect()
+}
+
+/// Вычисляет квадратный корень унитарной матрицы 2x2:
+/// `√U = (U + s·I) / √(tr U + 2s)`, где `s² = det U`.
+fn square_root(u: &Matrix2<Complex64>) -> Matrix2<Complex64> {
+    let s = u.determinant().sqrt();
+    let trace = u.trace();
+    // Выбираем знак s, при котором знаменатель не обращается в ноль
+    let s = if (trace + 2.0 * s).norm() >= (trace - 2.0 * s).norm() { s } else { -s };
+    (u + Matrix2::identity() * s) / (trace + 2.0 * s).sqrt()
+}
+
+/// Раскладывает гейт по базису. Возвращает схему на `gate.num_qubits()`
+/// кубитах, где кубит `j` схемы соответствует `j`-му целевому кубиту гейта.
+pub fn decompose_gate(gate: &CircuitGate, basis: &Basis) -> Result<Circuit, TriadError> {
+    let qubits: Vec<usize> = (0..gate.num_qubits()).collect();
+    let mut synthesizer = Synthesizer::new(basis);
+    synthesizer.gate(gate, &qubits)?;
+
+    let mut circuit = Circuit::new(qubits.len(), 0);
+    for instruction in synthesizer.gates {
+        circuit.try_push(instruction)?;
+    }
+    Ok(circuit)
+}
+
+/// Переписывает схему так, чтобы все ее гейты принадлежали базису.
+/// Измерения, сбросы и барьеры сохраняются; классически управляемый гейт
+/// заменяется последовательностью гейтов с тем же условием.
+pub fn decompose(circuit: &Circuit, basis: &Basis) -> Result<Circuit, TriadError> {
+    let mut decomposed = circuit.empty_like();
+    for instruction in circuit.instructions() {
+        for rewritten in decompose_instruction(instruction, basis)? {
+            decomposed.try_push(rewritten)?;
+        }
+    }
+    Ok(decomposed)
+}
+
+/// Раскладывает одну инструкцию схемы.
+fn decompose_instruction(instruction: &Instruction, basis: &Basis) -> Result<Vec<Instruction>, TriadError> {
+    match instruction {
+        Instruction::Gate { gate, qubits } => {
+            let mut synthesizer = Synthesizer::new(basis);
+            synthesizer.gate(gate, qubits)?;
+            Ok(synthesizer.gates)
+        },
+        Instruction::Conditional { cbits, value, instruction } => Ok(decompose_instruction(instruction, basis)?
+            .into_iter()
+            .map(|inner| Instruction::Conditional { cbits: cbits.clone(), value: *value, instruction: Box::new(inner) })
+            .collect()),
+        Instruction::Measure { .. } | Instruction::Reset(_) | Instruction::Barrier(_) => Ok(vec![instruction.clone()]),
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::gates::CustomGate;
+
+    /// Проверяет, что схема состоит из гейтов базиса и реализует тот же
+    /// оператор, что и исходная (с точностью до глобальной фазы).
+    fn assert_equivalent(original: &Circuit, basis: &Basis) -> Circuit {
+        let decomposed = decompose(original, basis).unwrap();
+        for instruction in decomposed.instructions() {
+            if let Instruction::Gate { gate, .. } = instruction {
+                assert!(basis.supports(gate), "гейт {} не входит в базис", gate.name());
+            }
+        }
+        assert!(equal_up_to_phase(&decomposed.unitary().unwrap(), &original.unitary().unwrap(), 1e-8));
+        decomposed
+    }
+
+    fn single_gate(gate: impl Into<CircuitGate>, qubits: &[usize], num_qubits: usize) -> Circuit {
+        let mut circuit = Circuit::new(num_qubits, 0);
+        circuit.gate(gate, qubits);
+        circuit
+    }
+
+    /// Произвольный двухкубитовый унитарный оператор без особой структуры.
+    fn generic_two_qubit() -> CustomGate {
+        let mut circuit = Circuit::new(2, 0);
+        circuit.gate(ParametricGate::U3 { theta: 0.7, phi: 1.9, lambda: -0.5 }, &[0])
+            .gate(ParametricGate::U3 { theta: 2.1, phi: -0.3, lambda: 0.8 }, &[1])
+            .gate(ParametricGate::RXX { theta: 0.9 }, &[0, 1])
+            .gate(ParametricGate::CRY { theta: -1.4 }, &[1, 0])
+            .gate(TwoQubitGate::SqrtISWAP, &[0, 1])
+            .gate(ParametricGate::U3 { theta: -1.2, phi: 0.4, lambda: 2.6 }, &[0]);
+        CustomGate::new(circuit.unitary().unwrap(), 2, "generic")
+    }
+
+    #[test]
+    fn test_zyz_angles_reconstruct_gate() {
+        let gates: Vec<CircuitGate> = vec![
+            BasicGate::Hadamard.into(), BasicGate::PauliY.into(), BasicGate::S.into(), BasicGate::SqrtX.into(),
+            ParametricGate::U3 { theta: 0.7, phi: 1.9, lambda: -0.5 }.into(),
+            RotationGate { axis: Axis::X, angle: 2.5 }.into(),
+        ];
+
+        for gate in gates {
+            let EulerAngles { phase, beta, gamma, delta } = zyz_angles(&gate.matrix());
+            let rebuilt = rotation(Axis::Z, beta) * rotation(Axis::Y, gamma) * rotation(Axis::Z, delta)
+                * Complex64::from_polar(1.0, phase);
+            let expected = Matrix2::from_row_slice(&gate.matrix());
+            assert!(same(&rebuilt, &expected), "{}: {} != {}", gate.name(), rebuilt, expected);
+        }
+    }
+
+    #[test]
+    fn test_kak_decomposition_reconstructs_gate() {
+        let gates: Vec<CircuitGate> = vec![
+            TwoQubitGate::CNOT.into(), TwoQubitGate::SWAP.into(), TwoQubitGate::ISWAP.into(),
+            ParametricGate::RYY { theta: 0.6 }.into(), ParametricGate::CPhase { theta: 1.1 }.into(),
+            generic_two_qubit().into(),
+        ];
+
+        for gate in gates {
+            let kak = kak_decomposition(&gate.matrix());
+            let local = |(m0, m1): &(Vec<Amplitude>, Vec<Amplitude>)| {
+                Matrix2::from_row_slice(m0).kronecker(&Matrix2::from_row_slice(m1))
+            };
+            let (a, b, c) = kak.coefficients;
+            let interaction = Matrix4::from_row_slice(&ParametricGate::RXX { theta: -2.0 * a }.matrix())
+                * Matrix4::from_row_slice(&ParametricGate::RYY { theta: -2.0 * b }.matrix())
+                * Matrix4::from_row_slice(&ParametricGate::RZZ { theta: -2.0 * c }.matrix());
+
+            let rebuilt = local(&kak.after) * interaction * local(&kak.before) * Complex64::from_polar(1.0, kak.phase);
+            let expected = Matrix4::from_row_slice(&gate.matrix());
+            assert!((rebuilt - expected).iter().all(|x| x.norm() < 1e-8), "{}", gate.name());
+        }
+    }
+
+    #[test]
+    fn test_two_qubit_gates_use_at_most_three_cnots() {
+        let basis = Basis::default();
+        let gates: Vec<CircuitGate> = vec![
+            TwoQubitGate::SWAP.into(), TwoQubitGate::ISWAP.into(), TwoQubitGate::SqrtISWAPDagger.into(),
+            ParametricGate::RZZ { theta: 0.3 }.into(), ParametricGate::CRX { theta: 2.2 }.into(),
+            generic_two_qubit().into(),
+        ];
+
+        for gate in gates {
+            let decomposed = assert_equivalent(&single_gate(gate.clone(), &[1, 0], 2), &basis);
+            let cnots = decomposed.gate_counts().get("CNOT").copied().unwrap_or(0);
+            assert!(cnots <= 3, "{}: {} CNOT", gate.name(), cnots);
+        }
+
+        // Управляемые однокубитовые гейты раскладываются через два CNOT
+        let crx = decompose_gate(&ParametricGate::CRX { theta: 2.2 }.into(), &basis).unwrap();
+        assert_eq!(crx.gate_counts().get("CNOT"), Some(&2));
+        // Локальные гейты не требуют CNOT
+        let local = CustomGate::new(row_major(&basic(BasicGate::Hadamard).kronecker(&rotation(Axis::X, 0.4))), 2, "local");
+        assert_eq!(decompose_gate(&local.into(), &basis).unwrap().gate_counts().get("CNOT"), None);
+    }
+
+    #[test]
+    fn test_multi_controlled_gates_become_toffoli_and_cnot_ladders() {
+        let mcx = BasicGate::PauliX.controlled(3);
+        let controlled_ry = RotationGate { axis: Axis::Y, angle: 0.9 }.controlled(2);
+        let fredkin = TwoQubitGate::SWAP.controlled(1);
+        let negated = CircuitGate::from(BasicGate::PauliZ).controlled_with(&[false, true]);
+
+        for basis in [Basis::default(), Basis::quest(), Basis::new(&[BasisGate::RZ, BasisGate::RX, BasisGate::CZ])] {
+            assert_equivalent(&single_gate(mcx.clone(), &[3, 0, 2, 1], 4), &basis);
+            assert_equivalent(&single_gate(controlled_ry.clone(), &[0, 2, 1], 3), &basis);
+            assert_equivalent(&single_gate(fredkin.clone(), &[2, 0, 1], 3), &basis);
+            assert_equivalent(&single_gate(negated.clone(), &[1, 2, 0], 3), &basis);
+        }
+
+        // Без Тоффоли в базисе он раскладывается в шесть CNOT
+        let network = decompose_gate(&toffoli(), &Basis::default()).unwrap();
+        assert_eq!(network.gate_counts().get("CNOT"), Some(&6));
+        let ladder = decompose_gate(&mcx, &Basis::quest()).unwrap();
+        assert!(ladder.gate_counts().contains_key(toffoli().name()));
+    }
+
+    #[test]
+    fn test_decompose_circuit_preserves_non_unitary_instructions() {
+        let mut circuit = Circuit::new(3, 2);
+        circuit.h(0).swap(0, 1).measure(0, 0).reset(1).barrier_all()
+            .c_if(&[0], 1, Instruction::gate(TwoQubitGate::SWAP, &[1, 2]))
+            .measure(2, 1);
+
+        let decomposed = decompose(&circuit, &Basis::quest()).unwrap();
+        assert_eq!(decomposed.num_cbits(), 2);
+        assert_eq!(decomposed.gate_counts().get("SWAP"), None);
+        assert_eq!(decomposed.gate_counts().get("CNOT"), Some(&6));
+        let conditional = decomposed.instructions().iter()
+            .filter(|instruction| matches!(instruction, Instruction::Conditional { value: 1, .. }))
+            .count();
+        assert_eq!(conditional, 3);
+
+        // Если SWAP есть в базисе, схема не меняется
+        let basis = Basis::new(&[BasisGate::H, BasisGate::SWAP]);
+        assert_eq!(decompose(&circuit, &basis).unwrap().len(), circuit.len());
+    }
+
+    #[test]
+    fn test_unsupported_decompositions_are_rejected() {
+        let mut circuit = Circuit::new(3, 0);
+        circuit.h(0).cnot(0, 1).gate(ParametricGate::RXX { theta: 0.8 }, &[1, 2]).ry(2, 0.4);
+        let generic = CustomGate::new(circuit.unitary().unwrap(), 3, "generic");
+        let non_unitary = CustomGate { matrix: vec![Amplitude::new(1.0, 0.0); 4], num_qubits: 1, name: "ones".to_string() };
+        let no_entangler = Basis::new(&[BasisGate::RZ, BasisGate::RY]);
+        let no_rotations = Basis::new(&[BasisGate::H, BasisGate::CNOT]);
+
+        assert!(matches!(decompose_gate(&generic.into(), &Basis::default()), Err(TriadError::InvalidArgument(_))));
+        assert!(matches!(decompose_gate(&non_unitary.into(), &Basis::default()), Err(TriadError::NonUnitary(_))));
+        assert!(matches!(decompose_gate(&TwoQubitGate::ISWAP.into(), &no_entangler), Err(TriadError::InvalidArgument(_))));
+        assert!(matches!(decompose_gate(&BasicGate::T.into(), &no_rotations), Err(TriadError::InvalidArgument(_))));
+    }
+}
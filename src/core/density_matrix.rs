@@ -0,0 +1,803 @@
+//! Симулятор матрицы плотности на чистом Rust.
+//!
+//! В отличие от вектора состояния, матрица плотности описывает и смешанные
+//! состояния, поэтому позволяет моделировать шум и декогеренцию с помощью
+//! каналов Крауса (`core::noise`).
+//!
+//! Матрица `ρ` размера 2^n x 2^n хранится векторизованной: элемент `ρ[r][c]`
+//! находится по индексу `r·2^n + c`, то есть биты строки занимают старшие `n`
+//! разрядов, а биты столбца — младшие. Тогда `UρU†` — это действие `U ⊗ U*`
+//! на вектор из 2n «кубитов», и для него используются ядра `StateVector`.
+
+use crate::core::gates::{Axis, Gate, RotationGate, controlled_matrix};
+use crate::core::noise::KrausChannel;
+use crate::core::pauli::{Pauli, parse_pauli_product};
+use crate::core::quantum_simulator::{
+    QuantumSimulator, AdvancedQuantumSimulator, validate_gate_targets, validate_joint_outcome,
+    validate_qubit, validate_qubits,
+};
+use crate::core::quantum_state::{QuantumState, Amplitude, MeasureWith, StateSnapshot};
+use crate::core::state_vector::StateVector;
+use crate::core::measurement::{Povm, choose_outcome, outcome_probabilities, validate_povm_targets};
+use crate::error::TriadError;
+use nalgebra::DMatrix;
+use num_complex::Complex64;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// Максимальное число кубитов для матрицы плотности
+/// (4^14 элементов занимают 4 ГБ памяти).
+pub const MAX_QUBITS: usize = 14;
+
+/// Допуск, используемый при сравнении вероятностей с нулем и единицей.
+const EPSILON: f64 = 1e-10;
+
+/// Квантовое состояние, представленное матрицей плотности.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DensityMatrix {
+    /// Число кубитов в системе.
+    num_qubits: usize,
+    /// Векторизованная матрица: 2n «кубитов», старшие n — индекс строки.
+    elements: StateVector,
+}
+
+impl DensityMatrix {
+    /// Создает состояние |0...0⟩⟨0...0| из указанного числа кубитов.
+    pub fn new(num_qubits: usize) -> Self {
+        Self::try_new(num_qubits).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Создает состояние |0...0⟩⟨0...0|, возвращая ошибку для недопустимого числа кубитов.
+    pub fn try_new(num_qubits: usize) -> Result<Self, TriadError> {
+        if num_qubits == 0 {
+            return Err(TriadError::NoQubits);
+        }
+        if num_qubits > MAX_QUBITS {
+            return Err(TriadError::TooManyQubits { requested: num_qubits, max: MAX_QUBITS });
+        }
+
+        Ok(Self {
+            num_qubits,
+            elements: StateVector::new(2 * num_qubits),
+        })
+    }
+
+    /// Создает чистое состояние |ψ⟩⟨ψ| по вектору состояния.
+    pub fn from_state_vector(state: &StateVector) -> Self {
+        let num_qubits = state.num_qubits();
+        assert!(num_qubits <= MAX_QUBITS, "{}", TriadError::TooManyQubits { requested: num_qubits, max: MAX_QUBITS });
+
+        let amplitudes = state.amplitudes();
+        let elements = amplitudes.iter()
+            .flat_map(|row| amplitudes.iter().map(move |col| row * col.conj()))
+            .collect();
+
+        Self {
+            num_qubits,
+            elements: StateVector::from_amplitudes(elements),
+        }
+    }
+
+    /// Создает максимально смешанное состояние I / 2^n.
+    pub fn maximally_mixed(num_qubits: usize) -> Self {
+        let mut state = Self::new(num_qubits);
+        let dim = state.dim();
+        let elements = (0..dim * dim)
+            .map(|i| if i / dim == i % dim { Complex64::new(1.0 / dim as f64, 0.0) } else { Complex64::new(0.0, 0.0) })
+            .collect();
+        state.elements = StateVector::from_amplitudes(elements);
+        state
+    }
+
+    /// Возвращает размерность пространства состояний 2^n.
+    pub fn dim(&self) -> usize {
+        1 << self.num_qubits
+    }
+
+    /// Возвращает элемент `ρ[row][col]`.
+    pub fn element(&self, row: usize, col: usize) -> Amplitude {
+        self.elements.amplitudes()[row * self.dim() + col]
+    }
+
+    /// Возвращает элементы матрицы по строкам.
+    pub fn elements(&self) -> &[Amplitude] {
+        self.elements.amplitudes()
+    }
+
+    /// Возвращает вектор вероятностей базисных состояний (диагональ матрицы).
+    pub fn probabilities(&self) -> Vec<f64> {
+        (0..self.dim()).map(|i| self.element(i, i).re).collect()
+    }
+
+    /// Возвращает след матрицы плотности.
+    pub fn trace(&self) -> f64 {
+        self.probabilities().iter().sum()
+    }
+
+    /// Возвращает чистоту состояния Tr(ρ²): 1 для чистых состояний
+    /// и 1/2^n для максимально смешанного.
+    pub fn purity(&self) -> f64 {
+        // Для эрмитовой матрицы Tr(ρ²) = Σ |ρ_ij|²
+        self.elements.norm_sqr()
+    }
+
+    /// Вычисляет точность (fidelity) Ульмана `F(ρ, σ) = (Tr √(√ρ σ √ρ))²`.
+    /// Для чистых состояний совпадает с |⟨ψ|φ⟩|².
+    pub fn fidelity(&self, other: &DensityMatrix) -> f64 {
+        self.try_fidelity(other).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Вычисляет точность Ульмана, возвращая ошибку для состояний разной размерности.
+    pub fn try_fidelity(&self, other: &DensityMatrix) -> Result<f64, TriadError> {
+        if self.num_qubits != other.num_qubits {
+            return Err(TriadError::InvalidArgument(format!(
+                "состояния должны иметь одинаковое число кубитов ({} и {})", self.num_qubits, other.num_qubits)));
+        }
+
+        let sqrt_rho = hermitian_function(&self.to_matrix(), f64::sqrt);
+        let product = &sqrt_rho * other.to_matrix() * &sqrt_rho;
+        let root_trace: f64 = product.symmetric_eigen().eigenvalues.iter()
+            .map(|&lambda| lambda.max(0.0).sqrt())
+            .sum();
+        Ok(root_trace * root_trace)
+    }
+
+    /// Проверяет, что индекс кубита находится в допустимых пределах.
+    fn check_qubit(&self, qubit: usize) {
+        validate_qubit(qubit, self.num_qubits).unwrap_or_else(|err| panic!("{}", err));
+    }
+
+    /// Применяет оператор `K` (матрица 2^k x 2^k по строкам) как `ρ → KρK†`.
+    /// Первый кубит в `targets` соответствует старшему биту индекса матрицы.
+    pub fn apply_operator_to(&mut self, targets: &[usize], matrix: &[Amplitude]) {
+        for &target in targets {
+            self.check_qubit(target);
+        }
+
+        let rows: Vec<usize> = targets.iter().map(|&q| q + self.num_qubits).collect();
+        let conjugate: Vec<Amplitude> = matrix.iter().map(|m| m.conj()).collect();
+        self.elements.apply_multi_qubit(&rows, matrix);
+        self.elements.apply_multi_qubit(targets, &conjugate);
+    }
+
+    /// Применяет канал Крауса к указанным кубитам: `ρ → Σ K_i ρ K_i†`.
+    pub fn apply_channel(&mut self, channel: &KrausChannel, targets: &[usize]) {
+        self.try_apply_channel(channel, targets).unwrap_or_else(|err| panic!("{}", err));
+    }
+
+    /// Применяет канал Крауса, проверяя число и индексы целевых кубитов.
+    pub fn try_apply_channel(&mut self, channel: &KrausChannel, targets: &[usize]) -> Result<(), TriadError> {
+        if channel.num_qubits() != targets.len() {
+            return Err(TriadError::InvalidArgument(format!(
+                "канал {} действует на {} кубит(ов), но указано {} целевых кубитов",
+                channel.name(), channel.num_qubits(), targets.len())));
+        }
+        validate_qubits(targets, self.num_qubits)?;
+
+        let mut sum = vec![Complex64::new(0.0, 0.0); self.elements.amplitudes().len()];
+        for operator in channel.operators() {
+            let mut term = self.clone();
+            term.apply_operator_to(targets, operator);
+            for (acc, value) in sum.iter_mut().zip(term.elements.amplitudes()) {
+                *acc += value;
+            }
+        }
+        self.elements = StateVector::from_amplitudes(sum);
+        Ok(())
+    }
+
+    /// Вычисляет вероятность получить `outcome` при измерении кубита.
+    pub fn probability_of_outcome(&self, qubit: usize, outcome: bool) -> f64 {
+        self.check_qubit(qubit);
+        self.probabilities().iter()
+            .enumerate()
+            .filter(|(i, _)| ((i >> qubit) & 1 == 1) == outcome)
+            .map(|(_, p)| p)
+            .sum()
+    }
+
+    /// Вычисляет совместную вероятность получить битовую строку `outcome`
+    /// при измерении кубитов `qubits` (бит `i` соответствует `qubits[i]`).
+    pub fn probability_of_joint_outcome(&self, qubits: &[usize], outcome: u64) -> f64 {
+        validate_joint_outcome(qubits, outcome, self.num_qubits)
+            .unwrap_or_else(|err| panic!("{}", err));
+
+        let (mask, expected) = qubits.iter().enumerate().fold((0usize, 0usize), |(mask, expected), (idx, &q)| {
+            let bit = ((outcome >> idx) & 1) as usize;
+            (mask | (1 << q), expected | (bit << q))
+        });
+
+        self.probabilities().iter()
+            .enumerate()
+            .filter(|(i, _)| i & mask == expected)
+            .map(|(_, p)| p)
+            .sum()
+    }
+
+    /// Проецирует состояние на указанный результат измерения кубита
+    /// и перенормирует его.
+    pub fn collapse(&mut self, qubit: usize, outcome: bool) {
+        let prob = self.probability_of_outcome(qubit, outcome);
+        if prob < EPSILON {
+            panic!("Невозможно спроецировать кубит {} на результат с нулевой вероятностью", qubit);
+        }
+
+        let dim = self.dim();
+        let keep = |i: usize| ((i >> qubit) & 1 == 1) == outcome;
+        let elements = self.elements.amplitudes().iter()
+            .enumerate()
+            .map(|(i, &value)| if keep(i / dim) && keep(i % dim) { value / prob } else { Complex64::new(0.0, 0.0) })
+            .collect();
+        self.elements = StateVector::from_amplitudes(elements);
+    }
+
+    /// Измеряет кубит, используя заданное случайное число из [0, 1).
+    /// Результат равен 1, если `random` не меньше вероятности получить 0.
+    /// Исход с вероятностью меньше `EPSILON` заменяется противоположным.
+    pub fn measure_with(&mut self, qubit: usize, random: f64) -> bool {
+        let prob_zero = self.probability_of_outcome(qubit, false);
+        let mut outcome = random >= prob_zero;
+        if self.probability_of_outcome(qubit, outcome) < EPSILON {
+            outcome = !outcome;
+        }
+        self.collapse(qubit, outcome);
+        outcome
+    }
+
+    /// Выполняет обобщенное измерение над кубитами `targets`, выбирая исход
+    /// по случайному числу `random` из [0, 1): `ρ → M_k ρ M_k† / p_k`.
+    /// Возвращает номер исхода.
+    pub fn measure_povm_with(&mut self, povm: &Povm, targets: &[usize], random: f64) -> usize {
+        validate_povm_targets(povm, targets, self.num_qubits).unwrap_or_else(|err| panic!("{}", err));
+
+        let probabilities = outcome_probabilities(povm, &self.reduced_density_matrix(targets));
+        let outcome = choose_outcome(&probabilities, random);
+
+        self.apply_operator_to(targets, &povm.operators()[outcome]);
+        let trace = self.trace();
+        self.elements = StateVector::from_amplitudes(
+            self.elements.amplitudes().iter().map(|&value| value / trace).collect());
+        outcome
+    }
+
+    /// Вычисляет редуцированную матрицу плотности кубитов `targets` (по строкам)
+    /// частичным следом по остальным кубитам. Первый кубит соответствует
+    /// старшему биту индекса, как у матриц гейтов.
+    pub fn reduced_density_matrix(&self, targets: &[usize]) -> Vec<Amplitude> {
+        validate_qubits(targets, self.num_qubits).unwrap_or_else(|err| panic!("{}", err));
+
+        let k = targets.len();
+        let dim = 1usize << k;
+        let offsets: Vec<usize> = (0..dim)
+            .map(|row| {
+                targets.iter().enumerate().fold(0, |acc, (j, &q)| {
+                    if (row >> (k - 1 - j)) & 1 == 1 { acc | (1 << q) } else { acc }
+                })
+            })
+            .collect();
+        let targets_mask = targets.iter().fold(0usize, |acc, &q| acc | (1 << q));
+
+        (0..dim * dim)
+            .map(|i| {
+                let (row, col) = (offsets[i / dim], offsets[i % dim]);
+                (0..self.dim())
+                    .filter(|base| base & targets_mask == 0)
+                    .map(|base| self.element(base | row, base | col))
+                    .sum()
+            })
+            .collect()
+    }
+
+    /// Вычисляет ожидаемое значение произведения операторов Паули Tr(Pρ).
+    pub fn expectation_pauli(&self, pauli_product: &[(usize, char)]) -> f64 {
+        self.try_expectation_pauli(pauli_product)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Вычисляет ожидаемое значение произведения операторов Паули,
+    /// возвращая ошибку для некорректного произведения.
+    pub fn try_expectation_pauli(&self, pauli_product: &[(usize, char)]) -> Result<f64, TriadError> {
+        let parsed = parse_pauli_product(pauli_product, self.num_qubits)?;
+        let mut transformed = self.elements.clone();
+
+        // Умножаем ρ слева на P: операторы действуют только на индексы строк
+        for (qubit, pauli) in parsed {
+            if pauli != Pauli::I {
+                transformed.apply_single_qubit(qubit + self.num_qubits, &pauli.matrix());
+            }
+        }
+
+        let dim = self.dim();
+        Ok((0..dim).map(|i| transformed.amplitudes()[i * dim + i].re).sum())
+    }
+
+    /// Возвращает матрицу в виде `nalgebra::DMatrix`.
+    fn to_matrix(&self) -> DMatrix<Complex64> {
+        DMatrix::from_row_slice(self.dim(), self.dim(), self.elements.amplitudes())
+    }
+
+    /// Возвращает частично транспонированную по кубиту `qubit` матрицу.
+    fn partial_transpose(&self, qubit: usize) -> DMatrix<Complex64> {
+        let mask = 1usize << qubit;
+        DMatrix::from_fn(self.dim(), self.dim(), |row, col| {
+            // Меняем местами бит кубита в индексах строки и столбца
+            let (row_bit, col_bit) = (row & mask, col & mask);
+            self.element((row & !mask) | col_bit, (col & !mask) | row_bit)
+        })
+    }
+}
+
+/// Применяет вещественную функцию к собственным значениям эрмитовой матрицы.
+fn hermitian_function(matrix: &DMatrix<Complex64>, function: impl Fn(f64) -> f64) -> DMatrix<Complex64> {
+    let eigen = matrix.clone().symmetric_eigen();
+    let values = DMatrix::from_diagonal(&eigen.eigenvalues.map(|lambda| Complex64::new(function(lambda.max(0.0)), 0.0)));
+    &eigen.eigenvectors * values * eigen.eigenvectors.adjoint()
+}
+
+impl From<&StateVector> for DensityMatrix {
+    fn from(state: &StateVector) -> Self {
+        Self::from_state_vector(state)
+    }
+}
+
+impl MeasureWith for DensityMatrix {
+    fn measure_with(&mut self, qubit: usize, random: f64) -> bool {
+        DensityMatrix::measure_with(self, qubit, random)
+    }
+}
+
+impl QuantumState for DensityMatrix {
+    fn num_qubits(&self) -> usize {
+        self.num_qubits
+    }
+
+    fn probability(&self, state: u64) -> f64 {
+        if state as usize >= self.dim() {
+            return 0.0;
+        }
+        self.element(state as usize, state as usize).re
+    }
+
+    /// Для чистого состояния возвращает амплитуду с точностью до глобальной
+    /// фазы: `ρ[i][k] / √ρ[k][k]`, где `k` — наиболее вероятное базисное
+    /// состояние. Для смешанных состояний амплитуда не определена,
+    /// и результат является лишь согласованным с диагональю приближением.
+    fn amplitude(&self, state: u64) -> Amplitude {
+        if state as usize >= self.dim() {
+            return Complex64::new(0.0, 0.0);
+        }
+
+        let probabilities = self.probabilities();
+        let (reference, &weight) = probabilities.iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .unwrap();
+        if weight < EPSILON {
+            return Complex64::new(0.0, 0.0);
+        }
+        self.element(state as usize, reference) / weight.sqrt()
+    }
+
+    /// Применяет унитарный оператор, заданный полной матрицей 2^n x 2^n по строкам.
+    fn apply_operator(&mut self, operator: &[Amplitude]) {
+        let targets: Vec<usize> = (0..self.num_qubits).rev().collect();
+        self.apply_operator_to(&targets, operator);
+    }
+
+    fn measure(&mut self, qubit: usize) -> bool {
+        let random = rand::thread_rng().gen::<f64>();
+        self.measure_with(qubit, random)
+    }
+
+    /// Для чистых состояний проверка точна: состояние запутано, если
+    /// редуцированное состояние какого-либо кубита смешанное. Для смешанных
+    /// состояний используется критерий Переса — Хородецких для разрезов
+    /// «один кубит — остальные»: он точен для двух кубитов, но при большем
+    /// числе кубитов может не обнаружить запутанность.
+    fn is_entangled(&self) -> bool {
+        if self.num_qubits < 2 {
+            return false;
+        }
+
+        if (self.purity() - 1.0).abs() < 1e-9 {
+            let state = StateVector::from_amplitudes((0..self.dim() as u64).map(|i| self.amplitude(i)).collect());
+            return state.is_entangled();
+        }
+
+        (0..self.num_qubits).any(|qubit| {
+            self.partial_transpose(qubit).symmetric_eigen().eigenvalues.iter().any(|&lambda| lambda < -1e-9)
+        })
+    }
+}
+
+/// Симулятор матрицы плотности на чистом Rust.
+/// Поддерживает смешанные состояния и каналы шума, до `MAX_QUBITS` кубитов.
+#[derive(Debug, Clone)]
+pub struct DensityMatrixSimulator {
+    /// Текущее состояние регистра.
+    state: DensityMatrix,
+    /// Генератор случайных чисел для измерений и выборок.
+    rng: StdRng,
+}
+
+impl DensityMatrixSimulator {
+    /// Создает новый симулятор с указанным количеством кубитов в состоянии |0...0⟩.
+    pub fn new(num_qubits: usize) -> Self {
+        Self {
+            state: DensityMatrix::new(num_qubits),
+            rng: StdRng::from_entropy(),
+        }
+    }
+
+    /// Создает новый симулятор, возвращая ошибку для недопустимого числа кубитов.
+    pub fn try_new(num_qubits: usize) -> Result<Self, TriadError> {
+        Ok(Self {
+            state: DensityMatrix::try_new(num_qubits)?,
+            rng: StdRng::from_entropy(),
+        })
+    }
+
+    /// Возвращает количество кубитов в регистре.
+    pub fn num_qubits(&self) -> usize {
+        self.state.num_qubits
+    }
+
+    /// Возвращает ссылку на текущую матрицу плотности.
+    pub fn density_matrix(&self) -> &DensityMatrix {
+        &self.state
+    }
+
+    /// Возвращает массив вероятностей для каждого из возможных состояний.
+    pub fn get_probabilities(&self) -> Vec<f64> {
+        self.state.probabilities()
+    }
+
+    /// Возвращает чистоту текущего состояния Tr(ρ²).
+    pub fn purity(&self) -> f64 {
+        self.state.purity()
+    }
+
+    /// Вычисляет точность текущего состояния относительно `other`.
+    pub fn fidelity(&self, other: &DensityMatrix) -> f64 {
+        self.state.fidelity(other)
+    }
+
+    /// Применяет канал Крауса к указанным кубитам.
+    pub fn apply_channel(&mut self, channel: &KrausChannel, targets: &[usize]) {
+        self.state.apply_channel(channel, targets);
+    }
+
+    /// Применяет канал Крауса, возвращая ошибку для некорректных кубитов.
+    pub fn try_apply_channel(&mut self, channel: &KrausChannel, targets: &[usize]) -> Result<(), TriadError> {
+        self.state.try_apply_channel(channel, targets)
+    }
+
+    /// Применяет однокубитовый оператор к кубиту.
+    fn apply_single(&mut self, qubit: usize, matrix: &[Amplitude]) {
+        self.state.apply_operator_to(&[qubit], matrix);
+    }
+
+    /// Применяет гейт вращения вокруг указанной оси.
+    fn rotate(&mut self, qubit: usize, axis: Axis, angle: f64) {
+        let gate = RotationGate { axis, angle };
+        self.apply_single(qubit, &gate.matrix());
+    }
+
+    /// Применяет однокубитовый оператор при условии, что контрольный кубит в |1⟩.
+    fn apply_controlled(&mut self, control: usize, target: usize, matrix: &[Amplitude]) {
+        let zero = Complex64::new(0.0, 0.0);
+        let one = Complex64::new(1.0, 0.0);
+        let full = vec![
+            one, zero, zero, zero,
+            zero, one, zero, zero,
+            zero, zero, matrix[0], matrix[1],
+            zero, zero, matrix[2], matrix[3],
+        ];
+        self.state.apply_operator_to(&[control, target], &full);
+    }
+}
+
+impl QuantumSimulator for DensityMatrixSimulator {
+    fn new(num_qubits: usize) -> Self where Self: Sized {
+        Self::new(num_qubits)
+    }
+
+    fn try_new(num_qubits: usize) -> Result<Self, TriadError> where Self: Sized {
+        Self::try_new(num_qubits)
+    }
+
+    fn num_qubits(&self) -> usize {
+        self.state.num_qubits
+    }
+
+    fn get_state(&self) -> Box<dyn QuantumState> {
+        Box::new(StateSnapshot::new(self.state.clone(), &self.rng))
+    }
+
+    fn reset(&mut self) {
+        self.state = DensityMatrix::new(self.state.num_qubits);
+    }
+
+    fn hadamard(&mut self, qubit: usize) {
+        let factor = Complex64::new(1.0 / 2.0_f64.sqrt(), 0.0);
+        self.apply_single(qubit, &[factor, factor, factor, -factor]);
+    }
+
+    fn x(&mut self, qubit: usize) {
+        self.apply_single(qubit, &Pauli::X.matrix());
+    }
+
+    fn y(&mut self, qubit: usize) {
+        self.apply_single(qubit, &Pauli::Y.matrix());
+    }
+
+    fn z(&mut self, qubit: usize) {
+        self.apply_single(qubit, &Pauli::Z.matrix());
+    }
+
+    fn cnot(&mut self, control: usize, target: usize) {
+        self.apply_controlled(control, target, &Pauli::X.matrix());
+    }
+
+    fn measure(&mut self, qubit: usize) -> bool {
+        let random = self.rng.gen::<f64>();
+        self.state.measure_with(qubit, random)
+    }
+
+    fn rng(&mut self) -> Option<&mut StdRng> {
+        Some(&mut self.rng)
+    }
+
+    fn try_measure_povm(&mut self, povm: &Povm, targets: &[usize]) -> Result<usize, TriadError> {
+        validate_povm_targets(povm, targets, self.state.num_qubits)?;
+        let random = self.rng.gen::<f64>();
+        Ok(self.state.measure_povm_with(povm, targets, random))
+    }
+
+    fn apply_gate(&mut self, gate: &dyn Gate, targets: &[usize]) {
+        validate_gate_targets(gate, targets, self.state.num_qubits)
+            .unwrap_or_else(|err| panic!("{}", err));
+        self.state.apply_operator_to(targets, &gate.matrix());
+    }
+
+    fn get_expectation_value(&self, pauli_product: &[(usize, char)]) -> f64 {
+        self.state.expectation_pauli(pauli_product)
+    }
+
+    fn probability_of_outcome(&self, qubit: usize, outcome: bool) -> f64 {
+        self.state.probability_of_outcome(qubit, outcome)
+    }
+
+    fn probability_of_joint_outcome(&self, qubits: &[usize], outcome: u64) -> f64 {
+        self.state.probability_of_joint_outcome(qubits, outcome)
+    }
+}
+
+impl AdvancedQuantumSimulator for DensityMatrixSimulator {
+    fn s_gate(&mut self, qubit: usize) {
+        let one = Complex64::new(1.0, 0.0);
+        let zero = Complex64::new(0.0, 0.0);
+        self.apply_single(qubit, &[one, zero, zero, Complex64::new(0.0, 1.0)]);
+    }
+
+    fn t_gate(&mut self, qubit: usize) {
+        let one = Complex64::new(1.0, 0.0);
+        let zero = Complex64::new(0.0, 0.0);
+        let phase = Complex64::from_polar(1.0, std::f64::consts::FRAC_PI_4);
+        self.apply_single(qubit, &[one, zero, zero, phase]);
+    }
+
+    fn cz(&mut self, control: usize, target: usize) {
+        self.apply_controlled(control, target, &Pauli::Z.matrix());
+    }
+
+    fn swap(&mut self, qubit1: usize, qubit2: usize) {
+        let zero = Complex64::new(0.0, 0.0);
+        let one = Complex64::new(1.0, 0.0);
+        let matrix = [
+            one, zero, zero, zero,
+            zero, zero, one, zero,
+            zero, one, zero, zero,
+            zero, zero, zero, one,
+        ];
+        self.state.apply_operator_to(&[qubit1, qubit2], &matrix);
+    }
+
+    fn apply_unitary(&mut self, qubit: usize, matrix: &[Amplitude]) {
+        self.apply_single(qubit, matrix);
+    }
+
+    fn rx(&mut self, qubit: usize, angle: f64) {
+        self.rotate(qubit, Axis::X, angle);
+    }
+
+    fn ry(&mut self, qubit: usize, angle: f64) {
+        self.rotate(qubit, Axis::Y, angle);
+    }
+
+    fn rz(&mut self, qubit: usize, angle: f64) {
+        self.rotate(qubit, Axis::Z, angle);
+    }
+
+    fn controlled_unitary(&mut self, control: usize, target: usize, matrix: &[Amplitude]) {
+        self.apply_controlled(control, target, matrix);
+    }
+
+    fn multi_controlled_unitary(&mut self, controls: &[usize], target: usize, matrix: &[Amplitude]) {
+        let qubits: Vec<usize> = controls.iter().copied().chain(std::iter::once(target)).collect();
+        self.state.apply_operator_to(&qubits, &controlled_matrix(controls.len(), matrix));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::circuit::Circuit;
+    use crate::core::gates::BasicGate;
+    use crate::core::state_vector::StateVectorSimulator;
+
+    const TOLERANCE: f64 = 1e-10;
+
+    #[test]
+    fn test_pure_evolution_matches_state_vector() {
+        let mut circuit = Circuit::new(3, 0);
+        circuit.h(0).cnot(0, 1).ry(2, 0.7).s(1).t(2).cz(2, 0).swap(0, 2).rx(1, -1.3);
+
+        let mut pure = StateVectorSimulator::new(3);
+        let mut mixed = DensityMatrixSimulator::new(3);
+        circuit.run_on(&mut pure).unwrap();
+        circuit.run_on(&mut mixed).unwrap();
+
+        let expected = DensityMatrix::from_state_vector(pure.state_vector());
+        for (a, b) in expected.elements().iter().zip(mixed.density_matrix().elements()) {
+            assert!((a - b).norm() < TOLERANCE);
+        }
+        assert!((mixed.purity() - 1.0).abs() < TOLERANCE);
+        assert!((mixed.get_expectation_value(&[(0, 'Z'), (2, 'X')]) - pure.get_expectation_value(&[(0, 'Z'), (2, 'X')])).abs() < TOLERANCE);
+        assert!((mixed.probability_of_joint_outcome(&[0, 1], 0b10) - pure.probability_of_joint_outcome(&[0, 1], 0b10)).abs() < TOLERANCE);
+    }
+
+    #[test]
+    fn test_measurement_collapses_state() {
+        let mut simulator = DensityMatrixSimulator::new(2);
+        simulator.hadamard(0);
+        simulator.cnot(0, 1);
+
+        let outcome = simulator.measure(0);
+        assert_eq!(simulator.measure(1), outcome);
+        assert!((simulator.purity() - 1.0).abs() < TOLERANCE);
+        assert!((simulator.density_matrix().trace() - 1.0).abs() < TOLERANCE);
+    }
+
+    #[test]
+    fn test_depolarizing_and_flip_channels() {
+        let mut simulator = DensityMatrixSimulator::new(1);
+        simulator.apply_channel(&KrausChannel::depolarizing(0.75).unwrap(), &[0]);
+        assert!((simulator.purity() - 0.5).abs() < TOLERANCE);
+        assert!((simulator.fidelity(&DensityMatrix::maximally_mixed(1)) - 1.0).abs() < 1e-8);
+
+        let mut simulator = DensityMatrixSimulator::new(1);
+        simulator.apply_channel(&KrausChannel::bit_flip(0.3).unwrap(), &[0]);
+        assert!((simulator.probability_of_outcome(0, true) - 0.3).abs() < TOLERANCE);
+
+        // Инверсия фазы уменьшает ⟨X⟩ состояния |+⟩ в (1 - 2p) раз
+        let mut simulator = DensityMatrixSimulator::new(1);
+        simulator.hadamard(0);
+        simulator.apply_channel(&KrausChannel::phase_flip(0.1).unwrap(), &[0]);
+        assert!((simulator.get_expectation_value(&[(0, 'X')]) - 0.8).abs() < TOLERANCE);
+    }
+
+    #[test]
+    fn test_damping_channels() {
+        let mut simulator = DensityMatrixSimulator::new(2);
+        simulator.x(1);
+        simulator.apply_channel(&KrausChannel::amplitude_damping(0.25).unwrap(), &[1]);
+        assert!((simulator.probability_of_outcome(1, true) - 0.75).abs() < TOLERANCE);
+        simulator.apply_channel(&KrausChannel::amplitude_damping(1.0).unwrap(), &[1]);
+        assert!((simulator.get_probabilities()[0] - 1.0).abs() < TOLERANCE);
+
+        let mut simulator = DensityMatrixSimulator::new(1);
+        simulator.hadamard(0);
+        simulator.apply_channel(&KrausChannel::phase_damping(0.36).unwrap(), &[0]);
+        assert!((simulator.get_expectation_value(&[(0, 'X')]) - 0.8).abs() < TOLERANCE);
+        assert!((simulator.probability_of_outcome(0, false) - 0.5).abs() < TOLERANCE);
+    }
+
+    #[test]
+    fn test_fidelity() {
+        let mut plus = StateVector::new(1);
+        plus.apply_single_qubit(0, &BasicGate::Hadamard.matrix());
+        let plus = DensityMatrix::from(&plus);
+        let zero = DensityMatrix::new(1);
+        let mut one = DensityMatrix::new(1);
+        one.apply_operator_to(&[0], &Pauli::X.matrix());
+
+        assert!((plus.fidelity(&plus) - 1.0).abs() < 1e-8);
+        assert!(zero.fidelity(&one).abs() < 1e-8);
+        assert!((plus.fidelity(&zero) - 0.5).abs() < 1e-8);
+        assert!((plus.fidelity(&DensityMatrix::maximally_mixed(1)) - 0.5).abs() < 1e-8);
+        assert!(plus.try_fidelity(&DensityMatrix::new(2)).is_err());
+    }
+
+    #[test]
+    fn test_entanglement_of_mixed_states() {
+        let mut bell = DensityMatrixSimulator::new(2);
+        bell.hadamard(0);
+        bell.cnot(0, 1);
+        assert!(bell.get_state().is_entangled());
+
+        // Сильная деполяризация разрушает запутанность состояния Белла
+        bell.apply_channel(&KrausChannel::depolarizing(0.7).unwrap(), &[0]);
+        assert!(bell.purity() < 1.0);
+        assert!(!bell.get_state().is_entangled());
+
+        // Классическая смесь |00⟩ и |11⟩ не запутана
+        let mut mixture = DensityMatrixSimulator::new(2);
+        mixture.hadamard(0);
+        mixture.cnot(0, 1);
+        mixture.apply_channel(&KrausChannel::phase_flip(0.5).unwrap(), &[0]);
+        assert!((mixture.purity() - 0.5).abs() < TOLERANCE);
+        assert!(!mixture.get_state().is_entangled());
+    }
+
+    #[test]
+    fn test_try_methods_return_errors() {
+        let mut simulator = DensityMatrixSimulator::new(2);
+        let channel = KrausChannel::bit_flip(0.1).unwrap();
+
+        assert!(matches!(DensityMatrixSimulator::try_new(MAX_QUBITS + 1), Err(TriadError::TooManyQubits { .. })));
+        assert_eq!(simulator.try_apply_channel(&channel, &[2]), Err(TriadError::InvalidQubit { qubit: 2, num_qubits: 2 }));
+        assert!(matches!(simulator.try_apply_channel(&channel, &[0, 1]), Err(TriadError::InvalidArgument(_))));
+        assert_eq!(simulator.try_measure(5), Err(TriadError::InvalidQubit { qubit: 5, num_qubits: 2 }));
+    }
+
+    #[test]
+    fn test_povm_statistics_and_post_measurement_state() {
+        use crate::core::measurement::Povm;
+
+        // Трин-POVM на |0⟩: вероятности исходов 2/3, 1/6, 1/6
+        let effects: Vec<Vec<Amplitude>> = (0..3).map(|k| {
+            let angle = 2.0 * std::f64::consts::PI * k as f64 / 3.0;
+            let (c, s) = ((angle / 2.0).cos(), (angle / 2.0).sin());
+            [c * c, c * s, s * c, s * s].iter().map(|&v| Complex64::new(2.0 * v / 3.0, 0.0)).collect()
+        }).collect();
+        let trine = Povm::new("trine", effects).unwrap();
+
+        let mut counts = [0usize; 3];
+        let mut simulator = DensityMatrixSimulator::new(1);
+        simulator.seed(11);
+        for _ in 0..3000 {
+            simulator.reset();
+            let outcome = simulator.measure_povm(&trine, &[0]);
+            counts[outcome] += 1;
+            // После исхода k состояние — чистое |ψ_k⟩
+            assert!((simulator.density_matrix().purity() - 1.0).abs() < 1e-8);
+            assert!((simulator.density_matrix().trace() - 1.0).abs() < 1e-8);
+        }
+        for (count, expected) in counts.iter().zip([2.0 / 3.0, 1.0 / 6.0, 1.0 / 6.0]) {
+            assert!((*count as f64 / 3000.0 - expected).abs() < 0.03);
+        }
+
+        // Те же исходы на векторе состояния при одинаковом зерне
+        let mut dense = StateVectorSimulator::new(1);
+        let mut mixed = DensityMatrixSimulator::new(1);
+        dense.seed(5);
+        mixed.seed(5);
+        for _ in 0..20 {
+            dense.reset();
+            mixed.reset();
+            assert_eq!(dense.measure_povm(&trine, &[0]), mixed.measure_povm(&trine, &[0]));
+        }
+        assert!(mixed.try_measure_povm(&trine, &[1]).is_err());
+    }
+
+    #[test]
+    fn test_measure_with_skips_outcomes_below_epsilon() {
+        // Вероятность |1⟩ равна sin²(5e-6) ≈ 2.5e-11, но random попадает в ее интервал
+        let mut pure = StateVector::new(1);
+        pure.apply_single_qubit(0, &RotationGate { axis: Axis::Y, angle: 1e-5 }.matrix());
+        let mut state = DensityMatrix::from(&pure);
+        assert!(!state.measure_with(0, 0.99999999999));
+        assert!((state.probability(0) - 1.0).abs() < TOLERANCE);
+    }
+}
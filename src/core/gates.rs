@@ -3,11 +3,20 @@
 //! Этот модуль содержит структуры и перечисления для представления
 //! квантовых гейтов и операций над ними.
 
+use crate::core::circuit::CircuitGate;
+use crate::core::quantum_simulator::UNITARITY_TOLERANCE;
 use crate::core::quantum_state::Amplitude;
-use std::f64::consts::PI;
+use crate::error::TriadError;
+#[cfg(feature = "native")]
+use nalgebra::DMatrix;
 use num_complex::Complex64;
 
 /// Базовый интерфейс для квантового гейта.
+///
+/// Операции, порождающие новый гейт (`inverse`, `adjoint`, `controlled`, `power`),
+/// возвращают `CircuitGate`. Реализации по умолчанию строят `CustomGate`
+/// по матрице; конкретные гейты переопределяют их, чтобы результат
+/// оставался гейтом библиотеки (например, S† для S или CRX для RX).
 pub trait Gate {
     /// Возвращает унитарную матрицу, представляющую гейт.
     fn matrix(&self) -> Vec<Amplitude>;
@@ -16,11 +25,221 @@ pub trait Gate {
     fn num_qubits(&self) -> usize;
     
     /// Возвращает название гейта.
-    fn name(&self) -> &'static str;
+    fn name(&self) -> &str;
+    
+    /// Возвращает обратный гейт U†.
+    fn inverse(&self) -> CircuitGate {
+        CircuitGate::Custom(adjoint_gate(&self.matrix(), self.num_qubits(), self.name()))
+    }
+    
+    /// Возвращает эрмитово сопряженный гейт U†. Для унитарного гейта
+    /// он совпадает с обратным, поэтому по умолчанию вызывается `inverse`.
+    fn adjoint(&self) -> CircuitGate {
+        self.inverse()
+    }
+    
+    /// Возвращает гейт, управляемый `num_controls` дополнительными кубитами.
+    /// Управляющие кубиты предшествуют целевым, гейт действует, когда все они в |1⟩.
+    ///
+    /// Реализация по умолчанию строит плотную матрицу размера
+    /// 2^(n + num_controls) × 2^(n + num_controls), поэтому время и память
+    /// растут экспоненциально с числом управляющих кубитов. Библиотечные
+    /// гейты переопределяют метод там, где есть готовый гейт (CNOT, CZ, CRX, ...).
+    fn controlled(&self, num_controls: usize) -> CircuitGate {
+        controlled_gate(&self.matrix(), self.num_qubits(), self.name(), num_controls)
+    }
+    
+    /// Возвращает целую степень гейта U^k; отрицательные степени — степени U†.
+    fn power(&self, exponent: i32) -> CircuitGate {
+        if exponent < 0 {
+            let inverse = self.inverse();
+            return power_gate(&inverse.matrix(), self.num_qubits(), inverse.name(), exponent.unsigned_abs());
+        }
+        power_gate(&self.matrix(), self.num_qubits(), self.name(), exponent as u32)
+    }
+    
+    /// Проверяет, что матрица гейта унитарна с точностью `tolerance`.
+    fn is_unitary(&self, tolerance: f64) -> bool {
+        is_unitary_matrix(&self.matrix(), tolerance)
+    }
+    
+    /// Возвращает матрицу гейта в виде `DMatrix` размера 2^n x 2^n.
+    /// Это типизированный аналог `matrix`: сама `matrix` возвращает матрицу
+    /// по строкам, чтобы трейт не зависел от nalgebra в сборке без `native`.
+    #[cfg(feature = "native")]
+    fn to_dmatrix(&self) -> DMatrix<Complex64> {
+        let dim = 1usize << self.num_qubits();
+        DMatrix::from_row_slice(dim, dim, &self.matrix())
+    }
+}
+
+/// Проверяет, что квадратная матрица (по строкам) унитарна: U·U† = I
+/// с точностью `tolerance` для каждого элемента.
+pub fn is_unitary_matrix(matrix: &[Amplitude], tolerance: f64) -> bool {
+    let dim = (matrix.len() as f64).sqrt() as usize;
+    if dim == 0 || dim * dim != matrix.len() {
+        return false;
+    }
+    
+    for i in 0..dim {
+        for j in 0..dim {
+            let product: Amplitude = (0..dim)
+                .map(|k| matrix[i * dim + k] * matrix[j * dim + k].conj())
+                .sum();
+            let expected = if i == j { 1.0 } else { 0.0 };
+            
+            // NaN не больше допуска, поэтому нечисловые матрицы отсекаются явно
+            let error = (product - Complex64::new(expected, 0.0)).norm();
+            if !error.is_finite() || error > tolerance {
+                return false;
+            }
+        }
+    }
+    
+    true
+}
+
+/// Проверяет, что матрицы одного размера совпадают с точностью до
+/// глобальной фазы: `a = e^{iφ}·b` поэлементно с точностью `tolerance`.
+pub fn equal_up_to_phase(a: &[Amplitude], b: &[Amplitude], tolerance: f64) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+
+    // Фазу определяем по наибольшему по модулю элементу `b`
+    let pivot = (0..b.len()).max_by(|&i, &j| b[i].norm().total_cmp(&b[j].norm()));
+    let phase = match pivot {
+        Some(i) if b[i].norm() > tolerance => a[i] / b[i],
+        _ => return a.iter().all(|x| x.norm() <= tolerance),
+    };
+
+    (phase.norm() - 1.0).abs() <= tolerance
+        && a.iter().zip(b).all(|(x, y)| (x - phase * y).norm() <= tolerance)
+}
+
+/// Строит матрицу оператора `matrix` (по строкам), управляемого `num_controls`
+/// кубитами. Управляющие кубиты — старшие биты индекса, целевые — младшие;
+/// оператор действует, когда все управляющие в |1⟩.
+pub fn controlled_matrix(num_controls: usize, matrix: &[Amplitude]) -> Vec<Amplitude> {
+    let target_dim = (matrix.len() as f64).sqrt() as usize;
+    assert_eq!(target_dim * target_dim, matrix.len(), "Матрица оператора должна быть квадратной");
+    let dim = target_dim << num_controls;
+    let active = dim - target_dim;
+
+    let mut full = identity_matrix(dim);
+    for row in 0..target_dim {
+        for col in 0..target_dim {
+            full[(active + row) * dim + active + col] = matrix[row * target_dim + col];
+        }
+    }
+    full
+}
+
+/// Возвращает единичную матрицу размера `dim`x`dim` (по строкам).
+pub fn identity_matrix(dim: usize) -> Vec<Amplitude> {
+    let mut matrix = vec![Complex64::new(0.0, 0.0); dim * dim];
+    for i in 0..dim {
+        matrix[i * dim + i] = Complex64::new(1.0, 0.0);
+    }
+    matrix
+}
+
+/// Перемножает квадратные матрицы одного размера (по строкам).
+pub(crate) fn multiply(a: &[Amplitude], b: &[Amplitude]) -> Vec<Amplitude> {
+    let dim = (a.len() as f64).sqrt() as usize;
+    let mut product = vec![Complex64::new(0.0, 0.0); a.len()];
+    for i in 0..dim {
+        for k in 0..dim {
+            let factor = a[i * dim + k];
+            for j in 0..dim {
+                product[i * dim + j] += factor * b[k * dim + j];
+            }
+        }
+    }
+    product
+}
+
+/// Возводит унитарную матрицу в целую неотрицательную степень
+/// быстрым возведением в степень. Ошибки округления при больших степенях
+/// накапливаются, поэтому после каждого умножения строки заново ортонормируются.
+fn matrix_power(matrix: &[Amplitude], mut exponent: u32) -> Vec<Amplitude> {
+    let dim = (matrix.len() as f64).sqrt() as usize;
+    let mut result = identity_matrix(dim);
+    let mut base = matrix.to_vec();
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = orthonormalize_rows(multiply(&result, &base), dim);
+        }
+        exponent >>= 1;
+        if exponent > 0 {
+            base = orthonormalize_rows(multiply(&base, &base), dim);
+        }
+    }
+    result
+}
+
+/// Ортонормирует строки квадратной матрицы модифицированным методом Грама — Шмидта.
+pub(crate) fn orthonormalize_rows(mut matrix: Vec<Amplitude>, dim: usize) -> Vec<Amplitude> {
+    for i in 0..dim {
+        for j in 0..i {
+            let overlap: Amplitude = (0..dim).map(|k| matrix[j * dim + k].conj() * matrix[i * dim + k]).sum();
+            for k in 0..dim {
+                let projection = overlap * matrix[j * dim + k];
+                matrix[i * dim + k] -= projection;
+            }
+        }
+        let norm = (0..dim).map(|k| matrix[i * dim + k].norm_sqr()).sum::<f64>().sqrt();
+        for k in 0..dim {
+            matrix[i * dim + k] /= norm;
+        }
+    }
+    matrix
+}
+
+/// Строит `CustomGate`, управляемый `num_controls` кубитами (имя получает префикс `c`
+/// для каждого кубита).
+fn controlled_gate(matrix: &[Amplitude], num_qubits: usize, name: &str, num_controls: usize) -> CircuitGate {
+    CircuitGate::Custom(CustomGate {
+        matrix: controlled_matrix(num_controls, matrix),
+        num_qubits: num_controls + num_qubits,
+        name: format!("{}{}", "c".repeat(num_controls), name),
+    })
+}
+
+/// Строит `CustomGate` для степени гейта (имя получает суффикс `^k`).
+fn power_gate(matrix: &[Amplitude], num_qubits: usize, name: &str, exponent: u32) -> CircuitGate {
+    CircuitGate::Custom(CustomGate {
+        matrix: matrix_power(matrix, exponent),
+        num_qubits,
+        name: format!("{}^{}", name, exponent),
+    })
+}
+
+/// Строит эрмитово сопряженный гейт (U†) по матрице исходного гейта.
+/// Имя получает суффикс `†` (или теряет его, если гейт уже сопряженный).
+fn adjoint_gate(matrix: &[Amplitude], num_qubits: usize, name: &str) -> CustomGate {
+    let dim = 1usize << num_qubits;
+    let mut adjoint = vec![Complex64::new(0.0, 0.0); dim * dim];
+
+    for i in 0..dim {
+        for j in 0..dim {
+            adjoint[j * dim + i] = matrix[i * dim + j].conj();
+        }
+    }
+
+    CustomGate {
+        matrix: adjoint,
+        num_qubits,
+        name: match name.strip_suffix('†') {
+            Some(original) => original.to_string(),
+            None => format!("{}†", name),
+        },
+    }
 }
 
 /// Перечисление базовых квантовых гейтов.
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BasicGate {
     /// Гейт Адамара
     Hadamard,
@@ -34,6 +253,14 @@ pub enum BasicGate {
     S,
     /// Гейт T
     T,
+    /// Гейт S† (обратный к S)
+    SDagger,
+    /// Гейт T† (обратный к T)
+    TDagger,
+    /// Квадратный корень из X (√X)
+    SqrtX,
+    /// Гейт √X† (обратный к √X)
+    SqrtXDagger,
 }
 
 impl Gate for BasicGate {
@@ -66,6 +293,22 @@ impl Gate for BasicGate {
                 Complex64::new(1.0, 0.0), Complex64::new(0.0, 0.0),
                 Complex64::new(0.0, 0.0), Complex64::new(0.5_f64.sqrt(), 0.5_f64.sqrt()),
             ],
+            BasicGate::SDagger => vec![
+                Complex64::new(1.0, 0.0), Complex64::new(0.0, 0.0),
+                Complex64::new(0.0, 0.0), Complex64::new(0.0, -1.0),
+            ],
+            BasicGate::TDagger => vec![
+                Complex64::new(1.0, 0.0), Complex64::new(0.0, 0.0),
+                Complex64::new(0.0, 0.0), Complex64::new(0.5_f64.sqrt(), -0.5_f64.sqrt()),
+            ],
+            BasicGate::SqrtX => vec![
+                Complex64::new(0.5, 0.5), Complex64::new(0.5, -0.5),
+                Complex64::new(0.5, -0.5), Complex64::new(0.5, 0.5),
+            ],
+            BasicGate::SqrtXDagger => vec![
+                Complex64::new(0.5, -0.5), Complex64::new(0.5, 0.5),
+                Complex64::new(0.5, 0.5), Complex64::new(0.5, -0.5),
+            ],
         }
     }
     
@@ -81,12 +324,45 @@ impl Gate for BasicGate {
             BasicGate::PauliZ => "PauliZ",
             BasicGate::S => "S",
             BasicGate::T => "T",
+            BasicGate::SDagger => "S†",
+            BasicGate::TDagger => "T†",
+            BasicGate::SqrtX => "SqrtX",
+            BasicGate::SqrtXDagger => "SqrtX†",
+        }
+    }
+    
+    fn inverse(&self) -> CircuitGate {
+        CircuitGate::Basic(match self {
+            BasicGate::S => BasicGate::SDagger,
+            BasicGate::SDagger => BasicGate::S,
+            BasicGate::T => BasicGate::TDagger,
+            BasicGate::TDagger => BasicGate::T,
+            BasicGate::SqrtX => BasicGate::SqrtXDagger,
+            BasicGate::SqrtXDagger => BasicGate::SqrtX,
+            gate => *gate,
+        })
+    }
+    
+    fn controlled(&self, num_controls: usize) -> CircuitGate {
+        use std::f64::consts::{FRAC_PI_2, FRAC_PI_4};
+        
+        let phase = |theta| CircuitGate::Parametric(ParametricGate::CPhase { theta });
+        match (self, num_controls) {
+            (_, 0) => CircuitGate::Basic(*self),
+            (BasicGate::PauliX, 1) => CircuitGate::TwoQubit(TwoQubitGate::CNOT),
+            (BasicGate::PauliZ, 1) => CircuitGate::TwoQubit(TwoQubitGate::CZ),
+            (BasicGate::S, 1) => phase(FRAC_PI_2),
+            (BasicGate::SDagger, 1) => phase(-FRAC_PI_2),
+            (BasicGate::T, 1) => phase(FRAC_PI_4),
+            (BasicGate::TDagger, 1) => phase(-FRAC_PI_4),
+            _ => controlled_gate(&self.matrix(), 1, self.name(), num_controls),
         }
     }
 }
 
 /// Гейт вращения вокруг осей X, Y или Z.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RotationGate {
     /// Ось вращения (X, Y или Z).
     pub axis: Axis,
@@ -96,6 +372,7 @@ pub struct RotationGate {
 
 /// Оси вращения для квантовых гейтов.
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Axis {
     X,
     Y,
@@ -134,10 +411,30 @@ impl Gate for RotationGate {
             Axis::Z => "RZ",
         }
     }
+    
+    fn inverse(&self) -> CircuitGate {
+        CircuitGate::Rotation(RotationGate { axis: self.axis, angle: -self.angle })
+    }
+    
+    fn controlled(&self, num_controls: usize) -> CircuitGate {
+        let theta = self.angle;
+        match (self.axis, num_controls) {
+            (_, 0) => CircuitGate::Rotation(self.clone()),
+            (Axis::X, 1) => CircuitGate::Parametric(ParametricGate::CRX { theta }),
+            (Axis::Y, 1) => CircuitGate::Parametric(ParametricGate::CRY { theta }),
+            (Axis::Z, 1) => CircuitGate::Parametric(ParametricGate::CRZ { theta }),
+            _ => controlled_gate(&self.matrix(), 1, self.name(), num_controls),
+        }
+    }
+    
+    fn power(&self, exponent: i32) -> CircuitGate {
+        CircuitGate::Rotation(RotationGate { axis: self.axis, angle: self.angle * exponent as f64 })
+    }
 }
 
 /// Двухкубитовый гейт (CNOT, CZ и т.д.).
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TwoQubitGate {
     /// Контролируемый NOT (CNOT)
     CNOT,
@@ -145,6 +442,14 @@ pub enum TwoQubitGate {
     CZ,
     /// SWAP-гейт
     SWAP,
+    /// iSWAP: обмен |01⟩ и |10⟩ с фазой i
+    ISWAP,
+    /// Гейт iSWAP† (обратный к iSWAP)
+    ISWAPDagger,
+    /// Квадратный корень из iSWAP (√iSWAP)
+    SqrtISWAP,
+    /// Гейт √iSWAP† (обратный к √iSWAP)
+    SqrtISWAPDagger,
 }
 
 impl Gate for TwoQubitGate {
@@ -168,6 +473,10 @@ impl Gate for TwoQubitGate {
                 Complex64::new(0.0, 0.0), Complex64::new(1.0, 0.0), Complex64::new(0.0, 0.0), Complex64::new(0.0, 0.0),
                 Complex64::new(0.0, 0.0), Complex64::new(0.0, 0.0), Complex64::new(0.0, 0.0), Complex64::new(1.0, 0.0),
             ],
+            TwoQubitGate::ISWAP => swap_with_phase(0.0, 1.0),
+            TwoQubitGate::ISWAPDagger => swap_with_phase(0.0, -1.0),
+            TwoQubitGate::SqrtISWAP => swap_with_phase(0.5_f64.sqrt(), 0.5_f64.sqrt()),
+            TwoQubitGate::SqrtISWAPDagger => swap_with_phase(0.5_f64.sqrt(), -0.5_f64.sqrt()),
         }
     }
     
@@ -180,12 +489,200 @@ impl Gate for TwoQubitGate {
             TwoQubitGate::CNOT => "CNOT",
             TwoQubitGate::CZ => "CZ",
             TwoQubitGate::SWAP => "SWAP",
+            TwoQubitGate::ISWAP => "iSWAP",
+            TwoQubitGate::ISWAPDagger => "iSWAP†",
+            TwoQubitGate::SqrtISWAP => "SqrtiSWAP",
+            TwoQubitGate::SqrtISWAPDagger => "SqrtiSWAP†",
         }
     }
+    
+    fn inverse(&self) -> CircuitGate {
+        CircuitGate::TwoQubit(match self {
+            TwoQubitGate::ISWAP => TwoQubitGate::ISWAPDagger,
+            TwoQubitGate::ISWAPDagger => TwoQubitGate::ISWAP,
+            TwoQubitGate::SqrtISWAP => TwoQubitGate::SqrtISWAPDagger,
+            TwoQubitGate::SqrtISWAPDagger => TwoQubitGate::SqrtISWAP,
+            gate => *gate,
+        })
+    }
+}
+
+/// Матрица семейства iSWAP: в подпространстве {|01⟩, |10⟩} действует
+/// `[[c, i·s], [i·s, c]]`, остальные базисные состояния не меняются.
+fn swap_with_phase(cos: f64, sin: f64) -> Vec<Amplitude> {
+    let (zero, one) = (Complex64::new(0.0, 0.0), Complex64::new(1.0, 0.0));
+    let (c, s) = (Complex64::new(cos, 0.0), Complex64::new(0.0, sin));
+    vec![
+        one, zero, zero, zero,
+        zero, c, s, zero,
+        zero, s, c, zero,
+        zero, zero, zero, one,
+    ]
+}
+
+/// Параметризованный гейт из стандартной библиотеки (углы в радианах).
+/// У двухкубитовых гейтов с управлением первый кубит — управляющий.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ParametricGate {
+    /// Фазовый гейт `U1(λ) = diag(1, e^{iλ})`
+    U1 { lambda: f64 },
+    /// Гейт `U2(φ, λ) = U3(π/2, φ, λ)`
+    U2 { phi: f64, lambda: f64 },
+    /// Общий однокубитовый гейт `U3(θ, φ, λ)` из OpenQASM
+    U3 { theta: f64, phi: f64, lambda: f64 },
+    /// Фазовый гейт `P(θ) = diag(1, e^{iθ})`
+    Phase { theta: f64 },
+    /// Вращение `exp(-iθ/2 · X⊗X)`
+    RXX { theta: f64 },
+    /// Вращение `exp(-iθ/2 · Y⊗Y)`
+    RYY { theta: f64 },
+    /// Вращение `exp(-iθ/2 · Z⊗Z)`
+    RZZ { theta: f64 },
+    /// Управляемый фазовый гейт `diag(1, 1, 1, e^{iθ})`
+    CPhase { theta: f64 },
+    /// Управляемое вращение вокруг оси X
+    CRX { theta: f64 },
+    /// Управляемое вращение вокруг оси Y
+    CRY { theta: f64 },
+    /// Управляемое вращение вокруг оси Z
+    CRZ { theta: f64 },
+}
+
+/// Матрица гейта `U3(θ, φ, λ)` из спецификации OpenQASM 2.0.
+fn u3_matrix(theta: f64, phi: f64, lambda: f64) -> Vec<Amplitude> {
+    let (cos, sin) = ((theta / 2.0).cos(), (theta / 2.0).sin());
+    vec![
+        Complex64::new(cos, 0.0), -Complex64::from_polar(sin, lambda),
+        Complex64::from_polar(sin, phi), Complex64::from_polar(cos, phi + lambda),
+    ]
+}
+
+impl Gate for ParametricGate {
+    fn matrix(&self) -> Vec<Amplitude> {
+        let zero = Complex64::new(0.0, 0.0);
+        let one = Complex64::new(1.0, 0.0);
+
+        match *self {
+            ParametricGate::U1 { lambda: theta } | ParametricGate::Phase { theta } => vec![
+                one, zero,
+                zero, Complex64::from_polar(1.0, theta),
+            ],
+            ParametricGate::U2 { phi, lambda } => u3_matrix(std::f64::consts::FRAC_PI_2, phi, lambda),
+            ParametricGate::U3 { theta, phi, lambda } => u3_matrix(theta, phi, lambda),
+            ParametricGate::RXX { theta } => {
+                let (c, s) = (Complex64::new((theta / 2.0).cos(), 0.0), Complex64::new(0.0, -(theta / 2.0).sin()));
+                vec![
+                    c, zero, zero, s,
+                    zero, c, s, zero,
+                    zero, s, c, zero,
+                    s, zero, zero, c,
+                ]
+            },
+            ParametricGate::RYY { theta } => {
+                let (c, s) = (Complex64::new((theta / 2.0).cos(), 0.0), Complex64::new(0.0, (theta / 2.0).sin()));
+                vec![
+                    c, zero, zero, s,
+                    zero, c, -s, zero,
+                    zero, -s, c, zero,
+                    s, zero, zero, c,
+                ]
+            },
+            ParametricGate::RZZ { theta } => {
+                let (even, odd) = (Complex64::from_polar(1.0, -theta / 2.0), Complex64::from_polar(1.0, theta / 2.0));
+                vec![
+                    even, zero, zero, zero,
+                    zero, odd, zero, zero,
+                    zero, zero, odd, zero,
+                    zero, zero, zero, even,
+                ]
+            },
+            ParametricGate::CPhase { theta } => controlled_matrix(1, &ParametricGate::Phase { theta }.matrix()),
+            ParametricGate::CRX { theta } => controlled_matrix(1, &RotationGate { axis: Axis::X, angle: theta }.matrix()),
+            ParametricGate::CRY { theta } => controlled_matrix(1, &RotationGate { axis: Axis::Y, angle: theta }.matrix()),
+            ParametricGate::CRZ { theta } => controlled_matrix(1, &RotationGate { axis: Axis::Z, angle: theta }.matrix()),
+        }
+    }
+    
+    fn num_qubits(&self) -> usize {
+        match self {
+            ParametricGate::U1 { .. } | ParametricGate::U2 { .. } | ParametricGate::U3 { .. } | ParametricGate::Phase { .. } => 1,
+            _ => 2,
+        }
+    }
+    
+    fn name(&self) -> &'static str {
+        match self {
+            ParametricGate::U1 { .. } => "U1",
+            ParametricGate::U2 { .. } => "U2",
+            ParametricGate::U3 { .. } => "U3",
+            ParametricGate::Phase { .. } => "Phase",
+            ParametricGate::RXX { .. } => "RXX",
+            ParametricGate::RYY { .. } => "RYY",
+            ParametricGate::RZZ { .. } => "RZZ",
+            ParametricGate::CPhase { .. } => "CPhase",
+            ParametricGate::CRX { .. } => "CRX",
+            ParametricGate::CRY { .. } => "CRY",
+            ParametricGate::CRZ { .. } => "CRZ",
+        }
+    }
+    
+    fn inverse(&self) -> CircuitGate {
+        use std::f64::consts::PI;
+
+        CircuitGate::Parametric(match *self {
+            ParametricGate::U1 { lambda } => ParametricGate::U1 { lambda: -lambda },
+            // U3(-θ, a, b) = U3(θ, a + π, b - π), поэтому U2† снова имеет вид U2
+            ParametricGate::U2 { phi, lambda } => ParametricGate::U2 { phi: PI - lambda, lambda: -phi - PI },
+            ParametricGate::U3 { theta, phi, lambda } => ParametricGate::U3 { theta: -theta, phi: -lambda, lambda: -phi },
+            ParametricGate::Phase { theta } => ParametricGate::Phase { theta: -theta },
+            ParametricGate::RXX { theta } => ParametricGate::RXX { theta: -theta },
+            ParametricGate::RYY { theta } => ParametricGate::RYY { theta: -theta },
+            ParametricGate::RZZ { theta } => ParametricGate::RZZ { theta: -theta },
+            ParametricGate::CPhase { theta } => ParametricGate::CPhase { theta: -theta },
+            ParametricGate::CRX { theta } => ParametricGate::CRX { theta: -theta },
+            ParametricGate::CRY { theta } => ParametricGate::CRY { theta: -theta },
+            ParametricGate::CRZ { theta } => ParametricGate::CRZ { theta: -theta },
+        })
+    }
+    
+    fn controlled(&self, num_controls: usize) -> CircuitGate {
+        match (*self, num_controls) {
+            (gate, 0) => CircuitGate::Parametric(gate),
+            (ParametricGate::U1 { lambda: theta } | ParametricGate::Phase { theta }, 1) => {
+                CircuitGate::Parametric(ParametricGate::CPhase { theta })
+            },
+            _ => controlled_gate(&self.matrix(), self.num_qubits(), self.name(), num_controls),
+        }
+    }
+    
+    fn power(&self, exponent: i32) -> CircuitGate {
+        let k = exponent as f64;
+        let scaled = match *self {
+            ParametricGate::U2 { .. } | ParametricGate::U3 { .. } => {
+                if exponent < 0 {
+                    let inverse = self.inverse();
+                    return power_gate(&inverse.matrix(), 1, inverse.name(), exponent.unsigned_abs());
+                }
+                return power_gate(&self.matrix(), 1, self.name(), exponent as u32);
+            },
+            ParametricGate::U1 { lambda } => ParametricGate::U1 { lambda: lambda * k },
+            ParametricGate::Phase { theta } => ParametricGate::Phase { theta: theta * k },
+            ParametricGate::RXX { theta } => ParametricGate::RXX { theta: theta * k },
+            ParametricGate::RYY { theta } => ParametricGate::RYY { theta: theta * k },
+            ParametricGate::RZZ { theta } => ParametricGate::RZZ { theta: theta * k },
+            ParametricGate::CPhase { theta } => ParametricGate::CPhase { theta: theta * k },
+            ParametricGate::CRX { theta } => ParametricGate::CRX { theta: theta * k },
+            ParametricGate::CRY { theta } => ParametricGate::CRY { theta: theta * k },
+            ParametricGate::CRZ { theta } => ParametricGate::CRZ { theta: theta * k },
+        };
+        CircuitGate::Parametric(scaled)
+    }
 }
 
 /// Создает пользовательский гейт с заданной унитарной матрицей.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CustomGate {
     /// Унитарная матрица гейта.
     pub matrix: Vec<Amplitude>,
@@ -195,6 +692,30 @@ pub struct CustomGate {
     pub name: String,
 }
 
+impl CustomGate {
+    /// Создает пользовательский гейт, паникуя, если матрица не является
+    /// унитарной матрицей 2^n x 2^n.
+    pub fn new(matrix: Vec<Amplitude>, num_qubits: usize, name: &str) -> Self {
+        Self::try_new(matrix, num_qubits, name).unwrap_or_else(|err| panic!("{}", err))
+    }
+    
+    /// Создает пользовательский гейт, возвращая ошибку для матрицы неверного
+    /// размера или неунитарной матрицы.
+    pub fn try_new(matrix: Vec<Amplitude>, num_qubits: usize, name: &str) -> Result<Self, TriadError> {
+        let dim = 1usize << num_qubits;
+        if num_qubits == 0 || matrix.len() != dim * dim {
+            return Err(TriadError::InvalidArgument(format!(
+                "матрица гейта {} должна иметь размер {}x{}", name, dim, dim)));
+        }
+        
+        let gate = CustomGate { matrix, num_qubits, name: name.to_string() };
+        if !gate.is_unitary(UNITARITY_TOLERANCE) {
+            return Err(TriadError::NonUnitary(name.to_string()));
+        }
+        Ok(gate)
+    }
+}
+
 impl Gate for CustomGate {
     fn matrix(&self) -> Vec<Amplitude> {
         self.matrix.clone()
@@ -204,9 +725,166 @@ impl Gate for CustomGate {
         self.num_qubits
     }
     
-    fn name(&self) -> &'static str {
-        // Это не совсем правильно, но другого варианта нет из-за ограничений интерфейса
-        // Лучше было бы изменить интерфейс, чтобы он возвращал &str вместо &'static str
-        "CustomGate"
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+
+    const EPSILON: f64 = 1e-12;
+
+    fn assert_close(actual: &[Amplitude], expected: &[Amplitude]) {
+        assert_eq!(actual.len(), expected.len());
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).norm() < EPSILON, "{:?} != {:?}", actual, expected);
+        }
+    }
+
+    fn library() -> Vec<(Box<dyn Gate>, Box<dyn Gate>)> {
+        let mut gates: Vec<(Box<dyn Gate>, Box<dyn Gate>)> = Vec::new();
+        for gate in [BasicGate::S, BasicGate::T, BasicGate::SDagger, BasicGate::TDagger, BasicGate::SqrtX, BasicGate::SqrtXDagger] {
+            gates.push((Box::new(gate), Box::new(gate.inverse())));
+        }
+        for gate in [TwoQubitGate::ISWAP, TwoQubitGate::ISWAPDagger, TwoQubitGate::SqrtISWAP, TwoQubitGate::SqrtISWAPDagger] {
+            gates.push((Box::new(gate), Box::new(gate.inverse())));
+        }
+        for gate in [
+            ParametricGate::U1 { lambda: 0.4 },
+            ParametricGate::U2 { phi: -1.1, lambda: 2.3 },
+            ParametricGate::U3 { theta: 0.7, phi: 1.9, lambda: -0.5 },
+            ParametricGate::Phase { theta: 1.3 },
+            ParametricGate::RXX { theta: 0.9 },
+            ParametricGate::RYY { theta: -2.1 },
+            ParametricGate::RZZ { theta: 0.6 },
+            ParametricGate::CPhase { theta: 2.5 },
+            ParametricGate::CRX { theta: 1.7 },
+            ParametricGate::CRY { theta: -0.8 },
+            ParametricGate::CRZ { theta: 3.0 },
+        ] {
+            gates.push((Box::new(gate), Box::new(gate.inverse())));
+        }
+        gates
+    }
+
+    #[test]
+    fn test_library_gates_are_unitary_and_invertible() {
+        for (gate, inverse) in library() {
+            let dim = 1usize << gate.num_qubits();
+            let matrix = gate.matrix();
+            assert_eq!(matrix.len(), dim * dim, "{}", gate.name());
+            assert!(is_unitary_matrix(&matrix, EPSILON), "{} не унитарен", gate.name());
+            assert_eq!(inverse.num_qubits(), gate.num_qubits());
+
+            let identity = controlled_matrix(gate.num_qubits() - 1, &[
+                Complex64::new(1.0, 0.0), Complex64::new(0.0, 0.0),
+                Complex64::new(0.0, 0.0), Complex64::new(1.0, 0.0),
+            ]);
+            assert_close(&multiply(&inverse.matrix(), &matrix), &identity);
+        }
+    }
+
+    #[test]
+    fn test_square_roots_square_to_base_gates() {
+        let sqrt_x = BasicGate::SqrtX.matrix();
+        assert_close(&multiply(&sqrt_x, &sqrt_x), &BasicGate::PauliX.matrix());
+
+        let sqrt_iswap = TwoQubitGate::SqrtISWAP.matrix();
+        assert_close(&multiply(&sqrt_iswap, &sqrt_iswap), &TwoQubitGate::ISWAP.matrix());
+
+        // iSWAP² = Z⊗Z = i·RZZ(π)
+        let iswap = TwoQubitGate::ISWAP.matrix();
+        let zz: Vec<Amplitude> = ParametricGate::RZZ { theta: PI }.matrix().iter().map(|z| z * Complex64::new(0.0, 1.0)).collect();
+        assert_close(&multiply(&iswap, &iswap), &zz);
+
+        assert_close(&multiply(&BasicGate::SDagger.matrix(), &BasicGate::SDagger.matrix()), &BasicGate::PauliZ.matrix());
+        assert_eq!(BasicGate::T.inverse().name(), "T†");
+        assert!(matches!(BasicGate::Hadamard.inverse(), CircuitGate::Basic(BasicGate::Hadamard)));
     }
-} 
\ No newline at end of file
+
+    #[test]
+    fn test_parametric_gates_match_known_matrices() {
+        assert_close(&ParametricGate::U3 { theta: PI / 2.0, phi: 0.0, lambda: PI }.matrix(), &BasicGate::Hadamard.matrix());
+        assert_close(&ParametricGate::U2 { phi: 0.3, lambda: -0.2 }.matrix(),
+            &ParametricGate::U3 { theta: PI / 2.0, phi: 0.3, lambda: -0.2 }.matrix());
+        assert_close(&ParametricGate::U1 { lambda: PI / 4.0 }.matrix(), &BasicGate::T.matrix());
+        assert_close(&ParametricGate::Phase { theta: -PI / 2.0 }.matrix(), &BasicGate::SDagger.matrix());
+        assert_close(&ParametricGate::CPhase { theta: PI }.matrix(), &TwoQubitGate::CZ.matrix());
+        assert_close(&ParametricGate::CRZ { theta: 0.5 }.matrix(),
+            &controlled_matrix(1, &RotationGate { axis: Axis::Z, angle: 0.5 }.matrix()));
+
+        // RXX(π) = -i·X⊗X, RYY(π) = -i·Y⊗Y
+        let minus_i = Complex64::new(0.0, -1.0);
+        let kron = |a: &[Amplitude], b: &[Amplitude]| -> Vec<Amplitude> {
+            (0..16).map(|idx| {
+                let (row, col) = (idx / 4, idx % 4);
+                minus_i * a[(row / 2) * 2 + col / 2] * b[(row % 2) * 2 + col % 2]
+            }).collect()
+        };
+        let (x, y) = (BasicGate::PauliX.matrix(), BasicGate::PauliY.matrix());
+        assert_close(&ParametricGate::RXX { theta: PI }.matrix(), &kron(&x, &x));
+        assert_close(&ParametricGate::RYY { theta: PI }.matrix(), &kron(&y, &y));
+        assert_eq!(ParametricGate::CRY { theta: 1.0 }.num_qubits(), 2);
+        assert_eq!(ParametricGate::U1 { lambda: 1.0 }.num_qubits(), 1);
+    }
+
+    #[test]
+    fn test_custom_gate_validation_and_names() {
+        let (zero, one) = (Complex64::new(0.0, 0.0), Complex64::new(1.0, 0.0));
+        let gate = CustomGate::new(vec![zero, one, one, zero], 1, "flip");
+        assert_eq!(gate.name(), "flip");
+        assert!(gate.is_unitary(1e-12));
+        assert_eq!(gate.inverse().name(), "flip†");
+        assert_eq!(gate.inverse().inverse().name(), "flip");
+        assert_eq!(gate.adjoint().name(), "flip†");
+        assert!(matches!(BasicGate::S.adjoint(), CircuitGate::Basic(BasicGate::SDagger)));
+
+        assert_eq!(CustomGate::try_new(vec![one; 4], 1, "ones").unwrap_err(), TriadError::NonUnitary("ones".to_string()));
+        assert!(matches!(CustomGate::try_new(vec![one, zero, zero, one], 2, "small"), Err(TriadError::InvalidArgument(_))));
+        assert!(std::panic::catch_unwind(|| CustomGate::new(vec![one; 4], 1, "ones")).is_err());
+
+        // Матрицы с NaN или бесконечностями не считаются унитарными
+        let nan = Complex64::new(f64::NAN, 0.0);
+        assert!(CustomGate::try_new(vec![nan, zero, zero, one], 1, "nan").is_err());
+        assert!(!RotationGate { axis: Axis::Z, angle: f64::INFINITY }.is_unitary(1e-12));
+    }
+
+    #[test]
+    fn test_controlled_and_power_keep_library_gates() {
+        assert!(matches!(BasicGate::PauliX.controlled(1), CircuitGate::TwoQubit(TwoQubitGate::CNOT)));
+        assert!(matches!(BasicGate::T.controlled(1), CircuitGate::Parametric(ParametricGate::CPhase { .. })));
+        assert!(matches!(RotationGate { axis: Axis::Y, angle: 0.3 }.controlled(1), CircuitGate::Parametric(ParametricGate::CRY { .. })));
+        assert!(matches!(RotationGate { axis: Axis::Z, angle: 0.3 }.power(-2),
+            CircuitGate::Rotation(RotationGate { axis: Axis::Z, angle }) if (angle + 0.6).abs() < EPSILON));
+
+        let toffoli = BasicGate::PauliX.controlled(2);
+        assert_eq!((toffoli.num_qubits(), toffoli.name()), (3, "ccPauliX"));
+        assert_close(&toffoli.matrix(), &controlled_matrix(2, &BasicGate::PauliX.matrix()));
+        assert_close(&ParametricGate::CRX { theta: 0.4 }.controlled(1).matrix(),
+            &controlled_matrix(2, &RotationGate { axis: Axis::X, angle: 0.4 }.matrix()));
+
+        assert_close(&BasicGate::T.power(2).matrix(), &BasicGate::S.matrix());
+        assert_close(&BasicGate::S.power(-3).matrix(), &BasicGate::S.matrix());
+        assert_close(&BasicGate::Hadamard.power(0).matrix(), &identity_matrix(2));
+        assert_close(&ParametricGate::U3 { theta: 0.3, phi: 0.1, lambda: 0.2 }.power(-1).matrix(),
+            &ParametricGate::U3 { theta: 0.3, phi: 0.1, lambda: 0.2 }.inverse().matrix());
+        assert_close(&TwoQubitGate::SqrtISWAP.power(2).matrix(), &TwoQubitGate::ISWAP.matrix());
+
+        // T^8 = I, поэтому T^(-2^31) — единичный оператор
+        assert_close(&BasicGate::T.power(i32::MIN).matrix(), &identity_matrix(2));
+        assert_eq!(ParametricGate::U2 { phi: 0.1, lambda: 0.2 }.power(i32::MIN).num_qubits(), 1);
+    }
+
+    #[cfg(feature = "native")]
+    #[test]
+    fn test_typed_matrix() {
+        let gate = ParametricGate::RYY { theta: 0.8 };
+        let matrix = gate.to_dmatrix();
+        assert_eq!(matrix.shape(), (4, 4));
+        assert_eq!(matrix[(1, 2)], gate.matrix()[6]);
+        assert!((&matrix * matrix.adjoint() - DMatrix::identity(4, 4)).norm() < EPSILON);
+    }
+}
@@ -0,0 +1,260 @@
+//! Обобщенные измерения (POVM).
+//!
+//! POVM задается операторами измерения `M_k`: исход `k` наступает с
+//! вероятностью `p_k = ⟨ψ|M_k†M_k|ψ⟩`, после чего состояние переходит в
+//! `M_k|ψ⟩/√p_k`. Эффекты `E_k = M_k†M_k` положительны и в сумме дают
+//! единичный оператор. Матрицы хранятся по строкам в том же порядке кубитов,
+//! что и матрицы гейтов: первый целевой кубит соответствует старшему биту индекса.
+
+use crate::core::gates::multiply;
+use crate::core::noise::KrausChannel;
+use crate::core::quantum_simulator::validate_qubits;
+use crate::core::quantum_state::Amplitude;
+use crate::error::TriadError;
+
+/// Допуск, с которым проверяются эрмитовость и положительность эффектов.
+pub const EFFECT_TOLERANCE: f64 = 1e-8;
+
+/// Обобщенное измерение, заданное операторами измерения.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Povm {
+    /// Операторы измерения как неселективный канал Крауса.
+    channel: KrausChannel,
+}
+
+impl Povm {
+    /// Создает POVM из операторов измерения `M_k`. Матрицы должны иметь
+    /// одинаковый размер 2^n x 2^n и удовлетворять условию Σ M_k†M_k = I.
+    pub fn from_operators(name: &str, operators: Vec<Vec<Amplitude>>) -> Result<Self, TriadError> {
+        Ok(Self { channel: KrausChannel::new(name, operators)? })
+    }
+
+    /// Создает POVM из эффектов `E_k`. Каждый эффект должен быть эрмитовой
+    /// неотрицательно определенной матрицей, а их сумма — единичной.
+    /// Состояние после измерения определяется операторами `M_k = √E_k`
+    /// (правило Людерса).
+    pub fn new(name: &str, effects: Vec<Vec<Amplitude>>) -> Result<Self, TriadError> {
+        let mut operators = Vec::with_capacity(effects.len());
+        for (idx, effect) in effects.iter().enumerate() {
+            let dim = (effect.len() as f64).sqrt() as usize;
+            if dim * dim != effect.len() {
+                return Err(TriadError::InvalidArgument(format!(
+                    "эффект {} измерения {} должен быть квадратной матрицей", idx, name)));
+            }
+
+            let hermitian = (0..dim * dim)
+                .all(|i| (effect[i] - effect[(i % dim) * dim + i / dim].conj()).norm() <= EFFECT_TOLERANCE);
+            if !hermitian {
+                return Err(TriadError::InvalidArgument(format!(
+                    "эффект {} измерения {} не является эрмитовым", idx, name)));
+            }
+
+            let (eigenvalues, eigenvectors) = hermitian_eigen(effect, dim);
+            if eigenvalues.iter().any(|&lambda| lambda < -EFFECT_TOLERANCE) {
+                return Err(TriadError::InvalidArgument(format!(
+                    "эффект {} измерения {} не является неотрицательно определенным", idx, name)));
+            }
+
+            // √E = V·diag(√λ)·V†
+            let root = (0..dim * dim).map(|i| {
+                let (row, col) = (i / dim, i % dim);
+                (0..dim)
+                    .map(|k| eigenvectors[row * dim + k] * eigenvalues[k].max(0.0).sqrt() * eigenvectors[col * dim + k].conj())
+                    .sum()
+            }).collect();
+            operators.push(root);
+        }
+
+        Self::from_operators(name, operators)
+    }
+
+    /// Возвращает название измерения.
+    pub fn name(&self) -> &str {
+        self.channel.name()
+    }
+
+    /// Возвращает число кубитов, на которые действует измерение.
+    pub fn num_qubits(&self) -> usize {
+        self.channel.num_qubits()
+    }
+
+    /// Возвращает число исходов измерения.
+    pub fn num_outcomes(&self) -> usize {
+        self.channel.operators().len()
+    }
+
+    /// Возвращает операторы измерения `M_k`.
+    pub fn operators(&self) -> &[Vec<Amplitude>] {
+        self.channel.operators()
+    }
+
+    /// Возвращает эффекты `E_k = M_k†M_k`.
+    pub fn effects(&self) -> Vec<Vec<Amplitude>> {
+        self.operators().iter()
+            .map(|operator| {
+                let dim = 1usize << self.num_qubits();
+                let adjoint: Vec<Amplitude> = (0..dim * dim)
+                    .map(|i| operator[(i % dim) * dim + i / dim].conj())
+                    .collect();
+                multiply(&adjoint, operator)
+            })
+            .collect()
+    }
+
+    /// Возвращает канал, которым действует измерение без учета исхода.
+    pub fn as_channel(&self) -> &KrausChannel {
+        &self.channel
+    }
+}
+
+/// Проверяет, что измерение можно выполнить над указанными кубитами регистра.
+pub fn validate_povm_targets(povm: &Povm, targets: &[usize], num_qubits: usize) -> Result<(), TriadError> {
+    if povm.num_qubits() != targets.len() {
+        return Err(TriadError::InvalidArgument(format!(
+            "измерение {} действует на {} кубит(ов), но указано {} целевых кубитов",
+            povm.name(), povm.num_qubits(), targets.len())));
+    }
+    validate_qubits(targets, num_qubits)
+}
+
+/// Вычисляет собственные значения и собственные векторы эрмитовой матрицы
+/// `dim x dim` (по строкам) методом вращений Якоби. Собственные векторы
+/// возвращаются столбцами унитарной матрицы (по строкам).
+fn hermitian_eigen(matrix: &[Amplitude], dim: usize) -> (Vec<f64>, Vec<Amplitude>) {
+    let zero = Amplitude::new(0.0, 0.0);
+    let mut a = matrix.to_vec();
+    let mut v: Vec<Amplitude> = (0..dim * dim)
+        .map(|i| if i / dim == i % dim { Amplitude::new(1.0, 0.0) } else { zero })
+        .collect();
+
+    let scale = a.iter().map(|x| x.norm_sqr()).sum::<f64>().max(f64::MIN_POSITIVE);
+    for _ in 0..100 {
+        let off_diagonal: f64 = (0..dim * dim).filter(|i| i / dim != i % dim).map(|i| a[i].norm_sqr()).sum();
+        if off_diagonal <= 1e-30 * scale {
+            break;
+        }
+
+        for p in 0..dim {
+            for q in p + 1..dim {
+                let apq = a[p * dim + q];
+                if apq.norm() == 0.0 {
+                    continue;
+                }
+                // J = diag(1, e^{-iφ})·R(θ) обнуляет элемент (p, q)
+                let phase = Amplitude::from_polar(1.0, -apq.arg());
+                let theta = 0.5 * (2.0 * apq.norm()).atan2(a[q * dim + q].re - a[p * dim + p].re);
+                let (c, s) = (theta.cos(), theta.sin());
+                let (jpp, jpq, jqp, jqq) = (Amplitude::new(c, 0.0), Amplitude::new(s, 0.0), -phase * s, phase * c);
+
+                for k in 0..dim {
+                    let (akp, akq) = (a[k * dim + p], a[k * dim + q]);
+                    a[k * dim + p] = akp * jpp + akq * jqp;
+                    a[k * dim + q] = akp * jpq + akq * jqq;
+                    let (vkp, vkq) = (v[k * dim + p], v[k * dim + q]);
+                    v[k * dim + p] = vkp * jpp + vkq * jqp;
+                    v[k * dim + q] = vkp * jpq + vkq * jqq;
+                }
+                for k in 0..dim {
+                    let (apk, aqk) = (a[p * dim + k], a[q * dim + k]);
+                    a[p * dim + k] = jpp.conj() * apk + jqp.conj() * aqk;
+                    a[q * dim + k] = jpq.conj() * apk + jqq.conj() * aqk;
+                }
+            }
+        }
+    }
+
+    ((0..dim).map(|k| a[k * dim + k].re).collect(), v)
+}
+
+/// Вычисляет вероятности исходов `p_k = Tr(E_k ρ)` по редуцированной матрице
+/// плотности `reduced` целевых кубитов (по строкам, в порядке кубитов измерения).
+pub(crate) fn outcome_probabilities(povm: &Povm, reduced: &[Amplitude]) -> Vec<f64> {
+    let dim = 1usize << povm.num_qubits();
+    povm.effects().iter()
+        .map(|effect| {
+            (0..dim * dim)
+                .map(|i| effect[i] * reduced[(i % dim) * dim + i / dim])
+                .sum::<Amplitude>()
+                .re
+                .max(0.0)
+        })
+        .collect()
+}
+
+/// Выбирает исход по вероятностям `probabilities` и случайному числу `random`
+/// из [0, 1). Вероятности нормируются на их сумму.
+pub(crate) fn choose_outcome(probabilities: &[f64], random: f64) -> usize {
+    let total: f64 = probabilities.iter().sum();
+    let threshold = random * total;
+    let mut cumulative = 0.0;
+    for (outcome, &prob) in probabilities.iter().enumerate() {
+        cumulative += prob;
+        if threshold < cumulative {
+            return outcome;
+        }
+    }
+    // Ошибки округления: выбираем последний исход с ненулевой вероятностью
+    probabilities.iter().rposition(|&prob| prob > 0.0).unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_complex::Complex64;
+
+    #[test]
+    fn test_operators_are_validated() {
+        let zero = Complex64::new(0.0, 0.0);
+        let one = Complex64::new(1.0, 0.0);
+        let povm = Povm::from_operators("z", vec![
+            vec![one, zero, zero, zero],
+            vec![zero, zero, zero, one],
+        ]).unwrap();
+        assert_eq!(povm.num_qubits(), 1);
+        assert_eq!(povm.num_outcomes(), 2);
+        assert_eq!(povm.effects(), povm.operators().to_vec());
+
+        assert!(Povm::from_operators("incomplete", vec![vec![one, zero, zero, zero]]).is_err());
+        assert!(validate_povm_targets(&povm, &[0, 1], 2).is_err());
+        assert_eq!(choose_outcome(&[0.0, 0.25, 0.75], 0.2), 1);
+        assert_eq!(choose_outcome(&[0.5, 0.5, 0.0], 1.0), 1);
+    }
+
+    #[test]
+    fn test_effects_give_square_root_operators() {
+        // Трин-POVM: три эффекта (2/3)|ψ_k⟩⟨ψ_k| с углами 0, 120 и 240 градусов
+        let effects: Vec<Vec<Amplitude>> = (0..3).map(|k| {
+            let angle = 2.0 * std::f64::consts::PI * k as f64 / 3.0;
+            let (c, s) = ((angle / 2.0).cos(), (angle / 2.0).sin());
+            [c * c, c * s, s * c, s * s].iter().map(|&v| Complex64::new(2.0 * v / 3.0, 0.0)).collect()
+        }).collect();
+
+        let povm = Povm::new("trine", effects.clone()).unwrap();
+        for (effect, expected) in povm.effects().iter().zip(&effects) {
+            for (a, b) in effect.iter().zip(expected) {
+                assert!((a - b).norm() < 1e-10);
+            }
+        }
+
+        // Комплексные эффекты на двух кубитах: E ⊗ E и I - E ⊗ E, где E = (I + 0.8·Y)/2
+        let zero = Complex64::new(0.0, 0.0);
+        let half = Complex64::new(0.5, 0.0);
+        let single = [half, Complex64::new(0.0, -0.4), Complex64::new(0.0, 0.4), half];
+        let product: Vec<Amplitude> = (0..16)
+            .map(|i| single[(i / 8) * 2 + (i % 4) / 2] * single[((i / 4) % 2) * 2 + i % 2])
+            .collect();
+        let complement: Vec<Amplitude> = (0..16)
+            .map(|i| if i / 4 == i % 4 { Complex64::new(1.0, 0.0) - product[i] } else { -product[i] })
+            .collect();
+        let povm = Povm::new("product", vec![product.clone(), complement]).unwrap();
+        assert_eq!(povm.num_qubits(), 2);
+        for (a, b) in povm.effects()[0].iter().zip(&product) {
+            assert!((a - b).norm() < 1e-10);
+        }
+
+        let negative = vec![Complex64::new(-1.0, 0.0), zero, zero, Complex64::new(2.0, 0.0)];
+        assert!(matches!(Povm::new("negative", vec![negative]), Err(TriadError::InvalidArgument(_))));
+        let non_hermitian = vec![Complex64::new(1.0, 0.0), Complex64::new(0.0, 1.0), zero, Complex64::new(1.0, 0.0)];
+        assert!(matches!(Povm::new("skew", vec![non_hermitian]), Err(TriadError::InvalidArgument(_))));
+    }
+}
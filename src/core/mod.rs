@@ -13,8 +13,70 @@ pub mod qubit;
 /// Определение типов и абстракций для квантовых гейтов
 pub mod gates;
 
+/// Операторы Паули и их произведения
+pub mod pauli;
+
+/// Статистика многократных измерений
+pub mod counts;
+
+/// Промежуточное представление квантовых схем
+pub mod circuit;
+
+/// Нативный симулятор вектора состояния на чистом Rust
+pub mod state_vector;
+
+/// Квантовые каналы шума в представлении Крауса
+pub mod noise;
+
+/// Обобщенные измерения (POVM)
+pub mod measurement;
+
+/// Симулятор матрицы плотности для смешанных состояний и шума
+#[cfg(feature = "native")]
+pub mod density_matrix;
+
+/// Стабилизаторный симулятор схем Клиффорда
+#[cfg(feature = "native")]
+pub mod stabilizer;
+
+/// Симулятор на основе матричного произведения состояний для слабо запутанных схем
+#[cfg(feature = "native")]
+pub mod mps;
+
+/// Разреженный симулятор вектора состояния для почти классических регистров
+#[cfg(feature = "native")]
+pub mod sparse;
+
+/// Разложение гейтов по нативному базису бэкенда
+#[cfg(feature = "native")]
+pub mod decompose;
+
+/// Квантовое преобразование Фурье
+pub mod qft;
+
+/// Проходы оптимизации квантовых схем
+pub mod optimize;
+
 // Реэкспорт основных типов для удобства использования
 pub use quantum_state::{QuantumState, Amplitude};
 pub use quantum_simulator::QuantumSimulator;
 pub use qubit::{Qubit, QubitState};
-pub use gates::Gate; 
\ No newline at end of file
+pub use gates::Gate;
+pub use pauli::Pauli;
+pub use counts::Counts;
+pub use circuit::{Circuit, CircuitGate, Instruction};
+pub use state_vector::{StateVector, StateVectorSimulator};
+pub use noise::KrausChannel;
+pub use measurement::Povm;
+pub use qft::QftOptions;
+pub use optimize::{OptimizationPass, OptimizationReport, PassManager};
+#[cfg(feature = "native")]
+pub use density_matrix::{DensityMatrix, DensityMatrixSimulator};
+#[cfg(feature = "native")]
+pub use stabilizer::{StabilizerState, StabilizerSimulator};
+#[cfg(feature = "native")]
+pub use mps::{MpsState, MpsSimulator};
+#[cfg(feature = "native")]
+pub use sparse::{SparseState, SparseSimulator, SparsityStats};
+#[cfg(feature = "native")]
+pub use decompose::{Basis, BasisGate}; 
\ No newline at end of file
@@ -0,0 +1,986 @@
+//! Симулятор на основе матричного произведения состояний (MPS).
+//!
+//! Состояние из n кубитов записывается цепочкой тензоров `A_k[l, s, r]`,
+//! где `s` — значение кубита `k`, а `l` и `r` — индексы связей с соседями.
+//! Память и время растут как O(n·χ²), где χ — размерность связи, поэтому
+//! слабо запутанные состояния (например, цепочки пар Белла) моделируются
+//! на сотнях и тысячах кубитов. Размерность связи ограничивается сверху;
+//! отброшенный при усечении вес сингулярных значений накапливается и
+//! доступен через `truncation_error()`.
+//!
+//! Цепочка хранится в смешанной канонической форме: тензоры слева от центра
+//! ортогональности левоортонормированы, справа — правоортонормированы. Тогда
+//! сингулярные значения на любой связи у центра совпадают с коэффициентами
+//! Шмидта, и усечение оптимально.
+//!
+//! Кубит с индексом `k` соответствует биту `k` индекса базисного состояния.
+
+use crate::core::gates::{Axis, Gate, RotationGate, controlled_matrix};
+use crate::core::pauli::{Pauli, parse_pauli_product};
+use crate::core::quantum_simulator::{
+    QuantumSimulator, AdvancedQuantumSimulator, validate_gate_targets, validate_joint_outcome, validate_qubit,
+    validate_qubits,
+};
+use crate::core::quantum_state::{QuantumState, Amplitude, MeasureWith, StateSnapshot};
+use crate::core::state_vector::{self, StateVector};
+use crate::error::TriadError;
+use nalgebra::DMatrix;
+use num_complex::Complex64;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// Максимальное число кубитов MPS-симулятора.
+pub const MAX_QUBITS: usize = 1 << 12;
+
+/// Размерность связи по умолчанию.
+pub const DEFAULT_MAX_BOND_DIMENSION: usize = 64;
+
+/// Сингулярные значения меньше этой доли от наибольшего отбрасываются всегда.
+const SVD_CUTOFF: f64 = 1e-12;
+
+/// Допуск, используемый при сравнении вероятностей с нулем.
+const EPSILON: f64 = 1e-10;
+
+/// Допуск проверки сингулярного разложения: относительная невязка
+/// ‖U·S·V† − M‖ и отклонение U†U и V†V от единичной матрицы.
+const SVD_TOLERANCE: f64 = 1e-10;
+
+/// Наибольшее число проходов одностороннего метода Якоби.
+const MAX_JACOBI_SWEEPS: usize = 64;
+
+/// Тензор одного узла цепочки: элемент `[l, s, r]` хранится по индексу `(l·2 + s)·right + r`.
+#[derive(Debug, Clone, PartialEq)]
+struct Site {
+    /// Размерность левой связи.
+    left: usize,
+    /// Размерность правой связи.
+    right: usize,
+    /// Элементы тензора.
+    data: Vec<Amplitude>,
+}
+
+impl Site {
+    /// Создает тензор базисного состояния |0⟩ со связями размерности 1.
+    fn zero() -> Self {
+        Self { left: 1, right: 1, data: vec![Complex64::new(1.0, 0.0), Complex64::new(0.0, 0.0)] }
+    }
+
+    /// Возвращает тензор как матрицу `(left·2) x right`.
+    fn left_matrix(&self) -> DMatrix<Complex64> {
+        DMatrix::from_row_slice(self.left * 2, self.right, &self.data)
+    }
+
+    /// Возвращает тензор как матрицу `left x (2·right)`.
+    fn right_matrix(&self) -> DMatrix<Complex64> {
+        DMatrix::from_row_slice(self.left, 2 * self.right, &self.data)
+    }
+
+    /// Возвращает матрицу `left x right` для значения кубита `s`.
+    fn slice(&self, s: usize) -> DMatrix<Complex64> {
+        DMatrix::from_fn(self.left, self.right, |l, r| self.data[(l * 2 + s) * self.right + r])
+    }
+}
+
+/// Возвращает элементы матрицы по строкам.
+fn row_major(matrix: &DMatrix<Complex64>) -> Vec<Amplitude> {
+    matrix.transpose().as_slice().to_vec()
+}
+
+/// Сингулярное разложение M = U·diag(S)·V†: столбцы U и строки V†
+/// ортонормированы, сингулярные значения упорядочены по убыванию.
+struct Svd {
+    u: DMatrix<Complex64>,
+    values: Vec<f64>,
+    v_t: DMatrix<Complex64>,
+}
+
+/// Вычисляет сингулярное разложение матрицы.
+///
+/// SVD из nalgebra на вырожденных комплексных матрицах иногда возвращает
+/// неточное разложение, поэтому результат проверяется, и при ошибке
+/// разложение пересчитывается односторонним методом Якоби.
+fn svd(matrix: &DMatrix<Complex64>) -> Svd {
+    let decomposition = matrix.clone().svd(true, true);
+    if let (Some(u), Some(v_t)) = (decomposition.u, decomposition.v_t) {
+        let candidate = Svd { u, values: decomposition.singular_values.iter().copied().collect(), v_t };
+        if is_accurate_svd(matrix, &candidate) {
+            return candidate;
+        }
+    }
+    jacobi_svd(matrix)
+}
+
+/// Проверяет невязку разложения, ортонормированность U и V† и порядок значений.
+fn is_accurate_svd(matrix: &DMatrix<Complex64>, svd: &Svd) -> bool {
+    let rank = svd.values.len();
+    if svd.values.iter().any(|s| !s.is_finite()) || svd.values.windows(2).any(|w| w[0] < w[1]) {
+        return false;
+    }
+
+    let mut scaled = svd.v_t.clone();
+    for (i, mut row) in scaled.row_iter_mut().enumerate() {
+        row *= Complex64::new(svd.values[i], 0.0);
+    }
+    let identity = DMatrix::<Complex64>::identity(rank, rank);
+    let residual = (&svd.u * scaled - matrix).norm();
+
+    // NaN не проходит ни одно из сравнений
+    residual <= SVD_TOLERANCE * matrix.norm().max(1.0)
+        && (svd.u.adjoint() * &svd.u - &identity).norm() <= SVD_TOLERANCE
+        && (&svd.v_t * svd.v_t.adjoint() - &identity).norm() <= SVD_TOLERANCE
+}
+
+/// Сингулярное разложение односторонним методом Якоби (Хестенса): столбцы
+/// матрицы попарно ортогонализуются вращениями, пока не станут ортогональными.
+fn jacobi_svd(matrix: &DMatrix<Complex64>) -> Svd {
+    let (rows, cols) = matrix.shape();
+    if cols > rows {
+        // M† = V·S·U†
+        let Svd { u, values, v_t } = jacobi_svd(&matrix.adjoint());
+        return Svd { u: v_t.adjoint(), values, v_t: u.adjoint() };
+    }
+
+    let mut a = matrix.clone();
+    let mut v = DMatrix::<Complex64>::identity(cols, cols);
+    for _ in 0..MAX_JACOBI_SWEEPS {
+        let mut rotated = false;
+        for p in 0..cols {
+            for q in p + 1..cols {
+                let alpha = a.column(p).norm_squared();
+                let beta = a.column(q).norm_squared();
+                let gamma = a.column(p).dotc(&a.column(q));
+                let magnitude = gamma.norm();
+                if magnitude <= f64::EPSILON * (alpha * beta).sqrt() {
+                    continue;
+                }
+                rotated = true;
+
+                // После умножения столбца q на фазу задача сводится к вещественному вращению
+                let phase = (gamma / magnitude).conj();
+                let zeta = (beta - alpha) / (2.0 * magnitude);
+                let t = zeta.signum() / (zeta.abs() + (1.0 + zeta * zeta).sqrt());
+                let c = 1.0 / (1.0 + t * t).sqrt();
+                let s = c * t;
+                rotate_columns(&mut a, p, q, c, s, phase);
+                rotate_columns(&mut v, p, q, c, s, phase);
+            }
+        }
+        if !rotated {
+            break;
+        }
+    }
+
+    let norms: Vec<f64> = (0..cols).map(|j| a.column(j).norm()).collect();
+    let mut order: Vec<usize> = (0..cols).collect();
+    order.sort_by(|&i, &j| norms[j].total_cmp(&norms[i]));
+
+    let u = DMatrix::from_fn(rows, cols, |i, j| {
+        let column = order[j];
+        if norms[column] > 0.0 { a[(i, column)] / norms[column] } else { Complex64::new(0.0, 0.0) }
+    });
+    let v_t = DMatrix::from_fn(cols, cols, |i, j| v[(j, order[i])].conj());
+    Svd { u, values: order.iter().map(|&j| norms[j]).collect(), v_t }
+}
+
+/// Заменяет столбцы `p` и `q` на `c·a_p − s·φ·a_q` и `s·a_p + c·φ·a_q`.
+fn rotate_columns(matrix: &mut DMatrix<Complex64>, p: usize, q: usize, c: f64, s: f64, phase: Complex64) {
+    for i in 0..matrix.nrows() {
+        let x = matrix[(i, p)];
+        let y = matrix[(i, q)] * phase;
+        matrix[(i, p)] = x * c - y * s;
+        matrix[(i, q)] = x * s + y * c;
+    }
+}
+
+/// Состояние в форме матричного произведения.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MpsState {
+    /// Тензоры узлов; узел `k` соответствует кубиту `k`.
+    sites: Vec<Site>,
+    /// Центр ортогональности.
+    center: usize,
+    /// Максимальная размерность связи.
+    max_bond_dimension: usize,
+    /// Накопленный отброшенный вес сингулярных значений.
+    truncation_error: f64,
+}
+
+impl MpsState {
+    /// Создает состояние |0...0⟩ с размерностью связи по умолчанию.
+    pub fn new(num_qubits: usize) -> Self {
+        Self::try_new(num_qubits, DEFAULT_MAX_BOND_DIMENSION).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Создает состояние |0...0⟩ с ограничением размерности связи `max_bond_dimension`,
+    /// возвращая ошибку для недопустимых параметров.
+    pub fn try_new(num_qubits: usize, max_bond_dimension: usize) -> Result<Self, TriadError> {
+        if num_qubits == 0 {
+            return Err(TriadError::NoQubits);
+        }
+        if num_qubits > MAX_QUBITS {
+            return Err(TriadError::TooManyQubits { requested: num_qubits, max: MAX_QUBITS });
+        }
+        if max_bond_dimension == 0 {
+            return Err(TriadError::InvalidArgument("размерность связи должна быть положительной".to_string()));
+        }
+
+        Ok(Self {
+            sites: vec![Site::zero(); num_qubits],
+            center: 0,
+            max_bond_dimension,
+            truncation_error: 0.0,
+        })
+    }
+
+    /// Возвращает максимальную размерность связи.
+    pub fn max_bond_dimension(&self) -> usize {
+        self.max_bond_dimension
+    }
+
+    /// Возвращает накопленный отброшенный вес: сумму по всем усечениям долей
+    /// квадратов отброшенных сингулярных значений. Для малых значений это
+    /// оценка сверху для 1 - F, где F — точность относительно точного состояния.
+    pub fn truncation_error(&self) -> f64 {
+        self.truncation_error
+    }
+
+    /// Возвращает размерности связей между соседними кубитами (n - 1 значение).
+    pub fn bond_dimensions(&self) -> Vec<usize> {
+        self.sites[..self.sites.len() - 1].iter().map(|site| site.right).collect()
+    }
+
+    /// Проверяет, что индекс кубита находится в допустимых пределах.
+    fn check_qubit(&self, qubit: usize) {
+        validate_qubit(qubit, self.sites.len()).unwrap_or_else(|err| panic!("{}", err));
+    }
+
+    /// Перемещает центр ортогональности на узел `target` QR-разложениями.
+    fn move_center(&mut self, target: usize) {
+        while self.center < target {
+            let i = self.center;
+            let qr = self.sites[i].left_matrix().qr();
+            let (q, r) = (qr.q(), qr.r());
+            let bond = q.ncols();
+
+            self.sites[i] = Site { left: self.sites[i].left, right: bond, data: row_major(&q) };
+            let next = &self.sites[i + 1];
+            let merged = r * next.right_matrix();
+            self.sites[i + 1] = Site { left: bond, right: next.right, data: row_major(&merged) };
+            self.center += 1;
+        }
+
+        while self.center > target {
+            let i = self.center;
+            // M = R†·Q† из QR-разложения M†
+            let qr = self.sites[i].right_matrix().adjoint().qr();
+            let (q, r) = (qr.q(), qr.r());
+            let bond = q.ncols();
+
+            self.sites[i] = Site { left: bond, right: self.sites[i].right, data: row_major(&q.adjoint()) };
+            let prev = &self.sites[i - 1];
+            let merged = prev.left_matrix() * r.adjoint();
+            self.sites[i - 1] = Site { left: prev.left, right: bond, data: row_major(&merged) };
+            self.center -= 1;
+        }
+    }
+
+    /// Применяет однокубитовый оператор (матрица 2x2 по строкам).
+    /// Унитарный оператор сохраняет каноническую форму.
+    pub fn apply_single_qubit(&mut self, qubit: usize, matrix: &[Amplitude]) {
+        self.check_qubit(qubit);
+        let site = &mut self.sites[qubit];
+        for l in 0..site.left {
+            for r in 0..site.right {
+                let i0 = (l * 2) * site.right + r;
+                let i1 = (l * 2 + 1) * site.right + r;
+                let (a0, a1) = (site.data[i0], site.data[i1]);
+                site.data[i0] = matrix[0] * a0 + matrix[1] * a1;
+                site.data[i1] = matrix[2] * a0 + matrix[3] * a1;
+            }
+        }
+    }
+
+    /// Применяет k-кубитовый оператор (матрица 2^k x 2^k по строкам) к кубитам `targets`.
+    /// Первый кубит соответствует старшему биту индекса матрицы. Несмежные
+    /// кубиты сначала сдвигаются друг к другу цепочкой SWAP, затем возвращаются.
+    pub fn apply_multi_qubit(&mut self, targets: &[usize], matrix: &[Amplitude]) {
+        validate_qubits(targets, self.sites.len()).unwrap_or_else(|err| panic!("{}", err));
+        assert_eq!(matrix.len(), 1 << (2 * targets.len()), "Размер матрицы не соответствует числу целевых кубитов");
+        if let [qubit] = targets {
+            self.apply_single_qubit(*qubit, matrix);
+            return;
+        }
+
+        // order[p] — логический кубит, находящийся сейчас на узле p
+        let mut order: Vec<usize> = (0..self.sites.len()).collect();
+        let base = *targets.iter().min().unwrap();
+        let last = *targets.iter().max().unwrap();
+
+        for (j, &target) in targets.iter().enumerate() {
+            let mut position = order.iter().position(|&q| q == target).unwrap();
+            while position > base + j {
+                self.swap_adjacent(position - 1);
+                order.swap(position - 1, position);
+                position -= 1;
+            }
+        }
+
+        self.apply_block(base, matrix);
+
+        for qubit in base..=last {
+            let mut position = order.iter().position(|&q| q == qubit).unwrap();
+            while position > qubit {
+                self.swap_adjacent(position - 1);
+                order.swap(position - 1, position);
+                position -= 1;
+            }
+        }
+    }
+
+    /// Меняет местами кубиты на узлах `site` и `site + 1`.
+    fn swap_adjacent(&mut self, site: usize) {
+        let zero = Complex64::new(0.0, 0.0);
+        let one = Complex64::new(1.0, 0.0);
+        let swap = [
+            one, zero, zero, zero,
+            zero, zero, one, zero,
+            zero, one, zero, zero,
+            zero, zero, zero, one,
+        ];
+        self.apply_block(site, &swap);
+    }
+
+    /// Применяет оператор к k смежным узлам, начиная с `start`, и заново
+    /// разлагает блок сингулярными разложениями слева направо.
+    fn apply_block(&mut self, start: usize, matrix: &[Amplitude]) {
+        let dim = (matrix.len() as f64).sqrt() as usize;
+        let k = dim.trailing_zeros() as usize;
+        self.move_center(start);
+
+        // θ[l, s, r] со старшим битом s на первом узле блока
+        let left = self.sites[start].left;
+        let mut theta = self.sites[start].left_matrix();
+        for site in &self.sites[start + 1..start + k] {
+            let product = theta * site.right_matrix();
+            theta = DMatrix::from_row_slice(product.nrows() * 2, site.right, &row_major(&product));
+        }
+        let right = theta.ncols();
+        let theta = row_major(&theta);
+
+        let mut applied = vec![Complex64::new(0.0, 0.0); theta.len()];
+        for l in 0..left {
+            for s in 0..dim {
+                for r in 0..right {
+                    applied[(l * dim + s) * right + r] = (0..dim)
+                        .map(|s2| matrix[s * dim + s2] * theta[(l * dim + s2) * right + r])
+                        .sum();
+                }
+            }
+        }
+
+        // Остаток блока хранится как матрица (L·2) x (2^(m-1)·R) по строкам
+        let mut rest = applied;
+        let mut rest_left = left;
+        for j in 0..k - 1 {
+            let cols = (dim >> (j + 1)) * right;
+            let Svd { u, values, v_t } = svd(&DMatrix::from_row_slice(rest_left * 2, cols, &rest));
+
+            let keep = self.truncate(&values);
+            let norm: f64 = values.iter().map(|s| s * s).sum::<f64>().sqrt();
+            let kept_norm: f64 = values.iter().take(keep).map(|s| s * s).sum::<f64>().sqrt();
+
+            let u = u.columns(0, keep).into_owned();
+            self.sites[start + j] = Site { left: rest_left, right: keep, data: row_major(&u) };
+
+            let mut carried = v_t.rows(0, keep).into_owned();
+            for (i, mut row) in carried.row_iter_mut().enumerate() {
+                row *= Complex64::new(values[i] * norm / kept_norm, 0.0);
+            }
+            rest = row_major(&carried);
+            rest_left = keep;
+        }
+
+        self.sites[start + k - 1] = Site { left: rest_left, right, data: rest };
+        self.center = start + k - 1;
+    }
+
+    /// Выбирает число сохраняемых сингулярных значений и учитывает отброшенный вес.
+    fn truncate(&mut self, values: &[f64]) -> usize {
+        let largest = values.first().copied().unwrap_or(0.0);
+        let significant = values.iter().take_while(|&&s| s > largest * SVD_CUTOFF).count().max(1);
+        let keep = significant.min(self.max_bond_dimension);
+
+        let total: f64 = values.iter().map(|s| s * s).sum();
+        let discarded: f64 = values[keep..].iter().map(|s| s * s).sum();
+        if total > 0.0 {
+            self.truncation_error += discarded / total;
+        }
+        keep
+    }
+
+    /// Вычисляет вероятность получить `outcome` при измерении кубита.
+    pub fn probability_of_outcome(&self, qubit: usize, outcome: bool) -> f64 {
+        self.check_qubit(qubit);
+        let mut copy = self.clone();
+        copy.move_center(qubit);
+        copy.local_probability(qubit, outcome)
+    }
+
+    /// Вероятность результата на узле центра ортогональности.
+    fn local_probability(&self, qubit: usize, outcome: bool) -> f64 {
+        debug_assert_eq!(self.center, qubit);
+        let site = &self.sites[qubit];
+        let s = outcome as usize;
+        (0..site.left)
+            .flat_map(|l| (0..site.right).map(move |r| (l * 2 + s) * site.right + r))
+            .map(|i| site.data[i].norm_sqr())
+            .sum()
+    }
+
+    /// Вычисляет совместную вероятность получить битовую строку `outcome`
+    /// при измерении кубитов `qubits` (бит `i` соответствует `qubits[i]`).
+    pub fn probability_of_joint_outcome(&self, qubits: &[usize], outcome: u64) -> f64 {
+        validate_joint_outcome(qubits, outcome, self.sites.len())
+            .unwrap_or_else(|err| panic!("{}", err));
+
+        let mut copy = self.clone();
+        let mut probability = 1.0;
+        for (i, &qubit) in qubits.iter().enumerate() {
+            let bit = (outcome >> i) & 1 == 1;
+            copy.move_center(qubit);
+            let p = copy.local_probability(qubit, bit);
+            if p < EPSILON {
+                return 0.0;
+            }
+            probability *= p;
+            copy.project(qubit, bit, p);
+        }
+        probability
+    }
+
+    /// Оставляет в узле центра только компоненту `outcome` и перенормирует состояние.
+    fn project(&mut self, qubit: usize, outcome: bool, probability: f64) {
+        let site = &mut self.sites[qubit];
+        let scale = 1.0 / probability.sqrt();
+        for (i, value) in site.data.iter_mut().enumerate() {
+            if ((i / site.right) % 2 == 1) == outcome {
+                *value *= scale;
+            } else {
+                *value = Complex64::new(0.0, 0.0);
+            }
+        }
+    }
+
+    /// Проецирует состояние на указанный результат измерения кубита
+    /// и перенормирует его.
+    pub fn collapse(&mut self, qubit: usize, outcome: bool) {
+        self.check_qubit(qubit);
+        self.move_center(qubit);
+        let probability = self.local_probability(qubit, outcome);
+        if probability < EPSILON {
+            panic!("Невозможно спроецировать кубит {} на результат с нулевой вероятностью", qubit);
+        }
+        self.project(qubit, outcome, probability);
+    }
+
+    /// Измеряет кубит, используя заданное случайное число из [0, 1).
+    /// Результат равен 1, если `random` не меньше вероятности получить 0.
+    /// Исход с вероятностью меньше `EPSILON` заменяется противоположным.
+    pub fn measure_with(&mut self, qubit: usize, random: f64) -> bool {
+        self.check_qubit(qubit);
+        self.move_center(qubit);
+        let mut outcome = random >= self.local_probability(qubit, false);
+        if self.local_probability(qubit, outcome) < EPSILON {
+            outcome = !outcome;
+        }
+        self.collapse(qubit, outcome);
+        outcome
+    }
+
+    /// Вычисляет скалярное произведение ⟨self|other⟩ сверткой трансфер-матриц.
+    pub fn inner_product(&self, other: &MpsState) -> Amplitude {
+        assert_eq!(self.sites.len(), other.sites.len(), "Состояния должны иметь одинаковое число кубитов");
+
+        let mut environment = DMatrix::from_element(1, 1, Complex64::new(1.0, 0.0));
+        for (a, b) in self.sites.iter().zip(&other.sites) {
+            environment = (0..2)
+                .map(|s| a.slice(s).adjoint() * &environment * b.slice(s))
+                .fold(DMatrix::zeros(a.right, b.right), |acc, term| acc + term);
+        }
+        environment[(0, 0)]
+    }
+
+    /// Вычисляет ожидаемое значение произведения операторов Паули.
+    pub fn expectation_pauli(&self, pauli_product: &[(usize, char)]) -> f64 {
+        self.try_expectation_pauli(pauli_product)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Вычисляет ожидаемое значение произведения операторов Паули,
+    /// возвращая ошибку для некорректного произведения.
+    pub fn try_expectation_pauli(&self, pauli_product: &[(usize, char)]) -> Result<f64, TriadError> {
+        let parsed = parse_pauli_product(pauli_product, self.sites.len())?;
+        let mut transformed = self.clone();
+        for (qubit, pauli) in parsed {
+            if pauli != Pauli::I {
+                transformed.apply_single_qubit(qubit, &pauli.matrix());
+            }
+        }
+        Ok(self.inner_product(&transformed).re)
+    }
+
+    /// Возвращает коэффициенты Шмидта на связи между кубитами `bond` и `bond + 1`.
+    pub fn schmidt_values(&self, bond: usize) -> Vec<f64> {
+        assert!(bond + 1 < self.sites.len(), "Связь {} выходит за пределы цепочки", bond);
+        let mut copy = self.clone();
+        copy.move_center(bond);
+        svd(&copy.sites[bond].left_matrix()).values.into_iter().filter(|&s| s > SVD_CUTOFF).collect()
+    }
+
+    /// Возвращает энтропию запутанности фон Неймана (в натуральных единицах)
+    /// между кубитами `0..=bond` и остальными.
+    pub fn entanglement_entropy(&self, bond: usize) -> f64 {
+        self.schmidt_values(bond).iter()
+            .map(|s| s * s)
+            .filter(|&p| p > 0.0)
+            .map(|p| -p * p.ln())
+            .sum()
+    }
+
+    /// Строит вектор состояния. Возвращает ошибку, если кубитов больше,
+    /// чем `state_vector::MAX_QUBITS`.
+    pub fn to_state_vector(&self) -> Result<StateVector, TriadError> {
+        let n = self.sites.len();
+        if n > state_vector::MAX_QUBITS {
+            return Err(TriadError::TooManyQubits { requested: n, max: state_vector::MAX_QUBITS });
+        }
+        Ok(StateVector::from_amplitudes((0..1u64 << n).map(|i| self.amplitude(i)).collect()))
+    }
+}
+
+impl MeasureWith for MpsState {
+    fn measure_with(&mut self, qubit: usize, random: f64) -> bool {
+        MpsState::measure_with(self, qubit, random)
+    }
+}
+
+impl QuantumState for MpsState {
+    fn num_qubits(&self) -> usize {
+        self.sites.len()
+    }
+
+    fn probability(&self, state: u64) -> f64 {
+        self.amplitude(state).norm_sqr()
+    }
+
+    /// Кубиты с номерами от 64 и выше считаются равными нулю.
+    fn amplitude(&self, state: u64) -> Amplitude {
+        if self.sites.len() < 64 && state >> self.sites.len() != 0 {
+            return Complex64::new(0.0, 0.0);
+        }
+
+        let row = self.sites.iter().enumerate().fold(
+            DMatrix::from_element(1, 1, Complex64::new(1.0, 0.0)),
+            |row, (k, site)| {
+                let bit = if k < 64 { ((state >> k) & 1) as usize } else { 0 };
+                row * site.slice(bit)
+            },
+        );
+        row[(0, 0)]
+    }
+
+    /// Применяет оператор, заданный полной матрицей 2^n x 2^n по строкам.
+    fn apply_operator(&mut self, operator: &[Amplitude]) {
+        let targets: Vec<usize> = (0..self.sites.len()).rev().collect();
+        self.apply_multi_qubit(&targets, operator);
+    }
+
+    fn measure(&mut self, qubit: usize) -> bool {
+        let random = rand::thread_rng().gen::<f64>();
+        self.measure_with(qubit, random)
+    }
+
+    /// Чистое состояние является произведением состояний кубитов тогда и только
+    /// тогда, когда на каждой связи ровно один ненулевой коэффициент Шмидта.
+    fn is_entangled(&self) -> bool {
+        (0..self.sites.len() - 1).any(|bond| {
+            self.schmidt_values(bond).iter().filter(|&&s| s * s > 1e-9).count() > 1
+        })
+    }
+}
+
+/// Симулятор на основе MPS с ограниченной размерностью связи.
+/// Поддерживает до `MAX_QUBITS` кубитов.
+#[derive(Debug, Clone)]
+pub struct MpsSimulator {
+    /// Текущее состояние регистра.
+    state: MpsState,
+    /// Генератор случайных чисел для измерений и выборок.
+    rng: StdRng,
+}
+
+impl MpsSimulator {
+    /// Создает новый симулятор с указанным количеством кубитов в состоянии |0...0⟩
+    /// и размерностью связи `DEFAULT_MAX_BOND_DIMENSION`.
+    pub fn new(num_qubits: usize) -> Self {
+        Self {
+            state: MpsState::new(num_qubits),
+            rng: StdRng::from_entropy(),
+        }
+    }
+
+    /// Создает новый симулятор, возвращая ошибку для недопустимого числа кубитов.
+    pub fn try_new(num_qubits: usize) -> Result<Self, TriadError> {
+        Self::try_with_max_bond_dimension(num_qubits, DEFAULT_MAX_BOND_DIMENSION)
+    }
+
+    /// Создает новый симулятор с ограничением размерности связи.
+    pub fn with_max_bond_dimension(num_qubits: usize, max_bond_dimension: usize) -> Self {
+        Self::try_with_max_bond_dimension(num_qubits, max_bond_dimension)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Создает новый симулятор с ограничением размерности связи,
+    /// возвращая ошибку для недопустимых параметров.
+    pub fn try_with_max_bond_dimension(num_qubits: usize, max_bond_dimension: usize) -> Result<Self, TriadError> {
+        Ok(Self {
+            state: MpsState::try_new(num_qubits, max_bond_dimension)?,
+            rng: StdRng::from_entropy(),
+        })
+    }
+
+    /// Возвращает количество кубитов в регистре.
+    pub fn num_qubits(&self) -> usize {
+        self.state.sites.len()
+    }
+
+    /// Возвращает ссылку на текущее MPS-состояние.
+    pub fn mps_state(&self) -> &MpsState {
+        &self.state
+    }
+
+    /// Возвращает максимальную размерность связи.
+    pub fn max_bond_dimension(&self) -> usize {
+        self.state.max_bond_dimension
+    }
+
+    /// Возвращает накопленную ошибку усечения (см. `MpsState::truncation_error`).
+    pub fn truncation_error(&self) -> f64 {
+        self.state.truncation_error
+    }
+
+    /// Возвращает размерности связей между соседними кубитами.
+    pub fn bond_dimensions(&self) -> Vec<usize> {
+        self.state.bond_dimensions()
+    }
+
+    /// Применяет гейт вращения вокруг указанной оси.
+    fn rotate(&mut self, qubit: usize, axis: Axis, angle: f64) {
+        let gate = RotationGate { axis, angle };
+        self.state.apply_single_qubit(qubit, &gate.matrix());
+    }
+
+    /// Применяет однокубитовый оператор к целевому кубиту при условии, что контрольный в |1⟩.
+    fn apply_controlled(&mut self, control: usize, target: usize, matrix: &[Amplitude]) {
+        let zero = Complex64::new(0.0, 0.0);
+        let one = Complex64::new(1.0, 0.0);
+        let full = [
+            one, zero, zero, zero,
+            zero, one, zero, zero,
+            zero, zero, matrix[0], matrix[1],
+            zero, zero, matrix[2], matrix[3],
+        ];
+        self.state.apply_multi_qubit(&[control, target], &full);
+    }
+}
+
+impl QuantumSimulator for MpsSimulator {
+    fn new(num_qubits: usize) -> Self where Self: Sized {
+        Self::new(num_qubits)
+    }
+
+    fn try_new(num_qubits: usize) -> Result<Self, TriadError> where Self: Sized {
+        Self::try_new(num_qubits)
+    }
+
+    fn num_qubits(&self) -> usize {
+        self.state.sites.len()
+    }
+
+    fn get_state(&self) -> Box<dyn QuantumState> {
+        Box::new(StateSnapshot::new(self.state.clone(), &self.rng))
+    }
+
+    /// Возвращает регистр в |0...0⟩ и обнуляет накопленную ошибку усечения.
+    fn reset(&mut self) {
+        self.state = MpsState::try_new(self.state.sites.len(), self.state.max_bond_dimension)
+            .unwrap_or_else(|err| panic!("{}", err));
+    }
+
+    fn hadamard(&mut self, qubit: usize) {
+        let factor = Complex64::new(1.0 / 2.0_f64.sqrt(), 0.0);
+        self.state.apply_single_qubit(qubit, &[factor, factor, factor, -factor]);
+    }
+
+    fn x(&mut self, qubit: usize) {
+        self.state.apply_single_qubit(qubit, &Pauli::X.matrix());
+    }
+
+    fn y(&mut self, qubit: usize) {
+        self.state.apply_single_qubit(qubit, &Pauli::Y.matrix());
+    }
+
+    fn z(&mut self, qubit: usize) {
+        self.state.apply_single_qubit(qubit, &Pauli::Z.matrix());
+    }
+
+    fn cnot(&mut self, control: usize, target: usize) {
+        self.apply_controlled(control, target, &Pauli::X.matrix());
+    }
+
+    fn measure(&mut self, qubit: usize) -> bool {
+        let random = self.rng.gen::<f64>();
+        self.state.measure_with(qubit, random)
+    }
+
+    fn rng(&mut self) -> Option<&mut StdRng> {
+        Some(&mut self.rng)
+    }
+
+    fn apply_gate(&mut self, gate: &dyn Gate, targets: &[usize]) {
+        validate_gate_targets(gate, targets, self.state.sites.len())
+            .unwrap_or_else(|err| panic!("{}", err));
+        self.state.apply_multi_qubit(targets, &gate.matrix());
+    }
+
+    fn get_expectation_value(&self, pauli_product: &[(usize, char)]) -> f64 {
+        self.state.expectation_pauli(pauli_product)
+    }
+
+    fn probability_of_outcome(&self, qubit: usize, outcome: bool) -> f64 {
+        self.state.probability_of_outcome(qubit, outcome)
+    }
+
+    fn probability_of_joint_outcome(&self, qubits: &[usize], outcome: u64) -> f64 {
+        self.state.probability_of_joint_outcome(qubits, outcome)
+    }
+}
+
+impl AdvancedQuantumSimulator for MpsSimulator {
+    fn s_gate(&mut self, qubit: usize) {
+        let one = Complex64::new(1.0, 0.0);
+        let zero = Complex64::new(0.0, 0.0);
+        self.state.apply_single_qubit(qubit, &[one, zero, zero, Complex64::new(0.0, 1.0)]);
+    }
+
+    fn t_gate(&mut self, qubit: usize) {
+        let one = Complex64::new(1.0, 0.0);
+        let zero = Complex64::new(0.0, 0.0);
+        let phase = Complex64::from_polar(1.0, std::f64::consts::FRAC_PI_4);
+        self.state.apply_single_qubit(qubit, &[one, zero, zero, phase]);
+    }
+
+    fn cz(&mut self, control: usize, target: usize) {
+        self.apply_controlled(control, target, &Pauli::Z.matrix());
+    }
+
+    fn swap(&mut self, qubit1: usize, qubit2: usize) {
+        let zero = Complex64::new(0.0, 0.0);
+        let one = Complex64::new(1.0, 0.0);
+        let matrix = [
+            one, zero, zero, zero,
+            zero, zero, one, zero,
+            zero, one, zero, zero,
+            zero, zero, zero, one,
+        ];
+        self.state.apply_multi_qubit(&[qubit1, qubit2], &matrix);
+    }
+
+    fn apply_unitary(&mut self, qubit: usize, matrix: &[Amplitude]) {
+        self.state.apply_single_qubit(qubit, matrix);
+    }
+
+    fn rx(&mut self, qubit: usize, angle: f64) {
+        self.rotate(qubit, Axis::X, angle);
+    }
+
+    fn ry(&mut self, qubit: usize, angle: f64) {
+        self.rotate(qubit, Axis::Y, angle);
+    }
+
+    fn rz(&mut self, qubit: usize, angle: f64) {
+        self.rotate(qubit, Axis::Z, angle);
+    }
+
+    fn controlled_unitary(&mut self, control: usize, target: usize, matrix: &[Amplitude]) {
+        self.apply_controlled(control, target, matrix);
+    }
+
+    fn multi_controlled_unitary(&mut self, controls: &[usize], target: usize, matrix: &[Amplitude]) {
+        let qubits: Vec<usize> = controls.iter().copied().chain(std::iter::once(target)).collect();
+        self.state.apply_multi_qubit(&qubits, &controlled_matrix(controls.len(), matrix));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::circuit::Circuit;
+    use crate::core::gates::CustomGate;
+    use crate::core::state_vector::StateVectorSimulator;
+    use std::f64::consts::PI;
+
+    /// Матрица Тоффоли: первые два кубита управляющие.
+    fn toffoli() -> CustomGate {
+        let mut matrix = vec![Complex64::new(0.0, 0.0); 64];
+        for row in 0..8 {
+            let col = if row >= 6 { row ^ 1 } else { row };
+            matrix[row * 8 + col] = Complex64::new(1.0, 0.0);
+        }
+        CustomGate { matrix, num_qubits: 3, name: "CCX".to_string() }
+    }
+
+    #[test]
+    fn test_exact_simulation_matches_state_vector() {
+        let mut circuit = Circuit::new(6, 0);
+        circuit.h(0).ry(1, 0.4).cnot(0, 5).rx(3, 1.1).cz(5, 2).t(2).swap(1, 4)
+            .gate(toffoli(), &[4, 0, 2]).cnot(3, 1).s(5).rz(0, -0.8).cnot(2, 3).h(4);
+
+        let mut pure = StateVectorSimulator::new(6);
+        let mut mps = MpsSimulator::new(6);
+        circuit.run_on(&mut pure).unwrap();
+        circuit.run_on(&mut mps).unwrap();
+
+        let state = mps.mps_state().to_state_vector().unwrap();
+        for (a, b) in state.amplitudes().iter().zip(pure.state_vector().amplitudes()) {
+            assert!((a - b).norm() < 1e-10);
+        }
+        assert!(mps.truncation_error() < 1e-20);
+
+        for product in [vec![(0, 'Z')], vec![(1, 'X'), (4, 'Y')], vec![(2, 'Z'), (3, 'Z'), (5, 'X')]] {
+            assert!((mps.get_expectation_value(&product) - pure.get_expectation_value(&product)).abs() < 1e-10);
+        }
+        for outcome in 0..8 {
+            let expected = pure.probability_of_joint_outcome(&[5, 0, 3], outcome);
+            assert!((mps.probability_of_joint_outcome(&[5, 0, 3], outcome) - expected).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_random_circuits_match_state_vector() {
+        use crate::core::gates::ParametricGate;
+
+        let mut rng = StdRng::seed_from_u64(2024);
+        for _ in 0..400 {
+            let n = 4;
+            let mut circuit = Circuit::new(n, 0);
+            for _ in 0..8 {
+                let (a, b) = (rng.gen_range(0..n), rng.gen_range(0..n - 1));
+                let b = if b >= a { b + 1 } else { b };
+                let angle = rng.gen_range(-PI..PI);
+                match rng.gen_range(0..6) {
+                    0 => circuit.h(a),
+                    1 => circuit.ry(a, angle),
+                    2 => circuit.cnot(a, b),
+                    3 => circuit.swap(a, b),
+                    4 => circuit.gate(ParametricGate::RXX { theta: angle }, &[a, b]),
+                    _ => circuit.gate(ParametricGate::RYY { theta: angle }, &[a, b]),
+                };
+            }
+
+            let mut pure = StateVectorSimulator::new(n);
+            let mut mps = MpsSimulator::new(n);
+            circuit.run_on(&mut pure).unwrap();
+            circuit.run_on(&mut mps).unwrap();
+
+            let state = mps.mps_state().to_state_vector().unwrap();
+            for (a, b) in state.amplitudes().iter().zip(pure.state_vector().amplitudes()) {
+                assert!((a - b).norm() < 1e-9, "MPS расходится с вектором состояния на схеме {:?}", circuit);
+            }
+        }
+
+        // Схема, на которой SVD nalgebra теряет точность на вырожденных матрицах
+        let mut circuit = Circuit::new(4, 0);
+        circuit.gate(ParametricGate::RXX { theta: 0.9 }, &[2, 3]).gate(ParametricGate::RXX { theta: 0.9 }, &[2, 3])
+            .swap(0, 3).swap(1, 2).swap(1, 2).cnot(2, 3)
+            .gate(ParametricGate::RXX { theta: 0.9 }, &[3, 0]).swap(2, 1);
+        let mut mps = MpsSimulator::new(4);
+        circuit.run_on(&mut mps).unwrap();
+        assert!((mps.mps_state().inner_product(mps.mps_state()).re - 1.0).abs() < 1e-10);
+        assert!(mps.probability_of_joint_outcome(&[0, 1, 2, 3], 0b1100).abs() < 1e-10);
+        assert!(mps.probability_of_joint_outcome(&[0, 1, 2, 3], 0b1110).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_chain_of_bell_pairs_stays_small() {
+        let n = 400;
+        let mut simulator = MpsSimulator::with_max_bond_dimension(n, 4);
+        for pair in (0..n).step_by(2) {
+            simulator.hadamard(pair);
+            simulator.cnot(pair, pair + 1);
+        }
+
+        let bonds = simulator.bond_dimensions();
+        assert!(bonds.iter().all(|&chi| chi <= 2));
+        assert!((simulator.mps_state().entanglement_entropy(0) - 2f64.ln()).abs() < 1e-10);
+        assert!(simulator.mps_state().entanglement_entropy(1).abs() < 1e-10);
+        assert_eq!(simulator.truncation_error(), 0.0);
+
+        for pair in (0..n).step_by(50) {
+            let first = simulator.measure(pair);
+            assert_eq!(simulator.measure(pair + 1), first);
+        }
+        assert!((simulator.get_expectation_value(&[(n - 2, 'X'), (n - 1, 'X')]) - 1.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_truncation_error_is_reported() {
+        // Размерность связи 1 не может представить состояние Белла: отбрасывается половина веса
+        let mut simulator = MpsSimulator::with_max_bond_dimension(2, 1);
+        simulator.hadamard(0);
+        simulator.cnot(0, 1);
+
+        assert!((simulator.truncation_error() - 0.5).abs() < 1e-10);
+        assert_eq!(simulator.bond_dimensions(), vec![1]);
+        assert!(!simulator.get_state().is_entangled());
+        assert!((simulator.get_state().probability(0b00) + simulator.get_state().probability(0b11) - 1.0).abs() < 1e-10);
+
+        simulator.reset();
+        assert_eq!(simulator.truncation_error(), 0.0);
+    }
+
+    #[test]
+    fn test_long_range_gates_and_entanglement() {
+        let mut simulator = MpsSimulator::new(5);
+        simulator.hadamard(0);
+        assert!(!simulator.get_state().is_entangled());
+
+        simulator.cnot(0, 4);
+        assert!(simulator.get_state().is_entangled());
+        // Кубиты 1..3 не участвуют, поэтому связи внутри остаются размерности 2
+        assert!(simulator.bond_dimensions().iter().all(|&chi| chi <= 2));
+        assert!((simulator.probability_of_joint_outcome(&[0, 4], 0b11) - 0.5).abs() < 1e-10);
+        assert!(simulator.probability_of_joint_outcome(&[0, 4], 0b01).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_try_methods_return_errors() {
+        assert!(matches!(MpsSimulator::try_with_max_bond_dimension(3, 0), Err(TriadError::InvalidArgument(_))));
+        assert!(matches!(MpsSimulator::try_new(MAX_QUBITS + 1), Err(TriadError::TooManyQubits { .. })));
+
+        let mut simulator = MpsSimulator::new(2);
+        assert_eq!(simulator.try_cnot(0, 2), Err(TriadError::InvalidQubit { qubit: 2, num_qubits: 2 }));
+        assert_eq!(simulator.try_measure(3), Err(TriadError::InvalidQubit { qubit: 3, num_qubits: 2 }));
+        assert!(MpsSimulator::new(40).mps_state().to_state_vector().is_err());
+    }
+
+    #[test]
+    fn test_measure_with_skips_outcomes_below_epsilon() {
+        // Вероятность |1⟩ равна sin²(5e-6) ≈ 2.5e-11, но random попадает в ее интервал
+        let rotation = RotationGate { axis: Axis::Y, angle: 1e-5 }.matrix();
+        let mut state = MpsState::new(1);
+        state.apply_single_qubit(0, &rotation);
+        assert!(!state.measure_with(0, 0.99999999999));
+        assert!((state.probability(0) - 1.0).abs() < EPSILON);
+    }
+}
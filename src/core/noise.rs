@@ -0,0 +1,199 @@
+//! Квантовые каналы шума в представлении Крауса.
+//!
+//! Канал задается набором операторов Крауса `K_i`, действующих на состояние
+//! как `ρ → Σ K_i ρ K_i†`. Матрицы хранятся по строкам в том же порядке
+//! кубитов, что и матрицы гейтов: первый целевой кубит соответствует старшему
+//! биту индекса матрицы.
+
+use crate::core::pauli::Pauli;
+use crate::core::quantum_state::Amplitude;
+use crate::error::TriadError;
+use num_complex::Complex64;
+
+/// Допуск, с которым проверяется условие полноты Σ K†K = I.
+pub const COMPLETENESS_TOLERANCE: f64 = 1e-8;
+
+/// Квантовый канал, заданный операторами Крауса.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KrausChannel {
+    /// Название канала.
+    name: String,
+    /// Число кубитов, на которые действует канал.
+    num_qubits: usize,
+    /// Операторы Крауса (матрицы 2^n x 2^n по строкам).
+    operators: Vec<Vec<Amplitude>>,
+}
+
+impl KrausChannel {
+    /// Создает канал из произвольного набора операторов Крауса.
+    ///
+    /// Все матрицы должны иметь одинаковый размер 2^n x 2^n и удовлетворять
+    /// условию полноты Σ K†K = I, иначе возвращается ошибка.
+    pub fn new(name: &str, operators: Vec<Vec<Amplitude>>) -> Result<Self, TriadError> {
+        let first = operators.first().ok_or_else(|| TriadError::InvalidArgument(
+            format!("канал {} должен содержать хотя бы один оператор Крауса", name)))?;
+
+        let dim = (first.len() as f64).sqrt() as usize;
+        if dim < 2 || dim * dim != first.len() || !dim.is_power_of_two() {
+            return Err(TriadError::InvalidArgument(format!(
+                "операторы Крауса канала {} должны быть матрицами 2^n x 2^n", name)));
+        }
+        if operators.iter().any(|k| k.len() != first.len()) {
+            return Err(TriadError::InvalidArgument(format!(
+                "операторы Крауса канала {} имеют разный размер", name)));
+        }
+        // Сравнение с NaN ложно, и проверка полноты пропустила бы такие операторы
+        if operators.iter().flatten().any(|a| !a.re.is_finite() || !a.im.is_finite()) {
+            return Err(TriadError::InvalidArgument(format!(
+                "операторы Крауса канала {} содержат бесконечность или NaN", name)));
+        }
+
+        for i in 0..dim {
+            for j in 0..dim {
+                // (Σ K†K)_ij = Σ_k Σ_r conj(K_ri) K_rj
+                let sum: Amplitude = operators.iter()
+                    .flat_map(|k| (0..dim).map(move |r| k[r * dim + i].conj() * k[r * dim + j]))
+                    .sum();
+                let expected = if i == j { 1.0 } else { 0.0 };
+                if (sum - Complex64::new(expected, 0.0)).norm() > COMPLETENESS_TOLERANCE {
+                    return Err(TriadError::NotTracePreserving(name.to_string()));
+                }
+            }
+        }
+
+        Ok(Self {
+            name: name.to_string(),
+            num_qubits: dim.trailing_zeros() as usize,
+            operators,
+        })
+    }
+
+    /// Деполяризующий канал: `ρ → (1 - p)ρ + p/3 (XρX + YρY + ZρZ)`.
+    /// Вероятность `p` должна лежать в [0, 3/4]; при `p = 3/4` состояние
+    /// становится максимально смешанным.
+    pub fn depolarizing(p: f64) -> Result<Self, TriadError> {
+        check_probability("depolarizing", p, 0.75)?;
+        Self::pauli_mixture("depolarizing", &[(Pauli::I, 1.0 - p), (Pauli::X, p / 3.0), (Pauli::Y, p / 3.0), (Pauli::Z, p / 3.0)])
+    }
+
+    /// Затухание амплитуды (релаксация T1): |1⟩ переходит в |0⟩ с вероятностью `gamma`.
+    pub fn amplitude_damping(gamma: f64) -> Result<Self, TriadError> {
+        check_probability("amplitude_damping", gamma, 1.0)?;
+        let zero = Complex64::new(0.0, 0.0);
+        let one = Complex64::new(1.0, 0.0);
+        Self::new("amplitude_damping", vec![
+            vec![one, zero, zero, Complex64::new((1.0 - gamma).sqrt(), 0.0)],
+            vec![zero, Complex64::new(gamma.sqrt(), 0.0), zero, zero],
+        ])
+    }
+
+    /// Затухание фазы (дефазировка T2): недиагональные элементы матрицы
+    /// плотности умножаются на `sqrt(1 - lambda)`, заселенности не меняются.
+    pub fn phase_damping(lambda: f64) -> Result<Self, TriadError> {
+        check_probability("phase_damping", lambda, 1.0)?;
+        let zero = Complex64::new(0.0, 0.0);
+        let one = Complex64::new(1.0, 0.0);
+        Self::new("phase_damping", vec![
+            vec![one, zero, zero, Complex64::new((1.0 - lambda).sqrt(), 0.0)],
+            vec![zero, zero, zero, Complex64::new(lambda.sqrt(), 0.0)],
+        ])
+    }
+
+    /// Инверсия бита: X применяется с вероятностью `p`.
+    pub fn bit_flip(p: f64) -> Result<Self, TriadError> {
+        check_probability("bit_flip", p, 1.0)?;
+        Self::pauli_mixture("bit_flip", &[(Pauli::I, 1.0 - p), (Pauli::X, p)])
+    }
+
+    /// Инверсия фазы: Z применяется с вероятностью `p`.
+    pub fn phase_flip(p: f64) -> Result<Self, TriadError> {
+        check_probability("phase_flip", p, 1.0)?;
+        Self::pauli_mixture("phase_flip", &[(Pauli::I, 1.0 - p), (Pauli::Z, p)])
+    }
+
+    /// Инверсия бита и фазы: Y применяется с вероятностью `p`.
+    pub fn bit_phase_flip(p: f64) -> Result<Self, TriadError> {
+        check_probability("bit_phase_flip", p, 1.0)?;
+        Self::pauli_mixture("bit_phase_flip", &[(Pauli::I, 1.0 - p), (Pauli::Y, p)])
+    }
+
+    /// Возвращает название канала.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Возвращает число кубитов, на которые действует канал.
+    pub fn num_qubits(&self) -> usize {
+        self.num_qubits
+    }
+
+    /// Возвращает операторы Крауса канала.
+    pub fn operators(&self) -> &[Vec<Amplitude>] {
+        &self.operators
+    }
+
+    /// Строит однокубитовый канал, применяющий операторы Паули с заданными вероятностями.
+    fn pauli_mixture(name: &str, terms: &[(Pauli, f64)]) -> Result<Self, TriadError> {
+        let operators = terms.iter()
+            .filter(|(_, probability)| *probability > 0.0)
+            .map(|(pauli, probability)| pauli.matrix().iter().map(|m| m * probability.sqrt()).collect())
+            .collect();
+        Self::new(name, operators)
+    }
+}
+
+/// Проверяет, что параметр канала лежит в промежутке [0, max].
+fn check_probability(channel: &str, value: f64, max: f64) -> Result<(), TriadError> {
+    if !(0.0..=max).contains(&value) {
+        return Err(TriadError::InvalidArgument(format!(
+            "параметр канала {} должен лежать в [0, {}], получено {}", channel, max, value)));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_standard_channels_are_trace_preserving() {
+        for p in [0.0, 0.1, 0.5, 1.0] {
+            assert_eq!(KrausChannel::amplitude_damping(p).unwrap().num_qubits(), 1);
+            assert!(KrausChannel::phase_damping(p).is_ok());
+            assert!(KrausChannel::bit_flip(p).is_ok());
+            assert!(KrausChannel::phase_flip(p).is_ok());
+            assert!(KrausChannel::bit_phase_flip(p).is_ok());
+        }
+        assert_eq!(KrausChannel::depolarizing(0.75).unwrap().operators().len(), 4);
+        // Операторы с нулевым весом не сохраняются
+        assert_eq!(KrausChannel::bit_flip(0.0).unwrap().operators().len(), 1);
+    }
+
+    #[test]
+    fn test_invalid_parameters_are_rejected() {
+        assert!(matches!(KrausChannel::depolarizing(0.8), Err(TriadError::InvalidArgument(_))));
+        assert!(matches!(KrausChannel::amplitude_damping(-0.1), Err(TriadError::InvalidArgument(_))));
+        assert!(matches!(KrausChannel::bit_flip(f64::NAN), Err(TriadError::InvalidArgument(_))));
+    }
+
+    #[test]
+    fn test_custom_kraus_sets_are_validated() {
+        let zero = Complex64::new(0.0, 0.0);
+        let half = Complex64::new(0.5f64.sqrt(), 0.0);
+
+        let channel = KrausChannel::new("reset", vec![
+            vec![Complex64::new(1.0, 0.0), zero, zero, zero],
+            vec![zero, Complex64::new(1.0, 0.0), zero, zero],
+        ]).unwrap();
+        assert_eq!(channel.name(), "reset");
+
+        assert_eq!(KrausChannel::new("half", vec![vec![half, zero, zero, half]]),
+            Err(TriadError::NotTracePreserving("half".to_string())));
+        assert!(KrausChannel::new("empty", Vec::new()).is_err());
+        assert!(KrausChannel::new("odd", vec![vec![zero; 9]]).is_err());
+        assert!(KrausChannel::new("mixed", vec![vec![zero; 4], vec![zero; 16]]).is_err());
+
+        let nan = Complex64::new(f64::NAN, 0.0);
+        assert!(matches!(KrausChannel::new("nan", vec![vec![nan, zero, zero, nan]]), Err(TriadError::InvalidArgument(_))));
+    }
+}
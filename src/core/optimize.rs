@@ -0,0 +1,528 @@
+//! Оптимизация квантовых схем.
+//!
+//! Схемы, построенные алгоритмами, часто содержат очевидную избыточность:
+//! H·H, X·X, соседние вращения вокруг одной оси, пары CNOT. `PassManager`
+//! применяет последовательность проходов (`OptimizationPass`) до неподвижной
+//! точки и возвращает отчет с числом гейтов и глубиной до и после.
+//!
+//! Проходы сохраняют унитарную матрицу схемы с точностью до глобальной фазы
+//! и не переносят гейты через измерения, сбросы, барьеры и классически
+//! управляемые операции.
+
+use crate::core::circuit::{Circuit, CircuitGate, Instruction};
+#[cfg(feature = "native")]
+use crate::core::decompose::{decompose_gate, Basis};
+#[cfg(feature = "native")]
+use crate::core::gates::{multiply, CustomGate};
+use crate::core::gates::{equal_up_to_phase, identity_matrix, Gate, ParametricGate, RotationGate};
+use std::collections::BTreeMap;
+
+/// Точность, с которой гейты считаются взаимно обратными, а вращения — тождественными.
+const TOLERANCE: f64 = 1e-9;
+
+/// Наибольшее число кубитов, на которых проверяется коммутация пары гейтов.
+const COMMUTATION_MAX_QUBITS: usize = 4;
+
+/// Наибольшее число кубитов схемы, для которой `PassManager` сверяет унитарные матрицы.
+const VERIFICATION_MAX_QUBITS: usize = 10;
+
+/// Проход оптимизации: преобразование схемы, сохраняющее ее действие.
+pub trait OptimizationPass {
+    /// Возвращает название прохода для отчета.
+    fn name(&self) -> &str;
+
+    /// Применяет проход и возвращает новую схему.
+    fn run(&self, circuit: &Circuit) -> Circuit;
+}
+
+/// Удаляет соседние пары взаимно обратных гейтов на одних и тех же кубитах
+/// (H·H, X·X, CNOT·CNOT, S·S† и т.п.).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CancelInverses;
+
+impl OptimizationPass for CancelInverses {
+    fn name(&self) -> &str {
+        "CancelInverses"
+    }
+
+    fn run(&self, circuit: &Circuit) -> Circuit {
+        combine_backwards(circuit, false, cancel)
+    }
+}
+
+/// Сливает соседние вращения вокруг одной оси (RZ·RZ, RX·RX, ...) и соседние
+/// параметризованные гейты одного типа (P, U1, RXX, RYY, RZZ, CPhase, CRX, CRY, CRZ);
+/// тождественные результаты удаляются.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MergeRotations;
+
+impl OptimizationPass for MergeRotations {
+    fn name(&self) -> &str {
+        "MergeRotations"
+    }
+
+    fn run(&self, circuit: &Circuit) -> Circuit {
+        combine_backwards(circuit, false, merge)
+    }
+}
+
+/// Сокращение и слияние с учетом коммутации: гейт переносится назад через
+/// коммутирующие с ним гейты к партнеру, с которым он сокращается или сливается
+/// (например, RZ на управляющем кубите проходит через CNOT).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CommutativeCancellation;
+
+impl OptimizationPass for CommutativeCancellation {
+    fn name(&self) -> &str {
+        "CommutativeCancellation"
+    }
+
+    fn run(&self, circuit: &Circuit) -> Circuit {
+        combine_backwards(circuit, true, |earlier, gate| cancel(earlier, gate).or_else(|| merge(earlier, gate)))
+    }
+}
+
+/// Пересинтез цепочек однокубитовых гейтов: произведение каждой цепочки
+/// раскладывается по углам Эйлера в гейты базиса и заменяет цепочку,
+/// если результат короче.
+#[cfg(feature = "native")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResynthesizeSingleQubitRuns {
+    basis: Basis,
+}
+
+#[cfg(feature = "native")]
+impl ResynthesizeSingleQubitRuns {
+    /// Создает проход, синтезирующий цепочки в гейты базиса `basis`.
+    pub fn new(basis: Basis) -> Self {
+        Self { basis }
+    }
+
+    /// Заменяет цепочку гейтов `run` на кубите `qubit`, если ее разложение короче.
+    fn resynthesize(&self, run: &[usize], qubit: usize, instructions: &[Instruction], replacements: &mut [Option<Vec<Instruction>>]) {
+        let Some(&last) = run.last() else { return };
+
+        let product = run.iter().fold(identity_matrix(2), |acc, &index| match &instructions[index] {
+            Instruction::Gate { gate, .. } => multiply(&gate.matrix(), &acc),
+            _ => acc,
+        });
+        let gate = CustomGate { matrix: product, num_qubits: 1, name: "run".to_string() };
+        let Ok(synthesized) = decompose_gate(&gate.into(), &self.basis) else { return };
+        if synthesized.len() >= run.len() {
+            return;
+        }
+
+        for &index in run {
+            replacements[index] = Some(Vec::new());
+        }
+        replacements[last] = Some(synthesized.instructions().iter()
+            .filter_map(|instruction| match instruction {
+                Instruction::Gate { gate, .. } => Some(Instruction::gate(gate.clone(), &[qubit])),
+                _ => None,
+            })
+            .collect());
+    }
+}
+
+#[cfg(feature = "native")]
+impl Default for ResynthesizeSingleQubitRuns {
+    fn default() -> Self {
+        Self::new(Basis::default())
+    }
+}
+
+#[cfg(feature = "native")]
+impl OptimizationPass for ResynthesizeSingleQubitRuns {
+    fn name(&self) -> &str {
+        "ResynthesizeSingleQubitRuns"
+    }
+
+    fn run(&self, circuit: &Circuit) -> Circuit {
+        let instructions = circuit.instructions();
+        let mut replacements: Vec<Option<Vec<Instruction>>> = vec![None; instructions.len()];
+        let mut runs: Vec<Vec<usize>> = vec![Vec::new(); circuit.num_qubits()];
+
+        for (index, instruction) in instructions.iter().enumerate() {
+            match instruction {
+                Instruction::Gate { qubits, .. } if qubits.len() == 1 => runs[qubits[0]].push(index),
+                _ => for qubit in instruction.qubits() {
+                    let run = std::mem::take(&mut runs[qubit]);
+                    self.resynthesize(&run, qubit, instructions, &mut replacements);
+                },
+            }
+        }
+        for (qubit, run) in runs.iter().enumerate() {
+            self.resynthesize(run, qubit, instructions, &mut replacements);
+        }
+
+        let mut optimized = circuit.empty_like();
+        for (instruction, replacement) in instructions.iter().zip(replacements) {
+            match replacement {
+                Some(gates) => gates.into_iter().for_each(|gate| { optimized.push(gate); }),
+                None => { optimized.push(instruction.clone()); },
+            }
+        }
+        optimized
+    }
+}
+
+/// Результат объединения двух гейтов на одних и тех же кубитах.
+enum Combined {
+    /// Гейты взаимно уничтожаются (с точностью до глобальной фазы).
+    Identity,
+    /// Гейты заменяются одним гейтом.
+    Gate(CircuitGate),
+}
+
+/// Сокращает пару гейтов, если второй обратен первому.
+fn cancel(earlier: &CircuitGate, gate: &CircuitGate) -> Option<Combined> {
+    let inverse = earlier.inverse();
+    (inverse.num_qubits() == gate.num_qubits() && equal_up_to_phase(&gate.matrix(), &inverse.matrix(), TOLERANCE))
+        .then_some(Combined::Identity)
+}
+
+/// Сливает пару вращений одного типа в одно, суммируя углы.
+fn merge(earlier: &CircuitGate, gate: &CircuitGate) -> Option<Combined> {
+    let merged: CircuitGate = match (earlier, gate) {
+        (CircuitGate::Rotation(a), CircuitGate::Rotation(b)) if a.axis == b.axis => {
+            RotationGate { axis: a.axis, angle: a.angle + b.angle }.into()
+        },
+        (CircuitGate::Parametric(a), CircuitGate::Parametric(b)) => merge_parametric(a, b)?.into(),
+        _ => return None,
+    };
+
+    let identity = identity_matrix(1 << merged.num_qubits());
+    Some(if equal_up_to_phase(&merged.matrix(), &identity, TOLERANCE) { Combined::Identity } else { Combined::Gate(merged) })
+}
+
+/// Сливает параметризованные гейты одного типа с одним углом.
+fn merge_parametric(a: &ParametricGate, b: &ParametricGate) -> Option<ParametricGate> {
+    use ParametricGate::*;
+
+    Some(match (*a, *b) {
+        (U1 { lambda: x }, U1 { lambda: y }) => U1 { lambda: x + y },
+        (Phase { theta: x }, Phase { theta: y }) => Phase { theta: x + y },
+        (RXX { theta: x }, RXX { theta: y }) => RXX { theta: x + y },
+        (RYY { theta: x }, RYY { theta: y }) => RYY { theta: x + y },
+        (RZZ { theta: x }, RZZ { theta: y }) => RZZ { theta: x + y },
+        (CPhase { theta: x }, CPhase { theta: y }) => CPhase { theta: x + y },
+        (CRX { theta: x }, CRX { theta: y }) => CRX { theta: x + y },
+        (CRY { theta: x }, CRY { theta: y }) => CRY { theta: x + y },
+        (CRZ { theta: x }, CRZ { theta: y }) => CRZ { theta: x + y },
+        _ => return None,
+    })
+}
+
+/// Объединяет каждый гейт с ближайшим предшествующим гейтом на тех же кубитах
+/// с помощью `combine`. Если `commutation` включено, поиск партнера идет назад
+/// через гейты, коммутирующие с текущим; иначе — только через гейты на других кубитах.
+fn combine_backwards(
+    circuit: &Circuit,
+    commutation: bool,
+    combine: impl Fn(&CircuitGate, &CircuitGate) -> Option<Combined>,
+) -> Circuit {
+    let mut slots: Vec<Option<Instruction>> = Vec::with_capacity(circuit.len());
+
+    'instructions: for instruction in circuit.instructions() {
+        let Instruction::Gate { gate, qubits } = instruction else {
+            slots.push(Some(instruction.clone()));
+            continue;
+        };
+
+        for index in (0..slots.len()).rev() {
+            let Some(previous) = &slots[index] else { continue };
+            if previous.qubits().iter().all(|qubit| !qubits.contains(qubit)) {
+                continue;
+            }
+
+            let Instruction::Gate { gate: earlier, qubits: earlier_qubits } = previous else { break };
+            if earlier_qubits == qubits {
+                if let Some(combined) = combine(earlier, gate) {
+                    slots[index] = match combined {
+                        Combined::Identity => None,
+                        Combined::Gate(merged) => Some(Instruction::Gate { gate: merged, qubits: qubits.clone() }),
+                    };
+                    continue 'instructions;
+                }
+            }
+            if !(commutation && commute(earlier, earlier_qubits, gate, qubits)) {
+                break;
+            }
+        }
+        slots.push(Some(instruction.clone()));
+    }
+
+    let mut optimized = circuit.empty_like();
+    for instruction in slots.into_iter().flatten() {
+        optimized.push(instruction);
+    }
+    optimized
+}
+
+/// Проверяет, что гейты коммутируют, сравнивая матрицы AB и BA
+/// на объединении их кубитов.
+fn commute(a: &CircuitGate, a_qubits: &[usize], b: &CircuitGate, b_qubits: &[usize]) -> bool {
+    let mut union = a_qubits.to_vec();
+    union.extend(b_qubits.iter().filter(|qubit| !a_qubits.contains(qubit)));
+    if union.len() > COMMUTATION_MAX_QUBITS {
+        return false;
+    }
+
+    let local = |qubits: &[usize]| -> Vec<usize> {
+        qubits.iter().map(|qubit| union.iter().position(|u| u == qubit).unwrap_or(0)).collect()
+    };
+    let (a_local, b_local) = (local(a_qubits), local(b_qubits));
+
+    let mut ab = Circuit::new(union.len(), 0);
+    ab.gate(a.clone(), &a_local).gate(b.clone(), &b_local);
+    let mut ba = Circuit::new(union.len(), 0);
+    ba.gate(b.clone(), &b_local).gate(a.clone(), &a_local);
+
+    match (ab.unitary(), ba.unitary()) {
+        (Ok(ab), Ok(ba)) => ab.iter().zip(&ba).all(|(x, y)| (x - y).norm() < TOLERANCE),
+        _ => false,
+    }
+}
+
+/// Отчет об оптимизации схемы.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OptimizationReport {
+    /// Число гейтов до оптимизации.
+    pub gates_before: usize,
+    /// Число гейтов после оптимизации.
+    pub gates_after: usize,
+    /// Глубина схемы до оптимизации.
+    pub depth_before: usize,
+    /// Глубина схемы после оптимизации.
+    pub depth_after: usize,
+    /// Число гейтов каждого типа до оптимизации.
+    pub counts_before: BTreeMap<String, usize>,
+    /// Число гейтов каждого типа после оптимизации.
+    pub counts_after: BTreeMap<String, usize>,
+    /// Число гейтов, удаленных каждым проходом (в порядке проходов).
+    pub removed_by_pass: Vec<(String, usize)>,
+    /// Число выполненных итераций по всем проходам.
+    pub iterations: usize,
+    /// Результат сверки унитарных матриц до и после оптимизации
+    /// (`None`, если сверка не выполнялась).
+    pub equivalent: Option<bool>,
+}
+
+/// Менеджер проходов оптимизации: применяет проходы по порядку, повторяя
+/// их, пока схема уменьшается (но не более `max_iterations` раз).
+pub struct PassManager {
+    /// Проходы в порядке применения.
+    passes: Vec<Box<dyn OptimizationPass>>,
+    /// Наибольшее число итераций по всем проходам.
+    max_iterations: usize,
+    /// Сверять ли унитарные матрицы небольших схем до и после оптимизации.
+    verify: bool,
+}
+
+impl PassManager {
+    /// Создает менеджер без проходов.
+    pub fn new() -> Self {
+        Self { passes: Vec::new(), max_iterations: 10, verify: false }
+    }
+
+    /// Добавляет проход в конец последовательности.
+    pub fn add_pass(&mut self, pass: impl OptimizationPass + 'static) -> &mut Self {
+        self.passes.push(Box::new(pass));
+        self
+    }
+
+    /// Задает наибольшее число итераций по всем проходам.
+    pub fn max_iterations(&mut self, max_iterations: usize) -> &mut Self {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    /// Включает сверку унитарных матриц до и после оптимизации для схем
+    /// без измерений и классических условий не более чем на 10 кубитах.
+    pub fn verify(&mut self, enabled: bool) -> &mut Self {
+        self.verify = enabled;
+        self
+    }
+
+    /// Возвращает названия проходов в порядке применения.
+    pub fn pass_names(&self) -> Vec<&str> {
+        self.passes.iter().map(|pass| pass.name()).collect()
+    }
+
+    /// Оптимизирует схему и возвращает результат вместе с отчетом.
+    pub fn run(&self, circuit: &Circuit) -> (Circuit, OptimizationReport) {
+        let mut removed = vec![0usize; self.passes.len()];
+        let mut current = circuit.clone();
+        let mut iterations = 0;
+
+        while iterations < self.max_iterations {
+            iterations += 1;
+            let size = current.len();
+            for (pass, removed) in self.passes.iter().zip(removed.iter_mut()) {
+                let next = pass.run(&current);
+                *removed += gate_total(&current).saturating_sub(gate_total(&next));
+                current = next;
+            }
+            if current.len() >= size {
+                break;
+            }
+        }
+
+        let equivalent = if self.verify && circuit.num_qubits() <= VERIFICATION_MAX_QUBITS {
+            match (circuit.unitary(), current.unitary()) {
+                (Ok(before), Ok(after)) => Some(equal_up_to_phase(&after, &before, 1e-8)),
+                _ => None,
+            }
+        } else {
+            None
+        };
+
+        let report = OptimizationReport {
+            gates_before: gate_total(circuit),
+            gates_after: gate_total(&current),
+            depth_before: circuit.depth(),
+            depth_after: current.depth(),
+            counts_before: circuit.gate_counts(),
+            counts_after: current.gate_counts(),
+            removed_by_pass: self.passes.iter().map(|pass| pass.name().to_string()).zip(removed).collect(),
+            iterations,
+            equivalent,
+        };
+        (current, report)
+    }
+}
+
+impl Default for PassManager {
+    /// Стандартная последовательность: сокращение обратных пар, слияние
+    /// вращений, сокращение с учетом коммутации и пересинтез однокубитовых цепочек.
+    fn default() -> Self {
+        let mut manager = Self::new();
+        manager.add_pass(CancelInverses).add_pass(MergeRotations).add_pass(CommutativeCancellation);
+        #[cfg(feature = "native")]
+        manager.add_pass(ResynthesizeSingleQubitRuns::default());
+        manager
+    }
+}
+
+/// Возвращает общее число гейтов схемы.
+fn gate_total(circuit: &Circuit) -> usize {
+    circuit.gate_counts().values().sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::gates::{BasicGate, TwoQubitGate};
+    use std::f64::consts::PI;
+
+    /// Проверяет, что проход сохраняет унитарную матрицу схемы.
+    fn assert_preserves(pass: &dyn OptimizationPass, circuit: &Circuit) -> Circuit {
+        let optimized = pass.run(circuit);
+        assert!(equal_up_to_phase(&optimized.unitary().unwrap(), &circuit.unitary().unwrap(), 1e-9),
+            "проход {} изменил схему", pass.name());
+        optimized
+    }
+
+    #[test]
+    fn test_cancel_inverses() {
+        let mut circuit = Circuit::new(3, 0);
+        circuit.h(0).h(0).x(1).cnot(0, 2).x(1).cnot(0, 2).s(2).gate(BasicGate::SDagger, &[2]);
+        assert!(assert_preserves(&CancelInverses, &circuit).is_empty());
+
+        // Вложенные пары сокращаются за один проход: H·CNOT·CNOT·H
+        let mut circuit = Circuit::new(2, 0);
+        circuit.h(1).cnot(0, 1).cnot(0, 1).h(1).t(0);
+        assert_eq!(assert_preserves(&CancelInverses, &circuit).len(), 1);
+
+        // Барьеры, измерения и гейты с другим порядком кубитов не сокращаются
+        let mut circuit = Circuit::new(2, 1);
+        circuit.h(0).barrier_all().h(0).cnot(0, 1).cnot(1, 0).x(1).measure(1, 0).x(1);
+        assert_eq!(CancelInverses.run(&circuit).len(), circuit.len());
+    }
+
+    #[test]
+    fn test_merge_rotations() {
+        let mut circuit = Circuit::new(2, 0);
+        circuit.rz(0, 0.3).rz(0, 0.4).rx(1, PI).rx(1, -PI)
+            .gate(ParametricGate::CPhase { theta: 0.5 }, &[0, 1])
+            .gate(ParametricGate::CPhase { theta: 0.25 }, &[0, 1]);
+
+        let optimized = assert_preserves(&MergeRotations, &circuit);
+        assert_eq!(optimized.len(), 2);
+        assert!(matches!(&optimized.instructions()[0],
+            Instruction::Gate { gate: CircuitGate::Rotation(RotationGate { angle, .. }), .. } if (angle - 0.7).abs() < 1e-12));
+        assert!(matches!(&optimized.instructions()[1],
+            Instruction::Gate { gate: CircuitGate::Parametric(ParametricGate::CPhase { theta }), .. } if (theta - 0.75).abs() < 1e-12));
+
+        // Вращения вокруг разных осей не сливаются
+        let mut circuit = Circuit::new(1, 0);
+        circuit.rz(0, 0.3).ry(0, 0.4);
+        assert_eq!(MergeRotations.run(&circuit).len(), 2);
+    }
+
+    #[test]
+    fn test_commutative_cancellation() {
+        // RZ на управляющем и X на целевом кубите коммутируют с CNOT
+        let mut circuit = Circuit::new(3, 0);
+        circuit.cnot(0, 1).rz(0, 0.5).x(1).cnot(0, 1).rz(0, 0.25).cz(1, 2).z(2).cz(1, 2);
+
+        assert_eq!(CancelInverses.run(&circuit).len(), circuit.len());
+        let optimized = assert_preserves(&CommutativeCancellation, &circuit);
+        assert_eq!(optimized.gate_counts().get("CNOT"), None);
+        assert_eq!(optimized.gate_counts().get("CZ"), None);
+        assert_eq!(optimized.gate_counts().get("RZ"), Some(&1));
+
+        // Некоммутирующие гейты останавливают поиск
+        let mut circuit = Circuit::new(2, 0);
+        circuit.cnot(0, 1).h(0).cnot(0, 1);
+        assert_eq!(CommutativeCancellation.run(&circuit).len(), 3);
+    }
+
+    #[test]
+    #[cfg(feature = "native")]
+    fn test_resynthesize_single_qubit_runs() {
+        let mut circuit = Circuit::new(2, 0);
+        circuit.h(0).t(0).h(0).s(0).rx(0, 0.3).cnot(0, 1).h(1).t(1);
+
+        let optimized = assert_preserves(&ResynthesizeSingleQubitRuns::default(), &circuit);
+        // Цепочка из пяти гейтов на кубите 0 сворачивается в три вращения,
+        // короткая цепочка H·T на кубите 1 остается без изменений
+        assert_eq!(optimized.len(), 3 + 1 + 2);
+        assert_eq!(optimized.gate_counts().get("Hadamard"), Some(&1));
+
+        use crate::core::decompose::BasisGate;
+        let u3 = ResynthesizeSingleQubitRuns::new(Basis::new(&[BasisGate::U3]));
+        assert_eq!(assert_preserves(&u3, &circuit).len(), 1 + 1 + 1);
+    }
+
+    #[test]
+    fn test_pass_manager_reports_and_preserves_unitary() {
+        let mut circuit = Circuit::new(3, 0);
+        circuit.h(0).h(0).cnot(0, 1).rz(0, 0.2).cnot(0, 1).rz(0, 0.3)
+            .gate(TwoQubitGate::SWAP, &[1, 2]).gate(TwoQubitGate::SWAP, &[1, 2])
+            .rx(2, 0.4).rx(2, 0.5).h(1).t(1).h(1).t(1).cnot(1, 2).t(2).gate(BasicGate::TDagger, &[2]);
+
+        let mut manager = PassManager::default();
+        manager.verify(true);
+        let (optimized, report) = manager.run(&circuit);
+
+        assert_eq!(report.equivalent, Some(true));
+        assert!(equal_up_to_phase(&optimized.unitary().unwrap(), &circuit.unitary().unwrap(), 1e-9));
+        assert_eq!(report.gates_before, circuit.len());
+        assert_eq!(report.gates_after, optimized.len());
+        assert!(report.gates_after < report.gates_before);
+        assert!(report.depth_after < report.depth_before);
+        assert_eq!(report.counts_before.get("SWAP"), Some(&2));
+        assert_eq!(report.counts_after.get("SWAP"), None);
+        assert_eq!(report.removed_by_pass.len(), manager.pass_names().len());
+        assert_eq!(report.removed_by_pass.iter().map(|(_, removed)| removed).sum::<usize>(),
+            report.gates_before - report.gates_after);
+
+        // Схемы с измерениями оптимизируются, но не сверяются
+        let mut measured = Circuit::new(1, 1);
+        measured.x(0).x(0).measure(0, 0);
+        let (optimized, report) = manager.run(&measured);
+        assert_eq!(optimized.len(), 1);
+        assert_eq!(report.equivalent, None);
+    }
+}
@@ -0,0 +1,101 @@
+//! Операторы Паули и произведения операторов Паули.
+//!
+//! Этот модуль содержит общее для всех бэкендов представление
+//! операторов Паули и проверку произведений вида `[(кубит, 'X'|'Y'|'Z'|'I')]`.
+
+use crate::core::quantum_state::Amplitude;
+use crate::error::TriadError;
+use num_complex::Complex64;
+
+/// Однокубитовый оператор Паули.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Pauli {
+    /// Тождественный оператор
+    I,
+    /// Оператор Паули-X
+    X,
+    /// Оператор Паули-Y
+    Y,
+    /// Оператор Паули-Z
+    Z,
+}
+
+impl Pauli {
+    /// Преобразует символ ('I', 'X', 'Y', 'Z' в любом регистре) в оператор Паули.
+    pub fn from_char(symbol: char) -> Option<Self> {
+        match symbol.to_ascii_uppercase() {
+            'I' => Some(Pauli::I),
+            'X' => Some(Pauli::X),
+            'Y' => Some(Pauli::Y),
+            'Z' => Some(Pauli::Z),
+            _ => None,
+        }
+    }
+
+    /// Возвращает символьное обозначение оператора.
+    pub fn to_char(self) -> char {
+        match self {
+            Pauli::I => 'I',
+            Pauli::X => 'X',
+            Pauli::Y => 'Y',
+            Pauli::Z => 'Z',
+        }
+    }
+
+    /// Возвращает матрицу 2x2 оператора (по строкам).
+    pub fn matrix(self) -> [Amplitude; 4] {
+        let zero = Complex64::new(0.0, 0.0);
+        let one = Complex64::new(1.0, 0.0);
+        let i = Complex64::new(0.0, 1.0);
+
+        match self {
+            Pauli::I => [one, zero, zero, one],
+            Pauli::X => [zero, one, one, zero],
+            Pauli::Y => [zero, -i, i, zero],
+            Pauli::Z => [one, zero, zero, -one],
+        }
+    }
+}
+
+/// Проверяет произведение операторов Паули для регистра из `num_qubits` кубитов.
+///
+/// Возвращает ошибку, если символ оператора неизвестен, индекс кубита выходит
+/// за пределы регистра или один кубит встречается в произведении несколько раз.
+pub fn parse_pauli_product(pauli_product: &[(usize, char)], num_qubits: usize) -> Result<Vec<(usize, Pauli)>, TriadError> {
+    let mut parsed = Vec::with_capacity(pauli_product.len());
+
+    for (idx, &(qubit, symbol)) in pauli_product.iter().enumerate() {
+        let pauli = Pauli::from_char(symbol).ok_or(TriadError::InvalidPauli(symbol))?;
+
+        if qubit >= num_qubits {
+            return Err(TriadError::InvalidQubit { qubit, num_qubits });
+        }
+
+        if pauli_product[..idx].iter().any(|&(q, _)| q == qubit) {
+            return Err(TriadError::DuplicateQubit(qubit));
+        }
+
+        parsed.push((qubit, pauli));
+    }
+
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_valid_product() {
+        let parsed = parse_pauli_product(&[(0, 'x'), (2, 'Z'), (1, 'I')], 3).unwrap();
+        assert_eq!(parsed, vec![(0, Pauli::X), (2, Pauli::Z), (1, Pauli::I)]);
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_input() {
+        assert_eq!(parse_pauli_product(&[(0, 'Q')], 2), Err(TriadError::InvalidPauli('Q')));
+        assert_eq!(parse_pauli_product(&[(2, 'X')], 2), Err(TriadError::InvalidQubit { qubit: 2, num_qubits: 2 }));
+        assert_eq!(parse_pauli_product(&[(1, 'X'), (1, 'Z')], 2), Err(TriadError::DuplicateQubit(1)));
+    }
+}
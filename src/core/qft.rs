@@ -0,0 +1,195 @@
+//! Квантовое преобразование Фурье (QFT) и обратное к нему.
+//!
+//! Для кубитов `qubits` (кубит `qubits[0]` — младший бит числа `x`) QFT
+//! действует как дискретное преобразование Фурье амплитуд:
+//! `|x⟩ → 1/√N Σ_y e^{2πi·xy/N} |y⟩`, где `N = 2^n`.
+
+use std::f64::consts::PI;
+
+use crate::core::circuit::{Circuit, CircuitGate, Instruction};
+use crate::core::gates::{BasicGate, ParametricGate, TwoQubitGate};
+
+/// Параметры построения схемы QFT.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QftOptions {
+    /// Степень приближения: отбрасываются управляемые фазовые вращения
+    /// на углы меньше π/2^(n-1-степень). При 0 преобразование точное.
+    pub approximation_degree: usize,
+    /// Добавлять ли завершающие SWAP-гейты, обращающие порядок кубитов.
+    /// Без них результат записывается в кубиты в обратном порядке.
+    pub do_swaps: bool,
+}
+
+impl Default for QftOptions {
+    fn default() -> Self {
+        Self { approximation_degree: 0, do_swaps: true }
+    }
+}
+
+impl QftOptions {
+    /// Возвращает параметры с указанной степенью приближения.
+    pub fn with_approximation_degree(self, approximation_degree: usize) -> Self {
+        Self { approximation_degree, ..self }
+    }
+
+    /// Возвращает параметры без завершающих SWAP-гейтов.
+    pub fn without_swaps(self) -> Self {
+        Self { do_swaps: false, ..self }
+    }
+}
+
+/// Строит инструкции QFT (или обратного преобразования при `inverse`)
+/// на кубитах `qubits`. Индексы кубитов не проверяются.
+pub fn qft_instructions(qubits: &[usize], options: QftOptions, inverse: bool) -> Vec<Instruction> {
+    let n = qubits.len();
+    // Наибольшее расстояние между кубитами, для которого вращение сохраняется
+    let max_distance = n.saturating_sub(1).saturating_sub(options.approximation_degree);
+
+    let mut instructions = Vec::new();
+    for j in (0..n).rev() {
+        instructions.push(Instruction::gate(BasicGate::Hadamard, &[qubits[j]]));
+        for k in (0..j).rev() {
+            let distance = j - k;
+            if distance > max_distance {
+                continue;
+            }
+            let theta = PI / 2f64.powi(distance as i32);
+            instructions.push(Instruction::gate(ParametricGate::CPhase { theta }, &[qubits[k], qubits[j]]));
+        }
+    }
+
+    if options.do_swaps {
+        for i in 0..n / 2 {
+            instructions.push(Instruction::gate(TwoQubitGate::SWAP, &[qubits[i], qubits[n - 1 - i]]));
+        }
+    }
+
+    if inverse {
+        // H и SWAP самообратны, а обратное фазовое вращение — вращение на -θ
+        instructions.reverse();
+        for instruction in &mut instructions {
+            if let Instruction::Gate { gate: CircuitGate::Parametric(ParametricGate::CPhase { theta }), .. } = instruction {
+                *theta = -*theta;
+            }
+        }
+    }
+
+    instructions
+}
+
+/// Строит схему QFT на `num_qubits` кубитах.
+pub fn qft_circuit(num_qubits: usize, options: QftOptions) -> Circuit {
+    let qubits: Vec<usize> = (0..num_qubits).collect();
+    let mut circuit = Circuit::new(num_qubits, 0);
+    circuit.qft_with(&qubits, options);
+    circuit
+}
+
+/// Строит схему обратного QFT на `num_qubits` кубитах.
+pub fn iqft_circuit(num_qubits: usize, options: QftOptions) -> Circuit {
+    let qubits: Vec<usize> = (0..num_qubits).collect();
+    let mut circuit = Circuit::new(num_qubits, 0);
+    circuit.iqft_with(&qubits, options);
+    circuit
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::quantum_state::Amplitude;
+    use crate::core::state_vector::StateVectorSimulator;
+
+    /// Дискретное преобразование Фурье амплитуд (знак `sign` = +1 для QFT).
+    fn dft(amplitudes: &[Amplitude], sign: f64) -> Vec<Amplitude> {
+        let dim = amplitudes.len();
+        (0..dim).map(|y| {
+            amplitudes.iter().enumerate()
+                .map(|(x, &a)| a * Amplitude::from_polar(1.0, sign * 2.0 * PI * (x * y) as f64 / dim as f64))
+                .sum::<Amplitude>() / (dim as f64).sqrt()
+        }).collect()
+    }
+
+    /// Произвольное нормированное состояние на `num_qubits` кубитах.
+    fn sample_state(num_qubits: usize) -> Vec<Amplitude> {
+        let raw: Vec<Amplitude> = (0..1usize << num_qubits)
+            .map(|i| Amplitude::new((i as f64 * 0.7).sin() + 0.3, (i as f64 * 1.3).cos()))
+            .collect();
+        let norm = raw.iter().map(|a| a.norm_sqr()).sum::<f64>().sqrt();
+        raw.iter().map(|a| a / norm).collect()
+    }
+
+    fn run(circuit: &Circuit, amplitudes: &[Amplitude]) -> Vec<Amplitude> {
+        let unitary = circuit.unitary().unwrap();
+        let dim = amplitudes.len();
+        (0..dim).map(|row| (0..dim).map(|col| unitary[row * dim + col] * amplitudes[col]).sum()).collect()
+    }
+
+    fn assert_close(a: &[Amplitude], b: &[Amplitude]) {
+        for (x, y) in a.iter().zip(b) {
+            assert!((x - y).norm() < 1e-10, "{} != {}", x, y);
+        }
+    }
+
+    #[test]
+    fn test_qft_matches_dft() {
+        for n in 1..=4 {
+            let state = sample_state(n);
+            assert_close(&run(&qft_circuit(n, QftOptions::default()), &state), &dft(&state, 1.0));
+            assert_close(&run(&iqft_circuit(n, QftOptions::default()), &state), &dft(&state, -1.0));
+        }
+    }
+
+    #[test]
+    fn test_qft_on_subset_of_qubits() {
+        // QFT на кубитах [3, 1] регистра из 4 кубитов: число x = q3 + 2·q1
+        let mut circuit = Circuit::new(4, 0);
+        circuit.x(3).qft(&[3, 1]);
+        let mut simulator = StateVectorSimulator::new(4);
+        circuit.run_on(&mut simulator).unwrap();
+        let state = simulator.state_vector();
+        // x = 1: амплитуда |y⟩ равна e^{2πi·y/4}/2
+        for y in 0..4usize {
+            let index = ((y & 1) << 3) | ((y >> 1) << 1);
+            let expected = Amplitude::from_polar(0.5, 2.0 * PI * y as f64 / 4.0);
+            assert!((state.amplitudes()[index] - expected).norm() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_swap_omission_and_approximation() {
+        let n = 4;
+        let state = sample_state(n);
+        let expected = dft(&state, 1.0);
+
+        // Без SWAP-гейтов результат записан в обратном порядке битов
+        let unswapped = run(&qft_circuit(n, QftOptions::default().without_swaps()), &state);
+        for (index, value) in unswapped.iter().enumerate() {
+            let reversed = (0..n).fold(0, |acc, bit| acc | (((index >> bit) & 1) << (n - 1 - bit)));
+            assert!((value - expected[reversed]).norm() < 1e-10);
+        }
+
+        // Степень 1 отбрасывает только вращение на π/8 между крайними кубитами
+        let options = QftOptions::default().with_approximation_degree(1);
+        let approximate = qft_circuit(n, options);
+        assert_eq!(approximate.gate_counts().get("CPhase").copied().unwrap_or(0), 5);
+        let fidelity = run(&approximate, &state).iter().zip(&expected)
+            .map(|(a, b)| a.conj() * b)
+            .sum::<Amplitude>()
+            .norm_sqr();
+        assert!(fidelity > 0.9 && fidelity < 1.0 - 1e-6);
+        assert_eq!(qft_circuit(n, QftOptions::default().with_approximation_degree(10)).len(), n + n / 2);
+    }
+
+    #[test]
+    fn test_qft_on_more_than_64_qubits() {
+        let qubits: Vec<usize> = (0..70).collect();
+        let instructions = qft_instructions(&qubits, QftOptions::default(), false);
+        assert_eq!(instructions.len(), 70 + 70 * 69 / 2 + 35);
+
+        let smallest = instructions.iter().filter_map(|instruction| match instruction {
+            Instruction::Gate { gate: CircuitGate::Parametric(ParametricGate::CPhase { theta }), .. } => Some(*theta),
+            _ => None,
+        }).fold(f64::INFINITY, f64::min);
+        assert!((smallest - PI / 2f64.powi(69)).abs() < 1e-30);
+    }
+}
@@ -4,13 +4,32 @@
 //! симуляции квантовых вычислений.
 
 use crate::core::quantum_state::QuantumState;
-use crate::core::gates::Gate;
+use crate::core::quantum_state::utils::subsystem_probability;
+use crate::core::gates::{Axis, BasicGate, Gate, RotationGate, is_unitary_matrix};
+use crate::core::measurement::{Povm, validate_povm_targets};
+use crate::core::pauli::{Pauli, parse_pauli_product};
+use crate::core::quantum_state::Amplitude;
+use crate::core::counts::{Counts, sample_sequential, validate_num_bits};
+use crate::error::TriadError;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// Допуск, с которым проверяется унитарность матриц гейтов.
+pub const UNITARITY_TOLERANCE: f64 = 1e-8;
 
 /// Интерфейс для квантового симулятора, способного выполнять квантовые операции.
 pub trait QuantumSimulator {
     /// Создает новый симулятор с заданным числом кубитов.
+    /// Паникует, если бэкенд не поддерживает такое число кубитов.
     fn new(num_qubits: usize) -> Self where Self: Sized;
     
+    /// Создает новый симулятор с заданным числом кубитов,
+    /// возвращая ошибку вместо паники.
+    fn try_new(num_qubits: usize) -> Result<Self, TriadError> where Self: Sized;
+    
+    /// Возвращает число кубитов в регистре.
+    fn num_qubits(&self) -> usize;
+    
     /// Получает текущее квантовое состояние.
     fn get_state(&self) -> Box<dyn QuantumState>;
     
@@ -35,11 +54,86 @@ pub trait QuantumSimulator {
     /// Измеряет заданный кубит и возвращает результат (0 или 1).
     fn measure(&mut self, qubit: usize) -> bool;
     
-    /// Применяет произвольный квантовый гейт.
-    fn apply_gate(&mut self, gate: &impl Gate);
+    /// Измеряет кубит в собственном базисе оператора Паули `basis`.
+    /// Возвращает `false` для собственного значения +1 и `true` для -1;
+    /// кубит переходит в соответствующее собственное состояние.
+    fn measure_in_basis(&mut self, qubit: usize, basis: Pauli) -> bool {
+        self.measure_pauli(&[(qubit, basis.to_char())])
+    }
+    
+    /// Измеряет наблюдаемую — произведение операторов Паули вида
+    /// `[(кубит, 'X'|'Y'|'Z'|'I')]` — и проецирует состояние на ее собственное
+    /// подпространство, не измеряя отдельные кубиты. Возвращает `false` для
+    /// собственного значения +1 и `true` для -1.
+    fn measure_pauli(&mut self, pauli_product: &[(usize, char)]) -> bool {
+        // Переводим каждый множитель в Z-базис, собираем четность на последнем
+        // кубите цепочкой CNOT, измеряем его и возвращаем базис обратно
+        let parsed = parse_pauli_product(pauli_product, self.num_qubits())
+            .unwrap_or_else(|err| panic!("{}", err));
+        let qubits: Vec<usize> = parsed.iter()
+            .filter(|&&(_, pauli)| pauli != Pauli::I)
+            .map(|&(qubit, _)| qubit)
+            .collect();
+        let Some((&parity, rest)) = qubits.split_last() else {
+            return false;
+        };
+        
+        for &(qubit, pauli) in &parsed {
+            rotate_to_z_basis(self, qubit, pauli);
+        }
+        for &qubit in rest {
+            self.cnot(qubit, parity);
+        }
+        let outcome = self.measure(parity);
+        for &qubit in rest.iter().rev() {
+            self.cnot(qubit, parity);
+        }
+        for &(qubit, pauli) in &parsed {
+            rotate_from_z_basis(self, qubit, pauli);
+        }
+        outcome
+    }
+    
+    /// Выполняет обобщенное измерение над кубитами `targets` и возвращает номер
+    /// исхода; состояние переходит в `M_k|ψ⟩/√p_k`. Паникует при некорректных
+    /// аргументах или если бэкенд не поддерживает POVM.
+    fn measure_povm(&mut self, povm: &Povm, targets: &[usize]) -> usize {
+        self.try_measure_povm(povm, targets).unwrap_or_else(|err| panic!("{}", err))
+    }
+    
+    /// Сбрасывает кубит в состояние |0⟩: кубит измеряется и при результате 1
+    /// переворачивается, поэтому запутанные с ним кубиты коллапсируют.
+    fn reset_qubit(&mut self, qubit: usize) {
+        if self.measure(qubit) {
+            self.x(qubit);
+        }
+    }
+    
+    /// Генератор случайных чисел, из которого берутся результаты измерений и выборок.
+    /// Бэкенды без собственного генератора возвращают `None` (по умолчанию);
+    /// тогда выборки используют системный источник энтропии, а `seed` не действует.
+    fn rng(&mut self) -> Option<&mut StdRng> {
+        None
+    }
+    
+    /// Инициализирует генератор случайных чисел значением `seed`:
+    /// одинаковые значения дают одинаковые последовательности измерений
+    /// при одинаковых схемах, в том числе на разных бэкендах.
+    fn seed(&mut self, seed: u64) {
+        if let Some(rng) = self.rng() {
+            *rng = StdRng::seed_from_u64(seed);
+        }
+    }
     
-    /// Применяет гейт вращения.
-    fn apply_rotation(&mut self, gate: &impl Gate);
+    /// Применяет произвольный квантовый гейт к указанным кубитам.
+    /// Число кубитов в `targets` должно совпадать с `gate.num_qubits()`;
+    /// первый кубит соответствует старшему биту индекса матрицы гейта.
+    fn apply_gate(&mut self, gate: &dyn Gate, targets: &[usize]);
+    
+    /// Применяет гейт вращения к указанным кубитам.
+    fn apply_rotation(&mut self, gate: &dyn Gate, targets: &[usize]) {
+        self.apply_gate(gate, targets);
+    }
     
     /// Вычисляет ожидаемое значение для произвольного набора операторов Паули.
     fn get_expectation_value(&self, pauli_product: &[(usize, char)]) -> f64;
@@ -60,6 +154,244 @@ pub trait QuantumSimulator {
         
         prob
     }
+    
+    /// Вычисляет совместную вероятность получить битовую строку `outcome`
+    /// при измерении кубитов `qubits`. Бит `i` строки `outcome` соответствует
+    /// кубиту `qubits[i]`.
+    fn probability_of_joint_outcome(&self, qubits: &[usize], outcome: u64) -> f64 {
+        // Значение по умолчанию, конкретные реализации могут переопределить этот метод
+        let state = self.get_state();
+        validate_joint_outcome(qubits, outcome, state.num_qubits())
+            .unwrap_or_else(|err| panic!("{}", err));
+        subsystem_probability(&*state, qubits, outcome)
+    }
+    
+    /// Извлекает `shots` выборок из распределения результатов измерения кубитов
+    /// `qubits`, не изменяя состояние и не выполняя схему заново.
+    /// Бит `i` каждого результата соответствует кубиту `qubits[i]`.
+    /// Паникует, если кубитов больше `MAX_COUNT_BITS`.
+    fn sample(&mut self, shots: usize, qubits: &[usize]) -> Counts {
+        // Значение по умолчанию разыгрывает биты последовательно по условным
+        // вероятностям; бэкенды с явным вектором амплитуд могут переопределить метод
+        validate_sample_qubits(qubits, self.num_qubits()).unwrap_or_else(|err| panic!("{}", err));
+        let seed = match self.rng() {
+            Some(rng) => rng.gen(),
+            None => rand::random(),
+        };
+        let mut rng = StdRng::seed_from_u64(seed);
+        sample_sequential(qubits.len(), shots, &mut rng, |len, prefix| {
+            self.probability_of_joint_outcome(&qubits[..len], prefix)
+        })
+    }
+    
+    // === Методы, возвращающие ошибку вместо паники ===
+    
+    /// Применяет гейт Адамара, проверяя индекс кубита.
+    fn try_hadamard(&mut self, qubit: usize) -> Result<(), TriadError> {
+        validate_qubit(qubit, self.num_qubits())?;
+        self.hadamard(qubit);
+        Ok(())
+    }
+    
+    /// Применяет X-гейт, проверяя индекс кубита.
+    fn try_x(&mut self, qubit: usize) -> Result<(), TriadError> {
+        validate_qubit(qubit, self.num_qubits())?;
+        self.x(qubit);
+        Ok(())
+    }
+    
+    /// Применяет Y-гейт, проверяя индекс кубита.
+    fn try_y(&mut self, qubit: usize) -> Result<(), TriadError> {
+        validate_qubit(qubit, self.num_qubits())?;
+        self.y(qubit);
+        Ok(())
+    }
+    
+    /// Применяет Z-гейт, проверяя индекс кубита.
+    fn try_z(&mut self, qubit: usize) -> Result<(), TriadError> {
+        validate_qubit(qubit, self.num_qubits())?;
+        self.z(qubit);
+        Ok(())
+    }
+    
+    /// Применяет CNOT-гейт, проверяя индексы кубитов.
+    fn try_cnot(&mut self, control: usize, target: usize) -> Result<(), TriadError> {
+        validate_qubits(&[control, target], self.num_qubits())?;
+        self.cnot(control, target);
+        Ok(())
+    }
+    
+    /// Измеряет кубит, проверяя его индекс.
+    fn try_measure(&mut self, qubit: usize) -> Result<bool, TriadError> {
+        validate_qubit(qubit, self.num_qubits())?;
+        Ok(self.measure(qubit))
+    }
+    
+    /// Измеряет кубит в базисе оператора Паули, проверяя его индекс.
+    fn try_measure_in_basis(&mut self, qubit: usize, basis: Pauli) -> Result<bool, TriadError> {
+        validate_qubit(qubit, self.num_qubits())?;
+        Ok(self.measure_in_basis(qubit, basis))
+    }
+    
+    /// Измеряет произведение операторов Паули, возвращая ошибку
+    /// для неизвестных операторов и некорректных кубитов.
+    fn try_measure_pauli(&mut self, pauli_product: &[(usize, char)]) -> Result<bool, TriadError> {
+        parse_pauli_product(pauli_product, self.num_qubits())?;
+        Ok(self.measure_pauli(pauli_product))
+    }
+    
+    /// Выполняет обобщенное измерение, проверяя целевые кубиты.
+    /// Бэкенды, умеющие применять неунитарные операторы, переопределяют
+    /// этот метод; по умолчанию возвращается `TriadError::Unsupported`.
+    fn try_measure_povm(&mut self, povm: &Povm, targets: &[usize]) -> Result<usize, TriadError> {
+        validate_povm_targets(povm, targets, self.num_qubits())?;
+        Err(TriadError::Unsupported(format!("обобщенное измерение {}", povm.name())))
+    }
+    
+    /// Сбрасывает кубит в |0⟩, проверяя его индекс.
+    fn try_reset_qubit(&mut self, qubit: usize) -> Result<(), TriadError> {
+        validate_qubit(qubit, self.num_qubits())?;
+        self.reset_qubit(qubit);
+        Ok(())
+    }
+    
+    /// Применяет произвольный гейт, проверяя целевые кубиты и унитарность матрицы.
+    fn try_apply_gate(&mut self, gate: &dyn Gate, targets: &[usize]) -> Result<(), TriadError> {
+        validate_gate_targets(gate, targets, self.num_qubits())?;
+        self.apply_gate(gate, targets);
+        Ok(())
+    }
+    
+    /// Вычисляет ожидаемое значение произведения операторов Паули,
+    /// возвращая ошибку для неизвестных операторов и некорректных кубитов.
+    fn try_expectation_value(&self, pauli_product: &[(usize, char)]) -> Result<f64, TriadError> {
+        crate::core::pauli::parse_pauli_product(pauli_product, self.num_qubits())?;
+        Ok(self.get_expectation_value(pauli_product))
+    }
+    
+    /// Вычисляет вероятность результата измерения, проверяя индекс кубита.
+    fn try_probability_of_outcome(&self, qubit: usize, outcome: bool) -> Result<f64, TriadError> {
+        validate_qubit(qubit, self.num_qubits())?;
+        Ok(self.probability_of_outcome(qubit, outcome))
+    }
+    
+    /// Вычисляет совместную вероятность результата, проверяя кубиты и битовую строку.
+    fn try_probability_of_joint_outcome(&self, qubits: &[usize], outcome: u64) -> Result<f64, TriadError> {
+        validate_joint_outcome(qubits, outcome, self.num_qubits())?;
+        Ok(self.probability_of_joint_outcome(qubits, outcome))
+    }
+    
+    /// Извлекает выборки результатов измерения, проверяя индексы кубитов.
+    fn try_sample(&mut self, shots: usize, qubits: &[usize]) -> Result<Counts, TriadError> {
+        validate_sample_qubits(qubits, self.num_qubits())?;
+        Ok(self.sample(shots, qubits))
+    }
+}
+
+/// Поворачивает кубит так, чтобы собственный базис оператора `pauli`
+/// перешел в вычислительный (+1 → |0⟩, -1 → |1⟩).
+fn rotate_to_z_basis<S: QuantumSimulator + ?Sized>(simulator: &mut S, qubit: usize, pauli: Pauli) {
+    match pauli {
+        Pauli::X => simulator.hadamard(qubit),
+        Pauli::Y => {
+            simulator.apply_gate(&BasicGate::SDagger, &[qubit]);
+            simulator.hadamard(qubit);
+        },
+        Pauli::I | Pauli::Z => {},
+    }
+}
+
+/// Обращает поворот `rotate_to_z_basis`.
+fn rotate_from_z_basis<S: QuantumSimulator + ?Sized>(simulator: &mut S, qubit: usize, pauli: Pauli) {
+    match pauli {
+        Pauli::X => simulator.hadamard(qubit),
+        Pauli::Y => {
+            simulator.hadamard(qubit);
+            simulator.apply_gate(&BasicGate::S, &[qubit]);
+        },
+        Pauli::I | Pauli::Z => {},
+    }
+}
+
+/// Проверяет, что индекс кубита находится в пределах регистра.
+pub fn validate_qubit(qubit: usize, num_qubits: usize) -> Result<(), TriadError> {
+    if qubit >= num_qubits {
+        return Err(TriadError::InvalidQubit { qubit, num_qubits });
+    }
+    Ok(())
+}
+
+/// Проверяет, что все кубиты находятся в пределах регистра и не повторяются.
+pub fn validate_qubits(qubits: &[usize], num_qubits: usize) -> Result<(), TriadError> {
+    for (idx, &qubit) in qubits.iter().enumerate() {
+        validate_qubit(qubit, num_qubits)?;
+        if qubits[..idx].contains(&qubit) {
+            return Err(TriadError::DuplicateQubit(qubit));
+        }
+    }
+    Ok(())
+}
+
+/// Проверяет кубиты для `sample`: кроме индексов проверяется, что результат
+/// помещается в `counts::MAX_COUNT_BITS` бит.
+pub fn validate_sample_qubits(qubits: &[usize], num_qubits: usize) -> Result<(), TriadError> {
+    validate_qubits(qubits, num_qubits)?;
+    validate_num_bits(qubits.len())
+}
+
+/// Проверяет набор кубитов и битовую строку для `probability_of_joint_outcome`.
+/// Возвращает ошибку, если кубит выходит за пределы регистра, повторяется
+/// или если `outcome` не помещается в `qubits.len()` бит.
+pub fn validate_joint_outcome(qubits: &[usize], outcome: u64, num_qubits: usize) -> Result<(), TriadError> {
+    validate_qubits(qubits, num_qubits)?;
+    
+    if qubits.len() < 64 && outcome >> qubits.len() != 0 {
+        return Err(TriadError::InvalidArgument(format!(
+            "результат измерения {:b} не помещается в {} бит", outcome, qubits.len())));
+    }
+    
+    Ok(())
+}
+
+/// Проверяет, что гейт можно применить к указанным кубитам регистра.
+/// Возвращает ошибку, если число целевых кубитов не совпадает с размерностью гейта,
+/// матрица имеет неверный размер или не унитарна, кубит выходит за пределы или повторяется.
+pub fn validate_gate_targets(gate: &dyn Gate, targets: &[usize], num_qubits: usize) -> Result<(), TriadError> {
+    if gate.num_qubits() != targets.len() {
+        return Err(TriadError::InvalidArgument(format!(
+            "гейт {} действует на {} кубит(ов), но указано {} целевых кубитов", 
+            gate.name(), gate.num_qubits(), targets.len())));
+    }
+    
+    let dim = 1usize << targets.len();
+    let matrix = gate.matrix();
+    if matrix.len() != dim * dim {
+        return Err(TriadError::InvalidArgument(format!(
+            "матрица гейта {} должна иметь размер {}x{}", gate.name(), dim, dim)));
+    }
+    
+    if !is_unitary_matrix(&matrix, UNITARITY_TOLERANCE) {
+        return Err(TriadError::NonUnitary(gate.name().to_string()));
+    }
+    
+    validate_qubits(targets, num_qubits)
+}
+
+/// Проверяет многоконтрольный гейт: управляющие и целевой кубиты должны
+/// находиться в пределах регистра и не повторяться, а матрица — быть
+/// унитарной матрицей 2x2.
+pub fn validate_controlled(controls: &[usize], target: usize, matrix: &[Amplitude], num_qubits: usize) -> Result<(), TriadError> {
+    if matrix.len() != 4 {
+        return Err(TriadError::InvalidArgument(
+            "матрица управляемого оператора должна иметь размер 2x2".to_string()));
+    }
+    
+    if !is_unitary_matrix(matrix, UNITARITY_TOLERANCE) {
+        return Err(TriadError::NonUnitary("multi_controlled_unitary".to_string()));
+    }
+    
+    let qubits: Vec<usize> = controls.iter().copied().chain(std::iter::once(target)).collect();
+    validate_qubits(&qubits, num_qubits)
 }
 
 /// Расширенный интерфейс для более продвинутых квантовых симуляторов.
@@ -91,4 +423,78 @@ pub trait AdvancedQuantumSimulator: QuantumSimulator {
     
     /// Применяет контролируемый унитарный гейт между указанными кубитами.
     fn controlled_unitary(&mut self, control: usize, target: usize, matrix: &[crate::core::quantum_state::Amplitude]);
+    
+    /// Применяет однокубитовый оператор к целевому кубиту при условии,
+    /// что все управляющие кубиты находятся в состоянии |1⟩.
+    fn multi_controlled_unitary(&mut self, controls: &[usize], target: usize, matrix: &[Amplitude]);
+    
+    /// Применяет X-гейт, управляемый несколькими кубитами.
+    fn mcx(&mut self, controls: &[usize], target: usize) {
+        self.multi_controlled_unitary(controls, target, &Pauli::X.matrix());
+    }
+    
+    /// Применяет Z-гейт, управляемый несколькими кубитами. Гейт симметричен:
+    /// фаза -1 набирается только базисным состоянием, в котором все кубиты равны 1.
+    fn mcz(&mut self, controls: &[usize], target: usize) {
+        self.multi_controlled_unitary(controls, target, &Pauli::Z.matrix());
+    }
+    
+    /// Применяет гейт Тоффоли (CCNOT).
+    fn toffoli(&mut self, control1: usize, control2: usize, target: usize) {
+        self.mcx(&[control1, control2], target);
+    }
+    
+    /// Применяет гейт Фредкина (CSWAP): обменивает `target1` и `target2`,
+    /// если управляющий кубит в состоянии |1⟩.
+    fn fredkin(&mut self, control: usize, target1: usize, target2: usize) {
+        self.cnot(target2, target1);
+        self.toffoli(control, target1, target2);
+        self.cnot(target2, target1);
+    }
+    
+    /// Применяет вращение вокруг оси X, управляемое кубитом `control`.
+    fn controlled_rx(&mut self, control: usize, target: usize, angle: f64) {
+        self.controlled_unitary(control, target, &RotationGate { axis: Axis::X, angle }.matrix());
+    }
+    
+    /// Применяет вращение вокруг оси Y, управляемое кубитом `control`.
+    fn controlled_ry(&mut self, control: usize, target: usize, angle: f64) {
+        self.controlled_unitary(control, target, &RotationGate { axis: Axis::Y, angle }.matrix());
+    }
+    
+    /// Применяет вращение вокруг оси Z, управляемое кубитом `control`.
+    fn controlled_rz(&mut self, control: usize, target: usize, angle: f64) {
+        self.controlled_unitary(control, target, &RotationGate { axis: Axis::Z, angle }.matrix());
+    }
+    
+    // === Методы, возвращающие ошибку вместо паники ===
+    
+    /// Применяет многоконтрольный оператор, проверяя кубиты и унитарность матрицы.
+    fn try_multi_controlled_unitary(&mut self, controls: &[usize], target: usize, matrix: &[Amplitude]) -> Result<(), TriadError> {
+        validate_controlled(controls, target, matrix, self.num_qubits())?;
+        self.multi_controlled_unitary(controls, target, matrix);
+        Ok(())
+    }
+    
+    /// Применяет многоконтрольный X-гейт, проверяя индексы кубитов.
+    fn try_mcx(&mut self, controls: &[usize], target: usize) -> Result<(), TriadError> {
+        self.try_multi_controlled_unitary(controls, target, &Pauli::X.matrix())
+    }
+    
+    /// Применяет многоконтрольный Z-гейт, проверяя индексы кубитов.
+    fn try_mcz(&mut self, controls: &[usize], target: usize) -> Result<(), TriadError> {
+        self.try_multi_controlled_unitary(controls, target, &Pauli::Z.matrix())
+    }
+    
+    /// Применяет гейт Тоффоли, проверяя индексы кубитов.
+    fn try_toffoli(&mut self, control1: usize, control2: usize, target: usize) -> Result<(), TriadError> {
+        self.try_mcx(&[control1, control2], target)
+    }
+    
+    /// Применяет гейт Фредкина, проверяя индексы кубитов.
+    fn try_fredkin(&mut self, control: usize, target1: usize, target2: usize) -> Result<(), TriadError> {
+        validate_qubits(&[control, target1, target2], self.num_qubits())?;
+        self.fredkin(control, target1, target2);
+        Ok(())
+    }
 } 
\ No newline at end of file
@@ -5,6 +5,8 @@
 
 use std::fmt;
 use num_complex::Complex64;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 
 /// Тип для представления квантовой амплитуды (комплексное число).
 pub type Amplitude = Complex64;
@@ -34,12 +36,76 @@ pub trait QuantumState: fmt::Debug {
     fn is_entangled(&self) -> bool;
 }
 
+/// Состояние, умеющее измерять кубит по заданному случайному числу из [0, 1).
+pub trait MeasureWith {
+    /// Измеряет кубит, используя `random` вместо собственного генератора.
+    fn measure_with(&mut self, qubit: usize, random: f64) -> bool;
+}
+
+/// Снимок состояния, возвращаемый из `QuantumSimulator::get_state`.
+///
+/// Измерения снимка разыгрываются его собственным генератором, зерно которого
+/// выбирается из копии генератора симулятора. Поэтому после `seed` измерения
+/// снимков воспроизводимы, а генератор самого симулятора не продвигается.
+#[derive(Clone)]
+pub struct StateSnapshot<S> {
+    /// Скопированное состояние.
+    state: S,
+    /// Генератор случайных чисел для измерений снимка.
+    rng: StdRng,
+}
+
+impl<S> StateSnapshot<S> {
+    /// Создает снимок состояния `state` с генератором, производным от `rng`.
+    pub fn new(state: S, rng: &StdRng) -> Self {
+        Self { state, rng: StdRng::seed_from_u64(rng.clone().gen()) }
+    }
+
+    /// Возвращает скопированное состояние.
+    pub fn state(&self) -> &S {
+        &self.state
+    }
+}
+
+impl<S: fmt::Debug> fmt::Debug for StateSnapshot<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.state.fmt(f)
+    }
+}
+
+impl<S: QuantumState + MeasureWith> QuantumState for StateSnapshot<S> {
+    fn num_qubits(&self) -> usize {
+        self.state.num_qubits()
+    }
+
+    fn probability(&self, state: u64) -> f64 {
+        self.state.probability(state)
+    }
+
+    fn amplitude(&self, state: u64) -> Amplitude {
+        self.state.amplitude(state)
+    }
+
+    fn apply_operator(&mut self, operator: &[Amplitude]) {
+        self.state.apply_operator(operator);
+    }
+
+    fn measure(&mut self, qubit: usize) -> bool {
+        let random = self.rng.gen::<f64>();
+        self.state.measure_with(qubit, random)
+    }
+
+    fn is_entangled(&self) -> bool {
+        self.state.is_entangled()
+    }
+}
+
 /// Вспомогательные функции для работы с квантовыми состояниями.
 pub mod utils {
     use super::*;
     
     /// Вычисляет тензорное произведение двух состояний.
-    pub fn tensor_product(state1: &impl QuantumState, state2: &impl QuantumState) -> Vec<Amplitude> {
+    pub fn tensor_product(state1: &(impl QuantumState + ?Sized), state2: &(impl QuantumState + ?Sized)) -> Vec<Amplitude> {
         let n1 = state1.num_qubits();
         let n2 = state2.num_qubits();
         
@@ -62,7 +128,7 @@ pub mod utils {
     }
     
     /// Вычисляет вероятность нахождения состояния в указанной подсистеме.
-    pub fn subsystem_probability(state: &impl QuantumState, qubits: &[usize], outcome: u64) -> f64 {
+    pub fn subsystem_probability(state: &(impl QuantumState + ?Sized), qubits: &[usize], outcome: u64) -> f64 {
         let mut prob = 0.0;
         
         // Суммируем вероятности по всем состояниям, совпадающим с outcome на указанных кубитах
@@ -99,13 +99,14 @@ mod tests {
     
     #[test]
     fn test_qubit_state_normalization() {
-        let mut state = QubitState::new(
+        let state = QubitState::new(
             Complex64::new(2.0, 0.0),
             Complex64::new(0.0, 2.0)
         );
         
-        // Проверяем, что состояние нормализовано
-        assert_eq!(state.prob_zero() + state.prob_one(), 1.0);
+        // Проверяем, что состояние нормализовано. Сумма вероятностей после
+        // деления на √8 равна 0.9999999999999998, поэтому сравнение с допуском
+        assert!((state.prob_zero() + state.prob_one() - 1.0).abs() < 1e-10);
         
         // Ожидаемые нормализованные значения
         let expected_alpha = Complex64::new(2.0, 0.0) / (8.0_f64).sqrt();
@@ -0,0 +1,894 @@
+//! Разреженный симулятор вектора состояния.
+//!
+//! Хранит только ненулевые амплитуды в хеш-таблице, поэтому память и время
+//! выполнения гейтов пропорциональны числу ненулевых амплитуд, а не 2^n.
+//! Подходит для регистров в почти классических состояниях: перестановочные
+//! гейты (X, CNOT, SWAP, Тоффоли) и диагональные гейты не увеличивают число
+//! амплитуд, а каждый гейт суперпозиции увеличивает его не более чем вдвое.
+//!
+//! После каждого гейта амплитуды с квадратом модуля не больше порога
+//! отсечения удаляются, а состояние перенормируется.
+//!
+//! Кубит с индексом `k` соответствует биту `k` индекса базисного состояния.
+
+use crate::core::gates::{Axis, Gate, RotationGate};
+use crate::core::pauli::{Pauli, parse_pauli_product};
+use crate::core::quantum_simulator::{
+    QuantumSimulator, AdvancedQuantumSimulator, validate_gate_targets, validate_joint_outcome, validate_qubit,
+    validate_qubits,
+};
+use crate::core::quantum_state::{QuantumState, Amplitude, MeasureWith, StateSnapshot};
+use crate::core::state_vector::{self, StateVector};
+use crate::core::counts::{Counts, extract_bits, sample_distribution};
+use crate::core::measurement::{Povm, choose_outcome, outcome_probabilities, validate_povm_targets};
+use crate::error::TriadError;
+use num_complex::Complex64;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::{HashMap, HashSet};
+
+/// Максимальное число кубитов: индекс базисного состояния хранится в `u64`.
+pub const MAX_QUBITS: usize = 64;
+
+/// Порог отсечения по умолчанию для квадрата модуля амплитуды.
+pub const DEFAULT_PRUNING_THRESHOLD: f64 = 1e-20;
+
+/// Допуск, используемый при сравнении вероятностей с нулем.
+const EPSILON: f64 = 1e-10;
+
+/// Статистика заполненности разреженного состояния.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SparsityStats {
+    /// Текущее число ненулевых амплитуд.
+    pub nonzero_amplitudes: usize,
+    /// Наибольшее число ненулевых амплитуд за время жизни состояния.
+    pub peak_nonzero_amplitudes: usize,
+    /// Доля ненулевых амплитуд среди всех 2^n базисных состояний.
+    pub fill_ratio: f64,
+    /// Число амплитуд, удаленных порогом отсечения.
+    pub pruned_amplitudes: usize,
+    /// Суммарная вероятность, удаленная порогом отсечения.
+    pub pruned_weight: f64,
+}
+
+/// Разреженное квантовое состояние: отображение базисных состояний
+/// в их ненулевые амплитуды.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SparseState {
+    /// Число кубитов в системе.
+    num_qubits: usize,
+    /// Ненулевые амплитуды базисных состояний.
+    amplitudes: HashMap<u64, Amplitude>,
+    /// Порог отсечения для квадрата модуля амплитуды.
+    pruning_threshold: f64,
+    /// Наибольшее число ненулевых амплитуд.
+    peak_nonzero: usize,
+    /// Число удаленных порогом амплитуд.
+    pruned_amplitudes: usize,
+    /// Суммарная удаленная порогом вероятность.
+    pruned_weight: f64,
+}
+
+impl SparseState {
+    /// Создает состояние |0...0⟩ из указанного числа кубитов.
+    pub fn new(num_qubits: usize) -> Self {
+        Self::try_new(num_qubits).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Создает состояние |0...0⟩, возвращая ошибку для недопустимого числа кубитов.
+    pub fn try_new(num_qubits: usize) -> Result<Self, TriadError> {
+        Self::try_with_pruning_threshold(num_qubits, DEFAULT_PRUNING_THRESHOLD)
+    }
+
+    /// Создает состояние |0...0⟩ с заданным порогом отсечения,
+    /// возвращая ошибку для недопустимых параметров.
+    pub fn try_with_pruning_threshold(num_qubits: usize, pruning_threshold: f64) -> Result<Self, TriadError> {
+        if num_qubits == 0 {
+            return Err(TriadError::NoQubits);
+        }
+        if num_qubits > MAX_QUBITS {
+            return Err(TriadError::TooManyQubits { requested: num_qubits, max: MAX_QUBITS });
+        }
+        check_threshold(pruning_threshold)?;
+
+        let mut amplitudes = HashMap::new();
+        amplitudes.insert(0, Complex64::new(1.0, 0.0));
+
+        Ok(Self {
+            num_qubits,
+            amplitudes,
+            pruning_threshold,
+            peak_nonzero: 1,
+            pruned_amplitudes: 0,
+            pruned_weight: 0.0,
+        })
+    }
+
+    /// Возвращает ненулевые амплитуды, отсортированные по индексу базисного состояния.
+    pub fn nonzero_amplitudes(&self) -> Vec<(u64, Amplitude)> {
+        let mut entries: Vec<(u64, Amplitude)> = self.amplitudes.iter().map(|(&i, &a)| (i, a)).collect();
+        entries.sort_by_key(|&(i, _)| i);
+        entries
+    }
+
+    /// Возвращает число ненулевых амплитуд.
+    pub fn num_nonzero(&self) -> usize {
+        self.amplitudes.len()
+    }
+
+    /// Возвращает порог отсечения.
+    pub fn pruning_threshold(&self) -> f64 {
+        self.pruning_threshold
+    }
+
+    /// Устанавливает порог отсечения и сразу применяет его к состоянию.
+    pub fn set_pruning_threshold(&mut self, threshold: f64) {
+        self.try_set_pruning_threshold(threshold).unwrap_or_else(|err| panic!("{}", err));
+    }
+
+    /// Устанавливает порог отсечения, возвращая ошибку для отрицательного
+    /// или нечислового значения.
+    pub fn try_set_pruning_threshold(&mut self, threshold: f64) -> Result<(), TriadError> {
+        check_threshold(threshold)?;
+        self.pruning_threshold = threshold;
+        self.prune();
+        Ok(())
+    }
+
+    /// Возвращает статистику заполненности состояния.
+    pub fn sparsity(&self) -> SparsityStats {
+        SparsityStats {
+            nonzero_amplitudes: self.amplitudes.len(),
+            peak_nonzero_amplitudes: self.peak_nonzero,
+            fill_ratio: self.amplitudes.len() as f64 / 2f64.powi(self.num_qubits as i32),
+            pruned_amplitudes: self.pruned_amplitudes,
+            pruned_weight: self.pruned_weight,
+        }
+    }
+
+    /// Возвращает квадрат нормы состояния.
+    pub fn norm_sqr(&self) -> f64 {
+        self.amplitudes.values().map(|a| a.norm_sqr()).sum()
+    }
+
+    /// Возвращает скалярное произведение ⟨self|other⟩.
+    pub fn inner_product(&self, other: &SparseState) -> Amplitude {
+        assert_eq!(self.num_qubits, other.num_qubits, "Состояния должны иметь одинаковое число кубитов");
+
+        self.amplitudes.iter()
+            .filter_map(|(i, a)| other.amplitudes.get(i).map(|b| a.conj() * b))
+            .sum()
+    }
+
+    /// Проверяет, что индекс кубита находится в допустимых пределах.
+    fn check_qubit(&self, qubit: usize) {
+        validate_qubit(qubit, self.num_qubits).unwrap_or_else(|err| panic!("{}", err));
+    }
+
+    /// Удаляет амплитуды не больше порога отсечения и перенормирует состояние.
+    fn prune(&mut self) {
+        let threshold = self.pruning_threshold;
+        let before = self.amplitudes.len();
+        let mut removed = 0.0;
+        self.amplitudes.retain(|_, a| {
+            let keep = a.norm_sqr() > threshold;
+            if !keep {
+                removed += a.norm_sqr();
+            }
+            keep
+        });
+
+        let pruned = before - self.amplitudes.len();
+        if pruned > 0 {
+            self.pruned_amplitudes += pruned;
+            self.pruned_weight += removed;
+            let norm = self.norm_sqr().sqrt();
+            if norm > 0.0 {
+                for amp in self.amplitudes.values_mut() {
+                    *amp /= norm;
+                }
+            }
+        }
+        self.peak_nonzero = self.peak_nonzero.max(self.amplitudes.len());
+    }
+
+    /// Применяет однокубитовый оператор (матрица 2x2 по строкам) к указанному кубиту.
+    pub fn apply_single_qubit(&mut self, qubit: usize, matrix: &[Amplitude]) {
+        self.apply_controlled(&[], qubit, matrix);
+    }
+
+    /// Применяет однокубитовый оператор к целевому кубиту при условии,
+    /// что все контрольные кубиты находятся в состоянии |1⟩.
+    pub fn apply_controlled(&mut self, controls: &[usize], target: usize, matrix: &[Amplitude]) {
+        assert_eq!(matrix.len(), 4, "Матрица однокубитового оператора должна быть размера 2x2");
+        self.check_qubit(target);
+
+        let mut control_mask = 0u64;
+        for &control in controls {
+            self.check_qubit(control);
+            if control == target {
+                panic!("Контрольный и целевой кубиты должны различаться: {}", control);
+            }
+            control_mask |= 1 << control;
+        }
+
+        let target_mask = 1u64 << target;
+        let zero = Complex64::new(0.0, 0.0);
+        let mut result = HashMap::with_capacity(self.amplitudes.len());
+
+        for (&i, &amp) in &self.amplitudes {
+            if i & control_mask != control_mask {
+                result.insert(i, amp);
+                continue;
+            }
+
+            // Пару (|..0..⟩, |..1..⟩) обрабатывает элемент с нулевым битом, если он есть
+            let i0 = i & !target_mask;
+            if i != i0 && self.amplitudes.contains_key(&i0) {
+                continue;
+            }
+
+            let i1 = i0 | target_mask;
+            let a0 = self.amplitudes.get(&i0).copied().unwrap_or(zero);
+            let a1 = self.amplitudes.get(&i1).copied().unwrap_or(zero);
+            insert_nonzero(&mut result, i0, matrix[0] * a0 + matrix[1] * a1);
+            insert_nonzero(&mut result, i1, matrix[2] * a0 + matrix[3] * a1);
+        }
+
+        self.amplitudes = result;
+        self.prune();
+    }
+
+    /// Применяет k-кубитовый оператор (матрица 2^k x 2^k по строкам) к указанным кубитам.
+    /// Первый кубит в `targets` соответствует старшему биту индекса строки матрицы.
+    pub fn apply_multi_qubit(&mut self, targets: &[usize], matrix: &[Amplitude]) {
+        let k = targets.len();
+        let dim = 1usize << k;
+        assert_eq!(matrix.len(), dim * dim, "Размер матрицы не соответствует числу целевых кубитов");
+        validate_qubits(targets, self.num_qubits).unwrap_or_else(|err| panic!("{}", err));
+
+        let offsets: Vec<u64> = (0..dim)
+            .map(|row| {
+                targets.iter().enumerate().fold(0, |acc, (j, &q)| {
+                    if (row >> (k - 1 - j)) & 1 == 1 { acc | (1 << q) } else { acc }
+                })
+            })
+            .collect();
+        let targets_mask = targets.iter().fold(0u64, |acc, &q| acc | (1 << q));
+
+        let bases: HashSet<u64> = self.amplitudes.keys().map(|&i| i & !targets_mask).collect();
+        let mut result = HashMap::with_capacity(self.amplitudes.len());
+        let zero = Complex64::new(0.0, 0.0);
+
+        for base in bases {
+            let buffer: Vec<Amplitude> = offsets.iter()
+                .map(|&offset| self.amplitudes.get(&(base | offset)).copied().unwrap_or(zero))
+                .collect();
+
+            for (row, &offset) in offsets.iter().enumerate() {
+                let value = matrix[row * dim..(row + 1) * dim].iter()
+                    .zip(&buffer)
+                    .map(|(m, a)| m * a)
+                    .sum();
+                insert_nonzero(&mut result, base | offset, value);
+            }
+        }
+
+        self.amplitudes = result;
+        self.prune();
+    }
+
+    /// Меняет местами состояния двух кубитов перестановкой индексов.
+    pub fn swap_qubits(&mut self, qubit1: usize, qubit2: usize) {
+        self.check_qubit(qubit1);
+        self.check_qubit(qubit2);
+
+        let (mask1, mask2) = (1u64 << qubit1, 1u64 << qubit2);
+        self.amplitudes = self.amplitudes.drain()
+            .map(|(i, amp)| {
+                if (i & mask1 != 0) != (i & mask2 != 0) { (i ^ mask1 ^ mask2, amp) } else { (i, amp) }
+            })
+            .collect();
+    }
+
+    /// Вычисляет вероятность получить `outcome` при измерении кубита.
+    pub fn probability_of_outcome(&self, qubit: usize, outcome: bool) -> f64 {
+        self.check_qubit(qubit);
+        let mask = 1u64 << qubit;
+
+        self.amplitudes.iter()
+            .filter(|(&i, _)| (i & mask != 0) == outcome)
+            .map(|(_, a)| a.norm_sqr())
+            .sum()
+    }
+
+    /// Вычисляет совместную вероятность получить битовую строку `outcome`
+    /// при измерении кубитов `qubits` (бит `i` соответствует `qubits[i]`).
+    pub fn probability_of_joint_outcome(&self, qubits: &[usize], outcome: u64) -> f64 {
+        validate_joint_outcome(qubits, outcome, self.num_qubits)
+            .unwrap_or_else(|err| panic!("{}", err));
+
+        let (mask, expected) = qubits.iter().enumerate().fold((0u64, 0u64), |(mask, expected), (idx, &q)| {
+            let bit = (outcome >> idx) & 1;
+            (mask | (1 << q), expected | (bit << q))
+        });
+
+        self.amplitudes.iter()
+            .filter(|(&i, _)| i & mask == expected)
+            .map(|(_, a)| a.norm_sqr())
+            .sum()
+    }
+
+    /// Вычисляет распределение результатов измерения кубитов `qubits`
+    /// за один проход по ненулевым амплитудам.
+    pub fn marginal_distribution(&self, qubits: &[usize]) -> Vec<(u64, f64)> {
+        validate_qubits(qubits, self.num_qubits).unwrap_or_else(|err| panic!("{}", err));
+
+        let mut distribution = std::collections::BTreeMap::new();
+        for (&index, amplitude) in &self.amplitudes {
+            *distribution.entry(extract_bits(index, qubits)).or_insert(0.0) += amplitude.norm_sqr();
+        }
+        distribution.into_iter().collect()
+    }
+
+    /// Проецирует состояние на указанный результат измерения кубита
+    /// и перенормирует его.
+    pub fn collapse(&mut self, qubit: usize, outcome: bool) {
+        let prob = self.probability_of_outcome(qubit, outcome);
+        if prob < EPSILON {
+            panic!("Невозможно спроецировать кубит {} на результат с нулевой вероятностью", qubit);
+        }
+
+        let mask = 1u64 << qubit;
+        let scale = 1.0 / prob.sqrt();
+        self.amplitudes.retain(|&i, amp| {
+            *amp *= scale;
+            (i & mask != 0) == outcome
+        });
+    }
+
+    /// Измеряет кубит, используя заданное случайное число из [0, 1).
+    /// Результат равен 1, если `random` не меньше вероятности получить 0.
+    /// Исход с вероятностью меньше `EPSILON` заменяется противоположным.
+    pub fn measure_with(&mut self, qubit: usize, random: f64) -> bool {
+        let prob_zero = self.probability_of_outcome(qubit, false);
+        let mut outcome = random >= prob_zero;
+        if self.probability_of_outcome(qubit, outcome) < EPSILON {
+            outcome = !outcome;
+        }
+        self.collapse(qubit, outcome);
+        outcome
+    }
+
+    /// Выполняет обобщенное измерение над кубитами `targets`, выбирая исход
+    /// по случайному числу `random` из [0, 1), и возвращает его номер.
+    pub fn measure_povm_with(&mut self, povm: &Povm, targets: &[usize], random: f64) -> usize {
+        validate_povm_targets(povm, targets, self.num_qubits).unwrap_or_else(|err| panic!("{}", err));
+
+        let probabilities = outcome_probabilities(povm, &self.reduced_density_matrix(targets));
+        let outcome = choose_outcome(&probabilities, random);
+
+        self.apply_multi_qubit(targets, &povm.operators()[outcome]);
+        let scale = 1.0 / self.norm_sqr().sqrt();
+        self.amplitudes.values_mut().for_each(|amp| *amp *= scale);
+        outcome
+    }
+
+    /// Вычисляет редуцированную матрицу плотности кубитов `targets` (по строкам).
+    /// Первый кубит соответствует старшему биту индекса, как у матриц гейтов.
+    pub fn reduced_density_matrix(&self, targets: &[usize]) -> Vec<Amplitude> {
+        validate_qubits(targets, self.num_qubits).unwrap_or_else(|err| panic!("{}", err));
+
+        let k = targets.len();
+        let dim = 1usize << k;
+        let targets_mask = targets.iter().fold(0u64, |acc, &q| acc | (1 << q));
+        // Номер строки подпространства для каждого базисного состояния
+        let row_of = |index: u64| targets.iter().enumerate()
+            .fold(0usize, |acc, (j, &q)| acc | ((((index >> q) & 1) as usize) << (k - 1 - j)));
+
+        let mut groups: HashMap<u64, Vec<(usize, Amplitude)>> = HashMap::new();
+        for (&index, &amplitude) in &self.amplitudes {
+            groups.entry(index & !targets_mask).or_default().push((row_of(index), amplitude));
+        }
+
+        let mut reduced = vec![Complex64::new(0.0, 0.0); dim * dim];
+        for group in groups.values() {
+            for &(row, a) in group {
+                for &(col, b) in group {
+                    reduced[row * dim + col] += a * b.conj();
+                }
+            }
+        }
+        reduced
+    }
+
+    /// Вычисляет ожидаемое значение произведения операторов Паули.
+    pub fn expectation_pauli(&self, pauli_product: &[(usize, char)]) -> f64 {
+        self.try_expectation_pauli(pauli_product)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Вычисляет ожидаемое значение произведения операторов Паули,
+    /// возвращая ошибку для некорректного произведения.
+    ///
+    /// Произведение Паули переставляет базисные состояния, поэтому вычисляется
+    /// за один проход без копирования состояния.
+    pub fn try_expectation_pauli(&self, pauli_product: &[(usize, char)]) -> Result<f64, TriadError> {
+        let parsed = parse_pauli_product(pauli_product, self.num_qubits)?;
+
+        // P|i⟩ = phase(i)·|i ^ flip⟩
+        let mut flip = 0u64;
+        let mut z_mask = 0u64;
+        let mut y_count = 0;
+        for (qubit, pauli) in parsed {
+            match pauli {
+                Pauli::I => {}
+                Pauli::X => flip |= 1 << qubit,
+                Pauli::Z => z_mask |= 1 << qubit,
+                Pauli::Y => {
+                    flip |= 1 << qubit;
+                    z_mask |= 1 << qubit;
+                    y_count += 1;
+                }
+            }
+        }
+        // Y = i·X·Z
+        let y_phase = Complex64::new(0.0, 1.0).powi(y_count);
+
+        let value: Amplitude = self.amplitudes.iter()
+            .filter_map(|(&i, &a)| {
+                let partner = self.amplitudes.get(&(i ^ flip))?;
+                let sign = if (i & z_mask).count_ones() % 2 == 1 { -1.0 } else { 1.0 };
+                Some(partner.conj() * a * sign)
+            })
+            .sum();
+
+        Ok((value * y_phase).re)
+    }
+
+    /// Вычисляет чистоту редуцированной матрицы плотности одного кубита.
+    fn single_qubit_purity(&self, qubit: usize) -> f64 {
+        let mask = 1u64 << qubit;
+        let mut rho00 = 0.0;
+        let mut rho11 = 0.0;
+        let mut rho01 = Complex64::new(0.0, 0.0);
+
+        for (&i, &a) in &self.amplitudes {
+            if i & mask != 0 {
+                rho11 += a.norm_sqr();
+                continue;
+            }
+            rho00 += a.norm_sqr();
+            if let Some(a1) = self.amplitudes.get(&(i | mask)) {
+                rho01 += a * a1.conj();
+            }
+        }
+
+        rho00 * rho00 + rho11 * rho11 + 2.0 * rho01.norm_sqr()
+    }
+
+    /// Строит плотный вектор состояния. Возвращает ошибку, если кубитов больше,
+    /// чем `state_vector::MAX_QUBITS`.
+    pub fn to_state_vector(&self) -> Result<StateVector, TriadError> {
+        if self.num_qubits > state_vector::MAX_QUBITS {
+            return Err(TriadError::TooManyQubits { requested: self.num_qubits, max: state_vector::MAX_QUBITS });
+        }
+
+        let mut amplitudes = vec![Complex64::new(0.0, 0.0); 1 << self.num_qubits];
+        for (&i, &a) in &self.amplitudes {
+            amplitudes[i as usize] = a;
+        }
+        Ok(StateVector::from_amplitudes(amplitudes))
+    }
+}
+
+/// Добавляет амплитуду в таблицу, если она не равна нулю точно.
+fn insert_nonzero(amplitudes: &mut HashMap<u64, Amplitude>, index: u64, value: Amplitude) {
+    if value.norm_sqr() > 0.0 {
+        amplitudes.insert(index, value);
+    }
+}
+
+/// Проверяет, что порог отсечения является неотрицательным числом.
+fn check_threshold(threshold: f64) -> Result<(), TriadError> {
+    if !(threshold >= 0.0 && threshold.is_finite()) {
+        return Err(TriadError::InvalidArgument(format!(
+            "порог отсечения должен быть неотрицательным числом, получено {}", threshold)));
+    }
+    Ok(())
+}
+
+impl MeasureWith for SparseState {
+    fn measure_with(&mut self, qubit: usize, random: f64) -> bool {
+        SparseState::measure_with(self, qubit, random)
+    }
+}
+
+impl QuantumState for SparseState {
+    fn num_qubits(&self) -> usize {
+        self.num_qubits
+    }
+
+    fn probability(&self, state: u64) -> f64 {
+        self.amplitude(state).norm_sqr()
+    }
+
+    fn amplitude(&self, state: u64) -> Amplitude {
+        self.amplitudes
+            .get(&state)
+            .copied()
+            .unwrap_or_else(|| Complex64::new(0.0, 0.0))
+    }
+
+    /// Применяет оператор, заданный полной матрицей 2^n x 2^n по строкам.
+    fn apply_operator(&mut self, operator: &[Amplitude]) {
+        let targets: Vec<usize> = (0..self.num_qubits).rev().collect();
+        self.apply_multi_qubit(&targets, operator);
+    }
+
+    fn measure(&mut self, qubit: usize) -> bool {
+        let random = rand::thread_rng().gen::<f64>();
+        self.measure_with(qubit, random)
+    }
+
+    /// Чистое состояние является тензорным произведением тогда и только тогда,
+    /// когда редуцированное состояние каждого кубита чистое.
+    fn is_entangled(&self) -> bool {
+        self.amplitudes.len() > 1
+            && (0..self.num_qubits).any(|q| self.single_qubit_purity(q) < 1.0 - 1e-9)
+    }
+}
+
+/// Разреженный симулятор вектора состояния.
+/// Поддерживает до `MAX_QUBITS` кубитов; память пропорциональна числу ненулевых амплитуд.
+#[derive(Debug, Clone)]
+pub struct SparseSimulator {
+    /// Текущее состояние регистра.
+    state: SparseState,
+    /// Генератор случайных чисел для измерений и выборок.
+    rng: StdRng,
+}
+
+impl SparseSimulator {
+    /// Создает новый симулятор с указанным количеством кубитов в состоянии |0...0⟩.
+    pub fn new(num_qubits: usize) -> Self {
+        Self {
+            state: SparseState::new(num_qubits),
+            rng: StdRng::from_entropy(),
+        }
+    }
+
+    /// Создает новый симулятор, возвращая ошибку для недопустимого числа кубитов.
+    pub fn try_new(num_qubits: usize) -> Result<Self, TriadError> {
+        Ok(Self {
+            state: SparseState::try_new(num_qubits)?,
+            rng: StdRng::from_entropy(),
+        })
+    }
+
+    /// Создает новый симулятор с заданным порогом отсечения.
+    pub fn with_pruning_threshold(num_qubits: usize, pruning_threshold: f64) -> Self {
+        Self::try_with_pruning_threshold(num_qubits, pruning_threshold)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Создает новый симулятор с заданным порогом отсечения,
+    /// возвращая ошибку для недопустимых параметров.
+    pub fn try_with_pruning_threshold(num_qubits: usize, pruning_threshold: f64) -> Result<Self, TriadError> {
+        Ok(Self {
+            state: SparseState::try_with_pruning_threshold(num_qubits, pruning_threshold)?,
+            rng: StdRng::from_entropy(),
+        })
+    }
+
+    /// Возвращает количество кубитов в регистре.
+    pub fn num_qubits(&self) -> usize {
+        self.state.num_qubits
+    }
+
+    /// Возвращает ссылку на текущее разреженное состояние.
+    pub fn sparse_state(&self) -> &SparseState {
+        &self.state
+    }
+
+    /// Возвращает статистику заполненности текущего состояния.
+    pub fn sparsity(&self) -> SparsityStats {
+        self.state.sparsity()
+    }
+
+    /// Устанавливает порог отсечения (см. `SparseState::set_pruning_threshold`).
+    pub fn set_pruning_threshold(&mut self, threshold: f64) {
+        self.state.set_pruning_threshold(threshold);
+    }
+
+    /// Применяет гейт вращения вокруг указанной оси.
+    fn rotate(&mut self, qubit: usize, axis: Axis, angle: f64) {
+        let gate = RotationGate { axis, angle };
+        self.state.apply_single_qubit(qubit, &gate.matrix());
+    }
+}
+
+impl QuantumSimulator for SparseSimulator {
+    fn new(num_qubits: usize) -> Self where Self: Sized {
+        Self::new(num_qubits)
+    }
+
+    fn try_new(num_qubits: usize) -> Result<Self, TriadError> where Self: Sized {
+        Self::try_new(num_qubits)
+    }
+
+    fn num_qubits(&self) -> usize {
+        self.state.num_qubits
+    }
+
+    fn get_state(&self) -> Box<dyn QuantumState> {
+        Box::new(StateSnapshot::new(self.state.clone(), &self.rng))
+    }
+
+    /// Возвращает регистр в |0...0⟩ с тем же порогом отсечения и сбрасывает статистику.
+    fn reset(&mut self) {
+        self.state = SparseState::try_with_pruning_threshold(self.state.num_qubits, self.state.pruning_threshold)
+            .unwrap_or_else(|err| panic!("{}", err));
+    }
+
+    fn hadamard(&mut self, qubit: usize) {
+        let factor = Complex64::new(1.0 / 2.0_f64.sqrt(), 0.0);
+        self.state.apply_single_qubit(qubit, &[factor, factor, factor, -factor]);
+    }
+
+    fn x(&mut self, qubit: usize) {
+        self.state.apply_single_qubit(qubit, &Pauli::X.matrix());
+    }
+
+    fn y(&mut self, qubit: usize) {
+        self.state.apply_single_qubit(qubit, &Pauli::Y.matrix());
+    }
+
+    fn z(&mut self, qubit: usize) {
+        self.state.apply_single_qubit(qubit, &Pauli::Z.matrix());
+    }
+
+    fn cnot(&mut self, control: usize, target: usize) {
+        self.state.apply_controlled(&[control], target, &Pauli::X.matrix());
+    }
+
+    fn measure(&mut self, qubit: usize) -> bool {
+        let random = self.rng.gen::<f64>();
+        self.state.measure_with(qubit, random)
+    }
+
+    fn rng(&mut self) -> Option<&mut StdRng> {
+        Some(&mut self.rng)
+    }
+
+    fn try_measure_povm(&mut self, povm: &Povm, targets: &[usize]) -> Result<usize, TriadError> {
+        validate_povm_targets(povm, targets, self.state.num_qubits)?;
+        let random = self.rng.gen::<f64>();
+        Ok(self.state.measure_povm_with(povm, targets, random))
+    }
+
+    fn apply_gate(&mut self, gate: &dyn Gate, targets: &[usize]) {
+        validate_gate_targets(gate, targets, self.state.num_qubits)
+            .unwrap_or_else(|err| panic!("{}", err));
+
+        match targets {
+            [qubit] => self.state.apply_single_qubit(*qubit, &gate.matrix()),
+            _ => self.state.apply_multi_qubit(targets, &gate.matrix()),
+        }
+    }
+
+    fn get_expectation_value(&self, pauli_product: &[(usize, char)]) -> f64 {
+        self.state.expectation_pauli(pauli_product)
+    }
+
+    fn probability_of_outcome(&self, qubit: usize, outcome: bool) -> f64 {
+        self.state.probability_of_outcome(qubit, outcome)
+    }
+
+    fn probability_of_joint_outcome(&self, qubits: &[usize], outcome: u64) -> f64 {
+        self.state.probability_of_joint_outcome(qubits, outcome)
+    }
+
+    fn sample(&mut self, shots: usize, qubits: &[usize]) -> Counts {
+        let distribution = self.state.marginal_distribution(qubits);
+        sample_distribution(&distribution, qubits.len(), shots, &mut self.rng)
+    }
+}
+
+impl AdvancedQuantumSimulator for SparseSimulator {
+    fn s_gate(&mut self, qubit: usize) {
+        let one = Complex64::new(1.0, 0.0);
+        let zero = Complex64::new(0.0, 0.0);
+        self.state.apply_single_qubit(qubit, &[one, zero, zero, Complex64::new(0.0, 1.0)]);
+    }
+
+    fn t_gate(&mut self, qubit: usize) {
+        let one = Complex64::new(1.0, 0.0);
+        let zero = Complex64::new(0.0, 0.0);
+        let phase = Complex64::from_polar(1.0, std::f64::consts::FRAC_PI_4);
+        self.state.apply_single_qubit(qubit, &[one, zero, zero, phase]);
+    }
+
+    fn cz(&mut self, control: usize, target: usize) {
+        self.state.apply_controlled(&[control], target, &Pauli::Z.matrix());
+    }
+
+    fn swap(&mut self, qubit1: usize, qubit2: usize) {
+        self.state.swap_qubits(qubit1, qubit2);
+    }
+
+    fn apply_unitary(&mut self, qubit: usize, matrix: &[Amplitude]) {
+        self.state.apply_single_qubit(qubit, matrix);
+    }
+
+    fn rx(&mut self, qubit: usize, angle: f64) {
+        self.rotate(qubit, Axis::X, angle);
+    }
+
+    fn ry(&mut self, qubit: usize, angle: f64) {
+        self.rotate(qubit, Axis::Y, angle);
+    }
+
+    fn rz(&mut self, qubit: usize, angle: f64) {
+        self.rotate(qubit, Axis::Z, angle);
+    }
+
+    fn controlled_unitary(&mut self, control: usize, target: usize, matrix: &[Amplitude]) {
+        self.state.apply_controlled(&[control], target, matrix);
+    }
+
+    fn multi_controlled_unitary(&mut self, controls: &[usize], target: usize, matrix: &[Amplitude]) {
+        self.state.apply_controlled(controls, target, matrix);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::circuit::Circuit;
+    use crate::core::gates::CustomGate;
+    use crate::core::state_vector::StateVectorSimulator;
+
+    const TOLERANCE: f64 = 1e-10;
+
+    #[test]
+    fn test_matches_state_vector() {
+        let swap_root = {
+            let (a, b) = (Complex64::new(0.5, 0.5), Complex64::new(0.5, -0.5));
+            let (zero, one) = (Complex64::new(0.0, 0.0), Complex64::new(1.0, 0.0));
+            CustomGate {
+                matrix: vec![one, zero, zero, zero, zero, a, b, zero, zero, b, a, zero, zero, zero, zero, one],
+                num_qubits: 2,
+                name: "SQRT_SWAP".to_string(),
+            }
+        };
+        let mut circuit = Circuit::new(5, 0);
+        circuit.h(0).cnot(0, 3).ry(2, 0.3).t(3).gate(swap_root, &[3, 1]).cz(1, 4).swap(0, 4).rx(2, -1.2).s(1);
+
+        let mut dense = StateVectorSimulator::new(5);
+        let mut sparse = SparseSimulator::new(5);
+        circuit.run_on(&mut dense).unwrap();
+        circuit.run_on(&mut sparse).unwrap();
+
+        let state = sparse.sparse_state().to_state_vector().unwrap();
+        for (a, b) in state.amplitudes().iter().zip(dense.state_vector().amplitudes()) {
+            assert!((a - b).norm() < TOLERANCE);
+        }
+        for product in [vec![(0, 'Z')], vec![(1, 'X'), (3, 'Y')], vec![(2, 'Y'), (4, 'Z'), (0, 'X')]] {
+            assert!((sparse.get_expectation_value(&product) - dense.get_expectation_value(&product)).abs() < TOLERANCE);
+        }
+        assert!((sparse.probability_of_joint_outcome(&[4, 2], 0b01) - dense.probability_of_joint_outcome(&[4, 2], 0b01)).abs() < TOLERANCE);
+        assert_eq!(sparse.get_state().is_entangled(), dense.get_state().is_entangled());
+    }
+
+    #[test]
+    fn test_classical_register_stays_sparse() {
+        let mut simulator = SparseSimulator::new(64);
+        for qubit in (0..64).step_by(3) {
+            simulator.x(qubit);
+        }
+        for qubit in 0..63 {
+            simulator.cnot(qubit, qubit + 1);
+        }
+        simulator.hadamard(10);
+        simulator.cnot(10, 63);
+
+        let stats = simulator.sparsity();
+        assert_eq!(stats.nonzero_amplitudes, 2);
+        assert_eq!(stats.peak_nonzero_amplitudes, 2);
+        assert!(stats.fill_ratio < 1e-18);
+        assert!(simulator.get_state().is_entangled());
+
+        let outcome = simulator.measure(10);
+        assert_eq!(simulator.sparsity().nonzero_amplitudes, 1);
+        assert!((simulator.probability_of_outcome(10, outcome) - 1.0).abs() < TOLERANCE);
+    }
+
+    #[test]
+    fn test_pruning_threshold_drops_small_amplitudes() {
+        let mut simulator = SparseSimulator::with_pruning_threshold(2, 1e-6);
+        simulator.ry(0, 1e-4);
+        // Амплитуда |1⟩ имеет вероятность ~2.5e-9 и отсекается
+        let stats = simulator.sparsity();
+        assert_eq!(stats.nonzero_amplitudes, 1);
+        assert_eq!(stats.pruned_amplitudes, 1);
+        assert!(stats.pruned_weight > 0.0 && stats.pruned_weight < 1e-8);
+        assert!((simulator.sparse_state().norm_sqr() - 1.0).abs() < TOLERANCE);
+
+        simulator.ry(1, 0.5);
+        assert_eq!(simulator.sparsity().nonzero_amplitudes, 2);
+        simulator.set_pruning_threshold(0.1);
+        assert_eq!(simulator.sparsity().nonzero_amplitudes, 1);
+        assert_eq!(simulator.sparsity().peak_nonzero_amplitudes, 2);
+
+        simulator.reset();
+        assert_eq!(simulator.sparsity().pruned_amplitudes, 0);
+        assert_eq!(simulator.sparse_state().pruning_threshold(), 0.1);
+    }
+
+    #[test]
+    fn test_try_methods_return_errors() {
+        assert!(matches!(SparseSimulator::try_with_pruning_threshold(3, -1.0), Err(TriadError::InvalidArgument(_))));
+        assert!(matches!(SparseSimulator::try_with_pruning_threshold(3, f64::NAN), Err(TriadError::InvalidArgument(_))));
+        assert_eq!(SparseSimulator::try_new(65).err(), Some(TriadError::TooManyQubits { requested: 65, max: 64 }));
+
+        let mut simulator = SparseSimulator::new(3);
+        assert_eq!(simulator.try_hadamard(3), Err(TriadError::InvalidQubit { qubit: 3, num_qubits: 3 }));
+        assert!(simulator.try_expectation_value(&[(0, 'Q')]).is_err());
+        assert!(SparseSimulator::new(40).sparse_state().to_state_vector().is_err());
+    }
+
+    #[test]
+    fn test_sample_matches_state_vector_distribution() {
+        let mut sparse = SparseSimulator::new(40);
+        let mut dense = StateVectorSimulator::new(3);
+        for simulator in [&mut sparse as &mut dyn QuantumSimulator, &mut dense] {
+            simulator.x(1);
+            simulator.hadamard(2);
+        }
+
+        let counts = sparse.sample(2000, &[1, 2]);
+        let expected = dense.state_vector().marginal_distribution(&[1, 2]);
+        assert_eq!(counts.iter().count(), expected.len());
+        for (outcome, prob) in expected {
+            assert!((counts.frequency(outcome) - prob).abs() < 0.05);
+        }
+    }
+
+    #[test]
+    fn test_povm_matches_state_vector() {
+        use crate::core::measurement::Povm;
+
+        // Слабое измерение Z: M_0 = √0.8 |0⟩⟨0| + √0.2 |1⟩⟨1|, M_1 = √0.2 |0⟩⟨0| + √0.8 |1⟩⟨1|
+        let weak = |a: f64, b: f64| vec![Complex64::new(a.sqrt(), 0.0), Complex64::new(0.0, 0.0),
+                                         Complex64::new(0.0, 0.0), Complex64::new(b.sqrt(), 0.0)];
+        let povm = Povm::from_operators("weak_z", vec![weak(0.8, 0.2), weak(0.2, 0.8)]).unwrap();
+
+        let mut sparse = SparseSimulator::new(3);
+        let mut dense = StateVectorSimulator::new(3);
+        for simulator in [&mut sparse as &mut dyn QuantumSimulator, &mut dense] {
+            simulator.seed(9);
+            simulator.hadamard(0);
+            simulator.cnot(0, 2);
+            for _ in 0..4 {
+                simulator.measure_povm(&povm, &[2]);
+            }
+        }
+
+        let expected = dense.state_vector();
+        for (index, amplitude) in sparse.sparse_state().nonzero_amplitudes() {
+            assert!((amplitude - expected.amplitudes()[index as usize]).norm() < TOLERANCE);
+        }
+        assert!((sparse.sparse_state().norm_sqr() - 1.0).abs() < TOLERANCE);
+    }
+
+    #[test]
+    fn test_measure_with_skips_outcomes_below_epsilon() {
+        // Вероятность |1⟩ равна sin²(5e-6) ≈ 2.5e-11, но random попадает в ее интервал
+        let rotation = RotationGate { axis: Axis::Y, angle: 1e-5 }.matrix();
+        let mut state = SparseState::new(1);
+        state.apply_single_qubit(0, &rotation);
+        assert!(!state.measure_with(0, 0.99999999999));
+        assert!((state.probability(0) - 1.0).abs() < EPSILON);
+    }
+}
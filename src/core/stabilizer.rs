@@ -0,0 +1,968 @@
+//! Стабилизаторный симулятор (таблица Ааронсона — Готтесмана, CHP).
+//!
+//! Состояния, получаемые из |0...0⟩ гейтами Клиффорда (H, S, CNOT и их
+//! произведениями) и измерениями, однозначно задаются n генераторами группы
+//! стабилизаторов. Таблица из n дестабилизаторов и n стабилизаторов занимает
+//! O(n²) бит, гейт выполняется за O(n), измерение — за O(n²), поэтому
+//! симулятор работает с тысячами кубитов. Гейты вне группы Клиффорда
+//! (T, произвольные вращения) отклоняются ошибкой `TriadError::NonClifford`.
+//!
+//! Кубит с индексом `k` соответствует биту `k` индекса базисного состояния.
+
+use crate::core::gates::{Axis, Gate, RotationGate, controlled_matrix};
+use crate::core::pauli::{Pauli, parse_pauli_product};
+use crate::core::quantum_simulator::{
+    QuantumSimulator, AdvancedQuantumSimulator, validate_controlled, validate_gate_targets, validate_joint_outcome,
+    validate_qubit, validate_qubits,
+};
+use crate::core::quantum_state::{QuantumState, Amplitude, MeasureWith, StateSnapshot};
+use crate::core::state_vector::{self, StateVector};
+use crate::error::TriadError;
+use num_complex::Complex64;
+use once_cell::sync::Lazy;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, VecDeque};
+
+/// Максимальное число кубитов стабилизаторного симулятора
+/// (таблица из 2^14 кубитов занимает 128 МБ памяти).
+pub const MAX_QUBITS: usize = 1 << 14;
+
+/// Оператор Паули со знаком, записанный битами `x` и `z` для каждого кубита.
+/// Пара (1, 1) обозначает Y, а не XZ.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct PauliRow {
+    /// Биты X-компоненты.
+    x: Vec<u64>,
+    /// Биты Z-компоненты.
+    z: Vec<u64>,
+    /// Знак оператора (`true` — минус).
+    sign: bool,
+}
+
+impl PauliRow {
+    /// Создает тождественный оператор на `words` 64-битных словах.
+    fn identity(words: usize) -> Self {
+        Self { x: vec![0; words], z: vec![0; words], sign: false }
+    }
+
+    /// Создает однокубитовый оператор Паули.
+    fn single(words: usize, qubit: usize, pauli: Pauli) -> Self {
+        let mut row = Self::identity(words);
+        row.set(qubit, matches!(pauli, Pauli::X | Pauli::Y), matches!(pauli, Pauli::Z | Pauli::Y));
+        row
+    }
+
+    /// Возвращает X-бит кубита.
+    fn x(&self, qubit: usize) -> bool {
+        (self.x[qubit / 64] >> (qubit % 64)) & 1 == 1
+    }
+
+    /// Возвращает Z-бит кубита.
+    fn z(&self, qubit: usize) -> bool {
+        (self.z[qubit / 64] >> (qubit % 64)) & 1 == 1
+    }
+
+    /// Устанавливает X- и Z-биты кубита.
+    fn set(&mut self, qubit: usize, x: bool, z: bool) {
+        let (word, mask) = (qubit / 64, 1u64 << (qubit % 64));
+        self.x[word] = if x { self.x[word] | mask } else { self.x[word] & !mask };
+        self.z[word] = if z { self.z[word] | mask } else { self.z[word] & !mask };
+    }
+
+    /// Заменяет оператор на произведение `other · self` (операция rowsum).
+    fn multiply_by(&mut self, other: &PauliRow) {
+        // Показатель степени i, возникающий при перемножении операторов по кубитам
+        let mut exponent: i64 = 0;
+        for w in 0..self.x.len() {
+            let (x1, z1, x2, z2) = (other.x[w], other.z[w], self.x[w], self.z[w]);
+            let (y1, only_x1, only_z1) = (x1 & z1, x1 & !z1, !x1 & z1);
+            let plus = (y1 & z2 & !x2) | (only_x1 & z2 & x2) | (only_z1 & x2 & !z2);
+            let minus = (y1 & x2 & !z2) | (only_x1 & z2 & !x2) | (only_z1 & x2 & z2);
+            exponent += plus.count_ones() as i64 - minus.count_ones() as i64;
+        }
+
+        let total = 2 * self.sign as i64 + 2 * other.sign as i64 + exponent;
+        self.sign = total.rem_euclid(4) == 2;
+        for w in 0..self.x.len() {
+            self.x[w] ^= other.x[w];
+            self.z[w] ^= other.z[w];
+        }
+    }
+
+    /// Проверяет, антикоммутируют ли два оператора.
+    fn anticommutes(&self, other: &PauliRow) -> bool {
+        let parity: u32 = (0..self.x.len())
+            .map(|w| ((self.x[w] & other.z[w]) ^ (self.z[w] & other.x[w])).count_ones())
+            .sum();
+        parity % 2 == 1
+    }
+}
+
+/// Стабилизаторное состояние: таблица из n дестабилизаторов (строки `0..n`)
+/// и n стабилизаторов (строки `n..2n`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StabilizerState {
+    /// Число кубитов в системе.
+    num_qubits: usize,
+    /// Строки таблицы.
+    rows: Vec<PauliRow>,
+}
+
+impl StabilizerState {
+    /// Создает состояние |0...0⟩ из указанного числа кубитов.
+    pub fn new(num_qubits: usize) -> Self {
+        Self::try_new(num_qubits).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Создает состояние |0...0⟩, возвращая ошибку для недопустимого числа кубитов.
+    pub fn try_new(num_qubits: usize) -> Result<Self, TriadError> {
+        if num_qubits == 0 {
+            return Err(TriadError::NoQubits);
+        }
+        if num_qubits > MAX_QUBITS {
+            return Err(TriadError::TooManyQubits { requested: num_qubits, max: MAX_QUBITS });
+        }
+
+        let words = num_qubits.div_ceil(64);
+        let destabilizers = (0..num_qubits).map(|q| PauliRow::single(words, q, Pauli::X));
+        let stabilizers = (0..num_qubits).map(|q| PauliRow::single(words, q, Pauli::Z));
+
+        Ok(Self {
+            num_qubits,
+            rows: destabilizers.chain(stabilizers).collect(),
+        })
+    }
+
+    /// Проверяет, что индекс кубита находится в допустимых пределах.
+    fn check_qubit(&self, qubit: usize) {
+        validate_qubit(qubit, self.num_qubits).unwrap_or_else(|err| panic!("{}", err));
+    }
+
+    /// Возвращает генераторы группы стабилизаторов в виде строк вида `+XZI`
+    /// (первый символ после знака соответствует кубиту 0).
+    pub fn stabilizers(&self) -> Vec<String> {
+        self.rows[self.num_qubits..].iter()
+            .map(|row| {
+                let sign = if row.sign { '-' } else { '+' };
+                std::iter::once(sign)
+                    .chain((0..self.num_qubits).map(|q| match (row.x(q), row.z(q)) {
+                        (false, false) => 'I',
+                        (true, false) => 'X',
+                        (true, true) => 'Y',
+                        (false, true) => 'Z',
+                    }))
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Применяет гейт Адамара.
+    pub fn hadamard(&mut self, qubit: usize) {
+        self.check_qubit(qubit);
+        for row in self.rows.iter_mut() {
+            let (x, z) = (row.x(qubit), row.z(qubit));
+            row.sign ^= x && z;
+            row.set(qubit, z, x);
+        }
+    }
+
+    /// Применяет фазовый гейт S = diag(1, i).
+    pub fn phase(&mut self, qubit: usize) {
+        self.check_qubit(qubit);
+        for row in self.rows.iter_mut() {
+            let (x, z) = (row.x(qubit), row.z(qubit));
+            row.sign ^= x && z;
+            row.set(qubit, x, z ^ x);
+        }
+    }
+
+    /// Применяет оператор Паули (меняет только знаки генераторов).
+    pub fn pauli(&mut self, qubit: usize, pauli: Pauli) {
+        self.check_qubit(qubit);
+        for row in self.rows.iter_mut() {
+            row.sign ^= match pauli {
+                Pauli::I => false,
+                Pauli::X => row.z(qubit),
+                Pauli::Y => row.x(qubit) ^ row.z(qubit),
+                Pauli::Z => row.x(qubit),
+            };
+        }
+    }
+
+    /// Применяет CNOT с контрольным кубитом `control` и целевым `target`.
+    pub fn cnot(&mut self, control: usize, target: usize) {
+        self.check_qubit(control);
+        self.check_qubit(target);
+        if control == target {
+            panic!("Контрольный и целевой кубиты должны различаться: {}", control);
+        }
+
+        for row in self.rows.iter_mut() {
+            let (xc, zc, xt, zt) = (row.x(control), row.z(control), row.x(target), row.z(target));
+            row.sign ^= xc && zt && !(xt ^ zc);
+            row.set(target, xt ^ xc, zt);
+            row.set(control, xc, zc ^ zt);
+        }
+    }
+
+    /// Применяет CZ = (I ⊗ H) · CNOT · (I ⊗ H).
+    pub fn cz(&mut self, control: usize, target: usize) {
+        self.hadamard(target);
+        self.cnot(control, target);
+        self.hadamard(target);
+    }
+
+    /// Меняет местами состояния двух кубитов.
+    pub fn swap(&mut self, qubit1: usize, qubit2: usize) {
+        self.check_qubit(qubit1);
+        self.check_qubit(qubit2);
+        for row in self.rows.iter_mut() {
+            let (x1, z1, x2, z2) = (row.x(qubit1), row.z(qubit1), row.x(qubit2), row.z(qubit2));
+            row.set(qubit1, x2, z2);
+            row.set(qubit2, x1, z1);
+        }
+    }
+
+    /// Применяет произвольный одно- или двухкубитовый гейт Клиффорда,
+    /// заданный матрицей (по строкам; первый кубит — старший бит индекса).
+    /// Возвращает `TriadError::NonClifford`, если матрица не принадлежит
+    /// группе Клиффорда даже с точностью до глобальной фазы.
+    pub fn apply_clifford(&mut self, name: &str, targets: &[usize], matrix: &[Amplitude]) -> Result<(), TriadError> {
+        let word = decompose_clifford(targets.len(), matrix)
+            .ok_or_else(|| TriadError::NonClifford(name.to_string()))?;
+
+        for op in word {
+            match op {
+                CliffordOp::H(j) => self.hadamard(targets[j]),
+                CliffordOp::S(j) => self.phase(targets[j]),
+                CliffordOp::Cnot(c, t) => self.cnot(targets[c], targets[t]),
+            }
+        }
+        Ok(())
+    }
+
+    /// Возвращает результат измерения кубита, если он детерминирован.
+    pub fn deterministic_outcome(&self, qubit: usize) -> Option<bool> {
+        self.check_qubit(qubit);
+        let n = self.num_qubits;
+        if self.rows[n..].iter().any(|row| row.x(qubit)) {
+            return None;
+        }
+
+        // Z_qubit выражается через стабилизаторы, парные дестабилизаторам с X на кубите
+        let mut product = PauliRow::identity(self.rows[0].x.len());
+        for i in 0..n {
+            if self.rows[i].x(qubit) {
+                product.multiply_by(&self.rows[i + n]);
+            }
+        }
+        Some(product.sign)
+    }
+
+    /// Вычисляет вероятность получить `outcome` при измерении кубита (0, 1/2 или 1).
+    pub fn probability_of_outcome(&self, qubit: usize, outcome: bool) -> f64 {
+        match self.deterministic_outcome(qubit) {
+            Some(value) if value == outcome => 1.0,
+            Some(_) => 0.0,
+            None => 0.5,
+        }
+    }
+
+    /// Вычисляет совместную вероятность получить битовую строку `outcome`
+    /// при измерении кубитов `qubits` (бит `i` соответствует `qubits[i]`).
+    pub fn probability_of_joint_outcome(&self, qubits: &[usize], outcome: u64) -> f64 {
+        validate_joint_outcome(qubits, outcome, self.num_qubits)
+            .unwrap_or_else(|err| panic!("{}", err));
+        let bits: Vec<bool> = (0..qubits.len()).map(|i| (outcome >> i) & 1 == 1).collect();
+        self.clone().project(qubits, &bits)
+    }
+
+    /// Проецирует состояние на значения `bits` кубитов `qubits` и возвращает
+    /// вероятность такого результата (при нулевой вероятности состояние не определено).
+    fn project(&mut self, qubits: &[usize], bits: &[bool]) -> f64 {
+        let mut probability = 1.0;
+        for (&qubit, &bit) in qubits.iter().zip(bits) {
+            match self.deterministic_outcome(qubit) {
+                Some(value) if value != bit => return 0.0,
+                Some(_) => {},
+                None => {
+                    probability *= 0.5;
+                    self.measure_with(qubit, if bit { 1.0 } else { 0.0 });
+                },
+            }
+        }
+        probability
+    }
+
+    /// Измеряет кубит, используя заданное случайное число из [0, 1).
+    /// Случайный результат равен 1, если `random` не меньше 1/2.
+    pub fn measure_with(&mut self, qubit: usize, random: f64) -> bool {
+        self.check_qubit(qubit);
+        let n = self.num_qubits;
+
+        let pivot = match (n..2 * n).find(|&p| self.rows[p].x(qubit)) {
+            Some(pivot) => pivot,
+            None => return self.deterministic_outcome(qubit).unwrap(),
+        };
+
+        let pivot_row = self.rows[pivot].clone();
+        for i in 0..2 * n {
+            if i != pivot && self.rows[i].x(qubit) {
+                self.rows[i].multiply_by(&pivot_row);
+            }
+        }
+
+        let outcome = random >= 0.5;
+        self.rows[pivot - n] = pivot_row;
+        self.rows[pivot] = PauliRow::single(self.rows[0].x.len(), qubit, Pauli::Z);
+        self.rows[pivot].sign = outcome;
+        outcome
+    }
+
+    /// Вычисляет ожидаемое значение произведения операторов Паули (0 или ±1).
+    pub fn expectation_pauli(&self, pauli_product: &[(usize, char)]) -> f64 {
+        self.try_expectation_pauli(pauli_product)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Вычисляет ожидаемое значение произведения операторов Паули,
+    /// возвращая ошибку для некорректного произведения.
+    pub fn try_expectation_pauli(&self, pauli_product: &[(usize, char)]) -> Result<f64, TriadError> {
+        let n = self.num_qubits;
+        let words = self.rows[0].x.len();
+        let mut operator = PauliRow::identity(words);
+        for (qubit, pauli) in parse_pauli_product(pauli_product, n)? {
+            operator.set(qubit, matches!(pauli, Pauli::X | Pauli::Y), matches!(pauli, Pauli::Z | Pauli::Y));
+        }
+
+        // Оператор, антикоммутирующий с каким-либо стабилизатором, имеет нулевое среднее
+        if self.rows[n..].iter().any(|row| row.anticommutes(&operator)) {
+            return Ok(0.0);
+        }
+
+        // Иначе он равен ± произведению стабилизаторов, парных антикоммутирующим дестабилизаторам
+        let mut product = PauliRow::identity(words);
+        for i in 0..n {
+            if self.rows[i].anticommutes(&operator) {
+                product.multiply_by(&self.rows[i + n]);
+            }
+        }
+        debug_assert!(product.x == operator.x && product.z == operator.z);
+        Ok(if product.sign { -1.0 } else { 1.0 })
+    }
+
+    /// Проверяет, находится ли кубит в чистом состоянии (не запутан с остальными):
+    /// это так, если X, Y или Z на этом кубите коммутирует со всеми стабилизаторами.
+    fn is_qubit_separable(&self, qubit: usize) -> bool {
+        let words = self.rows[0].x.len();
+        [Pauli::X, Pauli::Y, Pauli::Z].iter().any(|&pauli| {
+            let operator = PauliRow::single(words, qubit, pauli);
+            !self.rows[self.num_qubits..].iter().any(|row| row.anticommutes(&operator))
+        })
+    }
+
+    /// Строит вектор состояния (с точностью до глобальной фазы).
+    /// Возвращает ошибку, если кубитов больше, чем `state_vector::MAX_QUBITS`.
+    pub fn to_state_vector(&self) -> Result<StateVector, TriadError> {
+        let n = self.num_qubits;
+        if n > state_vector::MAX_QUBITS {
+            return Err(TriadError::TooManyQubits { requested: n, max: state_vector::MAX_QUBITS });
+        }
+
+        // Базисное состояние с ненулевой амплитудой: измеряем все кубиты, выбирая 0 где можно
+        let mut copy = self.clone();
+        let basis = (0..n).fold(0usize, |acc, q| acc | ((copy.measure_with(q, 0.0) as usize) << q));
+
+        // |ψ⟩ ∝ Π (I + g_i)/2 |basis⟩
+        let mut amplitudes = vec![Complex64::new(0.0, 0.0); 1 << n];
+        amplitudes[basis] = Complex64::new(1.0, 0.0);
+        for row in &self.rows[n..] {
+            let (x_mask, z_mask) = (row.x[0] as usize, row.z[0] as usize);
+            let y_phase = Complex64::new(0.0, 1.0).powu((x_mask & z_mask).count_ones());
+            let sign = if row.sign { -1.0 } else { 1.0 };
+
+            let mut next = amplitudes.clone();
+            for (i, &amplitude) in amplitudes.iter().enumerate() {
+                if amplitude.norm_sqr() == 0.0 {
+                    continue;
+                }
+                let parity = if (i & z_mask).count_ones() % 2 == 1 { -1.0 } else { 1.0 };
+                next[i ^ x_mask] += amplitude * y_phase * sign * parity;
+            }
+            amplitudes = next;
+        }
+
+        let mut state = StateVector::from_amplitudes(amplitudes);
+        state.normalize();
+        Ok(state)
+    }
+}
+
+impl MeasureWith for StabilizerState {
+    fn measure_with(&mut self, qubit: usize, random: f64) -> bool {
+        StabilizerState::measure_with(self, qubit, random)
+    }
+}
+
+impl QuantumState for StabilizerState {
+    fn num_qubits(&self) -> usize {
+        self.num_qubits
+    }
+
+    /// Кубиты с номерами от 64 и выше считаются равными нулю.
+    fn probability(&self, state: u64) -> f64 {
+        if self.num_qubits < 64 && state >> self.num_qubits != 0 {
+            return 0.0;
+        }
+        let qubits: Vec<usize> = (0..self.num_qubits).collect();
+        let bits: Vec<bool> = qubits.iter().map(|&q| q < 64 && (state >> q) & 1 == 1).collect();
+        self.clone().project(&qubits, &bits)
+    }
+
+    /// Амплитуда с точностью до глобальной фазы. Требует построения вектора
+    /// состояния, поэтому паникует для числа кубитов больше `state_vector::MAX_QUBITS`.
+    fn amplitude(&self, state: u64) -> Amplitude {
+        let vector = self.to_state_vector().unwrap_or_else(|err| panic!("{}", err));
+        vector.amplitude(state)
+    }
+
+    /// Применяет оператор, заданный полной матрицей 2^n x 2^n по строкам.
+    /// Поддерживаются только операторы Клиффорда на одном или двух кубитах.
+    fn apply_operator(&mut self, operator: &[Amplitude]) {
+        let targets: Vec<usize> = (0..self.num_qubits).rev().collect();
+        if targets.len() > 2 {
+            panic!("{}", TriadError::NonClifford(format!("оператор на {} кубитах", targets.len())));
+        }
+        self.apply_clifford("оператор", &targets, operator)
+            .unwrap_or_else(|err| panic!("{}", err));
+    }
+
+    fn measure(&mut self, qubit: usize) -> bool {
+        let random = rand::thread_rng().gen::<f64>();
+        self.measure_with(qubit, random)
+    }
+
+    fn is_entangled(&self) -> bool {
+        (0..self.num_qubits).any(|q| !self.is_qubit_separable(q))
+    }
+}
+
+/// Генератор группы Клиффорда, действующий на локальные индексы целевых кубитов.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CliffordOp {
+    /// Адамар на кубите `j`
+    H(usize),
+    /// Фазовый гейт S на кубите `j`
+    S(usize),
+    /// CNOT (контрольный, целевой)
+    Cnot(usize, usize),
+}
+
+/// Ключ матрицы с точностью до глобальной фазы.
+type MatrixKey = Vec<(i64, i64)>;
+
+/// Разложения всех одно- и двухкубитовых гейтов Клиффорда (24 и 11520 элементов
+/// с точностью до фазы) на генераторы H, S и CNOT.
+static CLIFFORD_TABLES: Lazy<[HashMap<MatrixKey, Vec<CliffordOp>>; 2]> = Lazy::new(|| [
+    clifford_table(1),
+    clifford_table(2),
+]);
+
+/// Находит разложение матрицы гейта Клиффорда на `k` кубитах.
+fn decompose_clifford(k: usize, matrix: &[Amplitude]) -> Option<Vec<CliffordOp>> {
+    if !(1..=2).contains(&k) || matrix.len() != 1 << (2 * k) {
+        return None;
+    }
+    CLIFFORD_TABLES[k - 1].get(&matrix_key(matrix)).cloned()
+}
+
+/// Проверяет, является ли матрица одно- или двухкубитового гейта (по строкам)
+/// элементом группы Клиффорда с точностью до глобальной фазы.
+pub fn is_clifford(matrix: &[Amplitude]) -> bool {
+    let k = match matrix.len() {
+        4 => 1,
+        16 => 2,
+        _ => return false,
+    };
+    decompose_clifford(k, matrix).is_some()
+}
+
+/// Перечисляет группу Клиффорда на `k` кубитах обходом в ширину.
+fn clifford_table(k: usize) -> HashMap<MatrixKey, Vec<CliffordOp>> {
+    let mut generators: Vec<CliffordOp> = (0..k).flat_map(|j| [CliffordOp::H(j), CliffordOp::S(j)]).collect();
+    if k == 2 {
+        generators.extend([CliffordOp::Cnot(0, 1), CliffordOp::Cnot(1, 0)]);
+    }
+    let generator_matrices: Vec<Vec<Amplitude>> = generators.iter().map(|&op| op_matrix(k, op)).collect();
+
+    let dim = 1usize << k;
+    let identity: Vec<Amplitude> = (0..dim * dim)
+        .map(|i| if i / dim == i % dim { Complex64::new(1.0, 0.0) } else { Complex64::new(0.0, 0.0) })
+        .collect();
+
+    let mut table = HashMap::new();
+    table.insert(matrix_key(&identity), Vec::new());
+    let mut queue = VecDeque::from([(identity, Vec::new())]);
+
+    while let Some((matrix, word)) = queue.pop_front() {
+        for (op, generator) in generators.iter().zip(&generator_matrices) {
+            let next = multiply(generator, &matrix, dim);
+            if let Entry::Vacant(entry) = table.entry(matrix_key(&next)) {
+                let mut next_word = word.clone();
+                next_word.push(*op);
+                entry.insert(next_word.clone());
+                queue.push_back((next, next_word));
+            }
+        }
+    }
+    table
+}
+
+/// Возвращает матрицу генератора на `k` кубитах (локальный кубит `j` — бит `k-1-j`).
+fn op_matrix(k: usize, op: CliffordOp) -> Vec<Amplitude> {
+    let dim = 1usize << k;
+    let bit = |j: usize| k - 1 - j;
+    let zero = Complex64::new(0.0, 0.0);
+    let factor = 1.0 / 2.0_f64.sqrt();
+
+    let mut matrix = vec![zero; dim * dim];
+    for row in 0..dim {
+        for col in 0..dim {
+            matrix[row * dim + col] = match op {
+                CliffordOp::H(j) | CliffordOp::S(j) => {
+                    let mask = 1 << bit(j);
+                    if row & !mask != col & !mask {
+                        continue;
+                    }
+                    let (r, c) = ((row & mask != 0) as usize, (col & mask != 0) as usize);
+                    match op {
+                        CliffordOp::H(_) => Complex64::new(if r == 1 && c == 1 { -factor } else { factor }, 0.0),
+                        _ if r != c => zero,
+                        _ if r == 1 => Complex64::new(0.0, 1.0),
+                        _ => Complex64::new(1.0, 0.0),
+                    }
+                },
+                CliffordOp::Cnot(control, target) => {
+                    let image = if col & (1 << bit(control)) != 0 { col ^ (1 << bit(target)) } else { col };
+                    if row == image { Complex64::new(1.0, 0.0) } else { zero }
+                },
+            };
+        }
+    }
+    matrix
+}
+
+/// Перемножает квадратные матрицы размера `dim` (по строкам).
+fn multiply(a: &[Amplitude], b: &[Amplitude], dim: usize) -> Vec<Amplitude> {
+    (0..dim * dim)
+        .map(|i| (0..dim).map(|k| a[(i / dim) * dim + k] * b[k * dim + i % dim]).sum())
+        .collect()
+}
+
+/// Нормирует фазу матрицы (первый ненулевой элемент становится вещественным
+/// положительным) и округляет элементы, чтобы сравнивать матрицы по ключу.
+fn matrix_key(matrix: &[Amplitude]) -> MatrixKey {
+    let phase = matrix.iter()
+        .find(|m| m.norm() > 1e-6)
+        .map(|m| m.conj() / m.norm())
+        .unwrap_or(Complex64::new(1.0, 0.0));
+
+    matrix.iter()
+        .map(|m| {
+            let value = m * phase;
+            ((value.re * 1e6).round() as i64, (value.im * 1e6).round() as i64)
+        })
+        .collect()
+}
+
+/// Стабилизаторный симулятор для схем из гейтов Клиффорда.
+/// Поддерживает до `MAX_QUBITS` кубитов.
+#[derive(Debug, Clone)]
+pub struct StabilizerSimulator {
+    /// Текущее состояние регистра.
+    state: StabilizerState,
+    /// Генератор случайных чисел для измерений и выборок.
+    rng: StdRng,
+}
+
+impl StabilizerSimulator {
+    /// Создает новый симулятор с указанным количеством кубитов в состоянии |0...0⟩.
+    pub fn new(num_qubits: usize) -> Self {
+        Self {
+            state: StabilizerState::new(num_qubits),
+            rng: StdRng::from_entropy(),
+        }
+    }
+
+    /// Создает новый симулятор, возвращая ошибку для недопустимого числа кубитов.
+    pub fn try_new(num_qubits: usize) -> Result<Self, TriadError> {
+        Ok(Self {
+            state: StabilizerState::try_new(num_qubits)?,
+            rng: StdRng::from_entropy(),
+        })
+    }
+
+    /// Возвращает количество кубитов в регистре.
+    pub fn num_qubits(&self) -> usize {
+        self.state.num_qubits
+    }
+
+    /// Возвращает ссылку на текущее стабилизаторное состояние.
+    pub fn stabilizer_state(&self) -> &StabilizerState {
+        &self.state
+    }
+
+    /// Применяет однокубитовый гейт, заданный матрицей, паникуя для гейтов вне группы Клиффорда.
+    fn apply_single(&mut self, name: &str, qubit: usize, matrix: &[Amplitude]) {
+        self.state.apply_clifford(name, &[qubit], matrix)
+            .unwrap_or_else(|err| panic!("{}", err));
+    }
+
+    /// Применяет гейт вращения, если его угол кратен π/2.
+    fn rotate(&mut self, qubit: usize, axis: Axis, angle: f64) {
+        let gate = RotationGate { axis, angle };
+        self.apply_single(gate.name(), qubit, &gate.matrix());
+    }
+}
+
+impl QuantumSimulator for StabilizerSimulator {
+    fn new(num_qubits: usize) -> Self where Self: Sized {
+        Self::new(num_qubits)
+    }
+
+    fn try_new(num_qubits: usize) -> Result<Self, TriadError> where Self: Sized {
+        Self::try_new(num_qubits)
+    }
+
+    fn num_qubits(&self) -> usize {
+        self.state.num_qubits
+    }
+
+    fn get_state(&self) -> Box<dyn QuantumState> {
+        Box::new(StateSnapshot::new(self.state.clone(), &self.rng))
+    }
+
+    fn reset(&mut self) {
+        self.state = StabilizerState::new(self.state.num_qubits);
+    }
+
+    fn hadamard(&mut self, qubit: usize) {
+        self.state.hadamard(qubit);
+    }
+
+    fn x(&mut self, qubit: usize) {
+        self.state.pauli(qubit, Pauli::X);
+    }
+
+    fn y(&mut self, qubit: usize) {
+        self.state.pauli(qubit, Pauli::Y);
+    }
+
+    fn z(&mut self, qubit: usize) {
+        self.state.pauli(qubit, Pauli::Z);
+    }
+
+    fn cnot(&mut self, control: usize, target: usize) {
+        self.state.cnot(control, target);
+    }
+
+    fn measure(&mut self, qubit: usize) -> bool {
+        let random = self.rng.gen::<f64>();
+        self.state.measure_with(qubit, random)
+    }
+
+    fn rng(&mut self) -> Option<&mut StdRng> {
+        Some(&mut self.rng)
+    }
+
+    /// Паникует с `TriadError::NonClifford`, если гейт не принадлежит группе Клиффорда.
+    fn apply_gate(&mut self, gate: &dyn Gate, targets: &[usize]) {
+        self.try_apply_gate(gate, targets).unwrap_or_else(|err| panic!("{}", err));
+    }
+
+    fn get_expectation_value(&self, pauli_product: &[(usize, char)]) -> f64 {
+        self.state.expectation_pauli(pauli_product)
+    }
+
+    fn probability_of_outcome(&self, qubit: usize, outcome: bool) -> f64 {
+        self.state.probability_of_outcome(qubit, outcome)
+    }
+
+    fn probability_of_joint_outcome(&self, qubits: &[usize], outcome: u64) -> f64 {
+        self.state.probability_of_joint_outcome(qubits, outcome)
+    }
+
+    fn try_apply_gate(&mut self, gate: &dyn Gate, targets: &[usize]) -> Result<(), TriadError> {
+        validate_gate_targets(gate, targets, self.state.num_qubits)?;
+        self.state.apply_clifford(gate.name(), targets, &gate.matrix())
+    }
+}
+
+impl AdvancedQuantumSimulator for StabilizerSimulator {
+    fn s_gate(&mut self, qubit: usize) {
+        self.state.phase(qubit);
+    }
+
+    /// T не является гейтом Клиффорда: всегда паникует с `TriadError::NonClifford`.
+    fn t_gate(&mut self, qubit: usize) {
+        self.state.check_qubit(qubit);
+        panic!("{}", TriadError::NonClifford("T".to_string()));
+    }
+
+    fn cz(&mut self, control: usize, target: usize) {
+        self.state.cz(control, target);
+    }
+
+    fn swap(&mut self, qubit1: usize, qubit2: usize) {
+        self.state.swap(qubit1, qubit2);
+    }
+
+    fn apply_unitary(&mut self, qubit: usize, matrix: &[Amplitude]) {
+        self.apply_single("unitary", qubit, matrix);
+    }
+
+    fn rx(&mut self, qubit: usize, angle: f64) {
+        self.rotate(qubit, Axis::X, angle);
+    }
+
+    fn ry(&mut self, qubit: usize, angle: f64) {
+        self.rotate(qubit, Axis::Y, angle);
+    }
+
+    fn rz(&mut self, qubit: usize, angle: f64) {
+        self.rotate(qubit, Axis::Z, angle);
+    }
+
+    fn controlled_unitary(&mut self, control: usize, target: usize, matrix: &[Amplitude]) {
+        let zero = Complex64::new(0.0, 0.0);
+        let one = Complex64::new(1.0, 0.0);
+        let full = [
+            one, zero, zero, zero,
+            zero, one, zero, zero,
+            zero, zero, matrix[0], matrix[1],
+            zero, zero, matrix[2], matrix[3],
+        ];
+        self.state.apply_clifford("controlled_unitary", &[control, target], &full)
+            .unwrap_or_else(|err| panic!("{}", err));
+    }
+
+    /// Паникует с `TriadError::NonClifford` для двух и более управляющих кубитов.
+    fn multi_controlled_unitary(&mut self, controls: &[usize], target: usize, matrix: &[Amplitude]) {
+        self.try_multi_controlled_unitary(controls, target, matrix).unwrap_or_else(|err| panic!("{}", err));
+    }
+
+    /// Гейт Фредкина не является гейтом Клиффорда: всегда паникует с `TriadError::NonClifford`.
+    fn fredkin(&mut self, control: usize, target1: usize, target2: usize) {
+        self.try_fredkin(control, target1, target2).unwrap_or_else(|err| panic!("{}", err));
+    }
+
+    fn try_multi_controlled_unitary(&mut self, controls: &[usize], target: usize, matrix: &[Amplitude]) -> Result<(), TriadError> {
+        validate_controlled(controls, target, matrix, self.state.num_qubits)?;
+        match *controls {
+            [] => self.state.apply_clifford("unitary", &[target], matrix),
+            [control] => self.state.apply_clifford("controlled_unitary", &[control, target], &controlled_matrix(1, matrix)),
+            _ => Err(TriadError::NonClifford(format!("{}-контрольный оператор", controls.len()))),
+        }
+    }
+
+    fn try_fredkin(&mut self, control: usize, target1: usize, target2: usize) -> Result<(), TriadError> {
+        validate_qubits(&[control, target1, target2], self.state.num_qubits)?;
+        Err(TriadError::NonClifford("Fredkin".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::circuit::{Circuit, CircuitGate};
+    use crate::core::gates::{BasicGate, CustomGate};
+    use crate::core::state_vector::StateVectorSimulator;
+    use std::f64::consts::PI;
+
+    #[test]
+    fn test_ghz_state_on_thousands_of_qubits() {
+        let n = 2000;
+        let mut simulator = StabilizerSimulator::new(n);
+        simulator.hadamard(0);
+        for q in 1..n {
+            simulator.cnot(q - 1, q);
+        }
+
+        assert_eq!(simulator.probability_of_outcome(n - 1, true), 0.5);
+        assert_eq!(simulator.get_expectation_value(&[(0, 'Z'), (n - 1, 'Z')]), 1.0);
+        assert!(simulator.get_state().is_entangled());
+
+        let first = simulator.measure(n / 2);
+        for q in (0..n).step_by(97) {
+            assert_eq!(simulator.measure(q), first);
+        }
+    }
+
+    #[test]
+    fn test_clifford_circuit_matches_state_vector() {
+        let mut circuit = Circuit::new(4, 0);
+        circuit.h(0).cnot(0, 1).s(1).h(2).cz(2, 3).y(3).swap(1, 3).rx(2, PI / 2.0)
+            .gate(CircuitGate::Basic(BasicGate::S).inverse(), &[0])
+            .cnot(3, 0).z(2).h(1).ry(0, -PI / 2.0).x(1);
+
+        let mut pure = StateVectorSimulator::new(4);
+        let mut stabilizer = StabilizerSimulator::new(4);
+        circuit.run_on(&mut pure).unwrap();
+        circuit.run_on(&mut stabilizer).unwrap();
+
+        let state = stabilizer.stabilizer_state().to_state_vector().unwrap();
+        assert!((state.inner_product(pure.state_vector()).norm() - 1.0).abs() < 1e-10);
+
+        for product in [vec![(0, 'Z')], vec![(0, 'X'), (2, 'Y')], vec![(1, 'Y'), (3, 'Z')], vec![(0, 'Z'), (1, 'Z'), (3, 'X')]] {
+            assert!((stabilizer.get_expectation_value(&product) - pure.get_expectation_value(&product)).abs() < 1e-10);
+        }
+        for outcome in 0..8 {
+            let expected = pure.probability_of_joint_outcome(&[3, 0, 1], outcome);
+            assert!((stabilizer.probability_of_joint_outcome(&[3, 0, 1], outcome) - expected).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_arbitrary_clifford_matrices_are_recognized() {
+        assert_eq!(CLIFFORD_TABLES[0].len(), 24);
+        assert_eq!(CLIFFORD_TABLES[1].len(), 11520);
+
+        // iSWAP — гейт Клиффорда, не входящий в набор собственных гейтов
+        let zero = Complex64::new(0.0, 0.0);
+        let one = Complex64::new(1.0, 0.0);
+        let i = Complex64::new(0.0, 1.0);
+        let iswap = CustomGate {
+            matrix: vec![one, zero, zero, zero, zero, zero, i, zero, zero, i, zero, zero, zero, zero, zero, one],
+            num_qubits: 2,
+            name: "iSWAP".to_string(),
+        };
+
+        let mut pure = StateVectorSimulator::new(2);
+        let mut stabilizer = StabilizerSimulator::new(2);
+        for simulator in [&mut pure as &mut dyn QuantumSimulator, &mut stabilizer] {
+            simulator.hadamard(1);
+            simulator.try_apply_gate(&iswap, &[1, 0]).unwrap();
+            simulator.try_apply_gate(&RotationGate { axis: Axis::Z, angle: -PI / 2.0 }, &[0]).unwrap();
+        }
+
+        let state = stabilizer.stabilizer_state().to_state_vector().unwrap();
+        assert!((state.inner_product(pure.state_vector()).norm() - 1.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_non_clifford_gates_are_rejected() {
+        let mut simulator = StabilizerSimulator::new(2);
+        assert_eq!(simulator.try_apply_gate(&BasicGate::T, &[0]), Err(TriadError::NonClifford("T".to_string())));
+        assert_eq!(simulator.try_apply_gate(&RotationGate { axis: Axis::X, angle: 0.3 }, &[1]),
+            Err(TriadError::NonClifford("RX".to_string())));
+
+        let mut circuit = Circuit::new(2, 0);
+        circuit.h(0).t(0);
+        assert_eq!(circuit.run_on(&mut simulator), Err(TriadError::NonClifford("T".to_string())));
+
+        let result = std::panic::catch_unwind(|| StabilizerSimulator::new(1).t_gate(0));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_multi_controlled_gates() {
+        let mut simulator = StabilizerSimulator::new(3);
+        simulator.x(0);
+        simulator.mcx(&[0], 2);
+        simulator.mcz(&[], 2);
+        simulator.controlled_rx(0, 1, PI);
+        assert_eq!(simulator.get_expectation_value(&[(1, 'Z')]), -1.0);
+        assert_eq!(simulator.get_expectation_value(&[(2, 'Z')]), -1.0);
+
+        assert!(matches!(simulator.try_toffoli(0, 1, 2), Err(TriadError::NonClifford(_))));
+        assert_eq!(simulator.try_fredkin(0, 1, 2), Err(TriadError::NonClifford("Fredkin".to_string())));
+        assert_eq!(simulator.try_mcx(&[0, 3], 1), Err(TriadError::InvalidQubit { qubit: 3, num_qubits: 3 }));
+    }
+
+    #[test]
+    fn test_measurement_and_expectations() {
+        let mut simulator = StabilizerSimulator::new(3);
+        simulator.x(2);
+        assert_eq!(simulator.stabilizer_state().deterministic_outcome(2), Some(true));
+        assert!(simulator.measure(2));
+
+        simulator.hadamard(0);
+        simulator.cnot(0, 1);
+        assert_eq!(simulator.get_expectation_value(&[(0, 'X'), (1, 'X')]), 1.0);
+        assert_eq!(simulator.get_expectation_value(&[(0, 'Y'), (1, 'Y')]), -1.0);
+        assert_eq!(simulator.get_expectation_value(&[(0, 'Z')]), 0.0);
+        assert_eq!(simulator.probability_of_joint_outcome(&[0, 1, 2], 0b111), 0.5);
+        assert_eq!(simulator.probability_of_joint_outcome(&[0, 1], 0b01), 0.0);
+        assert_eq!(simulator.get_state().probability(0b100), 0.5);
+        assert_eq!(simulator.stabilizer_state().stabilizers(), vec!["+XXI", "+ZZI", "-IIZ"]);
+
+        let outcome = simulator.measure(1);
+        assert_eq!(simulator.stabilizer_state().deterministic_outcome(0), Some(outcome));
+    }
+
+    #[test]
+    fn test_entanglement_detection() {
+        let mut simulator = StabilizerSimulator::new(3);
+        simulator.hadamard(0);
+        simulator.x(1);
+        simulator.s_gate(0);
+        assert!(!simulator.get_state().is_entangled());
+
+        simulator.cnot(0, 2);
+        assert!(simulator.get_state().is_entangled());
+    }
+
+    #[test]
+    fn test_try_methods_return_errors() {
+        assert!(matches!(StabilizerSimulator::try_new(MAX_QUBITS + 1), Err(TriadError::TooManyQubits { .. })));
+        assert_eq!(StabilizerSimulator::try_new(0).err(), Some(TriadError::NoQubits));
+
+        let mut simulator = StabilizerSimulator::new(2);
+        assert_eq!(simulator.try_hadamard(2), Err(TriadError::InvalidQubit { qubit: 2, num_qubits: 2 }));
+        assert_eq!(simulator.try_cnot(1, 1), Err(TriadError::DuplicateQubit(1)));
+        assert!(StabilizerSimulator::new(40).stabilizer_state().to_state_vector().is_err());
+    }
+
+    #[test]
+    fn test_sample_uses_conditional_probabilities() {
+        let n = 200;
+        let mut simulator = StabilizerSimulator::new(n);
+        simulator.hadamard(0);
+        for q in 1..n {
+            simulator.cnot(q - 1, q);
+        }
+        simulator.hadamard(n - 1);
+
+        let counts = simulator.sample(500, &[0, 100, 199]);
+        let ghz = counts.marginal(&[0, 1]);
+        assert_eq!(ghz.get(0b01) + ghz.get(0b10), 0);
+        assert!((counts.marginal(&[2]).frequency(1) - 0.5).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_sample_rejects_more_than_64_qubits() {
+        let mut simulator = StabilizerSimulator::new(100);
+        let qubits: Vec<usize> = (0..70).collect();
+        assert!(matches!(simulator.try_sample(10, &qubits), Err(TriadError::InvalidArgument(_))));
+        assert_eq!(simulator.try_sample(10, &qubits[..64]).unwrap().num_bits(), 64);
+    }
+
+    #[test]
+    fn test_pauli_measurement_and_unsupported_povm() {
+        use crate::core::measurement::Povm;
+
+        let mut simulator = StabilizerSimulator::new(3);
+        simulator.hadamard(0);
+        simulator.cnot(0, 1);
+        simulator.cnot(1, 2);
+        assert!(!simulator.measure_pauli(&[(0, 'X'), (1, 'X'), (2, 'X')]));
+        assert_eq!(simulator.measure_in_basis(2, Pauli::Z), simulator.measure(0));
+
+        let one = Complex64::new(1.0, 0.0);
+        let zero = Complex64::new(0.0, 0.0);
+        let povm = Povm::from_operators("z", vec![vec![one, zero, zero, zero], vec![zero, zero, zero, one]]).unwrap();
+        assert!(matches!(simulator.try_measure_povm(&povm, &[0]), Err(TriadError::Unsupported(_))));
+    }
+}
@@ -0,0 +1,966 @@
+//! Нативный симулятор вектора состояния на чистом Rust.
+//!
+//! Этот модуль содержит плотное представление квантового состояния
+//! в виде вектора амплитуд и симулятор, реализующий интерфейсы
+//! `QuantumSimulator` и `AdvancedQuantumSimulator` без зависимости от QuEST.
+//!
+//! Кубит с индексом `k` соответствует биту `k` индекса базисного состояния.
+
+use crate::core::gates::{Axis, Gate, RotationGate};
+use crate::core::pauli::{Pauli, parse_pauli_product};
+use crate::core::quantum_simulator::{
+    QuantumSimulator, AdvancedQuantumSimulator, validate_gate_targets, validate_joint_outcome, validate_qubit,
+    validate_qubits,
+};
+use crate::core::quantum_state::{QuantumState, Amplitude, MeasureWith, StateSnapshot};
+use crate::core::counts::{Counts, extract_bits, sample_distribution};
+use crate::core::measurement::{Povm, choose_outcome, outcome_probabilities, validate_povm_targets};
+use crate::error::TriadError;
+use num_complex::Complex64;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// Максимальное число кубитов для плотного вектора состояния
+/// (2^30 амплитуд занимают 16 ГБ памяти).
+pub const MAX_QUBITS: usize = 30;
+
+/// Допуск, используемый при сравнении вероятностей с нулем и единицей.
+const EPSILON: f64 = 1e-10;
+
+/// Плотное квантовое состояние: вектор из 2^n комплексных амплитуд.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StateVector {
+    /// Число кубитов в системе.
+    num_qubits: usize,
+    /// Амплитуды базисных состояний.
+    amplitudes: Vec<Amplitude>,
+}
+
+impl StateVector {
+    /// Создает состояние |0...0⟩ из указанного числа кубитов.
+    pub fn new(num_qubits: usize) -> Self {
+        Self::try_new(num_qubits).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Создает состояние |0...0⟩, возвращая ошибку для недопустимого числа кубитов.
+    pub fn try_new(num_qubits: usize) -> Result<Self, TriadError> {
+        if num_qubits == 0 {
+            return Err(TriadError::NoQubits);
+        }
+        if num_qubits > MAX_QUBITS {
+            return Err(TriadError::TooManyQubits { requested: num_qubits, max: MAX_QUBITS });
+        }
+
+        let mut amplitudes = vec![Complex64::new(0.0, 0.0); 1 << num_qubits];
+        amplitudes[0] = Complex64::new(1.0, 0.0);
+
+        Ok(Self { num_qubits, amplitudes })
+    }
+
+    /// Создает состояние из готового вектора амплитуд.
+    /// Длина вектора должна быть степенью двойки.
+    pub fn from_amplitudes(amplitudes: Vec<Amplitude>) -> Self {
+        let len = amplitudes.len();
+        if len < 2 || !len.is_power_of_two() {
+            panic!("Длина вектора амплитуд должна быть степенью двойки (получено {})", len);
+        }
+
+        Self {
+            num_qubits: len.trailing_zeros() as usize,
+            amplitudes,
+        }
+    }
+
+    /// Возвращает срез амплитуд всех базисных состояний.
+    pub fn amplitudes(&self) -> &[Amplitude] {
+        &self.amplitudes
+    }
+
+    /// Возвращает вектор вероятностей для всех базисных состояний.
+    pub fn probabilities(&self) -> Vec<f64> {
+        self.amplitudes.iter().map(|a| a.norm_sqr()).collect()
+    }
+
+    /// Возвращает квадрат нормы вектора состояния.
+    pub fn norm_sqr(&self) -> f64 {
+        self.amplitudes.iter().map(|a| a.norm_sqr()).sum()
+    }
+
+    /// Нормализует вектор состояния.
+    pub fn normalize(&mut self) {
+        let norm = self.norm_sqr().sqrt();
+        if norm > 0.0 {
+            for amp in self.amplitudes.iter_mut() {
+                *amp /= norm;
+            }
+        }
+    }
+
+    /// Возвращает скалярное произведение ⟨self|other⟩.
+    pub fn inner_product(&self, other: &StateVector) -> Amplitude {
+        assert_eq!(self.num_qubits, other.num_qubits, "Состояния должны иметь одинаковое число кубитов");
+
+        self.amplitudes.iter()
+            .zip(other.amplitudes.iter())
+            .map(|(a, b)| a.conj() * b)
+            .sum()
+    }
+
+    /// Проверяет, что индекс кубита находится в допустимых пределах.
+    fn check_qubit(&self, qubit: usize) {
+        validate_qubit(qubit, self.num_qubits).unwrap_or_else(|err| panic!("{}", err));
+    }
+
+    /// Применяет однокубитовый оператор (матрица 2x2 по строкам) к указанному кубиту.
+    pub fn apply_single_qubit(&mut self, qubit: usize, matrix: &[Amplitude]) {
+        self.apply_controlled(&[], qubit, matrix);
+    }
+
+    /// Применяет однокубитовый оператор к целевому кубиту при условии,
+    /// что все контрольные кубиты находятся в состоянии |1⟩.
+    pub fn apply_controlled(&mut self, controls: &[usize], target: usize, matrix: &[Amplitude]) {
+        assert_eq!(matrix.len(), 4, "Матрица однокубитового оператора должна быть размера 2x2");
+        self.check_qubit(target);
+
+        let mut control_mask = 0usize;
+        for &control in controls {
+            self.check_qubit(control);
+            if control == target {
+                panic!("Контрольный и целевой кубиты должны различаться: {}", control);
+            }
+            control_mask |= 1 << control;
+        }
+
+        let target_mask = 1usize << target;
+
+        for i in 0..self.amplitudes.len() {
+            // Обрабатываем каждую пару (|..0..⟩, |..1..⟩) один раз
+            if i & target_mask != 0 || i & control_mask != control_mask {
+                continue;
+            }
+
+            let j = i | target_mask;
+            let a0 = self.amplitudes[i];
+            let a1 = self.amplitudes[j];
+
+            self.amplitudes[i] = matrix[0] * a0 + matrix[1] * a1;
+            self.amplitudes[j] = matrix[2] * a0 + matrix[3] * a1;
+        }
+    }
+
+    /// Применяет k-кубитовый оператор (матрица 2^k x 2^k по строкам) к указанным кубитам.
+    ///
+    /// Первый кубит в `targets` соответствует старшему биту индекса строки матрицы,
+    /// что совпадает с порядком, принятым в `core::gates` (например, для CNOT
+    /// первый кубит является контрольным).
+    pub fn apply_multi_qubit(&mut self, targets: &[usize], matrix: &[Amplitude]) {
+        let k = targets.len();
+        let dim = 1usize << k;
+        assert_eq!(matrix.len(), dim * dim, "Размер матрицы не соответствует числу целевых кубитов");
+
+        for (idx, &target) in targets.iter().enumerate() {
+            self.check_qubit(target);
+            if targets[..idx].contains(&target) {
+                panic!("Целевые кубиты должны быть различными: {}", target);
+            }
+        }
+
+        let offsets = subspace_offsets(targets);
+        let targets_mask = targets.iter().fold(0usize, |acc, &q| acc | (1 << q));
+        let mut buffer = vec![Complex64::new(0.0, 0.0); dim];
+
+        for base in 0..self.amplitudes.len() {
+            if base & targets_mask != 0 {
+                continue;
+            }
+
+            for (row, &offset) in offsets.iter().enumerate() {
+                buffer[row] = self.amplitudes[base | offset];
+            }
+
+            for (row, &offset) in offsets.iter().enumerate() {
+                let matrix_row = &matrix[row * dim..(row + 1) * dim];
+                self.amplitudes[base | offset] = matrix_row.iter()
+                    .zip(buffer.iter())
+                    .map(|(m, a)| m * a)
+                    .sum();
+            }
+        }
+    }
+
+    /// Меняет местами состояния двух кубитов перестановкой амплитуд.
+    pub fn swap_qubits(&mut self, qubit1: usize, qubit2: usize) {
+        self.check_qubit(qubit1);
+        self.check_qubit(qubit2);
+        if qubit1 == qubit2 {
+            return;
+        }
+
+        let mask1 = 1usize << qubit1;
+        let mask2 = 1usize << qubit2;
+
+        for i in 0..self.amplitudes.len() {
+            // Переставляем |..1..0..⟩ и |..0..1..⟩ один раз для каждой пары
+            if i & mask1 != 0 && i & mask2 == 0 {
+                self.amplitudes.swap(i, i ^ mask1 ^ mask2);
+            }
+        }
+    }
+
+    /// Вычисляет вероятность получить `outcome` при измерении кубита.
+    pub fn probability_of_outcome(&self, qubit: usize, outcome: bool) -> f64 {
+        self.check_qubit(qubit);
+        let mask = 1usize << qubit;
+
+        self.amplitudes.iter()
+            .enumerate()
+            .filter(|(i, _)| (i & mask != 0) == outcome)
+            .map(|(_, a)| a.norm_sqr())
+            .sum()
+    }
+
+    /// Вычисляет совместную вероятность получить битовую строку `outcome`
+    /// при измерении кубитов `qubits` (бит `i` соответствует `qubits[i]`).
+    pub fn probability_of_joint_outcome(&self, qubits: &[usize], outcome: u64) -> f64 {
+        validate_joint_outcome(qubits, outcome, self.num_qubits)
+            .unwrap_or_else(|err| panic!("{}", err));
+
+        let (mask, expected) = qubits.iter().enumerate().fold((0usize, 0usize), |(mask, expected), (idx, &q)| {
+            let bit = ((outcome >> idx) & 1) as usize;
+            (mask | (1 << q), expected | (bit << q))
+        });
+
+        self.amplitudes.iter()
+            .enumerate()
+            .filter(|(i, _)| i & mask == expected)
+            .map(|(_, a)| a.norm_sqr())
+            .sum()
+    }
+
+    /// Вычисляет распределение результатов измерения кубитов `qubits`
+    /// за один проход по амплитудам (бит `i` результата соответствует `qubits[i]`).
+    /// Результаты с нулевой вероятностью не включаются.
+    pub fn marginal_distribution(&self, qubits: &[usize]) -> Vec<(u64, f64)> {
+        validate_qubits(qubits, self.num_qubits).unwrap_or_else(|err| panic!("{}", err));
+
+        let mut distribution = std::collections::BTreeMap::new();
+        for (index, amplitude) in self.amplitudes.iter().enumerate() {
+            let prob = amplitude.norm_sqr();
+            if prob > 0.0 {
+                *distribution.entry(extract_bits(index as u64, qubits)).or_insert(0.0) += prob;
+            }
+        }
+        distribution.into_iter().collect()
+    }
+
+    /// Проецирует состояние на указанный результат измерения кубита
+    /// и перенормирует его.
+    pub fn collapse(&mut self, qubit: usize, outcome: bool) {
+        let prob = self.probability_of_outcome(qubit, outcome);
+        if prob < EPSILON {
+            panic!("Невозможно спроецировать кубит {} на результат с нулевой вероятностью", qubit);
+        }
+
+        let mask = 1usize << qubit;
+        let scale = 1.0 / prob.sqrt();
+
+        for (i, amp) in self.amplitudes.iter_mut().enumerate() {
+            if (i & mask != 0) == outcome {
+                *amp *= scale;
+            } else {
+                *amp = Complex64::new(0.0, 0.0);
+            }
+        }
+    }
+
+    /// Измеряет кубит, используя заданное случайное число из [0, 1).
+    /// Результат равен 1, если `random` не меньше вероятности получить 0.
+    /// Исход с вероятностью меньше `EPSILON` заменяется противоположным.
+    pub fn measure_with(&mut self, qubit: usize, random: f64) -> bool {
+        let prob_zero = self.probability_of_outcome(qubit, false);
+        let mut outcome = random >= prob_zero;
+        if self.probability_of_outcome(qubit, outcome) < EPSILON {
+            outcome = !outcome;
+        }
+        self.collapse(qubit, outcome);
+        outcome
+    }
+
+    /// Выполняет обобщенное измерение над кубитами `targets`, выбирая исход
+    /// по случайному числу `random` из [0, 1), и возвращает его номер.
+    pub fn measure_povm_with(&mut self, povm: &Povm, targets: &[usize], random: f64) -> usize {
+        validate_povm_targets(povm, targets, self.num_qubits).unwrap_or_else(|err| panic!("{}", err));
+
+        let probabilities = outcome_probabilities(povm, &self.reduced_density_matrix(targets));
+        let outcome = choose_outcome(&probabilities, random);
+
+        self.apply_multi_qubit(targets, &povm.operators()[outcome]);
+        self.normalize();
+        outcome
+    }
+
+    /// Вычисляет редуцированную матрицу плотности кубитов `targets` (по строкам).
+    /// Первый кубит соответствует старшему биту индекса, как у матриц гейтов.
+    pub fn reduced_density_matrix(&self, targets: &[usize]) -> Vec<Amplitude> {
+        validate_qubits(targets, self.num_qubits).unwrap_or_else(|err| panic!("{}", err));
+
+        let offsets = subspace_offsets(targets);
+        let dim = offsets.len();
+        let targets_mask = targets.iter().fold(0usize, |acc, &q| acc | (1 << q));
+        let mut reduced = vec![Complex64::new(0.0, 0.0); dim * dim];
+
+        for base in (0..self.amplitudes.len()).filter(|base| base & targets_mask == 0) {
+            for (row, &row_offset) in offsets.iter().enumerate() {
+                let amplitude = self.amplitudes[base | row_offset];
+                for (col, &col_offset) in offsets.iter().enumerate() {
+                    reduced[row * dim + col] += amplitude * self.amplitudes[base | col_offset].conj();
+                }
+            }
+        }
+
+        reduced
+    }
+
+    /// Вычисляет ожидаемое значение произведения операторов Паули.
+    pub fn expectation_pauli(&self, pauli_product: &[(usize, char)]) -> f64 {
+        self.try_expectation_pauli(pauli_product)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Вычисляет ожидаемое значение произведения операторов Паули,
+    /// возвращая ошибку для некорректного произведения.
+    pub fn try_expectation_pauli(&self, pauli_product: &[(usize, char)]) -> Result<f64, TriadError> {
+        let parsed = parse_pauli_product(pauli_product, self.num_qubits)?;
+        let mut transformed = self.clone();
+
+        for (qubit, pauli) in parsed {
+            if pauli != Pauli::I {
+                transformed.apply_single_qubit(qubit, &pauli.matrix());
+            }
+        }
+
+        Ok(self.inner_product(&transformed).re)
+    }
+
+    /// Вычисляет чистоту редуцированной матрицы плотности одного кубита.
+    fn single_qubit_purity(&self, qubit: usize) -> f64 {
+        let mask = 1usize << qubit;
+        let mut rho00 = 0.0;
+        let mut rho11 = 0.0;
+        let mut rho01 = Complex64::new(0.0, 0.0);
+
+        for i in 0..self.amplitudes.len() {
+            if i & mask != 0 {
+                continue;
+            }
+            let a0 = self.amplitudes[i];
+            let a1 = self.amplitudes[i | mask];
+            rho00 += a0.norm_sqr();
+            rho11 += a1.norm_sqr();
+            rho01 += a0 * a1.conj();
+        }
+
+        rho00 * rho00 + rho11 * rho11 + 2.0 * rho01.norm_sqr()
+    }
+}
+
+/// Смещение в векторе состояния для каждого базисного индекса подпространства
+/// кубитов `targets`; первый кубит соответствует старшему биту.
+fn subspace_offsets(targets: &[usize]) -> Vec<usize> {
+    let k = targets.len();
+    (0..1usize << k)
+        .map(|row| {
+            targets.iter().enumerate().fold(0, |acc, (j, &q)| {
+                if (row >> (k - 1 - j)) & 1 == 1 { acc | (1 << q) } else { acc }
+            })
+        })
+        .collect()
+}
+
+impl MeasureWith for StateVector {
+    fn measure_with(&mut self, qubit: usize, random: f64) -> bool {
+        StateVector::measure_with(self, qubit, random)
+    }
+}
+
+impl QuantumState for StateVector {
+    fn num_qubits(&self) -> usize {
+        self.num_qubits
+    }
+
+    fn probability(&self, state: u64) -> f64 {
+        self.amplitude(state).norm_sqr()
+    }
+
+    fn amplitude(&self, state: u64) -> Amplitude {
+        self.amplitudes
+            .get(state as usize)
+            .copied()
+            .unwrap_or_else(|| Complex64::new(0.0, 0.0))
+    }
+
+    /// Применяет оператор, заданный полной матрицей 2^n x 2^n по строкам.
+    fn apply_operator(&mut self, operator: &[Amplitude]) {
+        let targets: Vec<usize> = (0..self.num_qubits).rev().collect();
+        self.apply_multi_qubit(&targets, operator);
+    }
+
+    fn measure(&mut self, qubit: usize) -> bool {
+        let random = rand::thread_rng().gen::<f64>();
+        self.measure_with(qubit, random)
+    }
+
+    /// Чистое состояние является тензорным произведением тогда и только тогда,
+    /// когда редуцированное состояние каждого кубита чистое.
+    fn is_entangled(&self) -> bool {
+        (0..self.num_qubits).any(|q| self.single_qubit_purity(q) < 1.0 - 1e-9)
+    }
+}
+
+/// Симулятор вектора состояния на чистом Rust.
+/// Работает без QuEST и поддерживает до `MAX_QUBITS` кубитов.
+#[derive(Debug, Clone)]
+pub struct StateVectorSimulator {
+    /// Текущее состояние регистра.
+    state: StateVector,
+    /// Генератор случайных чисел для измерений и выборок.
+    rng: StdRng,
+}
+
+impl StateVectorSimulator {
+    /// Создает новый симулятор с указанным количеством кубитов в состоянии |0...0⟩.
+    pub fn new(num_qubits: usize) -> Self {
+        Self {
+            state: StateVector::new(num_qubits),
+            rng: StdRng::from_entropy(),
+        }
+    }
+
+    /// Создает новый симулятор, возвращая ошибку для недопустимого числа кубитов.
+    pub fn try_new(num_qubits: usize) -> Result<Self, TriadError> {
+        Ok(Self {
+            state: StateVector::try_new(num_qubits)?,
+            rng: StdRng::from_entropy(),
+        })
+    }
+
+    /// Возвращает количество кубитов в регистре.
+    pub fn num_qubits(&self) -> usize {
+        self.state.num_qubits
+    }
+
+    /// Возвращает ссылку на текущий вектор состояния.
+    pub fn state_vector(&self) -> &StateVector {
+        &self.state
+    }
+
+    /// Возвращает массив вероятностей для каждого из возможных состояний.
+    pub fn get_probabilities(&self) -> Vec<f64> {
+        self.state.probabilities()
+    }
+
+    /// Применяет гейт вращения вокруг указанной оси.
+    fn rotate(&mut self, qubit: usize, axis: Axis, angle: f64) {
+        let gate = RotationGate { axis, angle };
+        self.state.apply_single_qubit(qubit, &gate.matrix());
+    }
+}
+
+impl QuantumSimulator for StateVectorSimulator {
+    fn new(num_qubits: usize) -> Self where Self: Sized {
+        Self::new(num_qubits)
+    }
+
+    fn try_new(num_qubits: usize) -> Result<Self, TriadError> where Self: Sized {
+        Self::try_new(num_qubits)
+    }
+
+    fn num_qubits(&self) -> usize {
+        self.state.num_qubits
+    }
+
+    fn get_state(&self) -> Box<dyn QuantumState> {
+        Box::new(StateSnapshot::new(self.state.clone(), &self.rng))
+    }
+
+    fn reset(&mut self) {
+        self.state = StateVector::new(self.state.num_qubits);
+    }
+
+    fn hadamard(&mut self, qubit: usize) {
+        let factor = Complex64::new(1.0 / 2.0_f64.sqrt(), 0.0);
+        self.state.apply_single_qubit(qubit, &[factor, factor, factor, -factor]);
+    }
+
+    fn x(&mut self, qubit: usize) {
+        self.state.apply_single_qubit(qubit, &Pauli::X.matrix());
+    }
+
+    fn y(&mut self, qubit: usize) {
+        self.state.apply_single_qubit(qubit, &Pauli::Y.matrix());
+    }
+
+    fn z(&mut self, qubit: usize) {
+        self.state.apply_single_qubit(qubit, &Pauli::Z.matrix());
+    }
+
+    fn cnot(&mut self, control: usize, target: usize) {
+        self.state.apply_controlled(&[control], target, &Pauli::X.matrix());
+    }
+
+    fn measure(&mut self, qubit: usize) -> bool {
+        let random = self.rng.gen::<f64>();
+        self.state.measure_with(qubit, random)
+    }
+
+    fn rng(&mut self) -> Option<&mut StdRng> {
+        Some(&mut self.rng)
+    }
+
+    fn try_measure_povm(&mut self, povm: &Povm, targets: &[usize]) -> Result<usize, TriadError> {
+        validate_povm_targets(povm, targets, self.state.num_qubits)?;
+        let random = self.rng.gen::<f64>();
+        Ok(self.state.measure_povm_with(povm, targets, random))
+    }
+
+    fn apply_gate(&mut self, gate: &dyn Gate, targets: &[usize]) {
+        validate_gate_targets(gate, targets, self.state.num_qubits)
+            .unwrap_or_else(|err| panic!("{}", err));
+
+        match targets {
+            [qubit] => self.state.apply_single_qubit(*qubit, &gate.matrix()),
+            _ => self.state.apply_multi_qubit(targets, &gate.matrix()),
+        }
+    }
+
+    fn get_expectation_value(&self, pauli_product: &[(usize, char)]) -> f64 {
+        self.state.expectation_pauli(pauli_product)
+    }
+
+    fn probability_of_outcome(&self, qubit: usize, outcome: bool) -> f64 {
+        self.state.probability_of_outcome(qubit, outcome)
+    }
+
+    fn probability_of_joint_outcome(&self, qubits: &[usize], outcome: u64) -> f64 {
+        self.state.probability_of_joint_outcome(qubits, outcome)
+    }
+
+    fn sample(&mut self, shots: usize, qubits: &[usize]) -> Counts {
+        let distribution = self.state.marginal_distribution(qubits);
+        sample_distribution(&distribution, qubits.len(), shots, &mut self.rng)
+    }
+}
+
+impl AdvancedQuantumSimulator for StateVectorSimulator {
+    fn s_gate(&mut self, qubit: usize) {
+        let one = Complex64::new(1.0, 0.0);
+        let zero = Complex64::new(0.0, 0.0);
+        self.state.apply_single_qubit(qubit, &[one, zero, zero, Complex64::new(0.0, 1.0)]);
+    }
+
+    fn t_gate(&mut self, qubit: usize) {
+        let one = Complex64::new(1.0, 0.0);
+        let zero = Complex64::new(0.0, 0.0);
+        let phase = Complex64::from_polar(1.0, std::f64::consts::FRAC_PI_4);
+        self.state.apply_single_qubit(qubit, &[one, zero, zero, phase]);
+    }
+
+    fn cz(&mut self, control: usize, target: usize) {
+        self.state.apply_controlled(&[control], target, &Pauli::Z.matrix());
+    }
+
+    fn swap(&mut self, qubit1: usize, qubit2: usize) {
+        self.state.swap_qubits(qubit1, qubit2);
+    }
+
+    fn apply_unitary(&mut self, qubit: usize, matrix: &[Amplitude]) {
+        self.state.apply_single_qubit(qubit, matrix);
+    }
+
+    fn rx(&mut self, qubit: usize, angle: f64) {
+        self.rotate(qubit, Axis::X, angle);
+    }
+
+    fn ry(&mut self, qubit: usize, angle: f64) {
+        self.rotate(qubit, Axis::Y, angle);
+    }
+
+    fn rz(&mut self, qubit: usize, angle: f64) {
+        self.rotate(qubit, Axis::Z, angle);
+    }
+
+    fn controlled_unitary(&mut self, control: usize, target: usize, matrix: &[Amplitude]) {
+        self.state.apply_controlled(&[control], target, matrix);
+    }
+
+    fn multi_controlled_unitary(&mut self, controls: &[usize], target: usize, matrix: &[Amplitude]) {
+        self.state.apply_controlled(controls, target, matrix);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::gates::{BasicGate, CustomGate, TwoQubitGate};
+    use crate::core::quantum_state::utils::subsystem_probability;
+    use std::f64::consts::PI;
+
+    #[test]
+    fn test_initial_state() {
+        let simulator = StateVectorSimulator::new(3);
+        let state = simulator.get_state();
+
+        assert_eq!(state.num_qubits(), 3);
+        assert!((state.probability(0) - 1.0).abs() < EPSILON);
+        assert!(!state.is_entangled());
+    }
+
+    #[test]
+    fn test_pauli_x_flips_qubit() {
+        let mut simulator = StateVectorSimulator::new(2);
+        simulator.x(1);
+
+        let state = simulator.get_state();
+        assert!((state.probability(0b10) - 1.0).abs() < EPSILON);
+        assert!(simulator.measure(1));
+        assert!(!simulator.measure(0));
+    }
+
+    #[test]
+    fn test_hadamard_superposition() {
+        let mut simulator = StateVectorSimulator::new(1);
+        simulator.hadamard(0);
+
+        assert!((simulator.probability_of_outcome(0, false) - 0.5).abs() < EPSILON);
+        assert!((simulator.probability_of_outcome(0, true) - 0.5).abs() < EPSILON);
+
+        // H·H = I
+        simulator.hadamard(0);
+        assert!((simulator.probability_of_outcome(0, false) - 1.0).abs() < EPSILON);
+    }
+
+    #[test]
+    fn test_bell_state_correlations() {
+        for _ in 0..20 {
+            let mut simulator = StateVectorSimulator::new(2);
+            simulator.hadamard(0);
+            simulator.cnot(0, 1);
+
+            assert!(simulator.get_state().is_entangled());
+
+            let m0 = simulator.measure(0);
+            let m1 = simulator.measure(1);
+            assert_eq!(m0, m1, "Результаты измерения состояния Белла должны совпадать");
+            assert!(!simulator.get_state().is_entangled());
+        }
+    }
+
+    #[test]
+    fn test_expectation_values() {
+        let mut simulator = StateVectorSimulator::new(2);
+        assert!((simulator.get_expectation_value(&[(0, 'Z')]) - 1.0).abs() < EPSILON);
+        assert!((simulator.get_expectation_value(&[]) - 1.0).abs() < EPSILON);
+
+        simulator.hadamard(0);
+        simulator.cnot(0, 1);
+
+        assert!((simulator.get_expectation_value(&[(0, 'Z'), (1, 'Z')]) - 1.0).abs() < EPSILON);
+        assert!((simulator.get_expectation_value(&[(0, 'X'), (1, 'X')]) - 1.0).abs() < EPSILON);
+        assert!((simulator.get_expectation_value(&[(0, 'Y'), (1, 'Y')]) + 1.0).abs() < EPSILON);
+        assert!(simulator.get_expectation_value(&[(0, 'Z')]).abs() < EPSILON);
+    }
+
+    #[test]
+    fn test_joint_outcome_probability() {
+        let mut simulator = StateVectorSimulator::new(3);
+        simulator.hadamard(0);
+        simulator.cnot(0, 2);
+        simulator.x(1);
+
+        // Состояние (|010⟩ + |111⟩)/√2
+        assert!((simulator.probability_of_joint_outcome(&[0, 2], 0b11) - 0.5).abs() < EPSILON);
+        assert!((simulator.probability_of_joint_outcome(&[0, 2], 0b01)).abs() < EPSILON);
+        assert!((simulator.probability_of_joint_outcome(&[2, 1], 0b10) - 0.5).abs() < EPSILON);
+        assert!((simulator.probability_of_joint_outcome(&[1], 0b1) - 1.0).abs() < EPSILON);
+        assert!((simulator.probability_of_joint_outcome(&[], 0) - 1.0).abs() < EPSILON);
+
+        // Нативная реализация совпадает с реализацией по умолчанию через get_state
+        let state = simulator.get_state();
+        let expected = subsystem_probability(&*state, &[1, 0], 0b01);
+        assert!((simulator.probability_of_joint_outcome(&[1, 0], 0b01) - expected).abs() < EPSILON);
+    }
+
+    #[test]
+    #[should_panic(expected = "не помещается")]
+    fn test_joint_outcome_rejects_wide_outcome() {
+        let simulator = StateVectorSimulator::new(2);
+        simulator.probability_of_joint_outcome(&[0], 0b10);
+    }
+
+    #[test]
+    fn test_rotations_match_gates() {
+        let mut simulator = StateVectorSimulator::new(1);
+        simulator.rx(0, PI);
+        assert!((simulator.probability_of_outcome(0, true) - 1.0).abs() < EPSILON);
+
+        simulator.reset();
+        simulator.ry(0, PI / 2.0);
+        assert!((simulator.get_expectation_value(&[(0, 'X')]) - 1.0).abs() < EPSILON);
+
+        // Rz(π/2)|+⟩ = |+i⟩ (с точностью до глобальной фазы)
+        simulator.rz(0, PI / 2.0);
+        assert!((simulator.get_expectation_value(&[(0, 'Y')]) - 1.0).abs() < EPSILON);
+    }
+
+    #[test]
+    fn test_swap_and_two_qubit_gates() {
+        let mut simulator = StateVectorSimulator::new(3);
+        simulator.x(0);
+        simulator.swap(0, 2);
+        assert!((simulator.get_state().probability(0b100) - 1.0).abs() < EPSILON);
+
+        // Матричный SWAP из core::gates
+        simulator.reset();
+        simulator.x(1);
+        simulator.apply_gate(&TwoQubitGate::SWAP, &[0, 1]);
+        assert!((simulator.get_state().probability(0b001) - 1.0).abs() < EPSILON);
+
+        // Матричный CNOT: первый целевой кубит является контрольным
+        simulator.apply_gate(&TwoQubitGate::CNOT, &[0, 2]);
+        assert!((simulator.get_state().probability(0b101) - 1.0).abs() < EPSILON);
+        simulator.apply_gate(&TwoQubitGate::CNOT, &[1, 2]);
+        assert!((simulator.get_state().probability(0b101) - 1.0).abs() < EPSILON);
+    }
+
+    #[test]
+    fn test_multi_controlled_gates() {
+        let mut simulator = StateVectorSimulator::new(4);
+        simulator.x(0);
+        simulator.toffoli(0, 1, 3);
+        assert!((simulator.get_state().probability(0b0001) - 1.0).abs() < EPSILON);
+        simulator.x(1);
+        simulator.toffoli(0, 1, 3);
+        assert!((simulator.get_state().probability(0b1011) - 1.0).abs() < EPSILON);
+
+        // Фредкин обменивает целевые кубиты только при управляющем |1⟩
+        simulator.fredkin(3, 1, 2);
+        assert!((simulator.get_state().probability(0b1101) - 1.0).abs() < EPSILON);
+        simulator.fredkin(1, 0, 3);
+        assert!((simulator.get_state().probability(0b1101) - 1.0).abs() < EPSILON);
+
+        simulator.mcx(&[0, 2, 3], 1);
+        assert!((simulator.get_state().probability(0b1111) - 1.0).abs() < EPSILON);
+
+        // MCZ меняет фазу только у |1111⟩
+        simulator.reset();
+        for qubit in 0..4 {
+            simulator.hadamard(qubit);
+        }
+        simulator.mcz(&[0, 1, 2], 3);
+        let state = simulator.get_state();
+        assert!((state.amplitude(0b1111) - Complex64::new(-0.25, 0.0)).norm() < EPSILON);
+        assert!((state.amplitude(0b0111) - Complex64::new(0.25, 0.0)).norm() < EPSILON);
+    }
+
+    #[test]
+    fn test_controlled_rotations() {
+        let mut simulator = StateVectorSimulator::new(2);
+        simulator.controlled_rx(0, 1, PI);
+        assert!((simulator.get_state().probability(0) - 1.0).abs() < EPSILON);
+
+        simulator.x(0);
+        simulator.controlled_ry(0, 1, PI / 2.0);
+        assert!((simulator.probability_of_outcome(1, true) - 0.5).abs() < EPSILON);
+        simulator.controlled_ry(0, 1, -PI / 2.0);
+        simulator.controlled_rz(0, 1, PI);
+        assert!((simulator.get_state().amplitude(0b01) - Complex64::new(0.0, -1.0)).norm() < EPSILON);
+
+        assert_eq!(simulator.try_toffoli(0, 1, 1), Err(TriadError::DuplicateQubit(1)));
+        assert_eq!(simulator.try_fredkin(0, 1, 2), Err(TriadError::InvalidQubit { qubit: 2, num_qubits: 2 }));
+        let ones = vec![Complex64::new(1.0, 0.0); 4];
+        assert!(matches!(simulator.try_multi_controlled_unitary(&[0], 1, &ones), Err(TriadError::NonUnitary(_))));
+        assert!(matches!(simulator.try_multi_controlled_unitary(&[0], 1, &ones[..2]), Err(TriadError::InvalidArgument(_))));
+    }
+
+    #[test]
+    fn test_apply_generic_gates() {
+        let mut simulator = StateVectorSimulator::new(3);
+        simulator.apply_gate(&BasicGate::PauliX, &[2]);
+        simulator.apply_gate(&BasicGate::Hadamard, &[0]);
+        simulator.apply_rotation(&RotationGate { axis: Axis::X, angle: PI }, &[1]);
+
+        // Тоффоли как пользовательский трехкубитовый гейт: контроли 2 и 1, цель 0
+        let mut matrix = vec![Complex64::new(0.0, 0.0); 64];
+        for i in 0..6 {
+            matrix[i * 8 + i] = Complex64::new(1.0, 0.0);
+        }
+        matrix[6 * 8 + 7] = Complex64::new(1.0, 0.0);
+        matrix[7 * 8 + 6] = Complex64::new(1.0, 0.0);
+        let toffoli = CustomGate { matrix, num_qubits: 3, name: "Toffoli".to_string() };
+
+        simulator.hadamard(0);
+        simulator.apply_gate(&toffoli, &[2, 1, 0]);
+        assert!((simulator.get_state().probability(0b111) - 1.0).abs() < EPSILON);
+    }
+
+    #[test]
+    #[should_panic(expected = "целевых кубитов")]
+    fn test_apply_gate_rejects_wrong_arity() {
+        let mut simulator = StateVectorSimulator::new(2);
+        simulator.apply_gate(&TwoQubitGate::CZ, &[0]);
+    }
+
+    #[test]
+    fn test_phase_gates() {
+        let mut simulator = StateVectorSimulator::new(1);
+        simulator.hadamard(0);
+        simulator.t_gate(0);
+        simulator.t_gate(0);
+        simulator.s_gate(0);
+        simulator.s_gate(0);
+        simulator.s_gate(0);
+        simulator.hadamard(0);
+
+        // T·T·S·S·S = S·S·S·S = I
+        assert!((simulator.probability_of_outcome(0, false) - 1.0).abs() < EPSILON);
+    }
+
+    #[test]
+    fn test_try_methods_return_errors() {
+        assert_eq!(StateVectorSimulator::try_new(0).unwrap_err(), TriadError::NoQubits);
+        assert_eq!(
+            StateVectorSimulator::try_new(MAX_QUBITS + 1).unwrap_err(),
+            TriadError::TooManyQubits { requested: MAX_QUBITS + 1, max: MAX_QUBITS }
+        );
+
+        let mut simulator = StateVectorSimulator::try_new(2).unwrap();
+        assert_eq!(simulator.try_hadamard(5), Err(TriadError::InvalidQubit { qubit: 5, num_qubits: 2 }));
+        assert_eq!(simulator.try_cnot(1, 1), Err(TriadError::DuplicateQubit(1)));
+        assert_eq!(simulator.try_expectation_value(&[(0, 'W')]), Err(TriadError::InvalidPauli('W')));
+        assert!(matches!(simulator.try_apply_gate(&TwoQubitGate::CNOT, &[0]), Err(TriadError::InvalidArgument(_))));
+
+        let not_unitary = CustomGate {
+            matrix: vec![Complex64::new(1.0, 0.0); 4],
+            num_qubits: 1,
+            name: "Ones".to_string(),
+        };
+        assert!(matches!(simulator.try_apply_gate(&not_unitary, &[0]), Err(TriadError::NonUnitary(_))));
+
+        // Ошибки не изменяют состояние
+        assert!((simulator.get_state().probability(0) - 1.0).abs() < EPSILON);
+        assert_eq!(simulator.try_x(1), Ok(()));
+        assert_eq!(simulator.try_measure(1), Ok(true));
+    }
+
+    #[test]
+    #[should_panic(expected = "Индекс кубита выходит за пределы")]
+    fn test_invalid_qubit_panics() {
+        let mut simulator = StateVectorSimulator::new(2);
+        simulator.hadamard(2);
+    }
+
+    #[test]
+    fn test_sample_does_not_collapse_state() {
+        let mut simulator = StateVectorSimulator::new(3);
+        simulator.hadamard(0);
+        simulator.cnot(0, 2);
+
+        let counts = simulator.sample(2000, &[2, 0]);
+        assert_eq!(counts.shots(), 2000);
+        assert_eq!(counts.get(0b01) + counts.get(0b10), 0);
+        assert!((counts.frequency(0b11) - 0.5).abs() < 0.05);
+        assert!(counts.expectation_z(&[0, 1]) > 1.0 - EPSILON);
+
+        // Состояние после выборки остается суперпозицией
+        assert!((simulator.probability_of_outcome(0, true) - 0.5).abs() < EPSILON);
+        assert!(simulator.try_sample(10, &[3]).is_err());
+        assert!(simulator.try_sample(10, &[1, 1]).is_err());
+    }
+
+    #[test]
+    fn test_seed_reproduces_measurements_and_samples() {
+        let run = |seed: u64| {
+            let mut simulator = StateVectorSimulator::new(6);
+            simulator.seed(seed);
+            for qubit in 0..6 {
+                simulator.hadamard(qubit);
+            }
+            let counts = simulator.sample(100, &[0, 1, 2]);
+            let bits: Vec<bool> = (0..6).map(|qubit| simulator.measure(qubit)).collect();
+            (counts, bits)
+        };
+
+        assert_eq!(run(42), run(42));
+        assert_ne!(run(42), run(43));
+    }
+
+    #[test]
+    fn test_measure_in_x_and_y_basis() {
+        let mut simulator = StateVectorSimulator::new(2);
+        simulator.seed(3);
+        simulator.hadamard(0);
+        simulator.hadamard(1);
+        simulator.s_gate(1);
+
+        // |+⟩ и |+i⟩ — собственные состояния X и Y с собственным значением +1
+        assert!(!simulator.measure_in_basis(0, Pauli::X));
+        assert!(!simulator.measure_in_basis(1, Pauli::Y));
+        assert!((simulator.get_expectation_value(&[(1, 'Y')]) - 1.0).abs() < EPSILON);
+
+        // После измерения |0⟩ в X-базисе кубит находится в |±⟩
+        let mut simulator = StateVectorSimulator::new(1);
+        let outcome = simulator.measure_in_basis(0, Pauli::X);
+        let expected = if outcome { -1.0 } else { 1.0 };
+        assert!((simulator.get_expectation_value(&[(0, 'X')]) - expected).abs() < EPSILON);
+        assert!(simulator.try_measure_in_basis(1, Pauli::Z).is_err());
+    }
+
+    #[test]
+    fn test_measure_pauli_projects_onto_eigenspace() {
+        let mut bell = StateVectorSimulator::new(2);
+        bell.hadamard(0);
+        bell.cnot(0, 1);
+        let before = bell.state_vector().clone();
+
+        // |Φ+⟩ — собственное состояние XX и ZZ (+1) и YY (-1): измерение его не меняет
+        assert!(!bell.measure_pauli(&[(0, 'X'), (1, 'X')]));
+        assert!(!bell.measure_pauli(&[(0, 'Z'), (1, 'Z')]));
+        assert!(bell.measure_pauli(&[(0, 'Y'), (1, 'Y')]));
+        assert!((bell.state_vector().inner_product(&before).norm() - 1.0).abs() < EPSILON);
+
+        // Измерение XX на |00⟩ дает состояние Белла, не измеряя кубиты по отдельности
+        let mut simulator = StateVectorSimulator::new(3);
+        let outcome = simulator.measure_pauli(&[(0, 'X'), (2, 'X'), (1, 'I')]);
+        let expected = if outcome { -1.0 } else { 1.0 };
+        assert!((simulator.get_expectation_value(&[(0, 'X'), (2, 'X')]) - expected).abs() < EPSILON);
+        assert!((simulator.get_expectation_value(&[(0, 'Z'), (2, 'Z')]) - 1.0).abs() < EPSILON);
+        assert!((simulator.probability_of_outcome(0, false) - 0.5).abs() < EPSILON);
+
+        assert!(!simulator.measure_pauli(&[]));
+        assert!(matches!(simulator.try_measure_pauli(&[(0, 'Q')]), Err(TriadError::InvalidPauli('Q'))));
+    }
+
+    #[test]
+    fn test_reduced_density_matrix_orders_targets_like_gates() {
+        let mut simulator = StateVectorSimulator::new(3);
+        simulator.x(0);
+        simulator.hadamard(2);
+        let state = simulator.state_vector();
+
+        // Первый целевой кубит — старший бит индекса
+        assert!((state.reduced_density_matrix(&[0, 1])[2 * 4 + 2].re - 1.0).abs() < EPSILON);
+        assert!((state.reduced_density_matrix(&[1, 0])[4 + 1].re - 1.0).abs() < EPSILON);
+        let plus = state.reduced_density_matrix(&[2]);
+        assert!(plus.iter().all(|value| (value.re - 0.5).abs() < EPSILON && value.im.abs() < EPSILON));
+    }
+
+    #[test]
+    fn test_measure_with_skips_outcomes_below_epsilon() {
+        // Вероятность |1⟩ равна sin²(5e-6) ≈ 2.5e-11, но random попадает в ее интервал
+        let rotation = RotationGate { axis: Axis::Y, angle: 1e-5 }.matrix();
+        let mut state = StateVector::new(1);
+        state.apply_single_qubit(0, &rotation);
+        assert!(!state.measure_with(0, 0.99999999999));
+        assert!((state.probability(0) - 1.0).abs() < EPSILON);
+    }
+}
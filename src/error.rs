@@ -0,0 +1,66 @@
+//! Ошибки библиотеки TRIAD.
+//!
+//! Все восстанавливаемые ошибки симуляторов и движка представлены
+//! перечислением `TriadError`, которое возвращают `try_*` методы.
+
+use thiserror::Error;
+
+/// Ошибка при работе с квантовыми симуляторами TRIAD.
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum TriadError {
+    /// Индекс кубита выходит за пределы регистра.
+    #[error("Индекс кубита выходит за пределы: {qubit} (кубитов в регистре: {num_qubits})")]
+    InvalidQubit { qubit: usize, num_qubits: usize },
+
+    /// Один и тот же кубит указан в операции несколько раз.
+    #[error("Кубит {0} указан несколько раз")]
+    DuplicateQubit(usize),
+
+    /// Запрошен регистр без кубитов.
+    #[error("Количество кубитов должно быть положительным")]
+    NoQubits,
+
+    /// Запрошено больше кубитов, чем поддерживает бэкенд.
+    #[error("Количество кубитов не может превышать {max} (запрошено {requested})")]
+    TooManyQubits { requested: usize, max: usize },
+
+    /// Операции требуется больше кубитов, чем есть в регистре.
+    #[error("Операция требует минимум {required} кубитов, в регистре {available}")]
+    NotEnoughQubits { required: usize, available: usize },
+
+    /// Матрица оператора не является унитарной.
+    #[error("Матрица гейта {0} не является унитарной")]
+    NonUnitary(String),
+
+    /// Гейт не принадлежит группе Клиффорда и не может быть применен
+    /// стабилизаторным симулятором.
+    #[error("Гейт {0} не является гейтом Клиффорда и не поддерживается стабилизаторным симулятором")]
+    NonClifford(String),
+
+    /// Операторы Крауса канала не удовлетворяют условию полноты Σ K†K = I.
+    #[error("Операторы Крауса канала {0} не сохраняют след")]
+    NotTracePreserving(String),
+
+    /// Неизвестный символ оператора Паули.
+    #[error("Неизвестный оператор Паули: {0}")]
+    InvalidPauli(char),
+
+    /// Некорректный аргумент операции.
+    #[error("Некорректный аргумент: {0}")]
+    InvalidArgument(String),
+
+    /// Ошибка разбора исходного текста схемы (например, OpenQASM).
+    #[error("Ошибка разбора в строке {line}, столбце {column}: {message}")]
+    Parse { line: usize, column: usize, message: String },
+
+    /// Операция не поддерживается выбранным бэкендом симуляции.
+    #[error("Операция не поддерживается бэкендом: {0}")]
+    Unsupported(String),
+
+    /// Ошибка, сообщенная бэкендом симуляции (например, QuEST).
+    #[error("Ошибка бэкенда: {0}")]
+    BackendFailure(String),
+}
+
+/// Результат операций TRIAD.
+pub type Result<T> = std::result::Result<T, TriadError>;
@@ -4,14 +4,12 @@
 //! наглядно демонстрируют принципы суперпозиции и квантовой запутанности.
 
 use crate::api::QuantumEngine;
-use crate::quest::QuESTSimulator;
+use crate::backend::DefaultSimulator;
+use crate::core::circuit::Instruction;
+use crate::core::gates::BasicGate;
 use crate::core::quantum_simulator::{QuantumSimulator, AdvancedQuantumSimulator};
-use crate::core::gates::*;
-use crate::core::qubit::Qubit;
-use std::f64::consts::PI;
 use std::time::Instant;
 use rand::Rng;
-use std::fmt::Debug;
 
 /// Реализует алгоритм квантовой телепортации произвольного состояния
 /// Берёт квантовое состояние (theta, phi) и телепортирует его с первого кубита на третий
@@ -35,71 +33,25 @@ pub fn quantum_teleportation(state: (f64, f64)) -> bool {
     engine.cnot(0, 1);
     engine.hadamard(0);
     
-    // Измеряем первые два кубита
-    let m0 = engine.measure(0);
-    let m1 = engine.measure(1);
-    
-    // Применяем нужные гейты в зависимости от результатов измерений
-    if m1 {
-        engine.x(2);
-    }
-    if m0 {
-        engine.z(2);
-    }
-    
-    // Проверяем успешность телепортации через статистическое измерение
-    let mut success_count = 0;
-    const TRIALS: usize = 100;
-    
-    for _ in 0..TRIALS {
-        // Создаем новый симулятор для проверки
-        let mut sim_copy = QuESTSimulator::new(1);
-        
-        // Подготавливаем исходное состояние на единственном кубите
-        {
-            sim_copy.ry(0, 2.0 * theta);
-            sim_copy.rz(0, phi);
-        }
-        
-        // Сравниваем с телепортированным состоянием
-        let original_prob0 = sim_copy.probability_of_outcome(0, false);
-        
-        // Создаем новый движок для телепортации
-        let mut teleport_engine = QuantumEngine::new(3);
-        // Подготавливаем состояние для телепортации
-        {
-            teleport_engine.simulator_mut().ry(0, 2.0 * theta);
-            teleport_engine.simulator_mut().rz(0, phi);
-        }
-        
-        // Выполняем протокол телепортации
-        teleport_engine.hadamard(1);
-        teleport_engine.cnot(1, 2);
-        teleport_engine.cnot(0, 1);
-        teleport_engine.hadamard(0);
-        
-        let m0 = teleport_engine.measure(0);
-        let m1 = teleport_engine.measure(1);
-        
-        if m1 {
-            teleport_engine.x(2);
-        }
-        if m0 {
-            teleport_engine.z(2);
-        }
-        
-        // Измеряем вероятность в телепортированном состоянии
-        let teleported_prob0 = teleport_engine.simulator().probability_of_outcome(2, false);
-        
-        // Сравниваем вероятности с небольшой погрешностью
-        if (original_prob0 - teleported_prob0).abs() < 0.1 {
-            success_count += 1;
-        }
-    }
-    
-    // Если успешность телепортации выше 90%, считаем протокол работающим
-    let success_rate = success_count as f64 / TRIALS as f64;
-    success_rate > 0.9
+    // Измеряем первые два кубита в классические биты 0 и 1
+    engine.measure_into(0, 0);
+    engine.measure_into(1, 1);
+    
+    // Применяем поправки в зависимости от результатов измерений
+    engine.c_if(&[1], 1, Instruction::gate(BasicGate::PauliX, &[2]));
+    engine.c_if(&[0], 1, Instruction::gate(BasicGate::PauliZ, &[2]));
+    
+    // Вероятность нуля в исходном состоянии
+    let mut original = DefaultSimulator::new(1);
+    original.ry(0, 2.0 * theta);
+    original.rz(0, phi);
+    let original_prob0 = original.probability_of_outcome(0, false);
+    
+    // Проверяем успешность телепортации по выборкам из итогового состояния,
+    // не повторяя протокол для каждого испытания
+    const SHOTS: usize = 1000;
+    let counts = engine.sample(SHOTS, &[2]);
+    (counts.frequency(0) - original_prob0).abs() < 0.1
 }
 
 /// Реализует квантовое сверхплотное кодирование
@@ -140,6 +92,9 @@ pub fn grover_search(num_qubits: usize, target_state: u64) -> u64 {
     // Создаём симулятор
     let mut engine = QuantumEngine::new(num_qubits);
     
+    // Многокубитный Z-гейт инвертирует фазу состояния |1...1⟩
+    let (target, controls) = (num_qubits - 1, (0..num_qubits - 1).collect::<Vec<usize>>());
+    
     // Инициализируем равную суперпозицию всех состояний
     engine.create_uniform_superposition();
     
@@ -157,9 +112,8 @@ pub fn grover_search(num_qubits: usize, target_state: u64) -> u64 {
             }
         }
         
-        // Применяем многокубитный Z-гейт (не напрямую доступен, поэтому используем обходной путь)
-        // В реальной имплементации нужен контролируемый Z-гейт с N-1 контролями
-        engine.z(num_qubits - 1);
+        // Применяем многокубитный Z-гейт
+        engine.mcz(&controls, target);
         
         // Возвращаем состояние кубитов
         for i in 0..num_qubits {
@@ -179,8 +133,8 @@ pub fn grover_search(num_qubits: usize, target_state: u64) -> u64 {
             engine.x(i);
         }
         
-        // Применяем многокубитный Z-гейт (то же обходное решение)
-        engine.z(num_qubits - 1);
+        // Применяем многокубитный Z-гейт
+        engine.mcz(&controls, target);
         
         // Возвращаемся, применив X и Адамара на все кубиты
         for i in 0..num_qubits {
@@ -272,8 +226,8 @@ fn mod_pow(a: u64, b: u64, n: u64) -> u64 {
 /// Функция для факторизации числа n с использованием алгоритма Шора
 pub fn shor_factorize(n: u64) -> Option<(u64, u64)> {
     // Базовые проверки
-    if n < 3 || n % 2 == 0 {
-        return if n % 2 == 0 { Some((2, n / 2)) } else { None };
+    if n < 3 || n & 1 == 0 {
+        return if n & 1 == 0 { Some((2, n / 2)) } else { None };
     }
     
     let mut rng = rand::thread_rng();
@@ -366,22 +320,21 @@ impl PauliHamiltonian {
         // В реальности коэффициенты рассчитываются более сложно
         let g = 1.0 / distance;
         
-        let mut terms = Vec::new();
-        
-        // Кинетическая энергия
-        terms.push((0.5, vec![(0, 'X'), (1, 'X')]));
-        terms.push((0.5, vec![(0, 'Y'), (1, 'Y')]));
-        
-        // Потенциальная энергия
-        terms.push((g, vec![(0, 'Z')]));
-        terms.push((g, vec![(1, 'Z')]));
-        terms.push((-g, vec![(0, 'Z'), (1, 'Z')]));
+        let terms = vec![
+            // Кинетическая энергия
+            (0.5, vec![(0, 'X'), (1, 'X')]),
+            (0.5, vec![(0, 'Y'), (1, 'Y')]),
+            // Потенциальная энергия
+            (g, vec![(0, 'Z')]),
+            (g, vec![(1, 'Z')]),
+            (-g, vec![(0, 'Z'), (1, 'Z')]),
+        ];
         
         PauliHamiltonian { terms }
     }
     
     /// Вычисляет ожидаемое значение гамильтониана для данного квантового состояния
-    pub fn expectation_value(&self, simulator: &QuESTSimulator) -> f64 {
+    pub fn expectation_value(&self, simulator: &DefaultSimulator) -> f64 {
         let mut energy = 0.0;
         
         for (coefficient, pauli_term) in &self.terms {
@@ -394,8 +347,8 @@ impl PauliHamiltonian {
 }
 
 /// Симулирует временную эволюцию системы с заданным гамильтонианом
-pub fn simulate_time_evolution(hamiltonian: &PauliHamiltonian, time: f64, num_qubits: usize) -> QuESTSimulator {
-    let mut simulator = QuESTSimulator::new(num_qubits);
+pub fn simulate_time_evolution(hamiltonian: &PauliHamiltonian, time: f64, num_qubits: usize) -> DefaultSimulator {
+    let mut simulator = DefaultSimulator::new(num_qubits);
     
     // Начинаем в суперпозиции
     simulator.reset();
@@ -516,11 +469,13 @@ mod tests {
         let hamiltonian = PauliHamiltonian::h2_molecule(1.0);
         assert!(!hamiltonian.terms.is_empty());
         
-        // Создаем симулятор и вычисляем энергию
-        let simulator = QuESTSimulator::new(2);
+        // Создаем симулятор в состоянии |11⟩ и вычисляем энергию
+        let mut simulator = DefaultSimulator::new(2);
+        simulator.x(0);
+        simulator.x(1);
         let energy = hamiltonian.expectation_value(&simulator);
         
-        // Проверяем, что энергия имеет разумное значение
-        assert!(energy < 0.0);
+        // В |11⟩ вклад дают только Z-члены: -g - g - g
+        assert!((energy + 3.0).abs() < 1e-10);
     }
 } 
\ No newline at end of file
@@ -3,12 +3,11 @@
 //! Этот пример демонстрирует реализацию алгоритма Дойча с использованием квантового симулятора TRIAD.
 //! Алгоритм определяет, является ли бинарная функция константной или сбалансированной за один запрос.
 
-use log::{info, debug};
+use log::info;
 use std::fmt;
 
-use crate::core::gates::{Gate, BasicGate, TwoQubitGate};
 use crate::core::quantum_simulator::QuantumSimulator;
-use crate::quest::QuESTSimulator;
+use crate::backend::DefaultSimulator;
 
 /// Тип квантового оракула для функции f(x).
 #[derive(Debug, Clone)]
@@ -39,7 +38,7 @@ impl fmt::Display for OracleType {
 /// Возвращает true, если функция константная (f(0) = f(1)), и false, если функция сбалансированная.
 fn deutsch_algorithm(oracle_type: OracleType) -> bool {
     // Создаем квантовый симулятор с 2 кубитами
-    let mut simulator = QuESTSimulator::new(2);
+    let mut simulator = DefaultSimulator::new(2);
     
     // Подготавливаем состояние |01⟩
     simulator.reset(); // Сначала сбрасываем кубиты в |00⟩
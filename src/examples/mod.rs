@@ -45,6 +45,7 @@ pub fn run_all_demos() {
 }
 
 /// Примеры использования API библиотеки TRIAD.
+#[allow(clippy::module_inception)]
 pub mod examples {
     use crate::api::QuantumEngine;
     
@@ -88,8 +89,7 @@ pub mod examples {
             engine.z(2);
         }
         
-        // Проверяем, что целевой кубит получил состояние |1⟩
-        let result = engine.measure(2);
-        result // Должно быть true
+        // Проверяем, что целевой кубит получил состояние |1⟩ (должно быть true)
+        engine.measure(2)
     }
 } 
\ No newline at end of file
@@ -4,14 +4,13 @@
 //! для генерации истинно случайных чисел, используя принципы квантовой механики.
 
 use crate::core::quantum_simulator::QuantumSimulator;
-use crate::quest::QuESTSimulator;
-use std::time::{Duration, Instant};
-use env_logger;
+use crate::backend::DefaultSimulator;
+use std::time::Instant;
 
 /// Генерирует случайный бит (0 или 1) с использованием квантового симулятора
 pub fn generate_random_bit() -> bool {
     // Создаем симулятор с одним кубитом
-    let mut simulator = QuESTSimulator::new(1);
+    let mut simulator = DefaultSimulator::new(1);
     
     // Проверяем, что симулятор корректно инициализирован
     assert_eq!(simulator.num_qubits(), 1, "Симулятор должен иметь ровно 1 кубит");
@@ -109,14 +108,6 @@ pub fn demonstrate_random_number_generation() {
               count_ones, (count_ones as f64 / 10.0).round());
 }
 
-fn main() {
-    // Инициализируем логирование
-    env_logger::init();
-    
-    // Запускаем демонстрацию
-    demonstrate_random_number_generation();
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -40,6 +40,7 @@
 pub mod core;
 
 /// Интеграция с QuEST (Quantum Exact Simulation Toolkit)
+#[cfg(feature = "quest")]
 pub mod quest;
 
 /// Примеры использования квантовых симуляторов и алгоритмов
@@ -48,8 +49,18 @@ pub mod examples;
 /// Публичный API библиотеки
 pub mod api;
 
+/// Ошибки библиотеки
+pub mod error;
+
+/// Импорт и экспорт схем в формате OpenQASM
+pub mod qasm;
+
+/// Выбор бэкенда симуляции во время выполнения
+pub mod backend;
+
 // Реэкспортируем основные компоненты для удобства
 pub use api::{QuantumEngine, QuantumSimulator, QuantumState};
+pub use error::TriadError;
 
 /// Версия библиотеки
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -5,8 +5,6 @@
 
 // Импортируем внутренние модули
 use triad::VERSION;
-use triad::quest::QuESTSimulator;
-use triad::core::quantum_simulator::QuantumSimulator;
 
 // Простая эмуляция квантовых вычислений без использования QuEST
 mod simple_demo {
@@ -60,7 +58,7 @@ mod simple_demo {
             println!("5. Измеряем первый кубит");
             
             // Имитируем результат (детерминистический для демонстрации)
-            let result = if oracle.contains("Константная") { true } else { false };
+            let result = oracle.contains("Константная");
             println!("Результат измерения: {}", if result { 0 } else { 1 });
             println!("Вывод: функция является {}", if result { "константной" } else { "сбалансированной" });
         }
@@ -195,7 +193,7 @@ mod simple_demo {
             }
             
             let option = input.parse::<usize>().unwrap_or(0);
-            if option < 1 || option > 4 {
+            if !(1..=4).contains(&option) {
                 println!("   Неверный ввод. Пожалуйста, выберите 1-4 или q для выхода.");
                 continue;
             }
@@ -242,7 +240,7 @@ mod simple_demo {
                 println!("   Ошибка телепортации! Состояние изменилось.");
                 println!("   (В реальных квантовых системах это может быть вызвано шумом или декогеренцией)");
             }
-            println!("");
+            println!();
         }
         
         println!("\nВажно: Несмотря на мгновенную корреляцию между запутанными кубитами, ");
@@ -267,6 +265,7 @@ mod simple_demo {
 }
 
 // Демонстрации, использующие квантовый симулятор QuEST
+#[cfg(feature = "quest")]
 mod quest_demo {
     use triad::quest::QuESTSimulator;
     use triad::core::quantum_simulator::QuantumSimulator;
@@ -484,6 +483,7 @@ mod quest_demo {
 }
 
 /// Безопасная демонстрация квантовой запутанности для случаев, когда QuEST не работает
+#[cfg(not(feature = "quest"))]
 mod safe_demo {
     use rand::Rng;
     
@@ -603,6 +603,7 @@ fn main() {
             // Запускаем только алгоритм Дойча
             simple_demo::demonstrate_deutsch_algorithm();
         },
+        #[cfg(feature = "quest")]
         Some("entanglement") | Some("quest") => {
             // Запускаем демонстрацию с использованием ТОЛЬКО QuEST
             println!("Запуск демонстрации с использованием QuEST...");
@@ -615,6 +616,12 @@ fn main() {
                 }
             }
         },
+        #[cfg(not(feature = "quest"))]
+        Some("entanglement") | Some("quest") => {
+            // Крейт собран без QuEST: показываем безопасную демонстрацию
+            println!("TRIAD собран без признака `quest`, демонстрация QuEST недоступна.");
+            safe_demo::demonstrate_entanglement();
+        },
         Some("help") | Some("-h") | Some("--help") => {
             // Выводим справку
             print_help();
@@ -622,7 +629,7 @@ fn main() {
         Some(unknown) => {
             // Неизвестная команда
             println!("Неизвестная команда: {}", unknown);
-            println!("");
+            println!();
             print_help();
         },
         None => {
@@ -635,15 +642,15 @@ fn main() {
 /// Выводит справку по использованию программы.
 fn print_help() {
     println!("Использование: triad-node [КОМАНДА]");
-    println!("");
+    println!();
     println!("КОМАНДЫ:");
     println!("  random         Демонстрация генерации квантовых случайных чисел");
     println!("  deutsch        Демонстрация алгоритма Дойча");
     println!("  entanglement   Демонстрация квантовой запутанности с использованием QuEST");
     println!("  quest          Демонстрация квантовой запутанности с использованием QuEST (то же, что и entanglement)");
     println!("  help           Показать эту справку");
-    println!("");
+    println!();
     println!("Если команда не указана, будут запущены все демонстрационные примеры.");
-    println!("");
+    println!();
     println!("Примечание: Используется настоящая библиотека QuEST для квантовых симуляций.");
 } 
\ No newline at end of file
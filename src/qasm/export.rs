@@ -0,0 +1,323 @@
+//! Экспорт схем `Circuit` в текст OpenQASM 2.0.
+//!
+//! Собственные гейты TRIAD записываются гейтами `qelib1.inc`, произвольные
+//! однокубитовые гейты — через `u3(θ, φ, λ)` с точностью до глобальной фазы.
+
+use crate::core::circuit::{Circuit, CircuitGate, Instruction, Register};
+use crate::core::gates::{Axis, BasicGate, Gate, ParametricGate, TwoQubitGate};
+use crate::core::quantum_state::Amplitude;
+use crate::error::TriadError;
+use std::f64::consts::PI;
+use std::fmt::Write;
+
+/// Допуск при распознавании углов и нулевых элементов матриц.
+const EPSILON: f64 = 1e-12;
+
+/// Преобразует схему в программу OpenQASM 2.0.
+///
+/// Если у схемы нет именованных регистров, используются регистры `q` и `c`.
+/// Возвращает ошибку для многокубитовых пользовательских гейтов, а также для
+/// условий, которые не совпадают целиком с одним классическим регистром.
+pub fn to_qasm2(circuit: &Circuit) -> Result<String, TriadError> {
+    let qregs = registers_or_default(circuit.qregs(), "q", circuit.num_qubits());
+    let cregs = registers_or_default(circuit.cregs(), "c", circuit.num_cbits());
+
+    let mut out = String::from("OPENQASM 2.0;\ninclude \"qelib1.inc\";\n");
+    for reg in &qregs {
+        writeln!(out, "qreg {}[{}];", reg.name, reg.size).unwrap();
+    }
+    for reg in &cregs {
+        writeln!(out, "creg {}[{}];", reg.name, reg.size).unwrap();
+    }
+
+    for instruction in circuit.instructions() {
+        let line = match instruction {
+            Instruction::Conditional { cbits, value, instruction } => {
+                let register = find_whole_register(&cregs, cbits).ok_or_else(|| TriadError::InvalidArgument(
+                    "в OpenQASM 2.0 условие должно проверять классический регистр целиком".to_string()))?;
+                if matches!(**instruction, Instruction::Conditional { .. } | Instruction::Barrier(_)) {
+                    return Err(TriadError::InvalidArgument(
+                        "в OpenQASM 2.0 условной может быть только одна квантовая операция".to_string()));
+                }
+                format!("if({}=={}) {}", register, value, operation(instruction, &qregs, &cregs)?)
+            },
+            _ => operation(instruction, &qregs, &cregs)?,
+        };
+        writeln!(out, "{};", line).unwrap();
+    }
+
+    Ok(out)
+}
+
+/// Возвращает регистры схемы либо один регистр по умолчанию.
+fn registers_or_default(registers: &[Register], name: &str, size: usize) -> Vec<Register> {
+    if !registers.is_empty() {
+        registers.to_vec()
+    } else if size > 0 {
+        vec![Register::new(name, size)]
+    } else {
+        Vec::new()
+    }
+}
+
+/// Возвращает имя элемента регистра (`q[3]`) для индекса схемы.
+fn bit_name(registers: &[Register], mut index: usize) -> String {
+    for reg in registers {
+        if index < reg.size {
+            return format!("{}[{}]", reg.name, index);
+        }
+        index -= reg.size;
+    }
+    unreachable!("индексы проверяются при построении схемы")
+}
+
+/// Ищет классический регистр, занимающий ровно биты `cbits` в порядке возрастания.
+fn find_whole_register<'a>(registers: &'a [Register], cbits: &[usize]) -> Option<&'a str> {
+    let mut offset = 0;
+    for reg in registers {
+        if cbits.len() == reg.size && cbits.iter().enumerate().all(|(i, &c)| c == offset + i) {
+            return Some(&reg.name);
+        }
+        offset += reg.size;
+    }
+    None
+}
+
+/// Записывает безусловную операцию без завершающей точки с запятой.
+fn operation(instruction: &Instruction, qregs: &[Register], cregs: &[Register]) -> Result<String, TriadError> {
+    let qubit_list = |qubits: &[usize]| qubits.iter().map(|&q| bit_name(qregs, q)).collect::<Vec<_>>().join(",");
+
+    Ok(match instruction {
+        Instruction::Gate { gate, qubits } => format!("{} {}", gate_name(gate)?, qubit_list(qubits)),
+        Instruction::Measure { qubit, cbit } => format!("measure {} -> {}", bit_name(qregs, *qubit), bit_name(cregs, *cbit)),
+        Instruction::Reset(qubit) => format!("reset {}", bit_name(qregs, *qubit)),
+        Instruction::Barrier(qubits) => format!("barrier {}", qubit_list(qubits)),
+        Instruction::Conditional { .. } => unreachable!("вложенные условия отклоняются выше"),
+    })
+}
+
+/// Возвращает имя гейта `qelib1.inc` вместе с параметрами.
+fn gate_name(gate: &CircuitGate) -> Result<String, TriadError> {
+    Ok(match gate {
+        CircuitGate::Basic(basic) => match basic {
+            BasicGate::Hadamard => "h",
+            BasicGate::PauliX => "x",
+            BasicGate::PauliY => "y",
+            BasicGate::PauliZ => "z",
+            BasicGate::S => "s",
+            BasicGate::T => "t",
+            BasicGate::SDagger => "sdg",
+            BasicGate::TDagger => "tdg",
+            BasicGate::SqrtX => "sx",
+            BasicGate::SqrtXDagger => "sxdg",
+        }.to_string(),
+        CircuitGate::Rotation(rotation) => {
+            let name = match rotation.axis {
+                Axis::X => "rx",
+                Axis::Y => "ry",
+                Axis::Z => "rz",
+            };
+            format!("{}({})", name, format_angle(rotation.angle))
+        },
+        CircuitGate::TwoQubit(two) => match two {
+            TwoQubitGate::CNOT => "cx",
+            TwoQubitGate::CZ => "cz",
+            TwoQubitGate::SWAP => "swap",
+            TwoQubitGate::ISWAP | TwoQubitGate::ISWAPDagger | TwoQubitGate::SqrtISWAP | TwoQubitGate::SqrtISWAPDagger => {
+                return Err(TriadError::InvalidArgument(format!(
+                    "гейт {} отсутствует в qelib1.inc", two.name())));
+            },
+        }.to_string(),
+        CircuitGate::Parametric(parametric) => match *parametric {
+            ParametricGate::U1 { lambda } => format!("u1({})", format_angle(lambda)),
+            ParametricGate::U2 { phi, lambda } => format!("u2({},{})", format_angle(phi), format_angle(lambda)),
+            ParametricGate::U3 { theta, phi, lambda } => {
+                format!("u3({},{},{})", format_angle(theta), format_angle(phi), format_angle(lambda))
+            },
+            ParametricGate::Phase { theta } => format!("p({})", format_angle(theta)),
+            ParametricGate::RXX { theta } => format!("rxx({})", format_angle(theta)),
+            ParametricGate::RZZ { theta } => format!("rzz({})", format_angle(theta)),
+            ParametricGate::CPhase { theta } => format!("cp({})", format_angle(theta)),
+            ParametricGate::CRX { theta } => format!("crx({})", format_angle(theta)),
+            ParametricGate::CRY { theta } => format!("cry({})", format_angle(theta)),
+            ParametricGate::CRZ { theta } => format!("crz({})", format_angle(theta)),
+            ParametricGate::RYY { .. } => return Err(TriadError::InvalidArgument(format!(
+                "гейт {} отсутствует в qelib1.inc", parametric.name()))),
+        },
+        CircuitGate::Custom(custom) if custom.num_qubits == 1 => match custom.name.as_str() {
+            "S†" => "sdg".to_string(),
+            "T†" => "tdg".to_string(),
+            _ => {
+                let (theta, phi, lambda) = u3_angles(&gate.matrix());
+                if theta.abs() < EPSILON && phi.abs() < EPSILON {
+                    if lambda.abs() < EPSILON {
+                        "id".to_string()
+                    } else {
+                        format!("u1({})", format_angle(lambda))
+                    }
+                } else {
+                    format!("u3({},{},{})", format_angle(theta), format_angle(phi), format_angle(lambda))
+                }
+            },
+        },
+        CircuitGate::Custom(custom) => return Err(TriadError::InvalidArgument(format!(
+            "многокубитовый гейт {} нельзя записать в OpenQASM 2.0", custom.name))),
+    })
+}
+
+/// Находит углы `(θ, φ, λ)`, при которых `U = e^{iγ}·u3(θ, φ, λ)`.
+pub(crate) fn u3_angles(matrix: &[Amplitude]) -> (f64, f64, f64) {
+    let (u00, u01, u10, u11) = (matrix[0], matrix[1], matrix[2], matrix[3]);
+    let theta = 2.0 * u10.norm().atan2(u00.norm());
+
+    let (phi, lambda) = if u00.norm() > EPSILON {
+        let phase = u00.arg();
+        if u10.norm() > EPSILON {
+            (u10.arg() - phase, (-u01).arg() - phase)
+        } else {
+            (0.0, u11.arg() - phase)
+        }
+    } else {
+        (0.0, (-u01).arg() - u10.arg())
+    };
+
+    (theta, normalize_angle(phi), normalize_angle(lambda))
+}
+
+/// Приводит угол к промежутку (-π, π].
+fn normalize_angle(angle: f64) -> f64 {
+    let mut angle = angle % (2.0 * PI);
+    if angle <= -PI {
+        angle += 2.0 * PI;
+    } else if angle > PI {
+        angle -= 2.0 * PI;
+    }
+    if angle.abs() < EPSILON { 0.0 } else { angle }
+}
+
+/// Записывает угол, по возможности в виде дроби от `pi`.
+fn format_angle(angle: f64) -> String {
+    for denominator in [1i64, 2, 3, 4, 6, 8] {
+        let numerator = angle * denominator as f64 / PI;
+        let rounded = numerator.round();
+        if rounded != 0.0 && (numerator - rounded).abs() < EPSILON {
+            let numerator = rounded as i64;
+            let sign = if numerator < 0 { "-" } else { "" };
+            let multiple = match numerator.abs() {
+                1 => "pi".to_string(),
+                n => format!("{}*pi", n),
+            };
+            return match denominator {
+                1 => format!("{}{}", sign, multiple),
+                d => format!("{}{}/{}", sign, multiple, d),
+            };
+        }
+    }
+    format!("{}", angle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::gates::CustomGate;
+    use crate::core::state_vector::StateVector;
+    use crate::core::state_vector::StateVectorSimulator;
+    use crate::qasm::qasm2::{parse_qasm2, u3_matrix};
+
+    fn final_state(circuit: &Circuit) -> StateVector {
+        let mut simulator = StateVectorSimulator::new(circuit.num_qubits());
+        circuit.run_on(&mut simulator).unwrap();
+        simulator.state_vector().clone()
+    }
+
+    #[test]
+    fn test_export_bell_circuit() {
+        let mut circuit = Circuit::new(2, 2);
+        circuit.h(0).cnot(0, 1).barrier_all().measure(0, 0).measure(1, 1);
+
+        assert_eq!(to_qasm2(&circuit).unwrap(), "OPENQASM 2.0;\ninclude \"qelib1.inc\";\n\
+            qreg q[2];\ncreg c[2];\nh q[0];\ncx q[0],q[1];\nbarrier q[0],q[1];\n\
+            measure q[0] -> c[0];\nmeasure q[1] -> c[1];\n");
+    }
+
+    #[test]
+    fn test_round_trip_preserves_state() {
+        let mut circuit = Circuit::new(3, 0);
+        circuit.h(0).s(1).t(2).rx(0, PI / 4.0).ry(1, 0.123).rz(2, -3.0 * PI / 2.0)
+            .cz(0, 2).swap(1, 2).cnot(2, 0)
+            .gate(CircuitGate::Basic(BasicGate::T).inverse(), &[1])
+            .gate(CustomGate { matrix: u3_matrix(0.3, 1.2, -0.7), num_qubits: 1, name: "U".to_string() }, &[0]);
+
+        let text = to_qasm2(&circuit).unwrap();
+        assert!(text.contains("rx(pi/4) q[0];"));
+        assert!(text.contains("tdg q[1];"));
+
+        let parsed = parse_qasm2(&text).unwrap();
+        assert_eq!(parsed.len(), circuit.len());
+
+        let fidelity = final_state(&circuit).inner_product(&final_state(&parsed)).norm();
+        assert!((fidelity - 1.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_round_trip_gate_library() {
+        let mut circuit = Circuit::new(3, 0);
+        circuit.h(0).h(1).h(2)
+            .gate(BasicGate::SqrtX, &[0])
+            .gate(BasicGate::SDagger, &[1])
+            .gate(ParametricGate::U2 { phi: 0.2, lambda: -1.0 }, &[2])
+            .gate(ParametricGate::Phase { theta: PI / 3.0 }, &[0])
+            .gate(ParametricGate::RXX { theta: 0.7 }, &[0, 2])
+            .gate(ParametricGate::RZZ { theta: -0.4 }, &[1, 0])
+            .gate(ParametricGate::CPhase { theta: PI / 8.0 }, &[2, 1])
+            .gate(ParametricGate::CRY { theta: 1.1 }, &[0, 1])
+            .gate(CircuitGate::Basic(BasicGate::SqrtX).inverse(), &[2]);
+
+        let text = to_qasm2(&circuit).unwrap();
+        for line in ["sx q[0];", "sdg q[1];", "p(pi/3) q[0];", "rxx(0.7) q[0],q[2];", "cp(pi/8) q[2],q[1];", "sxdg q[2];"] {
+            assert!(text.contains(line), "{}", text);
+        }
+
+        let parsed = parse_qasm2(&text).unwrap();
+        assert_eq!(parsed.gate_counts(), circuit.gate_counts());
+        let fidelity = final_state(&circuit).inner_product(&final_state(&parsed)).norm();
+        assert!((fidelity - 1.0).abs() < 1e-10);
+
+        let mut circuit = Circuit::new(2, 0);
+        circuit.gate(TwoQubitGate::ISWAP, &[0, 1]);
+        assert!(to_qasm2(&circuit).is_err());
+    }
+
+    #[test]
+    fn test_round_trip_registers_and_conditionals() {
+        let source = "OPENQASM 2.0;\ninclude \"qelib1.inc\";\nqreg a[1];\nqreg b[2];\ncreg m[1];\ncreg r[2];\n\
+            h a[0];\nmeasure a[0] -> m[0];\nif(m==1) x b[1];\nreset a[0];\nmeasure b[0] -> r[0];\n";
+
+        let circuit = parse_qasm2(source).unwrap();
+        assert_eq!(to_qasm2(&circuit).unwrap(), source);
+    }
+
+    #[test]
+    fn test_export_rejects_unrepresentable_operations() {
+        let mut circuit = Circuit::new(2, 2);
+        circuit.c_if(&[0], 1, Instruction::gate(BasicGate::PauliX, &[1]));
+        assert!(to_qasm2(&circuit).is_err());
+
+        let mut circuit = Circuit::new(2, 0);
+        circuit.gate(CustomGate { matrix: TwoQubitGate::CZ.matrix(), num_qubits: 2, name: "my_cz".to_string() }, &[0, 1]);
+        assert!(to_qasm2(&circuit).is_err());
+    }
+
+    #[test]
+    fn test_u3_angles_recover_matrix_up_to_phase() {
+        for &(theta, phi, lambda) in &[(0.3, 1.2, -0.7), (PI, 0.5, 0.1), (0.0, 0.0, 1.1), (2.0, -3.0, 2.5)] {
+            let original = u3_matrix(theta, phi, lambda);
+            let (t, p, l) = u3_angles(&original);
+            let recovered = u3_matrix(t, p, l);
+
+            // Матрицы совпадают с точностью до глобальной фазы
+            let overlap: Amplitude = original.iter().zip(&recovered).map(|(a, b)| a.conj() * b).sum();
+            assert!((overlap.norm() - 2.0).abs() < 1e-10);
+        }
+    }
+}
@@ -0,0 +1,249 @@
+//! Вещественные выражения OpenQASM для параметров гейтов.
+//!
+//! Поддерживаются числа, константа `pi` (или `π`), параметры гейтов, операторы
+//! `+ - * / ^`, унарный минус и функции `sin cos tan exp ln sqrt`.
+
+use crate::error::TriadError;
+use crate::qasm::lexer::{self, Cursor, TokenKind};
+use std::collections::HashMap;
+use std::f64::consts::PI;
+
+/// Выражение параметра гейта.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    /// Числовая константа с позицией в исходнике
+    Number { value: f64, line: usize, column: usize },
+    /// Идентификатор (параметр гейта или `pi`) с позицией в исходнике
+    Ident { name: String, line: usize, column: usize },
+    /// Унарный минус
+    Neg(Box<Expr>),
+    /// Бинарная операция: `+`, `-`, `*`, `/` или `^`
+    Binary(&'static str, Box<Expr>, Box<Expr>),
+    /// Вызов встроенной функции с позицией ее имени
+    Call { function: String, arg: Box<Expr>, line: usize, column: usize },
+}
+
+/// Встроенные функции выражений.
+const FUNCTIONS: [&str; 6] = ["sin", "cos", "tan", "exp", "ln", "sqrt"];
+
+/// Наибольшая глубина вложенности выражения: разбор и вычисление рекурсивны,
+/// поэтому более глубокие выражения отклоняются, а не переполняют стек.
+pub const MAX_EXPR_DEPTH: usize = 128;
+
+impl Expr {
+    /// Вычисляет выражение, подставляя значения параметров из `env`.
+    /// Деление на ноль и нечисловые результаты (бесконечность, NaN)
+    /// считаются ошибками разбора в позиции выражения.
+    pub fn eval(&self, env: &HashMap<String, f64>) -> Result<f64, TriadError> {
+        let value = match self {
+            Expr::Number { value, .. } => *value,
+            Expr::Ident { name, line, column } => match env.get(name) {
+                Some(value) => *value,
+                None if name == "pi" || name == "π" => PI,
+                None => return Err(lexer::error(*line, *column, &format!("неизвестный параметр '{}'", name))),
+            },
+            Expr::Neg(inner) => -inner.eval(env)?,
+            Expr::Binary(op, lhs, rhs) => {
+                let (a, b) = (lhs.eval(env)?, rhs.eval(env)?);
+                match *op {
+                    "+" => a + b,
+                    "-" => a - b,
+                    "*" => a * b,
+                    "/" if b == 0.0 => {
+                        let (line, column) = rhs.position();
+                        return Err(lexer::error(line, column, "деление на ноль"));
+                    },
+                    "/" => a / b,
+                    _ => a.powf(b),
+                }
+            },
+            Expr::Call { function, arg, .. } => {
+                let arg = arg.eval(env)?;
+                match function.as_str() {
+                    "sin" => arg.sin(),
+                    "cos" => arg.cos(),
+                    "tan" => arg.tan(),
+                    "exp" => arg.exp(),
+                    "ln" => arg.ln(),
+                    _ => arg.sqrt(),
+                }
+            },
+        };
+
+        if !value.is_finite() {
+            let (line, column) = self.position();
+            return Err(lexer::error(line, column, &format!("значение выражения не является конечным числом: {}", value)));
+        }
+        Ok(value)
+    }
+
+    /// Возвращает позицию начала выражения (строка и столбец).
+    pub fn position(&self) -> (usize, usize) {
+        match self {
+            Expr::Number { line, column, .. } | Expr::Ident { line, column, .. } | Expr::Call { line, column, .. } => (*line, *column),
+            Expr::Neg(inner) => inner.position(),
+            Expr::Binary(_, lhs, _) => lhs.position(),
+        }
+    }
+
+    /// Проверяет, что все идентификаторы выражения входят в `params` (или равны `pi`).
+    pub fn check_idents(&self, params: &[String]) -> Result<(), TriadError> {
+        match self {
+            Expr::Number { .. } => Ok(()),
+            Expr::Ident { name, line, column } => {
+                if name == "pi" || name == "π" || params.contains(name) {
+                    Ok(())
+                } else {
+                    Err(lexer::error(*line, *column, &format!("неизвестный параметр '{}'", name)))
+                }
+            },
+            Expr::Neg(inner) | Expr::Call { arg: inner, .. } => inner.check_idents(params),
+            Expr::Binary(_, lhs, rhs) => {
+                lhs.check_idents(params)?;
+                rhs.check_idents(params)
+            },
+        }
+    }
+}
+
+/// Разбирает выражение: сложение и вычитание.
+pub fn parse_expr(cursor: &mut Cursor) -> Result<Expr, TriadError> {
+    let mut lhs = parse_term(cursor)?;
+    // Каждая операция цепочки углубляет дерево на один уровень
+    let mut levels = 0;
+    loop {
+        let op = if cursor.eat_symbol("+") {
+            "+"
+        } else if cursor.eat_symbol("-") {
+            "-"
+        } else {
+            cursor.leave(levels);
+            return Ok(lhs);
+        };
+        cursor.enter(MAX_EXPR_DEPTH)?;
+        levels += 1;
+        lhs = Expr::Binary(op, Box::new(lhs), Box::new(parse_term(cursor)?));
+    }
+}
+
+/// Разбирает умножение и деление.
+fn parse_term(cursor: &mut Cursor) -> Result<Expr, TriadError> {
+    let mut lhs = parse_unary(cursor)?;
+    let mut levels = 0;
+    loop {
+        let op = if cursor.eat_symbol("*") {
+            "*"
+        } else if cursor.eat_symbol("/") {
+            "/"
+        } else {
+            cursor.leave(levels);
+            return Ok(lhs);
+        };
+        cursor.enter(MAX_EXPR_DEPTH)?;
+        levels += 1;
+        lhs = Expr::Binary(op, Box::new(lhs), Box::new(parse_unary(cursor)?));
+    }
+}
+
+/// Разбирает унарный минус и возведение в степень (правоассоциативное).
+fn parse_unary(cursor: &mut Cursor) -> Result<Expr, TriadError> {
+    // Скобки, вызовы функций и унарные операторы рекурсивно проходят через
+    // этот разбор, поэтому глубина ограничивается здесь
+    cursor.enter(MAX_EXPR_DEPTH)?;
+    let expr = parse_unary_inner(cursor)?;
+    cursor.leave(1);
+    Ok(expr)
+}
+
+/// Разбирает унарный оператор или степень на текущем уровне вложенности.
+fn parse_unary_inner(cursor: &mut Cursor) -> Result<Expr, TriadError> {
+    if cursor.eat_symbol("-") {
+        return Ok(Expr::Neg(Box::new(parse_unary(cursor)?)));
+    }
+    if cursor.eat_symbol("+") {
+        return parse_unary(cursor);
+    }
+
+    let base = parse_primary(cursor)?;
+    if cursor.eat_symbol("^") || cursor.eat_symbol("**") {
+        return Ok(Expr::Binary("^", Box::new(base), Box::new(parse_unary(cursor)?)));
+    }
+    Ok(base)
+}
+
+/// Разбирает числа, идентификаторы, вызовы функций и скобки.
+fn parse_primary(cursor: &mut Cursor) -> Result<Expr, TriadError> {
+    let token = cursor.peek().clone();
+    match token.kind {
+        TokenKind::Int(value) => {
+            cursor.next_token();
+            Ok(Expr::Number { value: value as f64, line: token.line, column: token.column })
+        },
+        TokenKind::Real(value) => {
+            cursor.next_token();
+            Ok(Expr::Number { value, line: token.line, column: token.column })
+        },
+        TokenKind::Ident(name) => {
+            cursor.next_token();
+            if FUNCTIONS.contains(&name.as_str()) && cursor.at_symbol("(") {
+                cursor.expect_symbol("(")?;
+                let arg = parse_expr(cursor)?;
+                cursor.expect_symbol(")")?;
+                Ok(Expr::Call { function: name, arg: Box::new(arg), line: token.line, column: token.column })
+            } else {
+                Ok(Expr::Ident { name, line: token.line, column: token.column })
+            }
+        },
+        TokenKind::Symbol("(") => {
+            cursor.next_token();
+            let inner = parse_expr(cursor)?;
+            cursor.expect_symbol(")")?;
+            Ok(inner)
+        },
+        _ => Err(cursor.error("ожидалось выражение")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::qasm::lexer::tokenize;
+
+    fn eval(source: &str, env: &[(&str, f64)]) -> Result<f64, TriadError> {
+        let mut cursor = Cursor::new(tokenize(source)?);
+        let expr = parse_expr(&mut cursor)?;
+        let env = env.iter().map(|(name, value)| (name.to_string(), *value)).collect();
+        expr.eval(&env)
+    }
+
+    #[test]
+    fn test_precedence_and_functions() {
+        assert!((eval("-pi/2 + 2*3^2", &[]).unwrap() - (18.0 - PI / 2.0)).abs() < 1e-12);
+        assert!((eval("sqrt(4) * cos(theta)", &[("theta", 0.0)]).unwrap() - 2.0).abs() < 1e-12);
+        assert!((eval("-(lambda+phi)/2", &[("lambda", 1.0), ("phi", 2.0)]).unwrap() + 1.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_unknown_parameter_reports_position() {
+        assert_eq!(eval("1 + gamma", &[]), Err(lexer::error(1, 5, "неизвестный параметр 'gamma'")));
+    }
+
+    #[test]
+    fn test_non_finite_values_are_rejected() {
+        assert_eq!(eval("1 / (2 - 2)", &[]), Err(lexer::error(1, 6, "деление на ноль")));
+        assert!(matches!(eval("2 * ln(x)", &[("x", 0.0)]), Err(TriadError::Parse { line: 1, column: 5, .. })));
+        assert!(matches!(eval("sqrt(-1)", &[]), Err(TriadError::Parse { line: 1, column: 1, .. })));
+    }
+
+    #[test]
+    fn test_nesting_depth_is_limited() {
+        let nested = format!("{}1{}", "(".repeat(100), ")".repeat(100));
+        assert_eq!(eval(&nested, &[]), Ok(1.0));
+
+        let too_deep = format!("{}1{}", "(".repeat(10_000), ")".repeat(10_000));
+        assert!(matches!(eval(&too_deep, &[]), Err(TriadError::Parse { line: 1, .. })));
+        assert!(eval(&"-".repeat(10_000), &[]).is_err());
+        assert!(eval(&format!("1{}", "+1".repeat(10_000)), &[]).is_err());
+        assert!(eval(&format!("2{}", "^2".repeat(10_000)), &[]).is_err());
+    }
+}
@@ -0,0 +1,345 @@
+//! Лексический анализатор OpenQASM.
+//!
+//! Разбивает исходный текст на токены с позициями (строка и столбец),
+//! чтобы ошибки разбора указывали точное место в исходнике.
+
+use crate::error::TriadError;
+
+/// Вид токена.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TokenKind {
+    /// Идентификатор или ключевое слово
+    Ident(String),
+    /// Целое число
+    Int(u64),
+    /// Вещественное число
+    Real(f64),
+    /// Строковый литерал (без кавычек)
+    Str(String),
+    /// Знак пунктуации или оператор (`;`, `->`, `==`, `@` и т.д.)
+    Symbol(&'static str),
+    /// Конец входных данных
+    Eof,
+}
+
+/// Токен с позицией начала в исходном тексте (нумерация с 1).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Token {
+    /// Вид токена.
+    pub kind: TokenKind,
+    /// Номер строки.
+    pub line: usize,
+    /// Номер столбца.
+    pub column: usize,
+}
+
+/// Многосимвольные операторы проверяются раньше односимвольных.
+const SYMBOLS: [&str; 32] = [
+    "->", "==", "!=", "<=", ">=", "&&", "||", "++", "+=", "-=", "**",
+    ";", ",", "[", "]", "(", ")", "{", "}", "+", "-", "*", "/", "^", "<", ">", "=", "@", ":", "!", "~", "%",
+];
+
+/// Разбивает исходный текст на токены. Последний токен всегда `Eof`.
+pub fn tokenize(source: &str) -> Result<Vec<Token>, TriadError> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+    let mut line = 1;
+    let mut column = 1;
+
+    while pos < chars.len() {
+        let c = chars[pos];
+
+        // Пробелы и переводы строк
+        if c.is_whitespace() {
+            if c == '\n' {
+                line += 1;
+                column = 1;
+            } else {
+                column += 1;
+            }
+            pos += 1;
+            continue;
+        }
+
+        // Однострочные комментарии
+        if c == '/' && chars.get(pos + 1) == Some(&'/') {
+            while pos < chars.len() && chars[pos] != '\n' {
+                pos += 1;
+            }
+            continue;
+        }
+
+        // Многострочные комментарии
+        if c == '/' && chars.get(pos + 1) == Some(&'*') {
+            let (start_line, start_column) = (line, column);
+            pos += 2;
+            column += 2;
+            loop {
+                match chars.get(pos) {
+                    None => return Err(error(start_line, start_column, "незакрытый комментарий")),
+                    Some('*') if chars.get(pos + 1) == Some(&'/') => {
+                        pos += 2;
+                        column += 2;
+                        break;
+                    },
+                    Some('\n') => {
+                        line += 1;
+                        column = 1;
+                        pos += 1;
+                    },
+                    Some(_) => {
+                        column += 1;
+                        pos += 1;
+                    },
+                }
+            }
+            continue;
+        }
+
+        let start = pos;
+        let kind = if c.is_alphabetic() || c == '_' || c == '$' {
+            // OpenQASM 3 допускает Unicode-идентификаторы (например, θ и π)
+            while pos < chars.len() && (chars[pos].is_alphanumeric() || chars[pos] == '_' || chars[pos] == '$') {
+                pos += 1;
+            }
+            TokenKind::Ident(chars[start..pos].iter().collect())
+        } else if c.is_ascii_digit() || (c == '.' && chars.get(pos + 1).is_some_and(|d| d.is_ascii_digit())) {
+            lex_number(&chars, &mut pos).ok_or_else(|| error(line, column, "некорректное число"))?
+        } else if c == '"' {
+            pos += 1;
+            while pos < chars.len() && chars[pos] != '"' && chars[pos] != '\n' {
+                pos += 1;
+            }
+            if chars.get(pos) != Some(&'"') {
+                return Err(error(line, column, "незакрытая строка"));
+            }
+            pos += 1;
+            TokenKind::Str(chars[start + 1..pos - 1].iter().collect())
+        } else {
+            let symbol = SYMBOLS.iter()
+                .find(|symbol| symbol.chars().enumerate().all(|(i, s)| chars.get(pos + i) == Some(&s)))
+                .ok_or_else(|| error(line, column, &format!("неожиданный символ '{}'", c)))?;
+            pos += symbol.len();
+            TokenKind::Symbol(symbol)
+        };
+
+        tokens.push(Token { kind, line, column });
+        column += pos - start;
+    }
+
+    tokens.push(Token { kind: TokenKind::Eof, line, column });
+    Ok(tokens)
+}
+
+/// Читает целое или вещественное число (с необязательной экспонентой).
+fn lex_number(chars: &[char], pos: &mut usize) -> Option<TokenKind> {
+    let start = *pos;
+    let mut is_real = false;
+
+    while *pos < chars.len() && chars[*pos].is_ascii_digit() {
+        *pos += 1;
+    }
+    if chars.get(*pos) == Some(&'.') {
+        is_real = true;
+        *pos += 1;
+        while *pos < chars.len() && chars[*pos].is_ascii_digit() {
+            *pos += 1;
+        }
+    }
+    if matches!(chars.get(*pos), Some('e') | Some('E')) {
+        let mut end = *pos + 1;
+        if matches!(chars.get(end), Some('+') | Some('-')) {
+            end += 1;
+        }
+        if chars.get(end).is_some_and(|d| d.is_ascii_digit()) {
+            is_real = true;
+            *pos = end;
+            while *pos < chars.len() && chars[*pos].is_ascii_digit() {
+                *pos += 1;
+            }
+        }
+    }
+
+    let text: String = chars[start..*pos].iter().collect();
+    if is_real {
+        text.parse().ok().map(TokenKind::Real)
+    } else {
+        text.parse().ok().map(TokenKind::Int)
+    }
+}
+
+/// Курсор по списку токенов с вспомогательными методами для парсеров.
+#[derive(Debug, Clone)]
+pub struct Cursor {
+    tokens: Vec<Token>,
+    pos: usize,
+    depth: usize,
+}
+
+impl Cursor {
+    /// Создает курсор по токенам, полученным из `tokenize`.
+    pub fn new(tokens: Vec<Token>) -> Self {
+        Self { tokens, pos: 0, depth: 0 }
+    }
+
+    /// Возвращает текущий токен, не продвигая курсор.
+    pub fn peek(&self) -> &Token {
+        &self.tokens[self.pos.min(self.tokens.len() - 1)]
+    }
+
+    /// Возвращает токен, следующий за текущим.
+    pub fn peek_next(&self) -> &Token {
+        &self.tokens[(self.pos + 1).min(self.tokens.len() - 1)]
+    }
+
+    /// Возвращает текущий токен и продвигает курсор.
+    pub fn next_token(&mut self) -> Token {
+        let token = self.peek().clone();
+        if self.pos < self.tokens.len() - 1 {
+            self.pos += 1;
+        }
+        token
+    }
+
+    /// Возвращает текущую позицию курсора.
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// Возвращает курсор в ранее сохраненную позицию.
+    pub fn seek(&mut self, position: usize) {
+        self.pos = position.min(self.tokens.len() - 1);
+    }
+
+    /// Увеличивает глубину вложенности, возвращая ошибку в текущей позиции,
+    /// если она превышает `limit`.
+    pub fn enter(&mut self, limit: usize) -> Result<(), TriadError> {
+        if self.depth >= limit {
+            return Err(self.error(&format!("превышена глубина вложенности {}", limit)));
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
+    /// Уменьшает глубину вложенности, увеличенную `enter`.
+    pub fn leave(&mut self, levels: usize) {
+        self.depth -= levels;
+    }
+
+    /// Проверяет, достигнут ли конец входных данных.
+    pub fn at_eof(&self) -> bool {
+        self.peek().kind == TokenKind::Eof
+    }
+
+    /// Проверяет, является ли текущий токен указанным символом.
+    pub fn at_symbol(&self, symbol: &str) -> bool {
+        matches!(self.peek().kind, TokenKind::Symbol(s) if s == symbol)
+    }
+
+    /// Проверяет, является ли текущий токен указанным ключевым словом.
+    pub fn at_keyword(&self, keyword: &str) -> bool {
+        matches!(&self.peek().kind, TokenKind::Ident(name) if name == keyword)
+    }
+
+    /// Пропускает символ, если он является текущим токеном.
+    pub fn eat_symbol(&mut self, symbol: &str) -> bool {
+        if self.at_symbol(symbol) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Пропускает ключевое слово, если оно является текущим токеном.
+    pub fn eat_keyword(&mut self, keyword: &str) -> bool {
+        if self.at_keyword(keyword) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Требует указанный символ.
+    pub fn expect_symbol(&mut self, symbol: &str) -> Result<(), TriadError> {
+        if self.eat_symbol(symbol) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("ожидалось '{}'", symbol)))
+        }
+    }
+
+    /// Требует указанное ключевое слово.
+    pub fn expect_keyword(&mut self, keyword: &str) -> Result<(), TriadError> {
+        if self.eat_keyword(keyword) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("ожидалось '{}'", keyword)))
+        }
+    }
+
+    /// Требует идентификатор и возвращает его вместе с токеном.
+    pub fn expect_ident(&mut self) -> Result<(String, Token), TriadError> {
+        match &self.peek().kind {
+            TokenKind::Ident(name) => {
+                let name = name.clone();
+                Ok((name, self.next_token()))
+            },
+            _ => Err(self.error("ожидался идентификатор")),
+        }
+    }
+
+    /// Требует неотрицательное целое число.
+    pub fn expect_int(&mut self) -> Result<u64, TriadError> {
+        match self.peek().kind {
+            TokenKind::Int(value) => {
+                self.next_token();
+                Ok(value)
+            },
+            _ => Err(self.error("ожидалось целое число")),
+        }
+    }
+
+    /// Создает ошибку разбора в позиции текущего токена.
+    pub fn error(&self, message: &str) -> TriadError {
+        let token = self.peek();
+        let found = match &token.kind {
+            TokenKind::Ident(name) => format!("'{}'", name),
+            TokenKind::Int(value) => format!("'{}'", value),
+            TokenKind::Real(value) => format!("'{}'", value),
+            TokenKind::Str(text) => format!("\"{}\"", text),
+            TokenKind::Symbol(symbol) => format!("'{}'", symbol),
+            TokenKind::Eof => String::from("конец файла"),
+        };
+        error(token.line, token.column, &format!("{}, найдено {}", message, found))
+    }
+}
+
+/// Создает ошибку разбора в указанной позиции.
+pub fn error(line: usize, column: usize, message: &str) -> TriadError {
+    TriadError::Parse { line, column, message: message.to_string() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokenize_positions_and_kinds() {
+        let tokens = tokenize("qreg q[2];\n// комментарий\nmeasure q -> c; rx(1.5e-1) q;").unwrap();
+
+        assert_eq!(tokens[0], Token { kind: TokenKind::Ident("qreg".into()), line: 1, column: 1 });
+        assert_eq!(tokens[3].kind, TokenKind::Int(2));
+        assert_eq!((tokens[6].line, tokens[6].column), (3, 1));
+        assert_eq!(tokens[8].kind, TokenKind::Symbol("->"));
+        assert_eq!(tokens[13].kind, TokenKind::Real(0.15));
+        assert_eq!(tokens.last().unwrap().kind, TokenKind::Eof);
+    }
+
+    #[test]
+    fn test_tokenize_reports_position_of_bad_character() {
+        assert_eq!(tokenize("h q;\n  x q#"), Err(error(2, 6, "неожиданный символ '#'")));
+    }
+}
@@ -0,0 +1,46 @@
+//! Импорт и экспорт квантовых схем в формате OpenQASM.
+//!
+//! Разбор программ строит схемы `core::circuit::Circuit`, которые затем можно
+//! выполнить на любом симуляторе; экспорт превращает схему обратно в текст.
+
+/// Лексический анализ исходного текста
+pub mod lexer;
+
+/// Вещественные выражения параметров гейтов
+pub mod expr;
+
+/// Разбор OpenQASM 2.0
+pub mod qasm2;
+
+/// Разбор подмножества OpenQASM 3
+pub mod qasm3;
+
+/// Экспорт схем в OpenQASM 2.0
+pub mod export;
+
+pub use export::to_qasm2;
+pub use qasm2::parse_qasm2;
+pub use qasm3::parse_qasm3;
+
+use crate::core::circuit::Circuit;
+use crate::error::TriadError;
+use lexer::{TokenKind, tokenize};
+
+/// Разбирает программу OpenQASM 2.0 или 3, выбирая разборщик по заголовку.
+pub fn parse_qasm(source: &str) -> Result<Circuit, TriadError> {
+    let tokens = tokenize(source)?;
+    let major = match (tokens.first().map(|t| &t.kind), tokens.get(1).map(|t| &t.kind)) {
+        (Some(TokenKind::Ident(keyword)), Some(TokenKind::Real(version))) if keyword == "OPENQASM" => version.trunc() as u64,
+        (Some(TokenKind::Ident(keyword)), Some(TokenKind::Int(version))) if keyword == "OPENQASM" => *version,
+        _ => {
+            let (line, column) = tokens.first().map_or((1, 1), |t| (t.line, t.column));
+            return Err(lexer::error(line, column, "ожидался заголовок OPENQASM <версия>;"));
+        },
+    };
+
+    match major {
+        2 => parse_qasm2(source),
+        3 => parse_qasm3(source),
+        _ => Err(lexer::error(tokens[1].line, tokens[1].column, &format!("версия OpenQASM {} не поддерживается", major))),
+    }
+}
@@ -0,0 +1,737 @@
+//! Разбор программ OpenQASM 2.0 в схемы `Circuit`.
+//!
+//! Поддерживаются регистры `qreg`/`creg`, встроенные гейты `U` и `CX`,
+//! библиотека `qelib1.inc`, пользовательские определения `gate`,
+//! `measure`, `reset`, `barrier` и условные операции `if (c == n)`.
+//! Гейты библиотеки, для которых в `core::gates` есть собственные типы,
+//! создаются напрямую; остальные, как и пользовательские гейты, раскрываются
+//! по своим определениям (не более `MAX_EXPANSION_STEPS` инструкций).
+
+use crate::core::circuit::{Circuit, CircuitGate, Instruction, Register};
+use crate::core::gates::{Axis, BasicGate, CustomGate, Gate, ParametricGate, RotationGate, TwoQubitGate};
+use crate::core::quantum_state::Amplitude;
+use crate::error::TriadError;
+use crate::qasm::expr::{Expr, MAX_EXPR_DEPTH, parse_expr};
+use crate::qasm::lexer::{self, Cursor, Token, TokenKind, tokenize};
+use std::cell::Cell;
+use std::collections::HashMap;
+
+/// Исходный текст стандартной библиотеки `qelib1.inc`.
+const QELIB1: &str = include_str!("qelib1.inc");
+
+/// Наибольшее суммарное число кубитов (или классических битов)
+/// во всех регистрах программы.
+pub const MAX_REGISTER_BITS: usize = 1 << 20;
+
+/// Наибольший объем работы при раскрытии программы в инструкции схемы:
+/// суммарное число порожденных гейтов и инструкций, а в OpenQASM 3 —
+/// также итераций циклов.
+pub const MAX_EXPANSION_STEPS: usize = 1_000_000;
+
+/// Вызов гейта внутри определения `gate`.
+#[derive(Debug, Clone)]
+struct GateCall {
+    /// Имя вызываемого гейта.
+    name: String,
+    /// Выражения параметров.
+    params: Vec<Expr>,
+    /// Индексы аргументов определения, к которым применяется гейт.
+    args: Vec<usize>,
+}
+
+/// Определение гейта, объявленное через `gate`.
+#[derive(Debug, Clone)]
+struct GateDef {
+    /// Имена параметров.
+    params: Vec<String>,
+    /// Число кубитов-аргументов.
+    num_qubits: usize,
+    /// Тело определения.
+    body: Vec<GateCall>,
+    /// Глубина вложенности определений: 1, если тело вызывает только `U` и `CX`.
+    depth: usize,
+    /// Определение взято из `qelib1.inc` и заменяется собственным гейтом TRIAD.
+    library: bool,
+}
+
+/// Аргумент операции: весь регистр или его отдельный элемент.
+#[derive(Debug, Clone)]
+struct Argument {
+    /// Имя регистра.
+    register: String,
+    /// Индекс внутри регистра (`None` — весь регистр).
+    index: Option<usize>,
+    /// Токен начала аргумента (для сообщений об ошибках).
+    token: Token,
+}
+
+/// Регистр с его смещением в общей нумерации схемы.
+#[derive(Debug, Clone)]
+struct DeclaredRegister {
+    /// Имя регистра.
+    name: String,
+    /// Индекс первого бита регистра в схеме.
+    offset: usize,
+    /// Число битов.
+    size: usize,
+}
+
+/// Разбирает программу OpenQASM 2.0 и строит по ней схему.
+///
+/// Кубиты и классические биты нумеруются подряд в порядке объявления регистров.
+/// Ошибки содержат строку и столбец места, где разбор был прерван.
+pub fn parse_qasm2(source: &str) -> Result<Circuit, TriadError> {
+    let mut parser = Parser::new(tokenize(source)?);
+    parser.parse_header()?;
+    parser.parse_program()?;
+    parser.finish()
+}
+
+/// Парсер программ OpenQASM 2.0.
+struct Parser {
+    /// Курсор по токенам текущего исходника.
+    cursor: Cursor,
+    /// Объявленные квантовые регистры.
+    qregs: Vec<DeclaredRegister>,
+    /// Объявленные классические регистры.
+    cregs: Vec<DeclaredRegister>,
+    /// Определения гейтов (включая `qelib1.inc`).
+    gates: HashMap<String, GateDef>,
+    /// Разобранные инструкции.
+    instructions: Vec<Instruction>,
+    /// Разбирается встроенная библиотека `qelib1.inc`.
+    in_library: bool,
+    /// Бюджет работы при раскрытии гейтов.
+    budget: Budget,
+}
+
+impl Parser {
+    /// Создает парсер по списку токенов.
+    fn new(tokens: Vec<Token>) -> Self {
+        Self {
+            cursor: Cursor::new(tokens),
+            qregs: Vec::new(),
+            cregs: Vec::new(),
+            gates: HashMap::new(),
+            instructions: Vec::new(),
+            in_library: false,
+            budget: Budget::new(),
+        }
+    }
+
+    /// Разбирает обязательный заголовок `OPENQASM 2.0;`.
+    fn parse_header(&mut self) -> Result<(), TriadError> {
+        self.cursor.expect_keyword("OPENQASM")?;
+        let version = match self.cursor.peek().kind {
+            TokenKind::Real(version) => version,
+            TokenKind::Int(version) => version as f64,
+            _ => return Err(self.cursor.error("ожидалась версия OpenQASM")),
+        };
+        if version != 2.0 {
+            return Err(self.cursor.error("поддерживается только OpenQASM 2.0"));
+        }
+        self.cursor.next_token();
+        self.cursor.expect_symbol(";")
+    }
+
+    /// Разбирает операторы до конца входных данных.
+    fn parse_program(&mut self) -> Result<(), TriadError> {
+        while !self.cursor.at_eof() {
+            self.parse_statement()?;
+        }
+        Ok(())
+    }
+
+    /// Собирает схему из объявленных регистров и разобранных инструкций.
+    fn finish(self) -> Result<Circuit, TriadError> {
+        let to_registers = |regs: &[DeclaredRegister]| -> Vec<Register> {
+            regs.iter().map(|r| Register::new(&r.name, r.size)).collect()
+        };
+
+        let mut circuit = Circuit::with_registers(to_registers(&self.qregs), to_registers(&self.cregs));
+        for instruction in self.instructions {
+            circuit.try_push(instruction)?;
+        }
+        Ok(circuit)
+    }
+
+    /// Разбирает один оператор верхнего уровня.
+    fn parse_statement(&mut self) -> Result<(), TriadError> {
+        let (keyword, token) = self.cursor.expect_ident()?;
+
+        match keyword.as_str() {
+            "include" => self.parse_include(&token),
+            "qreg" => self.parse_register(true),
+            "creg" => self.parse_register(false),
+            "gate" => self.parse_gate_definition(),
+            "opaque" => Err(lexer::error(token.line, token.column, "непрозрачные гейты (opaque) не поддерживаются")),
+            "if" => self.parse_conditional(),
+            _ => {
+                let instructions = self.parse_operation(keyword, token)?;
+                self.instructions.extend(instructions);
+                Ok(())
+            },
+        }
+    }
+
+    /// Разбирает `include "qelib1.inc";`.
+    fn parse_include(&mut self, token: &Token) -> Result<(), TriadError> {
+        let file = match &self.cursor.peek().kind {
+            TokenKind::Str(file) => file.clone(),
+            _ => return Err(self.cursor.error("ожидалось имя файла в кавычках")),
+        };
+        self.cursor.next_token();
+        self.cursor.expect_symbol(";")?;
+
+        if file != "qelib1.inc" {
+            return Err(lexer::error(token.line, token.column, &format!("поддерживается только include \"qelib1.inc\", найдено \"{}\"", file)));
+        }
+
+        // Разбираем библиотеку отдельным парсером, разделяющим таблицу гейтов
+        let mut library = Parser::new(tokenize(QELIB1)?);
+        library.gates = std::mem::take(&mut self.gates);
+        library.in_library = true;
+        library.parse_program()?;
+        self.gates = library.gates;
+        Ok(())
+    }
+
+    /// Разбирает объявление `qreg name[size];` или `creg name[size];`.
+    fn parse_register(&mut self, quantum: bool) -> Result<(), TriadError> {
+        let (name, token) = self.cursor.expect_ident()?;
+        self.cursor.expect_symbol("[")?;
+        let size = self.cursor.expect_int()? as usize;
+        self.cursor.expect_symbol("]")?;
+        self.cursor.expect_symbol(";")?;
+
+        if size == 0 {
+            return Err(lexer::error(token.line, token.column, "размер регистра должен быть положительным"));
+        }
+        if self.find_register(&name, true).is_some() || self.find_register(&name, false).is_some() {
+            return Err(lexer::error(token.line, token.column, &format!("регистр '{}' уже объявлен", name)));
+        }
+
+        let registers = if quantum { &mut self.qregs } else { &mut self.cregs };
+        let offset = registers.iter().map(|r| r.size).sum();
+        check_register_size(offset, size, &token)?;
+        registers.push(DeclaredRegister { name, offset, size });
+        Ok(())
+    }
+
+    /// Ищет объявленный регистр по имени.
+    fn find_register(&self, name: &str, quantum: bool) -> Option<&DeclaredRegister> {
+        let registers = if quantum { &self.qregs } else { &self.cregs };
+        registers.iter().find(|r| r.name == name)
+    }
+
+    /// Разбирает определение `gate name(params) args { body }`.
+    fn parse_gate_definition(&mut self) -> Result<(), TriadError> {
+        let (name, token) = self.cursor.expect_ident()?;
+        if name == "U" || name == "CX" || self.gates.contains_key(&name) {
+            return Err(lexer::error(token.line, token.column, &format!("гейт '{}' уже определен", name)));
+        }
+
+        let mut params = Vec::new();
+        if self.cursor.eat_symbol("(") && !self.cursor.eat_symbol(")") {
+            params = self.parse_ident_list()?;
+            self.cursor.expect_symbol(")")?;
+        }
+        let qargs = self.parse_ident_list()?;
+
+        self.cursor.expect_symbol("{")?;
+        let mut body = Vec::new();
+        while !self.cursor.eat_symbol("}") {
+            if self.cursor.eat_keyword("barrier") {
+                // Барьеры внутри определений не влияют на результат
+                self.parse_ident_list()?;
+                self.cursor.expect_symbol(";")?;
+                continue;
+            }
+            body.push(self.parse_gate_call(&params, &qargs)?);
+        }
+
+        // Определения раскрываются рекурсивно
+        let depth = 1 + body.iter().filter_map(|call| self.gates.get(&call.name)).map(|def| def.depth).max().unwrap_or(0);
+        if depth > MAX_EXPR_DEPTH {
+            return Err(lexer::error(token.line, token.column, &format!(
+                "вложенность определений гейтов превышает {}", MAX_EXPR_DEPTH)));
+        }
+        let library = self.in_library;
+        self.gates.insert(name, GateDef { params, num_qubits: qargs.len(), body, depth, library });
+        Ok(())
+    }
+
+    /// Разбирает вызов гейта внутри тела определения.
+    fn parse_gate_call(&mut self, params: &[String], qargs: &[String]) -> Result<GateCall, TriadError> {
+        let (name, token) = self.cursor.expect_ident()?;
+        let exprs = self.parse_params()?;
+        for expr in &exprs {
+            expr.check_idents(params)?;
+        }
+
+        let mut args = Vec::new();
+        loop {
+            let (arg, arg_token) = self.cursor.expect_ident()?;
+            let index = qargs.iter().position(|q| *q == arg)
+                .ok_or_else(|| lexer::error(arg_token.line, arg_token.column, &format!("неизвестный аргумент '{}'", arg)))?;
+            if args.contains(&index) {
+                return Err(lexer::error(arg_token.line, arg_token.column, &format!("аргумент '{}' указан несколько раз", arg)));
+            }
+            args.push(index);
+            if !self.cursor.eat_symbol(",") {
+                break;
+            }
+        }
+        self.cursor.expect_symbol(";")?;
+
+        self.check_signature(&name, exprs.len(), args.len(), &token)?;
+        Ok(GateCall { name, params: exprs, args })
+    }
+
+    /// Разбирает список идентификаторов через запятую.
+    fn parse_ident_list(&mut self) -> Result<Vec<String>, TriadError> {
+        let mut names = vec![self.cursor.expect_ident()?.0];
+        while self.cursor.eat_symbol(",") {
+            names.push(self.cursor.expect_ident()?.0);
+        }
+        Ok(names)
+    }
+
+    /// Разбирает необязательный список параметров `(expr, ...)`.
+    fn parse_params(&mut self) -> Result<Vec<Expr>, TriadError> {
+        let mut params = Vec::new();
+        if self.cursor.eat_symbol("(") && !self.cursor.eat_symbol(")") {
+            params.push(parse_expr(&mut self.cursor)?);
+            while self.cursor.eat_symbol(",") {
+                params.push(parse_expr(&mut self.cursor)?);
+            }
+            self.cursor.expect_symbol(")")?;
+        }
+        Ok(params)
+    }
+
+    /// Проверяет, что гейт определен и вызван с правильным числом параметров и кубитов.
+    fn check_signature(&self, name: &str, num_params: usize, num_qubits: usize, token: &Token) -> Result<(), TriadError> {
+        let (expected_params, expected_qubits) = match name {
+            "U" => (3, 1),
+            "CX" => (0, 2),
+            _ => match self.gates.get(name) {
+                Some(def) => (def.params.len(), def.num_qubits),
+                None => return Err(lexer::error(token.line, token.column, &format!("неизвестный гейт '{}'", name))),
+            },
+        };
+
+        if num_params != expected_params || num_qubits != expected_qubits {
+            return Err(lexer::error(token.line, token.column, &format!(
+                "гейт '{}' принимает {} параметр(ов) и {} кубит(ов), указано {} и {}",
+                name, expected_params, expected_qubits, num_params, num_qubits)));
+        }
+        Ok(())
+    }
+
+    /// Разбирает `if (creg == value) операция;`.
+    fn parse_conditional(&mut self) -> Result<(), TriadError> {
+        self.cursor.expect_symbol("(")?;
+        let (name, token) = self.cursor.expect_ident()?;
+        self.cursor.expect_symbol("==")?;
+        let value = self.cursor.expect_int()?;
+        self.cursor.expect_symbol(")")?;
+
+        let register = self.find_register(&name, false)
+            .ok_or_else(|| lexer::error(token.line, token.column, &format!("неизвестный классический регистр '{}'", name)))?;
+        if register.size < 64 && value >> register.size != 0 {
+            return Err(lexer::error(token.line, token.column, &format!(
+                "значение {} не помещается в регистр '{}' из {} бит", value, name, register.size)));
+        }
+        let cbits: Vec<usize> = (register.offset..register.offset + register.size).collect();
+
+        let (keyword, op_token) = self.cursor.expect_ident()?;
+        if matches!(keyword.as_str(), "if" | "barrier" | "gate" | "qreg" | "creg" | "include" | "opaque") {
+            return Err(lexer::error(op_token.line, op_token.column, &format!("'{}' не может быть условной операцией", keyword)));
+        }
+
+        for instruction in self.parse_operation(keyword, op_token)? {
+            self.instructions.push(Instruction::Conditional {
+                cbits: cbits.clone(),
+                value,
+                instruction: Box::new(instruction),
+            });
+        }
+        Ok(())
+    }
+
+    /// Разбирает квантовую операцию (`measure`, `reset`, `barrier` или вызов гейта)
+    /// и возвращает полученные инструкции.
+    fn parse_operation(&mut self, keyword: String, token: Token) -> Result<Vec<Instruction>, TriadError> {
+        match keyword.as_str() {
+            "measure" => {
+                let qubits = self.parse_argument(true)?;
+                self.cursor.expect_symbol("->")?;
+                let cbits = self.parse_argument(false)?;
+                self.cursor.expect_symbol(";")?;
+
+                let resolved = [self.resolve_arg(&qubits, true)?, self.resolve_arg(&cbits, false)?];
+                let pairs = broadcast(&resolved, &token)?;
+                self.budget.spend(pairs.len(), &token)?;
+                Ok(pairs.into_iter().map(|p| Instruction::Measure { qubit: p[0], cbit: p[1] }).collect())
+            },
+            "reset" => {
+                let qubits = self.parse_argument(true)?;
+                self.cursor.expect_symbol(";")?;
+                let resolved = self.resolve(&qubits, true)?;
+                self.budget.spend(resolved.len(), &token)?;
+                Ok(resolved.into_iter().map(Instruction::Reset).collect())
+            },
+            "barrier" => {
+                let mut qubits = Vec::new();
+                loop {
+                    let arg = self.parse_argument(true)?;
+                    qubits.extend(self.resolve(&arg, true)?);
+                    if !self.cursor.eat_symbol(",") {
+                        break;
+                    }
+                }
+                self.cursor.expect_symbol(";")?;
+                qubits.dedup();
+                self.budget.spend(1, &token)?;
+                Ok(vec![Instruction::Barrier(qubits)])
+            },
+            _ => {
+                let params = self.parse_params()?;
+                let mut args = vec![self.parse_argument(true)?];
+                while self.cursor.eat_symbol(",") {
+                    args.push(self.parse_argument(true)?);
+                }
+                self.cursor.expect_symbol(";")?;
+
+                self.check_signature(&keyword, params.len(), args.len(), &token)?;
+                let env = HashMap::new();
+                let values = params.iter().map(|p| p.eval(&env)).collect::<Result<Vec<f64>, TriadError>>()?;
+
+                let resolved = args.iter()
+                    .map(|arg| self.resolve_arg(arg, true))
+                    .collect::<Result<Vec<_>, TriadError>>()?;
+
+                let mut instructions = Vec::new();
+                for qubits in broadcast(&resolved, &token)? {
+                    if (1..qubits.len()).any(|i| qubits[..i].contains(&qubits[i])) {
+                        return Err(lexer::error(token.line, token.column, &format!("гейт '{}' применен к одному кубиту несколько раз", keyword)));
+                    }
+                    self.expand(&keyword, &values, &qubits, &token, &mut instructions)?;
+                }
+                Ok(instructions)
+            },
+        }
+    }
+
+    /// Разбирает аргумент `name` или `name[index]`.
+    fn parse_argument(&mut self, quantum: bool) -> Result<Argument, TriadError> {
+        let (register, token) = self.cursor.expect_ident()?;
+        let index = if self.cursor.eat_symbol("[") {
+            let index = self.cursor.expect_int()? as usize;
+            self.cursor.expect_symbol("]")?;
+            Some(index)
+        } else {
+            None
+        };
+
+        let argument = Argument { register, index, token };
+        self.resolve(&argument, quantum)?;
+        Ok(argument)
+    }
+
+    /// Преобразует аргумент в список индексов схемы.
+    fn resolve(&self, argument: &Argument, quantum: bool) -> Result<Vec<usize>, TriadError> {
+        let token = &argument.token;
+        let register = self.find_register(&argument.register, quantum).ok_or_else(|| {
+            let kind = if quantum { "квантовый" } else { "классический" };
+            lexer::error(token.line, token.column, &format!("неизвестный {} регистр '{}'", kind, argument.register))
+        })?;
+
+        match argument.index {
+            Some(index) if index >= register.size => Err(lexer::error(token.line, token.column, &format!(
+                "индекс {} выходит за пределы регистра '{}' размера {}", index, register.name, register.size))),
+            Some(index) => Ok(vec![register.offset + index]),
+            None => Ok((register.offset..register.offset + register.size).collect()),
+        }
+    }
+
+    /// Преобразует аргумент в индексы схемы с признаком «весь регистр».
+    fn resolve_arg(&self, argument: &Argument, quantum: bool) -> Result<(Vec<usize>, bool), TriadError> {
+        Ok((self.resolve(argument, quantum)?, argument.index.is_none()))
+    }
+
+    /// Раскрывает вызов гейта в инструкции схемы. Ошибки бюджета указывают
+    /// на вызов `token`, ошибки вычисления параметров — на выражение в теле.
+    fn expand(&self, name: &str, params: &[f64], qubits: &[usize], token: &Token, out: &mut Vec<Instruction>) -> Result<(), TriadError> {
+        // Собственными гейтами TRIAD заменяются только встроенные гейты и гейты
+        // библиотеки; пользовательские раскрываются по своим определениям
+        let native = self.gates.get(name).is_none_or(|def| def.library);
+        if let Some(gate) = native_gate(name, params).filter(|_| native) {
+            self.budget.spend(1, token)?;
+            out.push(Instruction::Gate { gate, qubits: qubits.to_vec() });
+            return Ok(());
+        }
+
+        // Сигнатуры проверены при разборе, поэтому определение существует
+        let def = &self.gates[name];
+        let env: HashMap<String, f64> = def.params.iter().cloned().zip(params.iter().copied()).collect();
+        for call in &def.body {
+            let values = call.params.iter().map(|p| p.eval(&env)).collect::<Result<Vec<f64>, TriadError>>()?;
+            let targets: Vec<usize> = call.args.iter().map(|&a| qubits[a]).collect();
+            self.expand(&call.name, &values, &targets, token, out)?;
+        }
+        Ok(())
+    }
+}
+
+/// Проверяет, что регистр размера `size`, объявленный после регистров общим
+/// размером `offset`, не выводит нумерацию за `MAX_REGISTER_BITS`.
+pub(crate) fn check_register_size(offset: usize, size: usize, token: &Token) -> Result<(), TriadError> {
+    match offset.checked_add(size) {
+        Some(total) if total <= MAX_REGISTER_BITS => Ok(()),
+        _ => Err(lexer::error(token.line, token.column, &format!(
+            "суммарный размер регистров превышает {}", MAX_REGISTER_BITS))),
+    }
+}
+
+/// Бюджет работы, общий для всех этапов раскрытия одной программы.
+pub(crate) struct Budget {
+    /// Оставшееся число шагов.
+    remaining: Cell<usize>,
+}
+
+impl Budget {
+    /// Создает бюджет из `MAX_EXPANSION_STEPS` шагов.
+    pub(crate) fn new() -> Self {
+        Self { remaining: Cell::new(MAX_EXPANSION_STEPS) }
+    }
+
+    /// Списывает `steps` шагов или возвращает ошибку в позиции `token`,
+    /// если бюджет исчерпан.
+    pub(crate) fn spend(&self, steps: usize, token: &Token) -> Result<(), TriadError> {
+        match self.remaining.get().checked_sub(steps) {
+            Some(remaining) => {
+                self.remaining.set(remaining);
+                Ok(())
+            },
+            None => Err(lexer::error(token.line, token.column, &format!(
+                "раскрытие программы требует больше {} шагов", MAX_EXPANSION_STEPS))),
+        }
+    }
+}
+
+/// Применяет правило расширения регистров: аргументы-регистры должны иметь
+/// одинаковый размер, отдельные биты повторяются для каждой позиции.
+pub(crate) fn broadcast(args: &[(Vec<usize>, bool)], token: &Token) -> Result<Vec<Vec<usize>>, TriadError> {
+    let mut width = 1;
+    for (indices, whole_register) in args {
+        if *whole_register {
+            if width != 1 && indices.len() != width {
+                return Err(lexer::error(token.line, token.column, "регистры в одной операции должны иметь одинаковый размер"));
+            }
+            width = indices.len();
+        }
+    }
+
+    Ok((0..width)
+        .map(|i| args.iter().map(|(indices, whole_register)| if *whole_register { indices[i] } else { indices[0] }).collect())
+        .collect())
+}
+
+/// Возвращает собственный гейт TRIAD для гейта OpenQASM, если такой есть.
+pub(crate) fn native_gate(name: &str, params: &[f64]) -> Option<CircuitGate> {
+    let custom = |name: &str, matrix: Vec<Amplitude>| Some(CircuitGate::Custom(CustomGate {
+        matrix,
+        num_qubits: 1,
+        name: name.to_string(),
+    }));
+
+    match (name, params) {
+        ("U", &[theta, phi, lambda]) | ("u3", &[theta, phi, lambda]) | ("u", &[theta, phi, lambda]) => {
+            Some(ParametricGate::U3 { theta, phi, lambda }.into())
+        },
+        ("u2", &[phi, lambda]) => Some(ParametricGate::U2 { phi, lambda }.into()),
+        ("u1", &[lambda]) => Some(ParametricGate::U1 { lambda }.into()),
+        ("p", &[theta]) => Some(ParametricGate::Phase { theta }.into()),
+        ("id", _) => custom("id", u3_matrix(0.0, 0.0, 0.0)),
+        ("x", _) => Some(BasicGate::PauliX.into()),
+        ("y", _) => Some(BasicGate::PauliY.into()),
+        ("z", _) => Some(BasicGate::PauliZ.into()),
+        ("h", _) => Some(BasicGate::Hadamard.into()),
+        ("s", _) => Some(BasicGate::S.into()),
+        ("t", _) => Some(BasicGate::T.into()),
+        ("sdg", _) => Some(BasicGate::SDagger.into()),
+        ("tdg", _) => Some(BasicGate::TDagger.into()),
+        ("sx", _) => Some(BasicGate::SqrtX.into()),
+        ("sxdg", _) => Some(BasicGate::SqrtXDagger.into()),
+        ("rx", &[angle]) => Some(RotationGate { axis: Axis::X, angle }.into()),
+        ("ry", &[angle]) => Some(RotationGate { axis: Axis::Y, angle }.into()),
+        ("rz", &[angle]) => Some(RotationGate { axis: Axis::Z, angle }.into()),
+        ("CX", _) | ("cx", _) => Some(TwoQubitGate::CNOT.into()),
+        ("cz", _) => Some(TwoQubitGate::CZ.into()),
+        ("swap", _) => Some(TwoQubitGate::SWAP.into()),
+        ("cp", &[theta]) | ("cu1", &[theta]) => Some(ParametricGate::CPhase { theta }.into()),
+        ("crx", &[theta]) => Some(ParametricGate::CRX { theta }.into()),
+        ("cry", &[theta]) => Some(ParametricGate::CRY { theta }.into()),
+        ("crz", &[theta]) => Some(ParametricGate::CRZ { theta }.into()),
+        ("rxx", &[theta]) => Some(ParametricGate::RXX { theta }.into()),
+        ("rzz", &[theta]) => Some(ParametricGate::RZZ { theta }.into()),
+        _ => None,
+    }
+}
+
+/// Матрица гейта `U(θ, φ, λ)` из спецификации OpenQASM 2.0.
+pub(crate) fn u3_matrix(theta: f64, phi: f64, lambda: f64) -> Vec<Amplitude> {
+    ParametricGate::U3 { theta, phi, lambda }.matrix()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::gates::Gate;
+    use crate::core::quantum_simulator::QuantumSimulator;
+    use crate::core::state_vector::StateVectorSimulator;
+    use std::f64::consts::PI;
+
+    const HEADER: &str = "OPENQASM 2.0;\ninclude \"qelib1.inc\";\n";
+
+    fn parse(body: &str) -> Result<Circuit, TriadError> {
+        parse_qasm2(&format!("{}{}", HEADER, body))
+    }
+
+    fn run(circuit: &Circuit) -> StateVectorSimulator {
+        let mut simulator = StateVectorSimulator::new(circuit.num_qubits());
+        circuit.run_on(&mut simulator).unwrap();
+        simulator
+    }
+
+    #[test]
+    fn test_parse_bell_with_registers_and_broadcast() {
+        let circuit = parse("qreg q[2];\ncreg c[2];\nh q[0];\ncx q[0],q[1];\nmeasure q -> c;").unwrap();
+
+        assert_eq!(circuit.qregs(), &[Register::new("q", 2)]);
+        assert_eq!(circuit.cregs(), &[Register::new("c", 2)]);
+        assert_eq!(circuit.len(), 4);
+        assert!(matches!(circuit.instructions()[3], Instruction::Measure { qubit: 1, cbit: 1 }));
+
+        let counts = circuit.gate_counts();
+        assert_eq!(counts.get("Hadamard"), Some(&1));
+        assert_eq!(counts.get("CNOT"), Some(&1));
+    }
+
+    #[test]
+    fn test_library_gates_match_their_definitions() {
+        // ccx раскрывается по определению из qelib1.inc и должен действовать как Тоффоли
+        for input in 0..8u64 {
+            let mut program = String::from("qreg a[1];\nqreg b[2];\n");
+            for bit in 0..3 {
+                if input & (1 << bit) != 0 {
+                    program.push_str(&format!("x {};\n", ["a[0]", "b[0]", "b[1]"][bit]));
+                }
+            }
+            program.push_str("ccx a[0],b[0],b[1];\n");
+
+            let circuit = parse(&program).unwrap();
+            let expected = if input & 0b011 == 0b011 { input ^ 0b100 } else { input };
+            assert!((run(&circuit).get_state().probability(expected) - 1.0).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_user_gate_definition_and_parameters() {
+        let circuit = parse(
+            "gate bell(theta) a, b { ry(theta) a; cx a, b; }\n\
+             qreg q[2];\n\
+             bell(pi/2) q[0], q[1];\n").unwrap();
+
+        assert_eq!(circuit.len(), 2);
+        match &circuit.instructions()[0] {
+            Instruction::Gate { gate: CircuitGate::Rotation(rotation), qubits } => {
+                assert!((rotation.angle - PI / 2.0).abs() < 1e-12);
+                assert_eq!(qubits, &vec![0]);
+            },
+            other => panic!("неожиданная инструкция: {:?}", other),
+        }
+
+        let simulator = run(&circuit);
+        assert!((simulator.probability_of_joint_outcome(&[0, 1], 0b11) - 0.5).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_user_gates_shadow_native_gates_and_are_bounded() {
+        // Без include имя h принадлежит пользовательскому гейту, равному X
+        let circuit = parse_qasm2("OPENQASM 2.0;\ngate h a { U(pi,0,pi) a; }\nqreg q[1];\nh q[0];").unwrap();
+        assert_eq!(circuit.gate_counts().get("Hadamard"), None);
+        assert!((run(&circuit).probability_of_outcome(0, true) - 1.0).abs() < 1e-10);
+
+        // Ошибка в параметре указывает на выражение в теле определения
+        let error = parse("gate g(a) q { rz(1/a) q; }\nqreg q[1];\ng(0) q[0];").unwrap_err();
+        assert_eq!(error, lexer::error(3, 20, "деление на ноль"));
+
+        // 40 удваивающих определений дали бы 2^40 инструкций
+        let mut program = String::from("gate d0 a { x a; }\n");
+        for i in 1..=40 {
+            program.push_str(&format!("gate d{} a {{ d{} a; d{} a; }}\n", i, i - 1, i - 1));
+        }
+        program.push_str("qreg q[1];\nd40 q[0];\n");
+        match parse(&program) {
+            Err(TriadError::Parse { line, message, .. }) => {
+                assert_eq!(line, 45);
+                assert!(message.contains("шагов"));
+            },
+            other => panic!("ожидалась ошибка разбора, получено {:?}", other),
+        }
+
+        let chain: String = (1..200).map(|i| format!("gate c{} a {{ c{} a; }}\n", i, i - 1)).collect();
+        let error = parse(&format!("gate c0 a {{ x a; }}\n{}", chain)).unwrap_err();
+        assert!(matches!(error, TriadError::Parse { message, .. } if message.contains("вложенность определений")));
+    }
+
+    #[test]
+    fn test_conditional_and_reset() {
+        let circuit = parse("qreg q[1];\ncreg c[1];\nx q[0];\nmeasure q[0] -> c[0];\nif (c == 1) x q[0];\nreset q;").unwrap();
+        assert!(matches!(&circuit.instructions()[2], Instruction::Conditional { cbits, value: 1, .. } if cbits == &vec![0]));
+        assert!(matches!(circuit.instructions()[3], Instruction::Reset(0)));
+    }
+
+    #[test]
+    fn test_u3_matches_builtin_gates() {
+        let hadamard = u3_matrix(PI / 2.0, 0.0, PI);
+        for (a, b) in hadamard.iter().zip(BasicGate::Hadamard.matrix()) {
+            assert!((a - b).norm() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_errors_carry_line_and_column() {
+        let error = |body: &str| match parse(body) {
+            Err(TriadError::Parse { line, column, message }) => (line, column, message),
+            other => panic!("ожидалась ошибка разбора, получено {:?}", other),
+        };
+
+        let (line, column, message) = error("qreg q[2];\nfoo q[0];");
+        assert_eq!((line, column), (4, 1));
+        assert!(message.contains("неизвестный гейт 'foo'"));
+
+        let (line, column, message) = error("qreg q[2];\nh q[5];");
+        assert_eq!((line, column), (4, 3));
+        assert!(message.contains("выходит за пределы"));
+
+        let (line, column, _) = error("qreg q[2];\ncx q[0] q[1];");
+        assert_eq!((line, column), (4, 9));
+
+        let (line, column, message) = error("qreg q[1];\nrz(1/0) q[0];");
+        assert_eq!((line, column), (4, 6));
+        assert!(message.contains("деление на ноль"));
+
+        let (line, column, message) = error("qreg a[18446744073709551615];\nqreg b[2];");
+        assert_eq!((line, column), (3, 6));
+        assert!(message.contains("суммарный размер регистров"));
+        assert!(error("qreg a[1048576];\nqreg b[1];").2.contains("суммарный размер регистров"));
+
+        assert!(error("qreg q[2];\nrx q[0];").2.contains("принимает 1 параметр"));
+        assert!(parse_qasm2("OPENQASM 3.0;").is_err());
+        assert!(parse_qasm2("qreg q[1];").is_err());
+    }
+}
@@ -0,0 +1,1333 @@
+//! Разбор подмножества OpenQASM 3 в схемы `Circuit`.
+//!
+//! Поддерживаются объявления `qubit`/`bit` (и `qreg`/`creg`), `stdgates.inc`,
+//! пользовательские гейты, модификаторы `ctrl @`, `negctrl @` (не более
+//! `MAX_CONTROLLED_QUBITS` кубитов вместе с целевыми), `inv @`,
+//! `pow(k) @` (целые `k`), `gphase`, измерения в середине схемы, `reset`,
+//! `barrier`, `if`/`else` по классическим битам и циклы `for` с известными
+//! границами. Программа сначала разбирается в дерево, затем понижается до
+//! инструкций схемы: циклы разворачиваются (не более `MAX_LOOP_ITERATIONS`
+//! итераций), ветвления превращаются в условные инструкции. Итерации циклов
+//! и порожденные гейты расходуют общий бюджет `MAX_EXPANSION_STEPS`,
+//! вложенность блоков ограничена `MAX_BLOCK_DEPTH`, а условий, модификаторов
+//! и определений гейтов — `MAX_EXPR_DEPTH`. Неподдерживаемые конструкции отклоняются с указанием позиции.
+
+use crate::core::circuit::{Circuit, CircuitGate, Instruction, Register};
+use crate::core::gates::{CustomGate, Gate, ParametricGate};
+use crate::error::TriadError;
+use crate::qasm::expr::{Expr, MAX_EXPR_DEPTH, parse_expr};
+use crate::qasm::lexer::{self, Cursor, Token, TokenKind, tokenize};
+use crate::qasm::qasm2::{Budget, broadcast, check_register_size, native_gate};
+use std::collections::HashMap;
+use std::f64::consts::PI;
+
+/// Исходный текст стандартной библиотеки `stdgates.inc`.
+const STDGATES: &str = include_str!("stdgates.inc");
+
+/// Максимальное число классических битов в одном условии: условие
+/// раскладывается по таблице истинности из 2^n строк.
+pub const MAX_CONDITION_BITS: usize = 8;
+
+/// Наибольшая степень в `pow(k) @`, которая раскрывается повторением гейтов.
+pub const MAX_UNROLLED_POWER: u32 = 16;
+
+/// Наибольшее число кубитов, на которых последовательность гейтов
+/// сворачивается в одну матрицу для возведения в большую степень.
+pub const MAX_POWER_QUBITS: usize = 8;
+
+/// Наибольшее число кубитов гейта с модификатором `ctrl @`/`negctrl @`:
+/// управляемый гейт хранится плотной матрицей 2^n × 2^n.
+pub const MAX_CONTROLLED_QUBITS: usize = 10;
+
+/// Наибольшее число итераций одного цикла `for`: циклы разворачиваются
+/// в инструкции схемы при разборе.
+pub const MAX_LOOP_ITERATIONS: usize = 100_000;
+
+/// Наибольшая вложенность блоков `if`/`for`. Блоки разделяют счетчик
+/// глубины с выражениями и условиями, но уровень блока занимает на стеке
+/// в несколько раз больше, поэтому и предел для них меньше `MAX_EXPR_DEPTH`.
+pub const MAX_BLOCK_DEPTH: usize = 32;
+
+/// Ключевые слова OpenQASM 3, которые этот разборщик не поддерживает.
+const UNSUPPORTED: [&str; 27] = [
+    "while", "def", "extern", "defcal", "defcalgrammar", "cal", "input", "output", "const",
+    "int", "uint", "float", "angle", "bool", "complex", "duration", "stretch", "array", "let",
+    "box", "delay", "switch", "break", "continue", "return", "end", "opaque",
+];
+
+/// Модификатор вызова гейта.
+#[derive(Debug, Clone)]
+enum Modifier {
+    /// `inv @`
+    Inv,
+    /// `pow(k) @`
+    Pow(Expr),
+    /// `ctrl(n) @` (`true`) или `negctrl(n) @` (`false`)
+    Ctrl(Option<Expr>, bool),
+}
+
+/// Операнд: регистр или его элемент с индексом-выражением.
+#[derive(Debug, Clone)]
+struct Operand {
+    /// Имя регистра или аргумента гейта.
+    name: String,
+    /// Индекс (`None` — весь регистр).
+    index: Option<Expr>,
+    /// Токен начала операнда.
+    token: Token,
+}
+
+/// Классическое условие.
+#[derive(Debug, Clone)]
+enum Condition {
+    /// Значение бита или регистра (истинно, если не равно нулю)
+    Value(Operand),
+    /// Сравнение с целым числом (`==` — `true`, `!=` — `false`)
+    Compare(Operand, Expr, bool),
+    /// Логическое отрицание
+    Not(Box<Condition>),
+    /// Конъюнкция
+    And(Box<Condition>, Box<Condition>),
+    /// Дизъюнкция
+    Or(Box<Condition>, Box<Condition>),
+}
+
+/// Оператор программы.
+#[derive(Debug, Clone)]
+enum Stmt {
+    /// Вызов гейта (возможно, с модификаторами)
+    Gate { modifiers: Vec<Modifier>, name: String, params: Vec<Expr>, args: Vec<Operand>, token: Token },
+    /// Измерение кубитов с записью в биты
+    Measure { qubits: Operand, cbits: Operand, token: Token },
+    /// Сброс кубитов
+    Reset { qubits: Operand },
+    /// Барьер
+    Barrier { args: Vec<Operand> },
+    /// Ветвление
+    If { condition: Condition, then_branch: Vec<Stmt>, else_branch: Vec<Stmt>, token: Token },
+    /// Цикл по списку значений
+    For { var: String, values: Vec<Expr>, range: Option<(Expr, Expr, Expr)>, body: Vec<Stmt>, token: Token },
+}
+
+/// Определение гейта.
+#[derive(Debug, Clone)]
+struct GateDef {
+    /// Имена параметров.
+    params: Vec<String>,
+    /// Имена кубитов-аргументов.
+    qargs: Vec<String>,
+    /// Тело (только вызовы гейтов).
+    body: Vec<Stmt>,
+    /// Глубина вложенности определений: 1, если тело вызывает только `U` и `gphase`.
+    depth: usize,
+    /// Определение взято из `stdgates.inc` и заменяется собственным гейтом TRIAD.
+    library: bool,
+}
+
+/// Результат раскрытия гейта: гейт на кубитах или глобальная фаза.
+#[derive(Debug, Clone)]
+enum Lowered {
+    /// Гейт на указанных кубитах
+    Gate(CircuitGate, Vec<usize>),
+    /// Глобальная фаза e^{iθ}
+    Phase(f64),
+}
+
+/// Регистр с его смещением в общей нумерации схемы.
+#[derive(Debug, Clone)]
+struct DeclaredRegister {
+    /// Имя регистра.
+    name: String,
+    /// Индекс первого бита регистра в схеме.
+    offset: usize,
+    /// Число битов.
+    size: usize,
+}
+
+/// Разбирает программу на подмножестве OpenQASM 3 и строит по ней схему.
+///
+/// Кубиты и классические биты нумеруются подряд в порядке объявления.
+/// Ошибки содержат строку и столбец места, где разбор был прерван.
+pub fn parse_qasm3(source: &str) -> Result<Circuit, TriadError> {
+    let mut parser = Parser::new(tokenize(source)?);
+    parser.parse_header()?;
+
+    let mut program = Vec::new();
+    while !parser.cursor.at_eof() {
+        if let Some(stmt) = parser.parse_statement(true)? {
+            program.push(stmt);
+        }
+    }
+
+    let mut instructions = Vec::new();
+    parser.lower_block(&program, &HashMap::new(), &mut instructions)?;
+
+    let to_registers = |regs: &[DeclaredRegister]| -> Vec<Register> {
+        regs.iter().map(|r| Register::new(&r.name, r.size)).collect()
+    };
+    let mut circuit = Circuit::with_registers(to_registers(&parser.qregs), to_registers(&parser.cregs));
+    for instruction in instructions {
+        circuit.try_push(instruction)?;
+    }
+    Ok(circuit)
+}
+
+/// Парсер программ OpenQASM 3.
+struct Parser {
+    /// Курсор по токенам текущего исходника.
+    cursor: Cursor,
+    /// Объявленные квантовые регистры.
+    qregs: Vec<DeclaredRegister>,
+    /// Объявленные классические регистры.
+    cregs: Vec<DeclaredRegister>,
+    /// Определения гейтов (включая `stdgates.inc`).
+    gates: HashMap<String, GateDef>,
+    /// Разбирается встроенная библиотека `stdgates.inc`.
+    in_library: bool,
+    /// Бюджет работы при понижении программы.
+    budget: Budget,
+}
+
+impl Parser {
+    /// Создает парсер по списку токенов.
+    fn new(tokens: Vec<Token>) -> Self {
+        Self {
+            cursor: Cursor::new(tokens),
+            qregs: Vec::new(),
+            cregs: Vec::new(),
+            gates: HashMap::new(),
+            in_library: false,
+            budget: Budget::new(),
+        }
+    }
+
+    /// Разбирает обязательный заголовок `OPENQASM 3;` или `OPENQASM 3.x;`.
+    fn parse_header(&mut self) -> Result<(), TriadError> {
+        self.cursor.expect_keyword("OPENQASM")?;
+        let major = match self.cursor.peek().kind {
+            TokenKind::Real(version) => version.trunc() as u64,
+            TokenKind::Int(version) => version,
+            _ => return Err(self.cursor.error("ожидалась версия OpenQASM")),
+        };
+        if major != 3 {
+            return Err(self.cursor.error("ожидалась версия OpenQASM 3"));
+        }
+        self.cursor.next_token();
+        self.cursor.expect_symbol(";")
+    }
+
+    // === Разбор ===
+
+    /// Разбирает оператор. Объявления и определения гейтов обрабатываются сразу
+    /// и не возвращают оператор; они допустимы только на верхнем уровне.
+    fn parse_statement(&mut self, top_level: bool) -> Result<Option<Stmt>, TriadError> {
+        let token = self.cursor.peek().clone();
+        let keyword = match &token.kind {
+            TokenKind::Ident(keyword) => keyword.clone(),
+            _ => return Err(self.cursor.error("ожидался оператор")),
+        };
+
+        let declaration = matches!(keyword.as_str(), "include" | "qubit" | "qreg" | "bit" | "creg" | "gate");
+        if declaration && !top_level {
+            return Err(lexer::error(token.line, token.column, &format!("'{}' допускается только на верхнем уровне программы", keyword)));
+        }
+        if UNSUPPORTED.contains(&keyword.as_str()) {
+            return Err(lexer::error(token.line, token.column, &format!("конструкция '{}' не поддерживается", keyword)));
+        }
+
+        match keyword.as_str() {
+            "include" => {
+                self.cursor.next_token();
+                self.parse_include(&token)?;
+                Ok(None)
+            },
+            "qubit" | "bit" => {
+                self.cursor.next_token();
+                self.parse_declaration(keyword == "qubit", &token)
+            },
+            "qreg" | "creg" => {
+                self.cursor.next_token();
+                let (name, name_token) = self.cursor.expect_ident()?;
+                self.cursor.expect_symbol("[")?;
+                let size = self.cursor.expect_int()? as usize;
+                self.cursor.expect_symbol("]")?;
+                self.cursor.expect_symbol(";")?;
+                self.declare(name, size, keyword == "qreg", &name_token)?;
+                Ok(None)
+            },
+            "gate" => {
+                self.cursor.next_token();
+                self.parse_gate_definition()?;
+                Ok(None)
+            },
+            "if" => {
+                self.cursor.next_token();
+                self.parse_if(token).map(Some)
+            },
+            "for" => {
+                self.cursor.next_token();
+                self.parse_for(token).map(Some)
+            },
+            "measure" => {
+                self.cursor.next_token();
+                let qubits = self.parse_operand()?;
+                self.cursor.expect_symbol("->")?;
+                let cbits = self.parse_operand()?;
+                self.cursor.expect_symbol(";")?;
+                Ok(Some(Stmt::Measure { qubits, cbits, token }))
+            },
+            "reset" => {
+                self.cursor.next_token();
+                let qubits = self.parse_operand()?;
+                self.cursor.expect_symbol(";")?;
+                Ok(Some(Stmt::Reset { qubits }))
+            },
+            "barrier" => {
+                self.cursor.next_token();
+                let args = self.parse_operand_list()?;
+                self.cursor.expect_symbol(";")?;
+                Ok(Some(Stmt::Barrier { args }))
+            },
+            _ => {
+                // Присваивание `c = measure q;` или `c[i] = measure q[i];`
+                let start = self.cursor.position();
+                if matches!(self.cursor.peek_next().kind, TokenKind::Symbol("=") | TokenKind::Symbol("[")) {
+                    let cbits = self.parse_operand()?;
+                    if self.cursor.eat_symbol("=") {
+                        self.cursor.expect_keyword("measure")?;
+                        let qubits = self.parse_operand()?;
+                        self.cursor.expect_symbol(";")?;
+                        return Ok(Some(Stmt::Measure { qubits, cbits, token }));
+                    }
+                    self.cursor.seek(start);
+                }
+                self.parse_gate_call().map(Some)
+            },
+        }
+    }
+
+    /// Разбирает блок `{ ... }` или одиночный оператор.
+    fn parse_block(&mut self) -> Result<Vec<Stmt>, TriadError> {
+        // Вложенные блоки разбираются и понижаются рекурсивно
+        self.cursor.enter(MAX_BLOCK_DEPTH)?;
+        let mut body = Vec::new();
+        if self.cursor.eat_symbol("{") {
+            while !self.cursor.eat_symbol("}") {
+                if self.cursor.at_eof() {
+                    return Err(self.cursor.error("ожидалось '}'"));
+                }
+                body.extend(self.parse_statement(false)?);
+            }
+        } else {
+            body.extend(self.parse_statement(false)?);
+        }
+        self.cursor.leave(1);
+        Ok(body)
+    }
+
+    /// Разбирает `include "stdgates.inc";`.
+    fn parse_include(&mut self, token: &Token) -> Result<(), TriadError> {
+        let file = match &self.cursor.peek().kind {
+            TokenKind::Str(file) => file.clone(),
+            _ => return Err(self.cursor.error("ожидалось имя файла в кавычках")),
+        };
+        self.cursor.next_token();
+        self.cursor.expect_symbol(";")?;
+
+        if file != "stdgates.inc" {
+            return Err(lexer::error(token.line, token.column, &format!("поддерживается только include \"stdgates.inc\", найдено \"{}\"", file)));
+        }
+
+        // Разбираем библиотеку отдельным парсером, разделяющим таблицу гейтов
+        let mut library = Parser::new(tokenize(STDGATES)?);
+        library.gates = std::mem::take(&mut self.gates);
+        library.in_library = true;
+        while !library.cursor.at_eof() {
+            library.parse_statement(true)?;
+        }
+        self.gates = library.gates;
+        Ok(())
+    }
+
+    /// Разбирает `qubit[n] q;`, `qubit q;`, `bit[n] c;`, `bit c;`
+    /// и объявление с измерением `bit[n] c = measure q;`.
+    fn parse_declaration(&mut self, quantum: bool, token: &Token) -> Result<Option<Stmt>, TriadError> {
+        let size = if self.cursor.eat_symbol("[") {
+            let size = self.cursor.expect_int()? as usize;
+            self.cursor.expect_symbol("]")?;
+            size
+        } else {
+            1
+        };
+        let (name, name_token) = self.cursor.expect_ident()?;
+        self.declare(name.clone(), size, quantum, &name_token)?;
+
+        if !quantum && self.cursor.eat_symbol("=") {
+            self.cursor.expect_keyword("measure")?;
+            let qubits = self.parse_operand()?;
+            self.cursor.expect_symbol(";")?;
+            let cbits = Operand { name, index: None, token: name_token };
+            return Ok(Some(Stmt::Measure { qubits, cbits, token: token.clone() }));
+        }
+
+        self.cursor.expect_symbol(";")?;
+        Ok(None)
+    }
+
+    /// Регистрирует новый регистр.
+    fn declare(&mut self, name: String, size: usize, quantum: bool, token: &Token) -> Result<(), TriadError> {
+        if size == 0 {
+            return Err(lexer::error(token.line, token.column, "размер регистра должен быть положительным"));
+        }
+        if self.find_register(&name, true).is_some() || self.find_register(&name, false).is_some() {
+            return Err(lexer::error(token.line, token.column, &format!("регистр '{}' уже объявлен", name)));
+        }
+
+        let registers = if quantum { &mut self.qregs } else { &mut self.cregs };
+        let offset = registers.iter().map(|r| r.size).sum();
+        check_register_size(offset, size, token)?;
+        registers.push(DeclaredRegister { name, offset, size });
+        Ok(())
+    }
+
+    /// Ищет объявленный регистр по имени.
+    fn find_register(&self, name: &str, quantum: bool) -> Option<&DeclaredRegister> {
+        let registers = if quantum { &self.qregs } else { &self.cregs };
+        registers.iter().find(|r| r.name == name)
+    }
+
+    /// Разбирает определение `gate name(params) args { body }`.
+    fn parse_gate_definition(&mut self) -> Result<(), TriadError> {
+        let (name, token) = self.cursor.expect_ident()?;
+        if name == "U" || name == "gphase" || self.gates.contains_key(&name) {
+            return Err(lexer::error(token.line, token.column, &format!("гейт '{}' уже определен", name)));
+        }
+
+        let mut params = Vec::new();
+        if self.cursor.eat_symbol("(") && !self.cursor.eat_symbol(")") {
+            params = self.parse_ident_list()?;
+            self.cursor.expect_symbol(")")?;
+        }
+        let qargs = self.parse_ident_list()?;
+
+        self.cursor.expect_symbol("{")?;
+        let mut body = Vec::new();
+        let mut depth = 1;
+        while !self.cursor.eat_symbol("}") {
+            let token = self.cursor.peek().clone();
+            if self.cursor.eat_keyword("barrier") {
+                // Барьеры внутри определений не влияют на результат
+                self.parse_operand_list()?;
+                self.cursor.expect_symbol(";")?;
+                continue;
+            }
+
+            let stmt = self.parse_gate_call()?;
+            if let Stmt::Gate { params: exprs, args, modifiers, name: callee, token } = &stmt {
+                // Тело может вызывать только ранее определенные гейты,
+                // поэтому рекурсивные определения невозможны
+                if callee != "U" && callee != "gphase" {
+                    match self.gates.get(callee) {
+                        Some(def) => depth = depth.max(def.depth + 1),
+                        None => return Err(lexer::error(token.line, token.column, &format!("неизвестный гейт '{}'", callee))),
+                    }
+                }
+                for expr in exprs {
+                    expr.check_idents(&params)?;
+                }
+                for modifier in modifiers {
+                    match modifier {
+                        Modifier::Pow(expr) | Modifier::Ctrl(Some(expr), _) => expr.check_idents(&params)?,
+                        _ => {},
+                    }
+                }
+                for arg in args {
+                    if arg.index.is_some() || !qargs.contains(&arg.name) {
+                        return Err(lexer::error(arg.token.line, arg.token.column, &format!("неизвестный аргумент '{}'", arg.name)));
+                    }
+                }
+            } else {
+                return Err(lexer::error(token.line, token.column, "в теле гейта допускаются только вызовы гейтов"));
+            }
+            body.push(stmt);
+        }
+
+        // Определения раскрываются рекурсивно
+        if depth > MAX_EXPR_DEPTH {
+            return Err(lexer::error(token.line, token.column, &format!(
+                "вложенность определений гейтов превышает {}", MAX_EXPR_DEPTH)));
+        }
+        let library = self.in_library;
+        self.gates.insert(name, GateDef { params, qargs, body, depth, library });
+        Ok(())
+    }
+
+    /// Разбирает вызов гейта с модификаторами: `ctrl @ inv @ name(params) args;`.
+    fn parse_gate_call(&mut self) -> Result<Stmt, TriadError> {
+        let mut modifiers = Vec::new();
+        loop {
+            let (name, token) = self.cursor.expect_ident()?;
+            let modifier = match name.as_str() {
+                "inv" => Some(Modifier::Inv),
+                "pow" => {
+                    self.cursor.expect_symbol("(")?;
+                    let exponent = parse_expr(&mut self.cursor)?;
+                    self.cursor.expect_symbol(")")?;
+                    Some(Modifier::Pow(exponent))
+                },
+                "ctrl" | "negctrl" => {
+                    let count = if self.cursor.eat_symbol("(") {
+                        let count = parse_expr(&mut self.cursor)?;
+                        self.cursor.expect_symbol(")")?;
+                        Some(count)
+                    } else {
+                        None
+                    };
+                    Some(Modifier::Ctrl(count, name == "ctrl"))
+                },
+                _ => None,
+            };
+
+            match modifier {
+                Some(modifier) => {
+                    self.cursor.expect_symbol("@")?;
+                    // Модификаторы применяются рекурсивно
+                    self.cursor.enter(MAX_EXPR_DEPTH)?;
+                    modifiers.push(modifier);
+                },
+                None => {
+                    let params = self.parse_params()?;
+                    let args = if self.cursor.at_symbol(";") { Vec::new() } else { self.parse_operand_list()? };
+                    self.cursor.expect_symbol(";")?;
+                    self.cursor.leave(modifiers.len());
+                    return Ok(Stmt::Gate { modifiers, name, params, args, token });
+                },
+            }
+        }
+    }
+
+    /// Разбирает `if (условие) блок [else блок]`.
+    fn parse_if(&mut self, token: Token) -> Result<Stmt, TriadError> {
+        self.cursor.expect_symbol("(")?;
+        let condition = self.parse_condition()?;
+        self.cursor.expect_symbol(")")?;
+
+        let then_branch = self.parse_block()?;
+        let else_branch = if self.cursor.eat_keyword("else") { self.parse_block()? } else { Vec::new() };
+        Ok(Stmt::If { condition, then_branch, else_branch, token })
+    }
+
+    /// Разбирает `for [тип] i in [a:b] блок`, `[a:step:b]` или `{v1, v2, ...}`.
+    fn parse_for(&mut self, token: Token) -> Result<Stmt, TriadError> {
+        // Необязательный тип переменной цикла, например `uint` или `int[32]`
+        if !matches!(&self.cursor.peek_next().kind, TokenKind::Ident(keyword) if keyword == "in") {
+            self.cursor.next_token();
+            if self.cursor.eat_symbol("[") {
+                self.cursor.expect_int()?;
+                self.cursor.expect_symbol("]")?;
+            }
+        }
+        let (var, _) = self.cursor.expect_ident()?;
+        self.cursor.expect_keyword("in")?;
+
+        let open = self.cursor.peek().clone();
+        let (values, range) = if self.cursor.eat_symbol("[") {
+            let start = parse_expr(&mut self.cursor)?;
+            self.cursor.expect_symbol(":")?;
+            let second = parse_expr(&mut self.cursor)?;
+            let range = if self.cursor.eat_symbol(":") {
+                let end = parse_expr(&mut self.cursor)?;
+                (start, second, end)
+            } else {
+                (start, Expr::Number { value: 1.0, line: open.line, column: open.column }, second)
+            };
+            self.cursor.expect_symbol("]")?;
+            (Vec::new(), Some(range))
+        } else if self.cursor.eat_symbol("{") {
+            let mut values = vec![parse_expr(&mut self.cursor)?];
+            while self.cursor.eat_symbol(",") {
+                values.push(parse_expr(&mut self.cursor)?);
+            }
+            self.cursor.expect_symbol("}")?;
+            (values, None)
+        } else {
+            return Err(self.cursor.error("ожидался диапазон [a:b] или множество {…}"));
+        };
+
+        let body = self.parse_block()?;
+        Ok(Stmt::For { var, values, range, body, token })
+    }
+
+    /// Разбирает условие с операторами `||`, `&&`, `!`, `==`, `!=`.
+    fn parse_condition(&mut self) -> Result<Condition, TriadError> {
+        let mut lhs = self.parse_conjunction()?;
+        let mut levels = 0;
+        while self.cursor.eat_symbol("||") {
+            self.cursor.enter(MAX_EXPR_DEPTH)?;
+            levels += 1;
+            lhs = Condition::Or(Box::new(lhs), Box::new(self.parse_conjunction()?));
+        }
+        self.cursor.leave(levels);
+        Ok(lhs)
+    }
+
+    /// Разбирает конъюнкцию условий.
+    fn parse_conjunction(&mut self) -> Result<Condition, TriadError> {
+        let mut lhs = self.parse_condition_atom()?;
+        let mut levels = 0;
+        while self.cursor.eat_symbol("&&") {
+            self.cursor.enter(MAX_EXPR_DEPTH)?;
+            levels += 1;
+            lhs = Condition::And(Box::new(lhs), Box::new(self.parse_condition_atom()?));
+        }
+        self.cursor.leave(levels);
+        Ok(lhs)
+    }
+
+    /// Разбирает отрицание, скобки или сравнение бита/регистра с числом.
+    fn parse_condition_atom(&mut self) -> Result<Condition, TriadError> {
+        // Условия вычисляются рекурсивно, поэтому глубина ограничивается здесь
+        self.cursor.enter(MAX_EXPR_DEPTH)?;
+        let condition = self.parse_condition_atom_inner()?;
+        self.cursor.leave(1);
+        Ok(condition)
+    }
+
+    /// Разбирает отрицание, скобки или сравнение на текущем уровне вложенности.
+    fn parse_condition_atom_inner(&mut self) -> Result<Condition, TriadError> {
+        if self.cursor.eat_symbol("!") {
+            return Ok(Condition::Not(Box::new(self.parse_condition_atom()?)));
+        }
+        if self.cursor.eat_symbol("(") {
+            let inner = self.parse_condition()?;
+            self.cursor.expect_symbol(")")?;
+            return Ok(inner);
+        }
+
+        let operand = self.parse_operand()?;
+        if self.cursor.eat_symbol("==") {
+            Ok(Condition::Compare(operand, parse_expr(&mut self.cursor)?, true))
+        } else if self.cursor.eat_symbol("!=") {
+            Ok(Condition::Compare(operand, parse_expr(&mut self.cursor)?, false))
+        } else if matches!(self.cursor.peek().kind, TokenKind::Symbol("<") | TokenKind::Symbol(">") | TokenKind::Symbol("<=") | TokenKind::Symbol(">=")) {
+            Err(self.cursor.error("поддерживаются только сравнения == и !="))
+        } else {
+            Ok(Condition::Value(operand))
+        }
+    }
+
+    /// Разбирает операнд `name` или `name[expr]`.
+    fn parse_operand(&mut self) -> Result<Operand, TriadError> {
+        let (name, token) = self.cursor.expect_ident()?;
+        let index = if self.cursor.eat_symbol("[") {
+            let index = parse_expr(&mut self.cursor)?;
+            if self.cursor.at_symbol(":") || self.cursor.at_symbol(",") {
+                return Err(self.cursor.error("срезы регистров не поддерживаются"));
+            }
+            self.cursor.expect_symbol("]")?;
+            Some(index)
+        } else {
+            None
+        };
+        Ok(Operand { name, index, token })
+    }
+
+    /// Разбирает список операндов через запятую.
+    fn parse_operand_list(&mut self) -> Result<Vec<Operand>, TriadError> {
+        let mut operands = vec![self.parse_operand()?];
+        while self.cursor.eat_symbol(",") {
+            operands.push(self.parse_operand()?);
+        }
+        Ok(operands)
+    }
+
+    /// Разбирает список идентификаторов через запятую.
+    fn parse_ident_list(&mut self) -> Result<Vec<String>, TriadError> {
+        let mut names = vec![self.cursor.expect_ident()?.0];
+        while self.cursor.eat_symbol(",") {
+            names.push(self.cursor.expect_ident()?.0);
+        }
+        Ok(names)
+    }
+
+    /// Разбирает необязательный список параметров `(expr, ...)`.
+    fn parse_params(&mut self) -> Result<Vec<Expr>, TriadError> {
+        let mut params = Vec::new();
+        if self.cursor.eat_symbol("(") && !self.cursor.eat_symbol(")") {
+            params.push(parse_expr(&mut self.cursor)?);
+            while self.cursor.eat_symbol(",") {
+                params.push(parse_expr(&mut self.cursor)?);
+            }
+            self.cursor.expect_symbol(")")?;
+        }
+        Ok(params)
+    }
+
+    // === Понижение до инструкций схемы ===
+
+    /// Понижает последовательность операторов; `env` содержит значения переменных циклов.
+    fn lower_block(&self, block: &[Stmt], env: &HashMap<String, f64>, out: &mut Vec<Instruction>) -> Result<(), TriadError> {
+        for stmt in block {
+            self.lower_statement(stmt, env, out)?;
+        }
+        Ok(())
+    }
+
+    /// Понижает один оператор.
+    fn lower_statement(&self, stmt: &Stmt, env: &HashMap<String, f64>, out: &mut Vec<Instruction>) -> Result<(), TriadError> {
+        match stmt {
+            Stmt::Gate { modifiers, name, params, args, token } => {
+                let values = params.iter().map(|p| p.eval(env)).collect::<Result<Vec<f64>, TriadError>>()?;
+                let resolved = args.iter()
+                    .map(|arg| self.resolve(arg, true, env))
+                    .collect::<Result<Vec<_>, TriadError>>()?;
+
+                for qubits in broadcast(&resolved, token)? {
+                    if (1..qubits.len()).any(|i| qubits[..i].contains(&qubits[i])) {
+                        return Err(lexer::error(token.line, token.column, &format!("гейт '{}' применен к одному кубиту несколько раз", name)));
+                    }
+                    for lowered in self.apply_modifiers(modifiers, name, &values, &qubits, env, token)? {
+                        // Глобальная фаза без управления ненаблюдаема
+                        if let Lowered::Gate(gate, qubits) = lowered {
+                            out.push(Instruction::Gate { gate, qubits });
+                        }
+                    }
+                }
+                Ok(())
+            },
+            Stmt::Measure { qubits, cbits, token } => {
+                let resolved = [self.resolve(qubits, true, env)?, self.resolve(cbits, false, env)?];
+                let pairs = broadcast(&resolved, token)?;
+                self.budget.spend(pairs.len(), token)?;
+                out.extend(pairs.into_iter().map(|pair| Instruction::Measure { qubit: pair[0], cbit: pair[1] }));
+                Ok(())
+            },
+            Stmt::Reset { qubits } => {
+                let resolved = self.resolve(qubits, true, env)?.0;
+                self.budget.spend(resolved.len(), &qubits.token)?;
+                out.extend(resolved.into_iter().map(Instruction::Reset));
+                Ok(())
+            },
+            Stmt::Barrier { args } => {
+                self.budget.spend(1, &args[0].token)?;
+                let mut qubits = Vec::new();
+                for arg in args {
+                    for qubit in self.resolve(arg, true, env)?.0 {
+                        if !qubits.contains(&qubit) {
+                            qubits.push(qubit);
+                        }
+                    }
+                }
+                out.push(Instruction::Barrier(qubits));
+                Ok(())
+            },
+            Stmt::If { condition, then_branch, else_branch, token } => {
+                self.lower_if(condition, then_branch, else_branch, token, env, out)
+            },
+            Stmt::For { var, values, range, body, token } => {
+                let iterations = match range {
+                    Some((start, step_expr, end)) => {
+                        let (start, step, end) = (eval_int(start, env)?, eval_int(step_expr, env)?, eval_int(end, env)?);
+                        if step == 0 {
+                            return Err(lexer::error(token.line, token.column, "шаг цикла не может быть нулевым"));
+                        }
+                        let mut iterations = Vec::new();
+                        let mut value = Some(start);
+                        while let Some(current) = value.filter(|&v| (step > 0 && v <= end) || (step < 0 && v >= end)) {
+                            if iterations.len() == MAX_LOOP_ITERATIONS {
+                                return Err(lexer::error(token.line, token.column, &format!(
+                                    "цикл выполняет больше {} итераций", MAX_LOOP_ITERATIONS)));
+                            }
+                            iterations.push(current as f64);
+                            // Переполнение означает, что следующее значение вышло за границу
+                            value = current.checked_add(step);
+                        }
+                        iterations
+                    },
+                    None => values.iter().map(|v| v.eval(env)).collect::<Result<Vec<f64>, TriadError>>()?,
+                };
+
+                let mut inner = env.clone();
+                for value in iterations {
+                    // Итерации расходуют бюджет и при пустом теле
+                    self.budget.spend(1, token)?;
+                    inner.insert(var.clone(), value);
+                    self.lower_block(body, &inner, out)?;
+                }
+                Ok(())
+            },
+        }
+    }
+
+    /// Понижает ветвление: каждая инструкция ветви становится условной
+    /// для каждого набора значений битов, при котором ветвь выполняется.
+    fn lower_if(
+        &self,
+        condition: &Condition,
+        then_branch: &[Stmt],
+        else_branch: &[Stmt],
+        token: &Token,
+        env: &HashMap<String, f64>,
+        out: &mut Vec<Instruction>,
+    ) -> Result<(), TriadError> {
+        let mut cbits = Vec::new();
+        self.collect_condition_bits(condition, env, &mut cbits)?;
+        if cbits.len() > MAX_CONDITION_BITS {
+            return Err(lexer::error(token.line, token.column, &format!(
+                "условие зависит от {} классических битов, поддерживается не более {}", cbits.len(), MAX_CONDITION_BITS)));
+        }
+
+        let (mut accepted, mut rejected) = (Vec::new(), Vec::new());
+        for value in 0..(1u64 << cbits.len()) {
+            if self.eval_condition(condition, &cbits, value, env)? {
+                accepted.push(value);
+            } else {
+                rejected.push(value);
+            }
+        }
+
+        for (branch, values) in [(then_branch, accepted), (else_branch, rejected)] {
+            let mut lowered = Vec::new();
+            self.lower_block(branch, env, &mut lowered)?;
+
+            // Инструкции ветви понижаются по отдельности, поэтому ветвь
+            // не должна изменять биты, от которых зависит условие
+            if lowered.iter().any(|i| written_cbits(i).iter().any(|c| cbits.contains(c))) {
+                return Err(lexer::error(token.line, token.column, "ветвь if не может перезаписывать биты собственного условия"));
+            }
+
+            if values.len() == 1usize << cbits.len() {
+                out.extend(lowered);
+                continue;
+            }
+            self.budget.spend(values.len().saturating_mul(lowered.len()), token)?;
+            for &value in &values {
+                for instruction in &lowered {
+                    out.push(Instruction::Conditional {
+                        cbits: cbits.clone(),
+                        value,
+                        instruction: Box::new(instruction.clone()),
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Собирает классические биты, от которых зависит условие.
+    fn collect_condition_bits(&self, condition: &Condition, env: &HashMap<String, f64>, cbits: &mut Vec<usize>) -> Result<(), TriadError> {
+        match condition {
+            Condition::Value(operand) | Condition::Compare(operand, _, _) => {
+                for cbit in self.resolve(operand, false, env)?.0 {
+                    if !cbits.contains(&cbit) {
+                        cbits.push(cbit);
+                    }
+                }
+                Ok(())
+            },
+            Condition::Not(inner) => self.collect_condition_bits(inner, env, cbits),
+            Condition::And(lhs, rhs) | Condition::Or(lhs, rhs) => {
+                self.collect_condition_bits(lhs, env, cbits)?;
+                self.collect_condition_bits(rhs, env, cbits)
+            },
+        }
+    }
+
+    /// Вычисляет условие для значения `value` битов `cbits` (бит `i` — `cbits[i]`).
+    fn eval_condition(&self, condition: &Condition, cbits: &[usize], value: u64, env: &HashMap<String, f64>) -> Result<bool, TriadError> {
+        // Значение операнда: биты регистра в порядке от младшего к старшему
+        let operand_value = |operand: &Operand| -> Result<u64, TriadError> {
+            let bits = self.resolve(operand, false, env)?.0;
+            Ok(bits.iter().enumerate().fold(0u64, |acc, (i, bit)| {
+                let position = cbits.iter().position(|c| c == bit).unwrap();
+                acc | (((value >> position) & 1) << i)
+            }))
+        };
+
+        Ok(match condition {
+            Condition::Value(operand) => operand_value(operand)? != 0,
+            Condition::Compare(operand, expr, equal) => {
+                let expected = eval_int(expr, env)?;
+                (operand_value(operand)? as i64 == expected) == *equal
+            },
+            Condition::Not(inner) => !self.eval_condition(inner, cbits, value, env)?,
+            Condition::And(lhs, rhs) => self.eval_condition(lhs, cbits, value, env)? && self.eval_condition(rhs, cbits, value, env)?,
+            Condition::Or(lhs, rhs) => self.eval_condition(lhs, cbits, value, env)? || self.eval_condition(rhs, cbits, value, env)?,
+        })
+    }
+
+    /// Преобразует операнд в индексы схемы с признаком «весь регистр».
+    fn resolve(&self, operand: &Operand, quantum: bool, env: &HashMap<String, f64>) -> Result<(Vec<usize>, bool), TriadError> {
+        let token = &operand.token;
+        let register = self.find_register(&operand.name, quantum).ok_or_else(|| {
+            let kind = if quantum { "квантовый" } else { "классический" };
+            lexer::error(token.line, token.column, &format!("неизвестный {} регистр '{}'", kind, operand.name))
+        })?;
+
+        match &operand.index {
+            Some(expr) => {
+                let index = eval_int(expr, env)?;
+                if index < 0 || index as usize >= register.size {
+                    return Err(lexer::error(token.line, token.column, &format!(
+                        "индекс {} выходит за пределы регистра '{}' размера {}", index, register.name, register.size)));
+                }
+                Ok((vec![register.offset + index as usize], false))
+            },
+            None => Ok(((register.offset..register.offset + register.size).collect(), true)),
+        }
+    }
+
+    /// Применяет модификаторы слева направо: каждый `ctrl` забирает
+    /// управляющие кубиты из начала списка аргументов.
+    fn apply_modifiers(
+        &self,
+        modifiers: &[Modifier],
+        name: &str,
+        params: &[f64],
+        qubits: &[usize],
+        env: &HashMap<String, f64>,
+        token: &Token,
+    ) -> Result<Vec<Lowered>, TriadError> {
+        let (modifier, rest) = match modifiers.split_first() {
+            Some(split) => split,
+            None => return self.expand(name, params, qubits, token),
+        };
+
+        match modifier {
+            Modifier::Inv => {
+                let inner = self.apply_modifiers(rest, name, params, qubits, env, token)?;
+                Ok(invert(inner))
+            },
+            Modifier::Pow(exponent) => {
+                let exponent = exponent.eval(env)?;
+                if exponent.fract() != 0.0 {
+                    return Err(lexer::error(token.line, token.column, "поддерживаются только целые степени в pow(k) @"));
+                }
+                if exponent < i32::MIN as f64 || exponent > i32::MAX as f64 {
+                    return Err(lexer::error(token.line, token.column, &format!("степень {} в pow(k) @ слишком велика", exponent)));
+                }
+                let inner = self.apply_modifiers(rest, name, params, qubits, env, token)?;
+                raise(inner, exponent as i32, name, token, &self.budget)
+            },
+            Modifier::Ctrl(count, positive) => {
+                let count = match count {
+                    Some(expr) => eval_int(expr, env)?,
+                    None => 1,
+                };
+                if count < 1 || count as usize > qubits.len() {
+                    return Err(lexer::error(token.line, token.column, &format!("некорректное число управляющих кубитов: {}", count)));
+                }
+
+                let (controls, targets) = qubits.split_at(count as usize);
+                let polarity = vec![*positive; controls.len()];
+                let inner = self.apply_modifiers(rest, name, params, targets, env, token)?;
+
+                inner.into_iter().map(|lowered| {
+                    let gate_qubits = match &lowered {
+                        Lowered::Gate(_, gate_qubits) => gate_qubits.as_slice(),
+                        Lowered::Phase(_) => &[],
+                    };
+                    // Уже управляемые гейты получают новые управляющие кубиты в начало
+                    let all: Vec<usize> = controls.iter().chain(gate_qubits).copied().collect();
+                    if all.len() > MAX_CONTROLLED_QUBITS {
+                        return Err(lexer::error(token.line, token.column, &format!(
+                            "управляемый гейт '{}' на {} кубитах не поддерживается, допускается не более {}",
+                            name, all.len(), MAX_CONTROLLED_QUBITS)));
+                    }
+                    // Управляемый гейт хранит матрицу размера 2^n
+                    self.budget.spend(1 << all.len(), token)?;
+                    Ok(match lowered {
+                        Lowered::Gate(gate, _) => Lowered::Gate(gate.controlled_with(&polarity), all),
+                        Lowered::Phase(angle) => Lowered::Gate(CircuitGate::controlled_phase(angle, &polarity), all),
+                    })
+                }).collect()
+            },
+        }
+    }
+
+    /// Раскрывает вызов гейта без модификаторов.
+    fn expand(&self, name: &str, params: &[f64], qubits: &[usize], token: &Token) -> Result<Vec<Lowered>, TriadError> {
+        let (expected_params, expected_qubits) = match name {
+            "U" => (3, 1),
+            "gphase" => (1, 0),
+            _ => match self.gates.get(name) {
+                Some(def) => (def.params.len(), def.qargs.len()),
+                None => return Err(lexer::error(token.line, token.column, &format!("неизвестный гейт '{}'", name))),
+            },
+        };
+        if params.len() != expected_params || qubits.len() != expected_qubits {
+            return Err(lexer::error(token.line, token.column, &format!(
+                "гейт '{}' принимает {} параметр(ов) и {} кубит(ов), указано {} и {}",
+                name, expected_params, expected_qubits, params.len(), qubits.len())));
+        }
+
+        self.budget.spend(1, token)?;
+        if name == "gphase" {
+            return Ok(vec![Lowered::Phase(params[0])]);
+        }
+        if name == "U" {
+            let gate = ParametricGate::U3 { theta: params[0], phi: params[1], lambda: params[2] };
+            return Ok(vec![Lowered::Gate(gate.into(), qubits.to_vec())]);
+        }
+        // Собственными гейтами TRIAD заменяются только гейты библиотеки;
+        // пользовательские раскрываются по своим определениям
+        let native = match name {
+            _ if !self.gates[name].library => None,
+            "phase" => native_gate("p", params),
+            "cphase" => native_gate("cp", params),
+            _ => native_gate(name, params),
+        };
+        if let Some(gate) = native {
+            return Ok(vec![Lowered::Gate(gate, qubits.to_vec())]);
+        }
+
+        let def = &self.gates[name];
+        let env: HashMap<String, f64> = def.params.iter().cloned().zip(params.iter().copied()).collect();
+        let mut lowered = Vec::new();
+        for stmt in &def.body {
+            if let Stmt::Gate { modifiers, name, params, args, token } = stmt {
+                let values = params.iter().map(|p| p.eval(&env)).collect::<Result<Vec<f64>, TriadError>>()?;
+                let targets: Vec<usize> = args.iter()
+                    .map(|arg| qubits[def.qargs.iter().position(|q| *q == arg.name).unwrap()])
+                    .collect();
+                lowered.extend(self.apply_modifiers(modifiers, name, &values, &targets, &env, token)?);
+            }
+        }
+        Ok(lowered)
+    }
+}
+
+/// Возвращает классические биты, в которые записывает инструкция.
+fn written_cbits(instruction: &Instruction) -> Vec<usize> {
+    match instruction {
+        Instruction::Measure { cbit, .. } => vec![*cbit],
+        Instruction::Conditional { instruction, .. } => written_cbits(instruction),
+        _ => Vec::new(),
+    }
+}
+
+/// Обращает последовательность гейтов.
+fn invert(sequence: Vec<Lowered>) -> Vec<Lowered> {
+    sequence.into_iter().rev().map(|lowered| match lowered {
+        Lowered::Gate(gate, qubits) => Lowered::Gate(gate.inverse(), qubits),
+        Lowered::Phase(angle) => Lowered::Phase(-angle),
+    }).collect()
+}
+
+/// Возводит последовательность гейтов в целую степень. Небольшие степени
+/// раскрываются повторением последовательности (с оплатой копий из
+/// `budget`), большие — возведением ее матрицы в степень за O(log k) умножений.
+fn raise(sequence: Vec<Lowered>, exponent: i32, name: &str, token: &Token, budget: &Budget) -> Result<Vec<Lowered>, TriadError> {
+    let count = exponent.unsigned_abs();
+    if count <= MAX_UNROLLED_POWER {
+        budget.spend(sequence.len() * count as usize, token)?;
+        let base = if exponent < 0 { invert(sequence) } else { sequence };
+        return Ok((0..count).flat_map(|_| base.iter().cloned()).collect());
+    }
+    if let [Lowered::Gate(gate, qubits)] = sequence.as_slice() {
+        return Ok(vec![Lowered::Gate(gate.power(exponent), qubits.clone())]);
+    }
+
+    let mut qubits: Vec<usize> = Vec::new();
+    let mut phase = 0.0;
+    for lowered in &sequence {
+        match lowered {
+            Lowered::Gate(_, gate_qubits) => {
+                for &qubit in gate_qubits {
+                    if !qubits.contains(&qubit) {
+                        qubits.push(qubit);
+                    }
+                }
+            },
+            Lowered::Phase(angle) => phase += angle,
+        }
+    }
+    let phase = (phase * exponent as f64).rem_euclid(2.0 * PI);
+    if qubits.is_empty() {
+        return Ok(vec![Lowered::Phase(phase)]);
+    }
+    if qubits.len() > MAX_POWER_QUBITS {
+        return Err(lexer::error(token.line, token.column, &format!(
+            "pow(k) @ для гейта '{}' на {} кубитах поддерживается только при |k| <= {}", name, qubits.len(), MAX_UNROLLED_POWER)));
+    }
+
+    // Кубит `qubits[i]` соответствует биту `i` индекса матрицы схемы
+    let mut circuit = Circuit::new(qubits.len(), 0);
+    for lowered in &sequence {
+        if let Lowered::Gate(gate, gate_qubits) = lowered {
+            let local: Vec<usize> = gate_qubits.iter().map(|q| qubits.iter().position(|x| x == q).unwrap()).collect();
+            circuit.push(Instruction::Gate { gate: gate.clone(), qubits: local });
+        }
+    }
+    let gate = CustomGate { matrix: circuit.unitary()?, num_qubits: qubits.len(), name: name.to_string() };
+
+    // В матрице гейта первый целевой кубит — старший бит индекса
+    qubits.reverse();
+    let mut lowered = vec![Lowered::Gate(gate.power(exponent), qubits)];
+    if phase != 0.0 {
+        lowered.push(Lowered::Phase(phase));
+    }
+    Ok(lowered)
+}
+
+/// Вычисляет выражение, которое должно быть целым числом.
+fn eval_int(expr: &Expr, env: &HashMap<String, f64>) -> Result<i64, TriadError> {
+    let value = expr.eval(env)?;
+    if value.fract() != 0.0 || !value.is_finite() {
+        return Err(expr_error(expr, &format!("ожидалось целое значение, получено {}", value)));
+    }
+    Ok(value as i64)
+}
+
+/// Создает ошибку в позиции начала выражения.
+fn expr_error(expr: &Expr, message: &str) -> TriadError {
+    let (line, column) = expr.position();
+    lexer::error(line, column, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::quantum_simulator::QuantumSimulator;
+    use crate::core::quantum_state::Amplitude;
+    use crate::core::state_vector::StateVectorSimulator;
+
+    const HEADER: &str = "OPENQASM 3.0;\ninclude \"stdgates.inc\";\n";
+
+    fn parse(body: &str) -> Result<Circuit, TriadError> {
+        parse_qasm3(&format!("{}{}", HEADER, body))
+    }
+
+    fn run(circuit: &Circuit) -> (StateVectorSimulator, Vec<bool>) {
+        let mut simulator = StateVectorSimulator::new(circuit.num_qubits());
+        let bits = circuit.run_on(&mut simulator).unwrap();
+        (simulator, bits)
+    }
+
+    fn error_position(body: &str) -> (usize, usize, String) {
+        match parse(body) {
+            Err(TriadError::Parse { line, column, message }) => (line, column, message),
+            other => panic!("ожидалась ошибка разбора, получено {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_teleportation_with_if_else_and_mid_circuit_measurement() {
+        let source = "qubit[3] q;\nbit[2] c;\n\
+            ry(1.1) q[0];\n\
+            h q[1]; cx q[1], q[2];\n\
+            cx q[0], q[1]; h q[0];\n\
+            c[0] = measure q[0];\n\
+            c[1] = measure q[1];\n\
+            if (c[1]) { x q[2]; }\n\
+            if (c[0] == 1) z q[2]; else { }\n";
+
+        for _ in 0..10 {
+            let (simulator, _) = run(&parse(source).unwrap());
+            let expected = (1.1f64 / 2.0).sin().powi(2);
+            assert!((simulator.probability_of_outcome(2, true) - expected).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_else_branch_executes_on_rejected_values() {
+        let circuit = parse("qubit[2] q;\nbit b;\nb = measure q[0];\nif (b) { x q[1]; } else { h q[1]; }\n").unwrap();
+        let (simulator, bits) = run(&circuit);
+        assert!(!bits[0]);
+        assert!((simulator.probability_of_outcome(1, true) - 0.5).abs() < 1e-10);
+
+        // Условие на весь регистр с != раскладывается на все неравные значения
+        let circuit = parse("qubit[1] q;\nbit[2] c;\nif (c != 2) x q[0];\n").unwrap();
+        assert_eq!(circuit.len(), 3);
+    }
+
+    #[test]
+    fn test_for_loop_unrolls_with_index_expressions() {
+        let circuit = parse("qubit[4] q;\nh q[0];\nfor uint i in [0:2] { cx q[i], q[i + 1]; }\nfor i in {1, 3} { rz(pi * i / 4) q[i]; }\n").unwrap();
+        assert_eq!(circuit.gate_counts().get("CNOT"), Some(&3));
+        assert_eq!(circuit.gate_counts().get("RZ"), Some(&2));
+
+        let (simulator, _) = run(&circuit);
+        assert!((simulator.probability_of_joint_outcome(&[0, 1, 2, 3], 0b1111) - 0.5).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_controlled_gates_are_limited_in_size() {
+        let call = |n: usize| {
+            let args: Vec<String> = (0..=n).map(|i| format!("q[{}]", i)).collect();
+            format!("qubit[{}] q;\nctrl({}) @ x {};\n", n + 1, n, args.join(", "))
+        };
+        let circuit = parse(&call(9)).unwrap();
+        assert_eq!(circuit.len(), 1);
+
+        let (line, _, message) = error_position(&call(30));
+        assert_eq!(line, 4);
+        assert!(message.contains("не более 10"));
+    }
+
+    #[test]
+    fn test_for_loop_bounds_are_limited() {
+        // Шаг, переполняющий i64, завершает цикл
+        let circuit = parse("qubit q;\nfor int i in [0:9223372036854775807:9223372036854775807] { x q[0]; }\n").unwrap();
+        assert_eq!(circuit.len(), 2);
+
+        let (line, column, message) = error_position("qubit q;\nfor int i in [0:100000000] { x q[0]; }\n");
+        assert_eq!((line, column), (4, 1));
+        assert!(message.contains("итераций"));
+
+        let (_, _, message) = error_position(
+            "qubit q;\nfor i in [0:999] { for j in [0:999] { x q[0]; } }\n");
+        assert!(message.contains("шагов"));
+
+        // Итерации с пустым телом тоже расходуют бюджет
+        let (line, column, message) = error_position(
+            "for i in [0:99999] { for j in [0:99999] { for k in [0:99999] { } } }\n");
+        assert_eq!((line, column), (3, 43));
+        assert!(message.contains("шагов"));
+    }
+
+    #[test]
+    fn test_hostile_programs_are_rejected() {
+        // Вложенные степени раскрываются с оплатой каждой копии
+        let (line, _, message) = error_position(&format!("qubit q;\n{}x q;\n", "pow(16) @ ".repeat(7)));
+        assert_eq!(line, 4);
+        assert!(message.contains("шагов"));
+
+        let deep_if = format!("bit c;\nqubit q;\n{}x q;\n", "if (c[0]) ".repeat(200_000));
+        let deep_not = format!("bit c;\nqubit q;\nif ({}c[0]) x q;\n", "!".repeat(200_000));
+        let long_or = format!("bit c;\nqubit q;\nif (c[0]{}) x q;\n", " || c[0]".repeat(200_000));
+        let deep_inv = format!("qubit q;\n{}x q;\n", "inv @ ".repeat(200_000));
+        for source in [deep_if, deep_not, long_or, deep_inv] {
+            let (line, _, message) = error_position(&source);
+            assert!(line >= 4);
+            assert!(message.contains("глубина вложенности"), "{}", message);
+        }
+
+        // Наибольшая допустимая вложенность разбирается без переполнения стека
+        let nested = format!("bit c;\nqubit q;\n{}if ({}c[0]{}) x q;\n", "for i in [0:0] ".repeat(MAX_BLOCK_DEPTH - 1),
+            "(".repeat(90), ")".repeat(90));
+        assert_eq!(parse(&nested).unwrap().len(), 1);
+
+        let chain: String = (1..200).map(|i| format!("gate g{} a {{ g{} a; }}\n", i, i - 1)).collect();
+        let (_, _, message) = error_position(&format!("gate g0 a {{ x a; }}\n{}", chain));
+        assert!(message.contains("вложенность определений"));
+    }
+
+    #[test]
+    fn test_gate_modifiers() {
+        // ctrl @ ctrl @ x действует как Тоффоли, negctrl срабатывает на |0⟩
+        let circuit = parse("qubit[4] q;\nx q[0]; x q[1];\nctrl(2) @ x q[0], q[1], q[2];\nnegctrl @ x q[3], q[0];\n").unwrap();
+        let (simulator, _) = run(&circuit);
+        assert!((simulator.get_state().probability(0b0110) - 1.0).abs() < 1e-10);
+
+        // inv @ s = sdg, pow(2) @ t = s, pow(-1) @ t = tdg
+        let circuit = parse("qubit q;\nh q;\ns q;\ninv @ s q;\npow(2) @ t q;\npow(-2) @ t q;\nh q;\n").unwrap();
+        let (simulator, _) = run(&circuit);
+        assert!((simulator.get_state().probability(0) - 1.0).abs() < 1e-10);
+
+        // ctrl @ gphase эквивалентен фазовому гейту на управляющем кубите
+        let circuit = parse("qubit[2] q;\nh q[0];\nctrl @ gphase(pi) q[0];\nh q[0];\n").unwrap();
+        let (simulator, _) = run(&circuit);
+        assert!((simulator.probability_of_outcome(0, true) - 1.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_controlled_rotation_keeps_phase_convention() {
+        let circuit = parse("qubit[2] q;\nx q[0];\nh q[1];\ncrz(pi) q[0], q[1];\nh q[1];\n").unwrap();
+        let (simulator, _) = run(&circuit);
+        // RZ(π) ~ Z с точностью до фазы, поэтому H·Z·H переводит |0⟩ в |1⟩
+        assert!((simulator.probability_of_outcome(1, true) - 1.0).abs() < 1e-10);
+
+        let circuit = parse("gate my(θ) a, b { ctrl @ ry(θ) a, b; }\nqubit[2] q;\nx q[0];\nmy(pi) q[0], q[1];\n").unwrap();
+        let (simulator, _) = run(&circuit);
+        assert!((simulator.probability_of_outcome(1, true) - 1.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_stdgates_definitions() {
+        // cswap раскрывается как ctrl @ swap, а swap — через три cx
+        for input in 0..8u64 {
+            let mut program = String::from("qubit[3] q;\n");
+            for bit in 0..3 {
+                if input & (1 << bit) != 0 {
+                    program.push_str(&format!("x q[{}];\n", bit));
+                }
+            }
+            program.push_str("cswap q[0], q[1], q[2];\n");
+
+            let swapped = (input & 1) | ((input >> 1) & 0b10) | ((input << 1) & 0b100);
+            let expected = if input & 1 == 1 { swapped } else { input };
+            let (simulator, _) = run(&parse(&program).unwrap());
+            assert!((simulator.get_state().probability(expected) - 1.0).abs() < 1e-10);
+        }
+
+        // sx · sx = x, включая фазу: управляемый вариант тоже совпадает с cx
+        let circuit = parse("qubit[2] q;\nh q[0];\nctrl @ sx q[0], q[1];\nctrl @ sx q[0], q[1];\ncx q[0], q[1];\nh q[0];\n").unwrap();
+        let (simulator, _) = run(&circuit);
+        assert!((simulator.get_state().probability(0) - 1.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_unsupported_constructs_are_rejected_with_positions() {
+        let (line, column, message) = error_position("qubit q;\nwhile (true) { x q; }\n");
+        assert_eq!((line, column), (4, 1));
+        assert!(message.contains("'while' не поддерживается"));
+
+        let (_, _, message) = error_position("qubit q;\nbit c;\nif (c) { c = measure q; }\n");
+        assert!(message.contains("биты собственного условия"));
+
+        let (_, _, message) = error_position("qubit q;\npow(0.5) @ x q;\n");
+        assert!(message.contains("целые степени"));
+
+        let (line, _, message) = error_position("qubit[2] a;\nqubit[18446744073709551615] b;\n");
+        assert_eq!(line, 4);
+        assert!(message.contains("суммарный размер регистров"));
+
+        let (line, column, _) = error_position("qubit[2] q;\nfor i in [0:2] { x q[i]; }\n");
+        assert_eq!((line, column), (4, 20));
+
+        assert!(parse_qasm3("OPENQASM 2.0;").is_err());
+
+        let (line, column, message) = error_position("qubit q;\nfor int i in [0:0:3] { x q; }\n");
+        assert_eq!((line, column), (4, 1));
+        assert!(message.contains("шаг цикла"));
+    }
+
+    #[test]
+    fn test_gate_bodies_resolve_only_earlier_gates() {
+        let (line, column, message) = error_position("qubit q;\ngate g a { g a; }\ng q;\n");
+        assert_eq!((line, column), (4, 12));
+        assert!(message.contains("неизвестный гейт 'g'"));
+
+        let (_, _, message) = error_position("gate f a { later a; }\ngate later a { x a; }\n");
+        assert!(message.contains("неизвестный гейт 'later'"));
+
+        // Без include имя h принадлежит пользовательскому гейту, равному X
+        let circuit = parse_qasm3("OPENQASM 3.0;\ngate h a { U(pi, 0, pi) a; }\nqubit q;\nh q;\n").unwrap();
+        assert_eq!(circuit.gate_counts().get("Hadamard"), None);
+        assert!((run(&circuit).0.probability_of_outcome(0, true) - 1.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_large_powers_use_matrix_power() {
+        let circuit = parse("qubit q;\npow(2147483647) @ x q;\n").unwrap();
+        assert_eq!(circuit.len(), 1);
+        assert!((run(&circuit).0.probability_of_outcome(0, true) - 1.0).abs() < 1e-10);
+
+        let circuit = parse("qubit q;\npow(-2147483648) @ x q;\n").unwrap();
+        assert!((run(&circuit).0.probability_of_outcome(0, false) - 1.0).abs() < 1e-10);
+
+        // Последовательность гейтов сворачивается в одну матрицу, а глобальная
+        // фаза сохраняется и становится наблюдаемой под управлением
+        let circuit = parse("gate g a, b { cx a, b; x b; }\ngate w a { gphase(pi/3); x a; }\nqubit[3] q;\n\
+            pow(1000001) @ g q[0], q[1];\nh q[2];\nctrl @ pow(1000000) @ w q[2], q[0];\n").unwrap();
+        assert_eq!(circuit.len(), 4);
+        let (simulator, _) = run(&circuit);
+        let state = simulator.get_state();
+        let expected = Amplitude::from_polar(0.5f64.sqrt(), 4.0 * PI / 3.0);
+        assert!((state.amplitude(0b110) - expected).norm() < 1e-8);
+
+        let (_, _, message) = error_position("qubit q;\npow(2147483648) @ x q;\n");
+        assert!(message.contains("слишком велика"));
+    }
+}
@@ -6,13 +6,13 @@
 
 #![allow(non_camel_case_types)]
 
-use libc::{c_void, c_int, c_longlong, c_char, c_double};
-use std::ffi::CString;
+use libc::{c_int, c_longlong, c_double, c_ulong};
 use std::fmt;
-use std::ptr;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
+use crate::error::TriadError;
+
 // Определения для FFI-типов QuEST (определены в C-библиотеке)
 #[repr(C)]
 pub struct QuESTEnvStruct {
@@ -24,17 +24,103 @@ pub struct QuregStruct {
     _private: [u8; 0],
 }
 
+/// Точность, с которой QuEST при двойной точности (`REAL_EPS`) проверяет
+/// унитарность матриц и вероятности проекций.
+pub const QUEST_REAL_EPS: f64 = 1e-13;
+
 /// Тип для представления среды выполнения QuEST.
 pub type QuESTEnv = *mut QuESTEnvStruct;
 
 /// Тип для представления квантового регистра в QuEST.
 pub type Qureg = *mut QuregStruct;
 
-/// Тип для представления комплексного матричного оператора 2x2.
-pub type ComplexMatrix2 = [Complex; 4];
+/// Комплексная матрица 2x2 в формате QuEST (`ComplexMatrix2`): вещественные
+/// и мнимые части хранятся раздельно, структура передается по значению.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ComplexMatrix2 {
+    pub real: [[c_double; 2]; 2],
+    pub imag: [[c_double; 2]; 2],
+}
+
+/// Комплексная матрица 4x4 в формате QuEST (`ComplexMatrix4`).
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ComplexMatrix4 {
+    pub real: [[c_double; 4]; 4],
+    pub imag: [[c_double; 4]; 4],
+}
+
+/// Комплексная матрица 2^n x 2^n в формате QuEST (`ComplexMatrixN`).
+/// Строки выделяются и освобождаются самой QuEST
+/// (`createComplexMatrixN`/`destroyComplexMatrixN`).
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct ComplexMatrixN {
+    pub num_qubits: c_int,
+    pub real: *mut *mut c_double,
+    pub imag: *mut *mut c_double,
+}
+
+impl ComplexMatrix2 {
+    /// Строит матрицу по элементам, перечисленным по строкам.
+    pub fn from_rows(matrix: &[Complex]) -> Self {
+        assert_eq!(matrix.len(), 4, "Матрица должна иметь размер 2x2");
+        let mut result = ComplexMatrix2 { real: [[0.0; 2]; 2], imag: [[0.0; 2]; 2] };
+        for (i, value) in matrix.iter().enumerate() {
+            result.real[i / 2][i % 2] = value.real;
+            result.imag[i / 2][i % 2] = value.imag;
+        }
+        result
+    }
+}
 
-/// Тип для представления комплексного матричного оператора 4x4.
-pub type ComplexMatrix4 = [Complex; 16];
+impl ComplexMatrix4 {
+    /// Строит матрицу по элементам, перечисленным по строкам.
+    pub fn from_rows(matrix: &[Complex]) -> Self {
+        assert_eq!(matrix.len(), 16, "Матрица должна иметь размер 4x4");
+        let mut result = ComplexMatrix4 { real: [[0.0; 4]; 4], imag: [[0.0; 4]; 4] };
+        for (i, value) in matrix.iter().enumerate() {
+            result.real[i / 4][i % 4] = value.real;
+            result.imag[i / 4][i % 4] = value.imag;
+        }
+        result
+    }
+}
+
+/// Матрица `ComplexMatrixN`, выделенная QuEST и освобождаемая при удалении.
+struct OwnedComplexMatrixN {
+    matrix: ComplexMatrixN,
+}
+
+impl OwnedComplexMatrixN {
+    /// Выделяет матрицу на `num_qubits` кубитах и заполняет ее элементами,
+    /// перечисленными по строкам.
+    fn from_rows(num_qubits: usize, matrix: &[Complex]) -> Self {
+        let dim = 1usize << num_qubits;
+        assert_eq!(matrix.len(), dim * dim, "Размер матрицы не соответствует числу кубитов");
+
+        let owned = OwnedComplexMatrixN { matrix: unsafe { createComplexMatrixN(num_qubits as c_int) } };
+        for row in 0..dim {
+            // SAFETY: createComplexMatrixN выделяет `dim` строк по `dim` элементов
+            unsafe {
+                let real = *owned.matrix.real.add(row);
+                let imag = *owned.matrix.imag.add(row);
+                for col in 0..dim {
+                    *real.add(col) = matrix[row * dim + col].real;
+                    *imag.add(col) = matrix[row * dim + col].imag;
+                }
+            }
+        }
+        owned
+    }
+}
+
+impl Drop for OwnedComplexMatrixN {
+    fn drop(&mut self) {
+        unsafe { destroyComplexMatrixN(self.matrix) };
+    }
+}
 
 /// Тип для представления комплексного числа в QuEST.
 #[repr(C)]
@@ -44,6 +130,16 @@ pub struct Complex {
     pub imag: c_double,
 }
 
+/// Коды операторов Паули, используемые в QuEST (`enum pauliOpType`).
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PauliOpType {
+    PauliI = 0,
+    PauliX = 1,
+    PauliY = 2,
+    PauliZ = 3,
+}
+
 /// Безопасная обертка для указателя QuESTEnv.
 /// Это обертка гарантирует, что указатель не будет использоваться неправильно
 /// и что он может быть безопасно передан между потоками (Send + Sync).
@@ -64,23 +160,39 @@ unsafe impl Sync for SafeQuESTEnv {}
 impl SafeQuESTEnv {
     /// Создает новую безопасную обертку для QuESTEnv.
     pub fn new() -> Self {
+        Self::try_new().unwrap_or_else(|err| panic!("{}", err))
+    }
+    
+    /// Создает новую безопасную обертку для QuESTEnv, возвращая ошибку вместо паники.
+    pub fn try_new() -> Result<Self, TriadError> {
         debug_print!("DEBUG: Создаем среду QuEST");
         let env = unsafe { createQuESTEnv() };
-        if env.is_null() {
-            panic!("Не удалось создать среду QuEST (null pointer)");
-        }
         debug_print!("DEBUG: Среда QuEST успешно создана");
         
-        SafeQuESTEnv {
+        Ok(SafeQuESTEnv {
             env,
             initialized: Arc::new(AtomicBool::new(true)),
-        }
+        })
     }
     
     /// Возвращает указатель на QuESTEnv.
     pub fn get_env(&self) -> QuESTEnv {
         self.env
     }
+    
+    /// Инициализирует генератор случайных чисел QuEST через `seedQuEST`.
+    /// Значение передается двумя 32-битными половинами, чтобы не зависеть
+    /// от размера `unsigned long` на платформе.
+    pub fn seed(&self, seed: u64) {
+        let mut seeds = [(seed & 0xffff_ffff) as c_ulong, (seed >> 32) as c_ulong];
+        unsafe { seedQuEST(self.env, seeds.as_mut_ptr(), seeds.len() as c_int) };
+    }
+}
+
+impl Default for SafeQuESTEnv {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Clone for SafeQuESTEnv {
@@ -118,40 +230,48 @@ unsafe impl Sync for QuregWrapper {}
 impl QuregWrapper {
     /// Создает новый квантовый регистр с указанным числом кубитов.
     pub fn new(num_qubits: usize, env: &SafeQuESTEnv) -> Self {
+        Self::try_new(num_qubits, env).unwrap_or_else(|err| panic!("{}", err))
+    }
+    
+    /// Создает новый квантовый регистр, возвращая ошибку вместо паники.
+    pub fn try_new(num_qubits: usize, env: &SafeQuESTEnv) -> Result<Self, TriadError> {
         // Проверяем, что количество кубитов имеет смысл
         if num_qubits == 0 {
-            panic!("Число кубитов не может быть нулем");
+            return Err(TriadError::NoQubits);
         }
         if num_qubits > 30 {
             // Ограничиваем максимальное число кубитов для предотвращения переполнения памяти
-            panic!("Число кубитов не может превышать 30 (ограничение для безопасности)");
+            return Err(TriadError::TooManyQubits { requested: num_qubits, max: 30 });
         }
         
         debug_print!("DEBUG: Создаем квантовый регистр с {} кубитами", num_qubits);
         
+        // При нехватке памяти QuEST завершает процесс (см. `validateQuregAllocation`
+        // в src/utils.c), поэтому сначала проверяем, что память под вещественные
+        // и мнимые части 2^n амплитуд может быть выделена
+        let mut probe: Vec<f64> = Vec::new();
+        probe.try_reserve_exact(2usize << num_qubits).map_err(|_| TriadError::BackendFailure(format!(
+            "недостаточно памяти для регистра QuEST из {} кубитов", num_qubits)))?;
+        drop(probe);
+
         // Вызываем C-функцию для создания регистра
         // Явно приводим num_qubits к типу c_int (обычно 32-бита, signed)
         let qureg = unsafe { createQureg(num_qubits as c_int, env.get_env()) };
         
-        // Проверяем, что указатель не является null
-        if qureg.is_null() {
-            panic!("Не удалось создать квантовый регистр (null pointer)");
-        }
-        
         // Проверяем, что число кубитов установлено корректно
         let actual_qubits = unsafe { getNumQubits(qureg) } as usize;
         debug_print!("DEBUG: Фактическое число кубитов: {}", actual_qubits);
         
         if actual_qubits != num_qubits {
             unsafe { destroyQureg(qureg, env.get_env()) };
-            panic!("Ошибка инициализации: запрошено {} кубитов, но фактическое число кубитов - {}", 
-                   num_qubits, actual_qubits);
+            return Err(TriadError::BackendFailure(format!(
+                "запрошено {} кубитов, но фактическое число кубитов - {}", num_qubits, actual_qubits)));
         }
         
-        QuregWrapper {
+        Ok(QuregWrapper {
             qureg,
             env: env.clone(),
-        }
+        })
     }
     
     /// Возвращает указатель на Qureg.
@@ -170,6 +290,19 @@ impl QuregWrapper {
         unsafe { getNumQubits(self.qureg) as usize }
     }
     
+    /// Проверяет, что кубиты находятся в пределах регистра и не повторяются.
+    fn check_distinct_qubits(&self, qubits: &[usize]) {
+        let num_qubits = self.get_num_qubits();
+        for (i, &qubit) in qubits.iter().enumerate() {
+            if qubit >= num_qubits {
+                panic!("Индекс кубита выходит за пределы: {}", qubit);
+            }
+            if qubits[..i].contains(&qubit) {
+                panic!("Кубит {} указан несколько раз", qubit);
+            }
+        }
+    }
+    
     /// Применяет гейт Адамара к указанному кубиту.
     pub fn hadamard(&mut self, qubit: usize) {
         if qubit >= self.get_num_qubits() {
@@ -226,18 +359,14 @@ impl QuregWrapper {
     
     /// Применяет CNOT-гейт с указанными контрольным и целевым кубитами.
     pub fn cnot(&mut self, control: usize, target: usize) {
-        if control >= self.get_num_qubits() || target >= self.get_num_qubits() {
-            panic!("Индекс кубита выходит за пределы: контроль = {}, цель = {}", control, target);
-        }
+        self.check_distinct_qubits(&[control, target]);
         
         unsafe { controlledNot(self.qureg, control as c_int, target as c_int) };
     }
     
     /// Применяет контролируемый фазовый поворот между двумя кубитами.
     pub fn controlled_phase_flip(&mut self, control: usize, target: usize) {
-        if control >= self.get_num_qubits() || target >= self.get_num_qubits() {
-            panic!("Индекс кубита выходит за пределы: контроль = {}, цель = {}", control, target);
-        }
+        self.check_distinct_qubits(&[control, target]);
         
         unsafe { controlledPhaseFlip(self.qureg, control as c_int, target as c_int) };
     }
@@ -280,6 +409,18 @@ impl QuregWrapper {
         result != 0
     }
     
+    /// Проецирует кубит на результат `outcome` и перенормирует состояние
+    /// через `collapseToOutcome`. Возвращает вероятность этого результата.
+    /// Паникует, если вероятность результата не больше `QUEST_REAL_EPS`.
+    pub fn collapse_to_outcome(&mut self, qubit: usize, outcome: bool) -> f64 {
+        let prob = self.calc_prob_of_outcome(qubit, outcome);
+        if prob <= QUEST_REAL_EPS {
+            panic!("Невозможно спроецировать кубит {} на результат с вероятностью {}", qubit, prob);
+        }
+        
+        unsafe { collapseToOutcome(self.qureg, qubit as c_int, outcome as c_int) }
+    }
+    
     /// Возвращает вероятность указанного состояния (заданного индексом).
     pub fn get_probability(&self, state_idx: usize) -> f64 {
         if state_idx >= (1 << self.get_num_qubits()) {
@@ -292,15 +433,94 @@ impl QuregWrapper {
     /// Возвращает вектор вероятностей для всех состояний.
     pub fn get_probabilities(&self) -> Vec<f64> {
         let num_amps = 1 << self.get_num_qubits();
-        let mut probs = vec![0.0; num_amps];
+        (0..num_amps).map(|i| unsafe { getProbAmp(self.qureg, i as c_longlong) }).collect()
+    }
+    
+    /// Возвращает амплитуду указанного состояния (заданного индексом).
+    pub fn get_amplitude(&self, state_idx: usize) -> Complex {
+        if state_idx >= (1 << self.get_num_qubits()) {
+            panic!("Индекс состояния выходит за пределы: {}", state_idx);
+        }
         
-        for i in 0..num_amps {
-            probs[i] = unsafe { getProbAmp(self.qureg, i as c_longlong) };
+        unsafe {
+            Complex {
+                real: getRealAmp(self.qureg, state_idx as c_longlong),
+                imag: getImagAmp(self.qureg, state_idx as c_longlong),
+            }
+        }
+    }
+    
+    /// Возвращает вектор амплитуд для всех состояний.
+    pub fn get_amplitudes(&self) -> Vec<Complex> {
+        let num_amps = unsafe { getNumAmps(self.qureg) } as usize;
+        
+        (0..num_amps)
+            .map(|i| unsafe {
+                Complex {
+                    real: getRealAmp(self.qureg, i as c_longlong),
+                    imag: getImagAmp(self.qureg, i as c_longlong),
+                }
+            })
+            .collect()
+    }
+    
+    /// Вычисляет вероятность получить `outcome` при измерении кубита.
+    /// Состояние регистра не изменяется.
+    pub fn calc_prob_of_outcome(&self, qubit: usize, outcome: bool) -> f64 {
+        if qubit >= self.get_num_qubits() {
+            panic!("Индекс кубита выходит за пределы: {}", qubit);
+        }
+        
+        unsafe { calcProbOfOutcome(self.qureg, qubit as c_int, outcome as c_int) }
+    }
+    
+    /// Вычисляет вероятности всех 2^k результатов измерения указанных кубитов.
+    /// Бит `i` индекса результата соответствует кубиту `qubits[i]`.
+    pub fn calc_prob_of_all_outcomes(&self, qubits: &[usize]) -> Vec<f64> {
+        self.check_distinct_qubits(qubits);
+        
+        let qubits: Vec<c_int> = qubits.iter().map(|&q| q as c_int).collect();
+        let mut probs = vec![0.0; 1 << qubits.len()];
+        
+        unsafe {
+            calcProbOfAllOutcomes(probs.as_mut_ptr(), self.qureg, qubits.as_ptr(), qubits.len() as c_int);
         }
         
         probs
     }
     
+    /// Вычисляет ожидаемое значение произведения операторов Паули
+    /// на указанных кубитах. Состояние регистра не изменяется;
+    /// `workspace` — рабочий регистр того же размера, его состояние затирается.
+    pub fn calc_expec_pauli_prod(&self, targets: &[usize], codes: &[PauliOpType], workspace: &QuregWrapper) -> f64 {
+        assert_eq!(targets.len(), codes.len(), "Число кубитов и операторов Паули должно совпадать");
+        assert_eq!(workspace.get_num_qubits(), self.get_num_qubits(), "Рабочий регистр должен совпадать по размеру");
+        
+        self.check_distinct_qubits(targets);
+        
+        let targets: Vec<c_int> = targets.iter().map(|&q| q as c_int).collect();
+        
+        unsafe {
+            calcExpecPauliProd(self.qureg, targets.as_ptr(), codes.as_ptr(), 
+                               targets.len() as c_int, workspace.get_qureg())
+        }
+    }
+    
+    /// Вычисляет ожидаемое значение взвешенной суммы произведений операторов Паули.
+    /// Каждый член суммы задается `num_qubits` кодами в `all_codes` подряд;
+    /// `workspace` — рабочий регистр того же размера.
+    pub fn calc_expec_pauli_sum(&self, all_codes: &[PauliOpType], coeffs: &[f64], workspace: &QuregWrapper) -> f64 {
+        let num_qubits = self.get_num_qubits();
+        assert_eq!(all_codes.len(), coeffs.len() * num_qubits, 
+                   "Каждый член суммы должен содержать по одному оператору Паули на кубит");
+        assert_eq!(workspace.get_num_qubits(), num_qubits, "Рабочий регистр должен совпадать по размеру");
+        
+        unsafe {
+            calcExpecPauliSum(self.qureg, all_codes.as_ptr(), coeffs.as_ptr(), 
+                              coeffs.len() as c_int, workspace.get_qureg())
+        }
+    }
+    
     /// Инициализирует состояние |0...0⟩.
     pub fn init_zero_state(&mut self) {
         unsafe { initZeroState(self.qureg) };
@@ -326,41 +546,69 @@ impl QuregWrapper {
             panic!("Индекс кубита выходит за пределы: {}", qubit);
         }
         
-        unsafe { unitary(self.qureg, qubit as c_int, matrix.as_ptr()) };
+        unsafe { unitary(self.qureg, qubit as c_int, *matrix) };
+    }
+
+    /// Применяет двухкубитовую унитарную матрицу к указанным кубитам.
+    /// Первый кубит соответствует старшему биту индекса матрицы
+    /// (в QuEST `targetQubit1` — младший бит, поэтому порядок меняется).
+    pub fn two_qubit_unitary(&mut self, qubit1: usize, qubit2: usize, matrix: &ComplexMatrix4) {
+        self.check_distinct_qubits(&[qubit1, qubit2]);
+        
+        unsafe { twoQubitUnitary(self.qureg, qubit2 as c_int, qubit1 as c_int, *matrix) };
+    }
+
+    /// Применяет унитарную матрицу 2^k x 2^k (по строкам) к k указанным кубитам.
+    /// Первый кубит соответствует старшему биту индекса матрицы.
+    pub fn multi_qubit_unitary(&mut self, targets: &[usize], matrix: &[Complex]) {
+        let dim = 1usize << targets.len();
+        assert_eq!(matrix.len(), dim * dim, "Размер матрицы не соответствует числу целевых кубитов");
+        
+        self.check_distinct_qubits(targets);
+        
+        // QuEST считает первый целевой кубит младшим битом индекса матрицы
+        let targets: Vec<c_int> = targets.iter().rev().map(|&q| q as c_int).collect();
+        let owned = OwnedComplexMatrixN::from_rows(targets.len(), matrix);
+        
+        unsafe { multiQubitUnitary(self.qureg, targets.as_ptr(), targets.len() as c_int, owned.matrix) };
     }
 
     /// Применяет контролируемую унитарную матрицу к указанному кубиту
     pub fn controlled_unitary(&mut self, control: usize, target: usize, matrix: &ComplexMatrix2) {
-        if control >= self.get_num_qubits() || target >= self.get_num_qubits() {
-            panic!("Индекс кубита выходит за пределы: контроль = {}, цель = {}", control, target);
-        }
+        self.check_distinct_qubits(&[control, target]);
+        
+        unsafe { controlledUnitary(self.qureg, control as c_int, target as c_int, *matrix) };
+    }
+
+    /// Применяет унитарную матрицу к целевому кубиту при условии,
+    /// что все управляющие кубиты находятся в состоянии |1⟩
+    pub fn multi_controlled_unitary(&mut self, controls: &[usize], target: usize, matrix: &ComplexMatrix2) {
+        let qubits: Vec<usize> = controls.iter().copied().chain(std::iter::once(target)).collect();
+        self.check_distinct_qubits(&qubits);
         
-        unsafe { controlledUnitary(self.qureg, control as c_int, target as c_int, matrix.as_ptr()) };
+        let controls: Vec<c_int> = controls.iter().map(|&q| q as c_int).collect();
+        unsafe {
+            multiControlledUnitary(self.qureg, controls.as_ptr(), controls.len() as c_int, target as c_int, *matrix)
+        };
     }
 
     /// Применяет контролируемое вращение X
     pub fn controlled_rotate_x(&mut self, control: usize, target: usize, angle: f64) {
-        if control >= self.get_num_qubits() || target >= self.get_num_qubits() {
-            panic!("Индекс кубита выходит за пределы: контроль = {}, цель = {}", control, target);
-        }
+        self.check_distinct_qubits(&[control, target]);
         
         unsafe { controlledRotateX(self.qureg, control as c_int, target as c_int, angle) };
     }
 
     /// Применяет контролируемое вращение Y
     pub fn controlled_rotate_y(&mut self, control: usize, target: usize, angle: f64) {
-        if control >= self.get_num_qubits() || target >= self.get_num_qubits() {
-            panic!("Индекс кубита выходит за пределы: контроль = {}, цель = {}", control, target);
-        }
+        self.check_distinct_qubits(&[control, target]);
         
         unsafe { controlledRotateY(self.qureg, control as c_int, target as c_int, angle) };
     }
 
     /// Применяет контролируемое вращение Z
     pub fn controlled_rotate_z(&mut self, control: usize, target: usize, angle: f64) {
-        if control >= self.get_num_qubits() || target >= self.get_num_qubits() {
-            panic!("Индекс кубита выходит за пределы: контроль = {}, цель = {}", control, target);
-        }
+        self.check_distinct_qubits(&[control, target]);
         
         unsafe { controlledRotateZ(self.qureg, control as c_int, target as c_int, angle) };
     }
@@ -369,14 +617,13 @@ impl QuregWrapper {
 impl fmt::Debug for QuregWrapper {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let num_qubits = self.get_num_qubits();
-        let num_amps = 1 << num_qubits;
         let probs = self.get_probabilities();
         
         writeln!(f, "QuregWrapper {{ num_qubits: {}, probabilities: [", num_qubits)?;
         
-        for i in 0..num_amps {
-            if i < 8 || probs[i] > 0.001 {
-                writeln!(f, "  |{:0width$b}>: {:.6}", i, probs[i], width = num_qubits)?;
+        for (i, &prob) in probs.iter().enumerate() {
+            if i < 8 || prob > 0.001 {
+                writeln!(f, "  |{:0width$b}>: {:.6}", i, prob, width = num_qubits)?;
             }
         }
         
@@ -398,6 +645,7 @@ extern "C" {
     // Функции для работы со средой и регистрами
     fn createQuESTEnv() -> QuESTEnv;
     fn destroyQuESTEnv(env: QuESTEnv);
+    fn seedQuEST(env: QuESTEnv, seedArray: *mut c_ulong, numSeeds: c_int);
     fn createQureg(numQubits: c_int, env: QuESTEnv) -> Qureg;
     fn destroyQureg(qureg: Qureg, env: QuESTEnv);
     
@@ -421,27 +669,38 @@ extern "C" {
     
     // Измерения и вероятности
     fn measure(qureg: Qureg, measureQubit: c_int) -> c_int;
+    fn collapseToOutcome(qureg: Qureg, measureQubit: c_int, outcome: c_int) -> c_double;
     fn getProbAmp(qureg: Qureg, index: c_longlong) -> c_double;
+    fn getRealAmp(qureg: Qureg, index: c_longlong) -> c_double;
+    fn getImagAmp(qureg: Qureg, index: c_longlong) -> c_double;
     fn calcProbOfOutcome(qureg: Qureg, measureQubit: c_int, outcome: c_int) -> c_double;
+    fn calcProbOfAllOutcomes(outcomeProbs: *mut c_double, qureg: Qureg, qubits: *const c_int, numQubits: c_int);
+    
+    // Ожидаемые значения операторов Паули
+    fn calcExpecPauliProd(qureg: Qureg, targetQubits: *const c_int, pauliCodes: *const PauliOpType, 
+                          numTargets: c_int, workspace: Qureg) -> c_double;
+    fn calcExpecPauliSum(qureg: Qureg, allPauliCodes: *const PauliOpType, termCoeffs: *const c_double, 
+                         numSumTerms: c_int, workspace: Qureg) -> c_double;
     
     // Функции информации о квантовом регистре
     fn getNumQubits(qureg: Qureg) -> c_int;
     fn getNumAmps(qureg: Qureg) -> c_longlong;
     
-    // Унитарные операторы
-    fn unitary(qureg: Qureg, targetQubit: c_int, u: *const Complex);
+    // Унитарные операторы (матрицы передаются по значению)
+    fn unitary(qureg: Qureg, targetQubit: c_int, u: ComplexMatrix2);
+    
+    fn twoQubitUnitary(qureg: Qureg, targetQubit1: c_int, targetQubit2: c_int, u: ComplexMatrix4);
+    fn multiQubitUnitary(qureg: Qureg, targs: *const c_int, numTargs: c_int, u: ComplexMatrixN);
+    fn createComplexMatrixN(numQubits: c_int) -> ComplexMatrixN;
+    fn destroyComplexMatrixN(matr: ComplexMatrixN);
     
     // Контролируемые операторы
-    fn controlledUnitary(qureg: Qureg, controlQubit: c_int, targetQubit: c_int, u: *const Complex);
+    fn controlledUnitary(qureg: Qureg, controlQubit: c_int, targetQubit: c_int, u: ComplexMatrix2);
     fn controlledRotateX(qureg: Qureg, controlQubit: c_int, targetQubit: c_int, angle: c_double);
     fn controlledRotateY(qureg: Qureg, controlQubit: c_int, targetQubit: c_int, angle: c_double);
     fn controlledRotateZ(qureg: Qureg, controlQubit: c_int, targetQubit: c_int, angle: c_double);
     
     // Мультиконтролируемые операторы
     fn multiControlledUnitary(qureg: Qureg, controlQubits: *const c_int, numControlQubits: c_int, 
-                               targetQubit: c_int, u: *const Complex);
-    
-    // Файловые операции
-    fn writeStateToFile(qureg: Qureg, filename: *const c_char) -> c_int;
-    fn readStateFromFile(qureg: Qureg, filename: *const c_char) -> c_int;
+                               targetQubit: c_int, u: ComplexMatrix2);
 } 
\ No newline at end of file
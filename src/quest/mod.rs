@@ -1,19 +1,32 @@
-/// Интеграция с библиотекой QuEST (Quantum Exact Simulation Toolkit).
-/// Обеспечивает высокопроизводительную симуляцию квантовых систем с использованием QuEST.
+//! Интеграция с библиотекой QuEST (Quantum Exact Simulation Toolkit).
+//! Обеспечивает высокопроизводительную симуляцию квантовых систем с использованием QuEST.
+//!
+//! Валидаторы QuEST аварийно завершают процесс (см. `invalidQuESTInputError`
+//! в src/utils.c), поэтому все входные данные проверяются здесь до вызова C-кода,
+//! а память под регистр перед его созданием пробно выделяется на стороне Rust
+//! (нехватка памяти возвращается как `TriadError::BackendFailure`). Процесс
+//! по-прежнему завершится, если память закончится между пробным выделением
+//! и вызовом `createQureg`.
 
 /// FFI-интерфейс для прямого взаимодействия с C-библиотекой QuEST.
 pub mod ffi;
 
-/// Реализация квантового симулятора на основе библиотеки QuEST
-
-use crate::core::gates::Gate;
-use crate::core::quantum_simulator::{QuantumSimulator, AdvancedQuantumSimulator};
-use crate::core::qubit::Qubit;
-use crate::core::quantum_state::{QuantumState, Amplitude};
+use crate::core::gates::{Gate, is_unitary_matrix, orthonormalize_rows};
+use crate::core::pauli::{Pauli, parse_pauli_product};
+use crate::core::quantum_simulator::{QuantumSimulator, AdvancedQuantumSimulator, validate_gate_targets, validate_joint_outcome, UNITARITY_TOLERANCE};
+use crate::core::quantum_state::{QuantumState, Amplitude, StateSnapshot};
+use crate::core::state_vector::StateVector;
+use crate::error::TriadError;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use std::fmt;
+use std::sync::Mutex;
 
 use ffi::*;
 
+/// Максимальное число кубитов в регистре QuEST (ограничение по памяти)
+pub const MAX_QUBITS: usize = 30;
+
 // Макрос для отладки, который ничего не делает
 macro_rules! debug_print {
     ($($arg:tt)*) => {};
@@ -25,51 +38,42 @@ pub struct QuESTSimulator {
     qureg: QuregWrapper, 
     /// Окружение QuEST
     env: SafeQuESTEnv,
+    /// Генератор случайных чисел для измерений и выборок
+    rng: StdRng,
+    /// Рабочий регистр для ожидаемых значений операторов Паули;
+    /// создается при первом вычислении и затем переиспользуется
+    workspace: Mutex<Option<QuregWrapper>>,
 }
 
 impl QuESTSimulator {
     /// Создает новый экземпляр симулятора с указанным количеством кубитов
     pub fn new(num_qubits: usize) -> Self {
+        Self::try_new(num_qubits).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Создает новый экземпляр симулятора, возвращая ошибку вместо паники
+    /// для некорректного числа кубитов или при нехватке памяти под регистр.
+    pub fn try_new(num_qubits: usize) -> Result<Self, TriadError> {
         // Проверяем, что количество кубитов имеет смысл
         if num_qubits == 0 {
-            panic!("Количество кубитов должно быть положительным");
+            return Err(TriadError::NoQubits);
         }
-        if num_qubits > 30 {
+        if num_qubits > MAX_QUBITS {
             // Ограничиваем максимальное число кубитов для предотвращения переполнения памяти
-            panic!("Количество кубитов не может превышать 30 для безопасности");
+            return Err(TriadError::TooManyQubits { requested: num_qubits, max: MAX_QUBITS });
         }
         
         debug_print!("INFO: Создаем QuESTSimulator с {} кубитами", num_qubits);
         
         // Создаем среду и регистр
-        let env = SafeQuESTEnv::new();
-        
-        // Проверяем, что среда создана успешно
-        if env.get_env().is_null() {
-            panic!("Ошибка инициализации: не удалось создать среду QuEST");
-        }
-        
-        let mut qureg = QuregWrapper::new(num_qubits, &env);
-        
-        // Проверяем, что регистр создан успешно
-        if qureg.get_qureg().is_null() {
-            panic!("Ошибка инициализации: не удалось создать квантовый регистр");
-        }
+        let env = SafeQuESTEnv::try_new()?;
+        let mut qureg = QuregWrapper::try_new(num_qubits, &env)?;
         
         qureg.init_zero_state();
         
-        // Проверяем, что число кубитов установлено корректно
-        let actual_qubits = qureg.get_num_qubits();
-        debug_print!("INFO: Фактическое число кубитов в регистре: {}", actual_qubits);
-        
-        if actual_qubits != num_qubits {
-            panic!("Ошибка инициализации: запрошено {} кубитов, но фактическое число кубитов - {}", 
-                   num_qubits, actual_qubits);
-        }
-        
         debug_print!("INFO: QuESTSimulator успешно создан");
         
-        QuESTSimulator { qureg, env }
+        Ok(QuESTSimulator { qureg, env, rng: StdRng::from_entropy(), workspace: Mutex::new(None) })
     }
 
     /// Возвращает количество кубитов в регистре
@@ -82,12 +86,79 @@ impl QuESTSimulator {
         self.qureg.get_probabilities()
     }
 
+    /// Копирует амплитуды из регистра QuEST в нативный вектор состояния
+    pub fn get_state_vector(&self) -> StateVector {
+        let amplitudes = self.qureg.get_amplitudes()
+            .into_iter()
+            .map(|c| Amplitude::new(c.real, c.imag))
+            .collect();
+        
+        StateVector::from_amplitudes(amplitudes)
+    }
+
+    /// Вычисляет ожидаемое значение взвешенной суммы произведений операторов Паули
+    /// (например, гамильтониана) через `calcExpecPauliSum`.
+    pub fn try_expectation_value_of_sum(&self, terms: &[(f64, Vec<(usize, char)>)]) -> Result<f64, TriadError> {
+        let num_qubits = self.num_qubits();
+        let mut all_codes = vec![PauliOpType::PauliI; terms.len() * num_qubits];
+        let mut coeffs = Vec::with_capacity(terms.len());
+        
+        for (term_idx, (coefficient, pauli_product)) in terms.iter().enumerate() {
+            for (qubit, pauli) in parse_pauli_product(pauli_product, num_qubits)? {
+                all_codes[term_idx * num_qubits + qubit] = pauli_code(pauli);
+            }
+            coeffs.push(*coefficient);
+        }
+        
+        if coeffs.is_empty() {
+            return Ok(0.0);
+        }
+        
+        self.with_workspace(|workspace| self.qureg.calc_expec_pauli_sum(&all_codes, &coeffs, workspace))
+    }
+
+    /// Выполняет `f` с рабочим регистром, создавая его при первом обращении.
+    fn with_workspace<T>(&self, f: impl FnOnce(&QuregWrapper) -> T) -> Result<T, TriadError> {
+        let mut workspace = self.workspace.lock().unwrap_or_else(|err| err.into_inner());
+        if workspace.is_none() {
+            *workspace = Some(QuregWrapper::try_new(self.num_qubits(), &self.env)?);
+        }
+        Ok(f(workspace.as_ref().unwrap()))
+    }
+
     /// Печатает состояние симулятора
     pub fn print_state(&self) {
         eprintln!("{:?}", self.qureg);
     }
 }
 
+/// Преобразует унитарную матрицу (по строкам) в комплексные числа QuEST.
+///
+/// QuEST проверяет унитарность с точностью `QUEST_REAL_EPS` и завершает процесс
+/// при ошибке, поэтому матрица проверяется с обычным допуском и затем
+/// ортонормируется. Паникует, если матрица не унитарна.
+fn to_quest_matrix(matrix: &[Amplitude]) -> Vec<Complex> {
+    if !is_unitary_matrix(matrix, UNITARITY_TOLERANCE) {
+        panic!("Матрица не является унитарной");
+    }
+    
+    let dim = (matrix.len() as f64).sqrt().round() as usize;
+    orthonormalize_rows(matrix.to_vec(), dim)
+        .into_iter()
+        .map(|a| Complex { real: a.re, imag: a.im })
+        .collect()
+}
+
+/// Преобразует оператор Паули в код, используемый QuEST
+fn pauli_code(pauli: Pauli) -> PauliOpType {
+    match pauli {
+        Pauli::I => PauliOpType::PauliI,
+        Pauli::X => PauliOpType::PauliX,
+        Pauli::Y => PauliOpType::PauliY,
+        Pauli::Z => PauliOpType::PauliZ,
+    }
+}
+
 impl fmt::Debug for QuESTSimulator {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "QuESTSimulator with {} qubits", self.num_qubits())
@@ -99,6 +170,14 @@ impl QuantumSimulator for QuESTSimulator {
         Self::new(num_qubits)
     }
     
+    fn try_new(num_qubits: usize) -> Result<Self, TriadError> where Self: Sized {
+        Self::try_new(num_qubits)
+    }
+    
+    fn num_qubits(&self) -> usize {
+        self.qureg.get_num_qubits()
+    }
+    
     fn reset(&mut self) {
         self.qureg.init_zero_state();
     }
@@ -123,49 +202,88 @@ impl QuantumSimulator for QuESTSimulator {
         self.qureg.cnot(control, target);
     }
 
-    /// Применяет квантовый гейт к регистру
-    fn apply_gate(&mut self, _gate: &impl Gate) {
-        // Базовую реализацию оставляем пустой,
-        // более конкретная реализация потребует работы с Gate trait
-    }
-
-    /// Метод для прямого применения гейта вращения
-    fn apply_rotation(&mut self, _gate: &impl Gate) {
-        // Базовую реализацию оставляем пустой,
-        // более конкретная реализация потребует работы с Gate trait
+    /// Применяет квантовый гейт к указанным кубитам регистра.
+    /// В зависимости от размерности гейта используется `unitary`,
+    /// `twoQubitUnitary` или `multiQubitUnitary`.
+    fn apply_gate(&mut self, gate: &dyn Gate, targets: &[usize]) {
+        validate_gate_targets(gate, targets, self.num_qubits())
+            .unwrap_or_else(|err| panic!("{}", err));
+        
+        let matrix = to_quest_matrix(&gate.matrix());
+        
+        match targets {
+            [qubit] => self.qureg.unitary(*qubit, &ComplexMatrix2::from_rows(&matrix)),
+            [qubit1, qubit2] => self.qureg.two_qubit_unitary(*qubit1, *qubit2, &ComplexMatrix4::from_rows(&matrix)),
+            _ => self.qureg.multi_qubit_unitary(targets, &matrix),
+        }
     }
 
-    /// Измеряет указанный кубит
+    /// Измеряет указанный кубит. Результат разыгрывается генератором симулятора,
+    /// как у нативных бэкендов, поэтому при одинаковом зерне последовательности
+    /// измерений совпадают; затем состояние проецируется через `collapseToOutcome`.
     fn measure(&mut self, qubit: usize) -> bool {
-        self.qureg.measure_qubit(qubit)
+        let prob_zero = self.qureg.calc_prob_of_outcome(qubit, false);
+        let mut outcome = self.rng.gen::<f64>() >= prob_zero;
+        // QuEST не проецирует на результаты с вероятностью не больше REAL_EPS
+        let prob = if outcome { 1.0 - prob_zero } else { prob_zero };
+        if prob <= QUEST_REAL_EPS {
+            outcome = !outcome;
+        }
+        self.qureg.collapse_to_outcome(qubit, outcome);
+        outcome
+    }
+    
+    fn rng(&mut self) -> Option<&mut StdRng> {
+        Some(&mut self.rng)
+    }
+    
+    /// Инициализирует генератор симулятора и генератор QuEST (`seedQuEST`).
+    fn seed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+        self.env.seed(seed);
     }
 
-    /// Получает состояние регистра в виде вектора состояния
+    /// Получает состояние регистра в виде вектора состояния.
+    /// Возвращает снимок амплитуд, не связанный с регистром QuEST.
     fn get_state(&self) -> Box<dyn QuantumState> {
-        // Заглушка для реализации
-        unimplemented!("Метод get_state пока не реализован")
+        Box::new(StateSnapshot::new(self.get_state_vector(), &self.rng))
     }
 
-    /// Вычисляет ожидаемое значение Паули-оператора
+    /// Вычисляет ожидаемое значение произведения операторов Паули.
+    /// Паникует при некорректном произведении (см. `try_expectation_value`).
     fn get_expectation_value(&self, pauli_product: &[(usize, char)]) -> f64 {
-        // Упрощенная реализация, можно расширить
-        if pauli_product.is_empty() {
-            return 1.0;
+        self.try_expectation_value(pauli_product)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+    
+    /// Вычисляет точное ожидаемое значение произведения операторов Паули
+    /// вида `[(кубит, 'X'|'Y'|'Z'|'I')]` через `calcExpecPauliProd`.
+    fn try_expectation_value(&self, pauli_product: &[(usize, char)]) -> Result<f64, TriadError> {
+        let parsed = parse_pauli_product(pauli_product, self.num_qubits())?;
+        if parsed.is_empty() {
+            return Ok(1.0);
         }
         
-        // В текущей реализации просто возвращаем 0, 
-        // полная реализация будет добавлена позже
-        0.0
+        let targets: Vec<usize> = parsed.iter().map(|&(q, _)| q).collect();
+        let codes: Vec<PauliOpType> = parsed.iter().map(|&(_, p)| pauli_code(p)).collect();
+        
+        self.with_workspace(|workspace| self.qureg.calc_expec_pauli_prod(&targets, &codes, workspace))
     }
     
     /// Вычисляет вероятность получения указанного результата при измерении кубита
-    fn probability_of_outcome(&self, _qubit: usize, outcome: bool) -> f64 {
-        // Для примера используем простую логику вместо фактического вычисления
-        // В реальной реализации нужно использовать QuEST API
-        if outcome {
-            0.5  // Вероятность получения 1
-        } else {
-            0.5  // Вероятность получения 0
+    fn probability_of_outcome(&self, qubit: usize, outcome: bool) -> f64 {
+        self.qureg.calc_prob_of_outcome(qubit, outcome)
+    }
+    
+    /// Вычисляет совместную вероятность результата измерения набора кубитов
+    fn probability_of_joint_outcome(&self, qubits: &[usize], outcome: u64) -> f64 {
+        validate_joint_outcome(qubits, outcome, self.num_qubits())
+            .unwrap_or_else(|err| panic!("{}", err));
+        
+        match qubits.len() {
+            0 => 1.0,
+            1 => self.qureg.calc_prob_of_outcome(qubits[0], outcome == 1),
+            _ => self.qureg.calc_prob_of_all_outcomes(qubits)[outcome as usize],
         }
     }
 }
@@ -198,13 +316,7 @@ impl AdvancedQuantumSimulator for QuESTSimulator {
         // Унитарный оператор через QuEST API
         assert_eq!(matrix.len(), 4, "Матрица унитарного оператора должна быть размера 2x2");
         
-        // Создаем ComplexMatrix2, подходящий для FFI
-        let complex_matrix = [
-            Complex { real: matrix[0].re, imag: matrix[0].im },
-            Complex { real: matrix[1].re, imag: matrix[1].im },
-            Complex { real: matrix[2].re, imag: matrix[2].im },
-            Complex { real: matrix[3].re, imag: matrix[3].im },
-        ];
+        let complex_matrix = ComplexMatrix2::from_rows(&to_quest_matrix(matrix));
         
         self.qureg.unitary(qubit, &complex_matrix);
     }
@@ -228,14 +340,78 @@ impl AdvancedQuantumSimulator for QuESTSimulator {
         // Контролируемый унитарный оператор через QuEST API
         assert_eq!(matrix.len(), 4, "Матрица унитарного оператора должна быть размера 2x2");
         
-        // Создаем ComplexMatrix2, подходящий для FFI
-        let complex_matrix = [
-            Complex { real: matrix[0].re, imag: matrix[0].im },
-            Complex { real: matrix[1].re, imag: matrix[1].im },
-            Complex { real: matrix[2].re, imag: matrix[2].im },
-            Complex { real: matrix[3].re, imag: matrix[3].im },
-        ];
+        let complex_matrix = ComplexMatrix2::from_rows(&to_quest_matrix(matrix));
         
         self.qureg.controlled_unitary(control, target, &complex_matrix);
     }
-} 
\ No newline at end of file
+    
+    fn multi_controlled_unitary(&mut self, controls: &[usize], target: usize, matrix: &[Amplitude]) {
+        // QuEST требует хотя бы один управляющий кубит
+        if controls.is_empty() {
+            self.apply_unitary(target, matrix);
+            return;
+        }
+        assert_eq!(matrix.len(), 4, "Матрица унитарного оператора должна быть размера 2x2");
+        
+        let complex_matrix = ComplexMatrix2::from_rows(&to_quest_matrix(matrix));
+        
+        self.qureg.multi_controlled_unitary(controls, target, &complex_matrix);
+    }
+    
+    fn controlled_rx(&mut self, control: usize, target: usize, angle: f64) {
+        self.qureg.controlled_rotate_x(control, target, angle);
+    }
+    
+    fn controlled_ry(&mut self, control: usize, target: usize, angle: f64) {
+        self.qureg.controlled_rotate_y(control, target, angle);
+    }
+    
+    fn controlled_rz(&mut self, control: usize, target: usize, angle: f64) {
+        self.qureg.controlled_rotate_z(control, target, angle);
+    }
+} 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_state_snapshot_matches_register() {
+        let mut simulator = QuESTSimulator::new(3);
+        simulator.hadamard(0);
+        simulator.cnot(0, 1);
+        simulator.x(2);
+
+        let state = simulator.get_state();
+        assert_eq!(state.num_qubits(), 3);
+        assert!((state.probability(0b100) - 0.5).abs() < 1e-10);
+        assert!((state.probability(0b111) - 0.5).abs() < 1e-10);
+        assert!((state.amplitude(0b111).re - std::f64::consts::FRAC_1_SQRT_2).abs() < 1e-10);
+        assert!(state.probability(0b001) < 1e-10);
+        assert!(state.is_entangled());
+
+        // Измерение снимка не затрагивает регистр QuEST
+        let mut snapshot = simulator.get_state();
+        let outcome = snapshot.measure(0);
+        assert_eq!(snapshot.measure(1), outcome);
+        assert!((simulator.probability_of_outcome(0, true) - 0.5).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_seeded_snapshot_measurements_repeat() {
+        let measure_snapshot = || {
+            let mut simulator = QuESTSimulator::new(3);
+            simulator.seed(11);
+            for qubit in 0..3 {
+                simulator.hadamard(qubit);
+            }
+            let mut state = simulator.get_state();
+            (0..3).map(|qubit| state.measure(qubit)).collect::<Vec<bool>>()
+        };
+        assert_eq!(measure_snapshot(), measure_snapshot());
+
+        let mut simulator = QuESTSimulator::new(2);
+        simulator.hadamard(0);
+        assert!(!simulator.get_state().is_entangled());
+    }
+}
@@ -1,71 +1,87 @@
 // Вспомогательные функции для библиотеки QuEST
 #include <stdio.h>
 #include <stdlib.h>
-#include <time.h>
 
 // Макрос для отладочного вывода, который ничего не делает
 #define DEBUG_PRINT(...) ((void)0)
 
+// Признак того, что генератор случайных чисел уже инициализирован
+static int rng_initialized = 0;
+
 // Генератор случайных чисел для квантовых измерений
 double genrand_real1() {
     // Простой генератор случайных чисел от 0 до 1 (не криптографический!)
-    static int initialized = 0;
-    if (!initialized) {
-        srand(time(NULL));
-        initialized = 1;
+    // Без явного seedQuEST инициализируется фиксированным зерном, чтобы
+    // результаты не зависели от времени запуска
+    if (!rng_initialized) {
+        srand(5489u);
+        rng_initialized = 1;
         DEBUG_PRINT("INFO: Инициализирован генератор случайных чисел\n");
     }
     return (double)rand() / (double)RAND_MAX;
 }
 
-// Функция для инициализации генератора случайных чисел
+// Функция для инициализации генератора случайных чисел (вызывается из seedQuEST)
 void init_by_array(unsigned long init_key[], int key_length) {
-    // Используем первое значение для инициализации
-    srand(init_key[0]);
-    DEBUG_PRINT("INFO: Генератор случайных чисел инициализирован с seed=%lu\n", init_key[0]);
+    // Смешиваем все значения, чтобы учитывались обе половины 64-битного зерна
+    unsigned long seed = 0;
+    for (int i = 0; i < key_length; i++) {
+        seed = seed * 1000003UL + init_key[i];
+    }
+    srand((unsigned int)(seed ^ ((seed >> 16) >> 16)));
+    rng_initialized = 1;
+    DEBUG_PRINT("INFO: Генератор случайных чисел инициализирован с seed=%lu\n", seed);
+}
+
+// Переопределяем обработчик ошибок валидации QuEST. Валидаторы QuEST
+// продолжают работу после вызова обработчика, поэтому возвращаться из него
+// нельзя. Все входные данные проверяются на стороне Rust (src/quest), а память
+// под регистр пробно выделяется там же перед createQureg, так что аварийное
+// завершение остается лишь последней защитой: validateQuregAllocation сработает,
+// только если память закончится между пробным выделением и созданием регистра.
+void invalidQuESTInputError(const char* errMsg, const char* errFunc) {
+    fprintf(stderr, "QuEST: %s: %s\n", errFunc, errMsg);
+    abort();
 }
 
 // Функции валидации для QuEST
 void validateControlTarget(int controlQubit, int targetQubit, int numQubits) {
-    if (controlQubit >= numQubits || targetQubit >= numQubits || controlQubit == targetQubit) {
-        // Вместо вывода сообщения об ошибке, просто завершаем программу
-        exit(1);
+    if (controlQubit < 0 || targetQubit < 0 || controlQubit >= numQubits || targetQubit >= numQubits) {
+        invalidQuESTInputError("Invalid qubit index", __func__);
+    } else if (controlQubit == targetQubit) {
+        invalidQuESTInputError("Control qubit cannot equal target qubit", __func__);
     }
 }
 
 void validateTarget(int targetQubit, int numQubits) {
-    if (targetQubit >= numQubits) {
-        // Вместо вывода сообщения об ошибке, просто завершаем программу
-        exit(1);
+    if (targetQubit < 0 || targetQubit >= numQubits) {
+        invalidQuESTInputError("Invalid target qubit", __func__);
     }
 }
 
 void validateNumQubitsInQureg(int numQubits) {
     DEBUG_PRINT("DEBUG: Проверка числа кубитов: %d\n", numQubits);
     if (numQubits <= 0 || numQubits > 50) {
-        // Вместо вывода сообщения об ошибке, просто завершаем программу
-        exit(1);
+        invalidQuESTInputError("Invalid number of qubits", __func__);
     }
 }
 
 void validateMemoryAllocationSize(long long int numValues) {
     if (numValues <= 0) {
-        // Вместо вывода сообщения об ошибке, просто завершаем программу
-        exit(1);
+        invalidQuESTInputError("Invalid memory allocation size", __func__);
     }
 }
 
 void validateQuregAllocation(void* qureg, int numQubits) {
+    (void)numQubits;
     if (qureg == NULL) {
-        // Вместо вывода сообщения об ошибке, просто завершаем программу
-        exit(1);
+        invalidQuESTInputError("Could not allocate memory for Qureg", __func__);
     }
 }
 
 // Функция для обработки переполнения буфера QASM
 void raiseQASMBufferOverflow() {
-    // Вместо вывода сообщения об ошибке, просто завершаем программу
-    exit(1);
+    invalidQuESTInputError("QASM line buffer filled", __func__);
 }
 
 // Информационная функция TRIAD